@@ -0,0 +1,121 @@
+//! Compares how long a heavy UI blocks the event loop thread per frame, and how long it takes
+//! until a frame is painted, tessellating inline and through the double-buffered pipeline of the
+//! `threaded-tessellation` feature.
+//!
+//! ```sh
+//! cargo bench --bench tessellation --features threaded-tessellation
+//! ```
+
+use std::time::{Duration, Instant};
+
+use egui::{Context, FullOutput, Pos2, RawInput, Rect};
+use egui_tests::tessellation::TessellationPipeline;
+
+const ITERATIONS: usize = 100;
+/// Stands in for recording the compute hooks, the scene and its post processing.
+const SCENE_WORK: Duration = Duration::from_millis(2);
+/// Stands in for handling the input and building the UI of the next frame.
+const INPUT_WORK: Duration = Duration::from_millis(4);
+
+fn heavy_ui(ctx: &Context) -> FullOutput {
+    let raw_input = RawInput {
+        screen_rect: Some(Rect::from_min_size(Pos2::ZERO, egui::vec2(1920.0, 1080.0))),
+        ..Default::default()
+    };
+    ctx.run(raw_input, |ctx| {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            egui::Grid::new("cells").show(ui, |ui| {
+                for row in 0..60 {
+                    for column in 0..30 {
+                        ui.label(format!("{row}:{column}"));
+                    }
+                    ui.end_row();
+                }
+            });
+            let painter = ui.painter();
+            for index in 0..2000 {
+                let center = Pos2::new((index % 80) as f32 * 24.0, (index / 80) as f32 * 40.0);
+                painter.circle_stroke(center, 10.0, (1.5, egui::Color32::LIGHT_BLUE));
+            }
+        });
+    })
+}
+
+fn busy(duration: Duration) {
+    let start = Instant::now();
+    while start.elapsed() < duration {
+        std::hint::spin_loop();
+    }
+}
+
+fn median(mut samples: Vec<Duration>) -> Duration {
+    samples.sort();
+    samples[samples.len() / 2]
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+fn main() {
+    let ctx = Context::default();
+    // Lays out the fonts, so later frames only tessellate
+    let mut output = heavy_ui(&ctx);
+    for _ in 0..3 {
+        output = heavy_ui(&ctx);
+    }
+    let pixels_per_point = output.pixels_per_point;
+    let frame_output = || FullOutput {
+        shapes: output.shapes.clone(),
+        pixels_per_point,
+        ..Default::default()
+    };
+    println!(
+        "{} shapes, {:?} of scene and {:?} of input work per frame",
+        output.shapes.len(),
+        SCENE_WORK,
+        INPUT_WORK
+    );
+
+    let mut inline_blocked = Vec::with_capacity(ITERATIONS);
+    for _ in 0..ITERATIONS {
+        let shapes = output.shapes.clone();
+        let start = Instant::now();
+        let paint_jobs = ctx.tessellate(shapes, pixels_per_point);
+        busy(SCENE_WORK);
+        inline_blocked.push(start.elapsed());
+        std::hint::black_box(paint_jobs);
+        busy(INPUT_WORK);
+    }
+
+    let mut pipeline = TessellationPipeline::default();
+    let mut pipelined_blocked = Vec::with_capacity(ITERATIONS);
+    let mut pipelined_latency = Vec::with_capacity(ITERATIONS);
+    let mut previous_start: Option<Instant> = None;
+    for _ in 0..ITERATIONS {
+        let mut output = frame_output();
+        let start = Instant::now();
+        let frame = pipeline.advance(&ctx, &mut output, true);
+        busy(SCENE_WORK);
+        let painted = Instant::now();
+        pipelined_blocked.push(painted - start);
+        // The meshes painted now are the ones of the previous frame
+        if let Some(previous_start) = previous_start.replace(start) {
+            pipelined_latency.push(painted - previous_start);
+        }
+        std::hint::black_box(frame);
+        busy(INPUT_WORK);
+    }
+
+    let inline = median(inline_blocked);
+    println!(
+        "inline:    {:.3} ms blocked per frame, painted {:.3} ms after the UI was built",
+        millis(inline),
+        millis(inline)
+    );
+    println!(
+        "pipelined: {:.3} ms blocked per frame, painted {:.3} ms after the UI was built",
+        millis(median(pipelined_blocked)),
+        millis(median(pipelined_latency))
+    );
+}
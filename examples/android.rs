@@ -0,0 +1,34 @@
+//! The entry point of an Android app, built with `cargo-apk` or `xbuild`:
+//!
+//! ```sh
+//! cargo apk run --example android --target aarch64-linux-android
+//! ```
+//!
+//! The library itself is built as a `cdylib` too, so the same `android_main` can live in an app's
+//! own `lib.rs`.
+#![cfg(target_os = "android")]
+
+use egui_tests::{AndroidApp, App, AppBuilder, Frame};
+
+#[derive(Default)]
+struct AndroidDemo {
+    text: String,
+}
+
+impl App for AndroidDemo {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut Frame) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.heading("Hello from Android");
+            // Focusing the text field shows the soft keyboard
+            ui.text_edit_singleline(&mut self.text);
+        });
+    }
+}
+
+#[no_mangle]
+fn android_main(app: AndroidApp) {
+    egui_tests::log_console::init(log::LevelFilter::Info);
+    AppBuilder::new()
+        .with_android_app(app)
+        .run(AndroidDemo::default());
+}
@@ -0,0 +1,226 @@
+//! An editor-style layout of dockable tabs sharing one document, with undo and redo and commands
+//! in the command palette (Ctrl+Shift+P).
+//!
+//! ```sh
+//! cargo run --example docking_editor
+//! ```
+
+use std::{cell::RefCell, rc::Rc};
+
+use egui_tests::{
+    dock::{Dock, DockState, Tab},
+    shortcuts::Action,
+    undo::{Command, CommandStack},
+    App, AppBuilder, Frame,
+};
+
+#[derive(Clone)]
+struct Item {
+    name: String,
+    value: f32,
+}
+
+/// State shared by all tabs.
+#[derive(Default)]
+struct Editor {
+    items: Vec<Item>,
+    selected: Option<usize>,
+    history: CommandStack<Edit>,
+}
+
+enum Edit {
+    Add(Item),
+    Rename(usize, String, String),
+    SetValue(usize, f32, f32),
+}
+
+impl Command for Edit {
+    type Target = Vec<Item>;
+
+    fn apply(&mut self, items: &mut Vec<Item>) {
+        match self {
+            Self::Add(item) => items.push(item.clone()),
+            Self::Rename(index, _, after) => items[*index].name = after.clone(),
+            Self::SetValue(index, _, after) => items[*index].value = *after,
+        }
+    }
+
+    fn undo(&mut self, items: &mut Vec<Item>) {
+        match self {
+            Self::Add(_) => {
+                items.pop();
+            }
+            Self::Rename(index, before, _) => items[*index].name = before.clone(),
+            Self::SetValue(index, before, _) => items[*index].value = *before,
+        }
+    }
+
+    fn merge(&mut self, next: &Self) -> bool {
+        match (self, next) {
+            (Self::Rename(index, _, after), Self::Rename(next_index, _, next))
+                if index == next_index =>
+            {
+                *after = next.clone();
+            }
+            (Self::SetValue(index, _, after), Self::SetValue(next_index, _, next))
+                if index == next_index =>
+            {
+                *after = *next;
+            }
+            _ => return false,
+        }
+        true
+    }
+}
+
+impl Editor {
+    fn add_item(&mut self) {
+        let item = Item {
+            name: format!("Item {}", self.items.len() + 1),
+            value: 0.5,
+        };
+        self.history.execute(&mut self.items, Edit::Add(item));
+        self.selected = Some(self.items.len() - 1);
+    }
+}
+
+struct Outline(Rc<RefCell<Editor>>);
+
+impl Tab for Outline {
+    fn title(&self) -> egui::WidgetText {
+        "Outline".into()
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, _frame: &mut Frame) {
+        let mut editor = self.0.borrow_mut();
+        if ui.button("➕ Add item").clicked() {
+            editor.add_item();
+        }
+        ui.separator();
+        for index in 0..editor.items.len() {
+            let selected = editor.selected == Some(index);
+            if ui
+                .selectable_label(selected, &editor.items[index].name)
+                .clicked()
+            {
+                editor.selected = Some(index);
+            }
+        }
+    }
+}
+
+struct Properties(Rc<RefCell<Editor>>);
+
+impl Tab for Properties {
+    fn title(&self) -> egui::WidgetText {
+        "Properties".into()
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, _frame: &mut Frame) {
+        let editor = &mut *self.0.borrow_mut();
+        let Some(index) = editor.selected.filter(|&index| index < editor.items.len()) else {
+            ui.weak("Nothing selected");
+            return;
+        };
+        let item = &mut editor.items[index];
+        let before = item.clone();
+        ui.text_edit_singleline(&mut item.name);
+        ui.add(egui::Slider::new(&mut item.value, 0.0..=1.0).text("Value"));
+        if item.name != before.name {
+            let edit = Edit::Rename(index, before.name, item.name.clone());
+            editor.history.push(edit);
+        } else if item.value != before.value {
+            let edit = Edit::SetValue(index, before.value, item.value);
+            editor.history.push(edit);
+        }
+    }
+}
+
+struct Preview(Rc<RefCell<Editor>>);
+
+impl Tab for Preview {
+    fn title(&self) -> egui::WidgetText {
+        "Preview".into()
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, _frame: &mut Frame) {
+        let editor = self.0.borrow();
+        for item in &editor.items {
+            ui.add(egui::ProgressBar::new(item.value).text(&item.name));
+        }
+    }
+
+    fn closeable(&self) -> bool {
+        false
+    }
+}
+
+struct EditorApp {
+    editor: Rc<RefCell<Editor>>,
+    dock: Dock,
+}
+
+impl Default for EditorApp {
+    fn default() -> Self {
+        let editor = Rc::new(RefCell::new(Editor::default()));
+        let mut layout = DockState::new(vec!["preview".to_owned()]);
+        let surface = layout.main_surface_mut();
+        let [preview, _] = surface.split_left(
+            egui_dock::NodeIndex::root(),
+            0.25,
+            vec!["outline".to_owned()],
+        );
+        surface.split_right(preview, 0.7, vec!["properties".to_owned()]);
+        let dock = Dock::new("editor", layout)
+            .with_tab("outline", Outline(editor.clone()))
+            .with_tab("properties", Properties(editor.clone()))
+            .with_tab("preview", Preview(editor.clone()));
+        Self { editor, dock }
+    }
+}
+
+fn add_item_action() -> Action {
+    Action::Custom("add_item".to_owned())
+}
+
+impl App for EditorApp {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut Frame) {
+        if frame.shortcuts().name(&add_item_action()).is_none() {
+            frame.shortcuts_mut().register(
+                add_item_action(),
+                "Add item",
+                Some(egui::KeyboardShortcut::new(
+                    egui::Modifiers::COMMAND,
+                    egui::Key::N,
+                )),
+            );
+        }
+        {
+            let editor = &mut *self.editor.borrow_mut();
+            if frame.action_triggered(&add_item_action()) {
+                editor.add_item();
+            }
+            editor.history.handle_actions(frame, &mut editor.items);
+        }
+
+        egui::TopBottomPanel::top("menu").show(ctx, |ui| {
+            egui::menu::bar(ui, |ui| {
+                ui.menu_button("Edit", |ui| {
+                    let editor = &mut *self.editor.borrow_mut();
+                    editor.history.ui(ui, &mut editor.items);
+                });
+                ui.menu_button("View", |ui| self.dock.menu_ui(ui));
+            });
+        });
+        self.dock.show(ctx, frame);
+    }
+}
+
+fn main() {
+    egui_tests::log_console::init(log::LevelFilter::Info);
+    AppBuilder::new()
+        .with_title("Docking editor")
+        .with_app_id("egui-example-docking-editor")
+        .with_inner_size(1000.0, 640.0)
+        .run(EditorApp::default());
+}
@@ -0,0 +1,62 @@
+//! A transparent, always on top HUD which lets the mouse through except over its widgets.
+//!
+//! ```sh
+//! cargo run --example overlay_hud
+//! ```
+//!
+//! Quit with Ctrl+Q, or toggle click-through with Ctrl+Shift+O to move the HUD.
+
+use egui_tests::{App, AppBuilder, Frame};
+use winit::window::WindowLevel;
+
+#[derive(Default)]
+struct Hud {
+    counter: u32,
+}
+
+impl App for Hud {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut Frame) {
+        let fps = frame.diagnostics().average_fps().unwrap_or_default();
+        egui::Window::new("HUD")
+            .title_bar(false)
+            .resizable(false)
+            .anchor(egui::Align2::RIGHT_TOP, [-16.0, 16.0])
+            .frame(egui::Frame::popup(&ctx.style()).multiply_with_opacity(0.85))
+            .show(ctx, |ui| {
+                ui.heading(format!("{fps:.0} FPS"));
+                ui.label(format!(
+                    "Frame time {:.1} ms",
+                    frame.frame_time().as_secs_f64() * 1000.0
+                ));
+                ui.horizontal(|ui| {
+                    if ui.button("➕").clicked() {
+                        self.counter += 1;
+                    }
+                    ui.label(format!("Clicked {} times", self.counter));
+                });
+                let mut dimmed = frame.clear_color() != egui::Rgba::TRANSPARENT;
+                if ui.checkbox(&mut dimmed, "Dim the screen").changed() {
+                    frame.set_clear_color(if dimmed {
+                        egui::Rgba::from_black_alpha(0.4)
+                    } else {
+                        egui::Rgba::TRANSPARENT
+                    });
+                }
+                if ui.button("Quit").clicked() {
+                    frame.quit();
+                }
+            });
+        ctx.request_repaint_after(std::time::Duration::from_millis(250));
+    }
+}
+
+fn main() {
+    egui_tests::log_console::init(log::LevelFilter::Info);
+    AppBuilder::new()
+        .with_title("Overlay HUD")
+        .with_transparent(true)
+        .with_decorations(false)
+        .with_window_level(WindowLevel::AlwaysOnTop)
+        .with_click_through(true)
+        .run(Hud::default());
+}
@@ -0,0 +1,185 @@
+//! A triangle drawn with raw wgpu calls inside an egui widget, which can be dragged to rotate it.
+//!
+//! ```sh
+//! cargo run --example paint_callback
+//! ```
+
+use egui_tests::{
+    gpu::GpuContext,
+    paint_callback::{self, CallbackResources, CallbackTrait, PaintCallbackInfo},
+    App, AppBuilder, Frame,
+};
+
+const SHADER: &str = r"
+// The angle in x, padded to the 16 bytes uniforms need
+@group(0) @binding(0)
+var<uniform> uniforms: vec4<f32>;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) color: vec3<f32>,
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let corner = f32(index) * 2.0943951 + uniforms.x;
+    var colors = array<vec3<f32>, 3>(
+        vec3<f32>(1.0, 0.3, 0.3),
+        vec3<f32>(0.3, 1.0, 0.3),
+        vec3<f32>(0.3, 0.3, 1.0),
+    );
+    var out: VertexOutput;
+    out.position = vec4<f32>(cos(corner), sin(corner), 0.0, 1.0) * vec4<f32>(0.8, 0.8, 1.0, 1.0);
+    out.color = colors[index];
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(in.color, 1.0);
+}
+";
+
+/// Created once and kept in the callback resources of the egui renderer.
+struct TriangleResources {
+    pipeline: wgpu::RenderPipeline,
+    uniforms: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+}
+
+impl TriangleResources {
+    fn new(gpu: &GpuContext) -> Self {
+        let device = gpu.device();
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("triangle shader"),
+            source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+        });
+        let uniforms = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("triangle uniforms"),
+            size: 16,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("triangle bind group layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("triangle bind group"),
+            layout: &bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniforms.as_entire_binding(),
+            }],
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("triangle pipeline layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("triangle pipeline"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(gpu.target_format().into())],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: gpu.msaa_samples(),
+                ..Default::default()
+            },
+            multiview: None,
+        });
+
+        Self {
+            pipeline,
+            uniforms,
+            bind_group,
+        }
+    }
+}
+
+/// The data of one frame, handed to the renderer with the shape.
+struct TriangleCallback {
+    angle: f32,
+}
+
+impl CallbackTrait for TriangleCallback {
+    fn prepare(
+        &self,
+        _device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        _encoder: &mut wgpu::CommandEncoder,
+        resources: &mut CallbackResources,
+    ) -> Vec<wgpu::CommandBuffer> {
+        let resources: &TriangleResources = resources.get().unwrap();
+        queue.write_buffer(&resources.uniforms, 0, &self.angle.to_le_bytes());
+        Vec::new()
+    }
+
+    fn paint<'a>(
+        &'a self,
+        _info: PaintCallbackInfo,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        resources: &'a CallbackResources,
+    ) {
+        let resources: &TriangleResources = resources.get().unwrap();
+        render_pass.set_pipeline(&resources.pipeline);
+        render_pass.set_bind_group(0, &resources.bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
+
+#[derive(Default)]
+struct PaintCallbackDemo {
+    angle: f32,
+}
+
+impl App for PaintCallbackDemo {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut Frame) {
+        // Created again after the device was recreated
+        if !frame.gpu().has_callback_resources::<TriangleResources>() {
+            frame
+                .gpu()
+                .insert_callback_resources(TriangleResources::new(frame.gpu()));
+        }
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.label("Drag the triangle to rotate it.");
+            egui::Frame::canvas(ui.style()).show(ui, |ui| {
+                let (rect, response) =
+                    ui.allocate_exact_size(egui::vec2(300.0, 300.0), egui::Sense::drag());
+                self.angle += response.drag_delta().x * 0.01;
+                ui.painter().add(paint_callback::shape(
+                    rect,
+                    TriangleCallback { angle: self.angle },
+                ));
+            });
+        });
+    }
+}
+
+fn main() {
+    egui_tests::log_console::init(log::LevelFilter::Info);
+    AppBuilder::new()
+        .with_title("Paint callback")
+        .with_transparent(false)
+        .run(PaintCallbackDemo::default());
+}
@@ -0,0 +1,351 @@
+//! A rotating cube drawn underneath the UI with a depth buffer and MSAA.
+//!
+//! ```sh
+//! cargo run --example viewport_3d
+//! ```
+
+use egui_tests::{App, AppBuilder, Frame, SceneFrame, SceneRenderer};
+use wgpu::util::DeviceExt;
+
+const SHADER: &str = r"
+struct Uniforms {
+    mvp: mat4x4<f32>,
+}
+
+@group(0) @binding(0)
+var<uniform> uniforms: Uniforms;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) color: vec3<f32>,
+}
+
+@vertex
+fn vs_main(@location(0) position: vec3<f32>) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = uniforms.mvp * vec4<f32>(position, 1.0);
+    out.color = position * 0.5 + 0.5;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(in.color, 1.0);
+}
+";
+
+const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+
+const VERTICES: [[f32; 3]; 8] = [
+    [-1.0, -1.0, -1.0],
+    [1.0, -1.0, -1.0],
+    [1.0, 1.0, -1.0],
+    [-1.0, 1.0, -1.0],
+    [-1.0, -1.0, 1.0],
+    [1.0, -1.0, 1.0],
+    [1.0, 1.0, 1.0],
+    [-1.0, 1.0, 1.0],
+];
+
+#[rustfmt::skip]
+const INDICES: [u16; 36] = [
+    0, 1, 2, 2, 3, 0, // back
+    4, 6, 5, 6, 4, 7, // front
+    0, 3, 7, 7, 4, 0, // left
+    1, 5, 6, 6, 2, 1, // right
+    3, 2, 6, 6, 7, 3, // top
+    0, 4, 5, 5, 1, 0, // bottom
+];
+
+/// Column-major, as WGSL expects.
+type Mat4 = [[f32; 4]; 4];
+
+fn mul(a: Mat4, b: Mat4) -> Mat4 {
+    let mut out = [[0.0; 4]; 4];
+    for (column, out) in out.iter_mut().enumerate() {
+        for (row, out) in out.iter_mut().enumerate() {
+            *out = (0..4).map(|k| a[k][row] * b[column][k]).sum();
+        }
+    }
+    out
+}
+
+/// Right-handed perspective projection to wgpu's depth range of `0..1`.
+fn perspective(fov_y: f32, aspect: f32, near: f32, far: f32) -> Mat4 {
+    let f = 1.0 / (fov_y / 2.0).tan();
+    [
+        [f / aspect, 0.0, 0.0, 0.0],
+        [0.0, f, 0.0, 0.0],
+        [0.0, 0.0, far / (near - far), -1.0],
+        [0.0, 0.0, near * far / (near - far), 0.0],
+    ]
+}
+
+fn rotation(yaw: f32, pitch: f32) -> Mat4 {
+    let (sy, cy) = yaw.sin_cos();
+    let (sp, cp) = pitch.sin_cos();
+    let yaw = [
+        [cy, 0.0, -sy, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [sy, 0.0, cy, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ];
+    let pitch = [
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, cp, sp, 0.0],
+        [0.0, -sp, cp, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ];
+    mul(pitch, yaw)
+}
+
+fn translation(z: f32) -> Mat4 {
+    [
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [0.0, 0.0, z, 1.0],
+    ]
+}
+
+/// GPU resources, created for the format and sample count of the first frame.
+struct Pipeline {
+    format: wgpu::TextureFormat,
+    sample_count: u32,
+    pipeline: wgpu::RenderPipeline,
+    vertices: wgpu::Buffer,
+    indices: wgpu::Buffer,
+    uniforms: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+}
+
+impl Pipeline {
+    fn new(frame: &SceneFrame) -> Self {
+        let device = frame.device;
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("cube shader"),
+            source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+        });
+        let uniforms = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("cube uniforms"),
+            size: std::mem::size_of::<Mat4>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("cube bind group layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("cube bind group"),
+            layout: &bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniforms.as_entire_binding(),
+            }],
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("cube pipeline layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("cube pipeline"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: std::mem::size_of::<[f32; 3]>() as u64,
+                    step_mode: wgpu::VertexStepMode::Vertex,
+                    attributes: &wgpu::vertex_attr_array![0 => Float32x3],
+                }],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(frame.format.into())],
+            }),
+            // The depth test hides the back faces
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: frame.depth_format.map(|format| wgpu::DepthStencilState {
+                format,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: Default::default(),
+                bias: Default::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: frame.sample_count,
+                ..Default::default()
+            },
+            multiview: None,
+        });
+        let vertices = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("cube vertices"),
+            contents: &VERTICES
+                .iter()
+                .flatten()
+                .flat_map(|v| v.to_ne_bytes())
+                .collect::<Vec<_>>(),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        let indices = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("cube indices"),
+            contents: &INDICES
+                .iter()
+                .flat_map(|i| i.to_ne_bytes())
+                .collect::<Vec<_>>(),
+            usage: wgpu::BufferUsages::INDEX,
+        });
+        Self {
+            format: frame.format,
+            sample_count: frame.sample_count,
+            pipeline,
+            vertices,
+            indices,
+            uniforms,
+            bind_group,
+        }
+    }
+}
+
+struct Cube {
+    yaw: f32,
+    pitch: f32,
+    /// Radians per second around the vertical axis.
+    spin: f32,
+    background: egui::Color32,
+    pipeline: Option<Pipeline>,
+}
+
+impl Default for Cube {
+    fn default() -> Self {
+        Self {
+            yaw: 0.5,
+            pitch: 0.4,
+            spin: 0.8,
+            background: egui::Color32::from_rgb(20, 24, 32),
+            pipeline: None,
+        }
+    }
+}
+
+impl SceneRenderer for Cube {
+    fn render(&mut self, frame: &mut SceneFrame) {
+        self.yaw += self.spin * frame.time.delta_secs();
+        let pipeline = match self.pipeline.take() {
+            Some(pipeline)
+                if pipeline.format == frame.format
+                    && pipeline.sample_count == frame.sample_count =>
+            {
+                pipeline
+            }
+            _ => Pipeline::new(frame),
+        };
+
+        let [width, height] = frame.size_in_pixels;
+        let aspect = width.max(1) as f32 / height.max(1) as f32;
+        let mvp = mul(
+            perspective(std::f32::consts::FRAC_PI_4, aspect, 0.1, 100.0),
+            mul(translation(-5.0), rotation(self.yaw, self.pitch)),
+        );
+        let bytes: Vec<u8> = mvp.iter().flatten().flat_map(|v| v.to_ne_bytes()).collect();
+        frame.queue.write_buffer(&pipeline.uniforms, 0, &bytes);
+
+        let [r, g, b, _] = egui::Rgba::from(self.background).to_array();
+        let mut pass = frame
+            .encoder
+            .begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("cube"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: frame.view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color {
+                            r: r.into(),
+                            g: g.into(),
+                            b: b.into(),
+                            a: 1.0,
+                        }),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: frame.depth_stencil_attachment(),
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+        pass.set_pipeline(&pipeline.pipeline);
+        pass.set_bind_group(0, &pipeline.bind_group, &[]);
+        pass.set_vertex_buffer(0, pipeline.vertices.slice(..));
+        pass.set_index_buffer(pipeline.indices.slice(..), wgpu::IndexFormat::Uint16);
+        pass.draw_indexed(0..INDICES.len() as u32, 0, 0..1);
+        drop(pass);
+        self.pipeline = Some(pipeline);
+    }
+}
+
+#[derive(Default)]
+struct ViewportApp {
+    cube: Cube,
+}
+
+impl App for ViewportApp {
+    fn setup(&mut self, _device: &wgpu::Device, _queue: &wgpu::Queue) {
+        // Also called after the device was recreated, the pipeline belongs to the old one
+        self.cube.pipeline = None;
+    }
+
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut Frame) {
+        egui::Window::new("Cube").show(ctx, |ui| {
+            ui.add(egui::Slider::new(&mut self.cube.spin, -3.0..=3.0).text("Spin"));
+            ui.add(
+                egui::Slider::new(&mut self.cube.pitch, -1.5..=1.5)
+                    .text("Pitch")
+                    .step_by(0.01),
+            );
+            ui.horizontal(|ui| {
+                ui.label("Background");
+                ui.color_edit_button_srgba(&mut self.cube.background);
+            });
+        });
+
+        // Dragging outside of the UI rotates the cube
+        if !ctx.is_pointer_over_area() {
+            let delta = ctx.input(|input| {
+                if input.pointer.primary_down() {
+                    input.pointer.delta()
+                } else {
+                    egui::Vec2::ZERO
+                }
+            });
+            self.cube.yaw += delta.x * 0.01;
+            self.cube.pitch = (self.cube.pitch + delta.y * 0.01).clamp(-1.5, 1.5);
+        }
+        if self.cube.spin != 0.0 {
+            ctx.request_repaint();
+        }
+    }
+
+    fn scene_renderer(&mut self) -> Option<&mut dyn SceneRenderer> {
+        Some(&mut self.cube)
+    }
+}
+
+fn main() {
+    egui_tests::log_console::init(log::LevelFilter::Info);
+    AppBuilder::new()
+        .with_title("3D viewport")
+        .with_depth_buffer(Some(DEPTH_FORMAT))
+        .with_msaa_samples(4)
+        .run(ViewportApp::default());
+}
@@ -0,0 +1,56 @@
+//! A small app which also runs in the browser, using only the parts of the library available
+//! there.
+//!
+//! ```sh
+//! cargo run --example wasm_demo
+//! # In the browser, with the wasm-bindgen CLI
+//! cargo build --example wasm_demo --target wasm32-unknown-unknown --release
+//! wasm-bindgen --target web --out-dir examples/web \
+//!     target/wasm32-unknown-unknown/release/examples/wasm_demo.wasm
+//! ```
+//!
+//! and serve `examples/web` with a page running
+//! `import init from "./wasm_demo.js"; init();` as a module script.
+
+use egui_tests::{notifications, App, AppBuilder, Frame};
+
+#[derive(Default)]
+struct WebDemo {
+    name: String,
+}
+
+impl App for WebDemo {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut Frame) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.heading("Hello from egui and wgpu");
+            ui.label(if cfg!(target_arch = "wasm32") {
+                "Running in the browser"
+            } else {
+                "Running natively"
+            });
+            ui.horizontal(|ui| {
+                ui.label("Name:");
+                ui.text_edit_singleline(&mut self.name);
+            });
+            if ui.button("Greet").clicked() {
+                notifications::notify_info(ctx, format!("Hello, {}!", self.name));
+            }
+            let mut theme = frame.theme().preference();
+            if theme.ui(ui) {
+                frame.set_theme_preference(theme);
+            }
+            if ui.button("Settings").clicked() {
+                frame.settings_mut().set_visible(true);
+            }
+        });
+    }
+}
+
+fn main() {
+    #[cfg(target_arch = "wasm32")]
+    std::panic::set_hook(Box::new(console_error_panic_hook::hook));
+    egui_tests::log_console::init(log::LevelFilter::Info);
+    AppBuilder::new()
+        .with_title("Web demo")
+        .run(WebDemo::default());
+}
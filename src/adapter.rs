@@ -0,0 +1,134 @@
+//! Picks the adapter the GPU context is created on.
+//!
+//! Hardware adapters of the type matching [`crate::graphics::GraphicsConfig::power_preference`]
+//! are preferred. The backends can be forced with the [`BACKEND_ENV_VAR`] environment variable,
+//! e.g. `WGPU_BACKEND=vulkan`. If no hardware adapter is found, a software adapter is used.
+
+use wgpu::{Backends, DeviceType, PowerPreference};
+
+/// Environment variable overriding [`crate::graphics::GraphicsConfig::backends`], a comma
+/// separated list of backend names as accepted by [`parse_backends`].
+pub const BACKEND_ENV_VAR: &str = "WGPU_BACKEND";
+
+/// Parses a comma separated list of backend names (`vulkan`, `dx12`, `dx11`, `metal`, `gl`),
+/// ignoring case. Returns `None` if a name is unknown or the list is empty.
+pub fn parse_backends(names: &str) -> Option<Backends> {
+    let mut backends = Backends::empty();
+    for name in names.split(',').map(str::trim) {
+        backends |= match name.to_lowercase().as_str() {
+            "vulkan" | "vk" => Backends::VULKAN,
+            "dx12" | "d3d12" => Backends::DX12,
+            "dx11" | "d3d11" => Backends::DX11,
+            "metal" | "mtl" => Backends::METAL,
+            "gl" | "gles" | "opengl" | "webgl" => Backends::GL,
+            _ => return None,
+        };
+    }
+    Some(backends)
+}
+
+/// The backends forced by the [`BACKEND_ENV_VAR`] environment variable, if it is set.
+pub fn backends_from_env() -> Option<Backends> {
+    let names = std::env::var(BACKEND_ENV_VAR).ok()?;
+    let backends = parse_backends(&names);
+    if backends.is_none() {
+        log::warn!("Ignoring unknown backends in {BACKEND_ENV_VAR}={names}");
+    }
+    backends
+}
+
+/// Returns the index of the best hardware adapter in `adapters`, preferring discrete GPUs for
+/// [`PowerPreference::HighPerformance`] and integrated GPUs for [`PowerPreference::LowPower`].
+///
+/// Software adapters are never selected, `None` means a software fallback is needed. Ties keep
+/// the enumeration order.
+pub fn select_adapter(
+    adapters: &[wgpu::AdapterInfo],
+    power_preference: PowerPreference,
+) -> Option<usize> {
+    let preferred = match power_preference {
+        PowerPreference::HighPerformance => Some(DeviceType::DiscreteGpu),
+        PowerPreference::LowPower => Some(DeviceType::IntegratedGpu),
+        PowerPreference::None => None,
+    };
+    let rank = |info: &wgpu::AdapterInfo| match info.device_type {
+        _ if Some(info.device_type) == preferred => Some(0),
+        DeviceType::DiscreteGpu | DeviceType::IntegratedGpu => Some(1),
+        DeviceType::VirtualGpu | DeviceType::Other => Some(2),
+        DeviceType::Cpu => None,
+    };
+    adapters
+        .iter()
+        .enumerate()
+        .filter_map(|(index, info)| Some((rank(info)?, index)))
+        .min()
+        .map(|(_, index)| index)
+}
+
+/// Requests the adapter to render to `surface` with, or to offscreen textures without a surface,
+/// falling back to a software adapter with a warning if there is no hardware adapter.
+pub(crate) async fn request_adapter(
+    instance: &wgpu::Instance,
+    backends: Backends,
+    surface: Option<&wgpu::Surface>,
+    power_preference: PowerPreference,
+) -> Option<wgpu::Adapter> {
+    let adapter = request_hardware_adapter(instance, backends, surface, power_preference).await;
+    if let Some(adapter) = adapter {
+        let info = adapter.get_info();
+        log::info!(
+            "Using {} ({:?}, {:?})",
+            info.name,
+            info.device_type,
+            info.backend
+        );
+        return Some(adapter);
+    }
+
+    log::warn!("No hardware adapter found for {backends:?}, falling back to a software adapter");
+    instance
+        .request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference,
+            force_fallback_adapter: true,
+            compatible_surface: surface,
+        })
+        .await
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+async fn request_hardware_adapter(
+    instance: &wgpu::Instance,
+    backends: Backends,
+    surface: Option<&wgpu::Surface>,
+    power_preference: PowerPreference,
+) -> Option<wgpu::Adapter> {
+    // Only adapters which can render to our surface
+    let mut adapters: Vec<_> = instance
+        .enumerate_adapters(backends)
+        .filter(|adapter| surface.is_none_or(|surface| adapter.is_surface_supported(surface)))
+        .collect();
+    let infos: Vec<_> = adapters.iter().map(wgpu::Adapter::get_info).collect();
+    for info in &infos {
+        log::debug!("Found adapter {info:?}");
+    }
+    let index = select_adapter(&infos, power_preference)?;
+    Some(adapters.swap_remove(index))
+}
+
+/// Adapters can not be enumerated on the web, the browser picks one.
+#[cfg(target_arch = "wasm32")]
+async fn request_hardware_adapter(
+    instance: &wgpu::Instance,
+    _backends: Backends,
+    surface: Option<&wgpu::Surface>,
+    power_preference: PowerPreference,
+) -> Option<wgpu::Adapter> {
+    instance
+        .request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference,
+            force_fallback_adapter: false,
+            compatible_surface: surface,
+        })
+        .await
+        .filter(|adapter| adapter.get_info().device_type != DeviceType::Cpu)
+}
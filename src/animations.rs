@@ -0,0 +1,195 @@
+//! Values that ease towards a target over time, e.g. to slide or fade panels.
+//!
+//! While an [`Animated`] value is moving, it requests a repaint for the next frame, so the UI
+//! only redraws continuously while something is actually animating:
+//!
+//! ```no_run
+//! # use egui_tests::animations::{Animated, Easing};
+//! # fn ui(ctx: &egui::Context, open: bool, width: &mut Animated<f32>) {
+//! let width = width.animate(ctx, if open { 240.0 } else { 0.0 });
+//! if width > 0.0 {
+//!     egui::SidePanel::left("sidebar")
+//!         .exact_width(width)
+//!         .show(ctx, |ui| ui.label("Sliding in"));
+//! }
+//! # }
+//! ```
+
+use egui::{Color32, Vec2};
+
+/// Maps the linear progress of an animation in `0.0..=1.0` to the eased progress.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Easing {
+    Linear,
+    EaseInQuad,
+    EaseOutQuad,
+    EaseInOutQuad,
+    EaseInCubic,
+    #[default]
+    EaseOutCubic,
+    EaseInOutCubic,
+    /// Overshoots the target slightly before settling.
+    EaseOutBack,
+}
+
+impl Easing {
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Self::Linear => t,
+            Self::EaseInQuad => t * t,
+            Self::EaseOutQuad => 1.0 - (1.0 - t) * (1.0 - t),
+            Self::EaseInOutQuad => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(2) / 2.0
+                }
+            }
+            Self::EaseInCubic => t * t * t,
+            Self::EaseOutCubic => 1.0 - (1.0 - t).powi(3),
+            Self::EaseInOutCubic => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+            Self::EaseOutBack => {
+                const C1: f32 = 1.70158;
+                const C3: f32 = C1 + 1.0;
+                1.0 + C3 * (t - 1.0).powi(3) + C1 * (t - 1.0).powi(2)
+            }
+        }
+    }
+}
+
+/// Values which can be interpolated linearly.
+pub trait Lerp: Copy + PartialEq {
+    /// `self` at `t == 0.0` and `to` at `t == 1.0`. `t` may leave that range for easings which
+    /// overshoot.
+    fn lerp(self, to: Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    fn lerp(self, to: Self, t: f32) -> Self {
+        self + (to - self) * t
+    }
+}
+
+impl Lerp for Vec2 {
+    fn lerp(self, to: Self, t: f32) -> Self {
+        self + (to - self) * t
+    }
+}
+
+impl Lerp for Color32 {
+    /// Interpolates the premultiplied channels in gamma space.
+    fn lerp(self, to: Self, t: f32) -> Self {
+        let channel = |from: u8, to: u8| f32::from(from).lerp(f32::from(to), t).round() as u8;
+        Color32::from_rgba_premultiplied(
+            channel(self.r(), to.r()),
+            channel(self.g(), to.g()),
+            channel(self.b(), to.b()),
+            channel(self.a(), to.a()),
+        )
+    }
+}
+
+/// A value moving towards its target over a fixed duration, timed by the egui input time.
+#[derive(Debug, Clone)]
+pub struct Animated<T> {
+    from: T,
+    to: T,
+    /// egui time in seconds the current animation started at.
+    start: f64,
+    duration: f32,
+    easing: Easing,
+}
+
+impl<T: Lerp> Animated<T> {
+    /// Starts at rest at `value`, animating changes over 0.2 seconds.
+    pub fn new(value: T) -> Self {
+        Self {
+            from: value,
+            to: value,
+            start: f64::NEG_INFINITY,
+            duration: 0.2,
+            easing: Easing::default(),
+        }
+    }
+
+    /// Duration of an animation in seconds.
+    pub fn with_duration(mut self, seconds: f32) -> Self {
+        self.duration = seconds;
+        self
+    }
+
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Changes the duration, including that of a running animation.
+    pub fn set_duration(&mut self, seconds: f32) {
+        self.duration = seconds;
+    }
+
+    pub fn target(&self) -> T {
+        self.to
+    }
+
+    /// Starts animating from the current value towards `target`, if it changed.
+    pub fn set_target(&mut self, ctx: &egui::Context, target: T) {
+        if target != self.to {
+            let now = ctx.input(|input| input.time);
+            self.from = self.value_at(now);
+            self.to = target;
+            self.start = now;
+        }
+    }
+
+    /// Jumps to `value` without animating.
+    pub fn set_immediate(&mut self, value: T) {
+        self.from = value;
+        self.to = value;
+        self.start = f64::NEG_INFINITY;
+    }
+
+    /// The current value, requesting a repaint while it is still moving.
+    pub fn value(&self, ctx: &egui::Context) -> T {
+        let now = ctx.input(|input| input.time);
+        if self.is_animating_at(now) {
+            ctx.request_repaint();
+        }
+        self.value_at(now)
+    }
+
+    /// Shorthand for [`Self::set_target`] followed by [`Self::value`].
+    pub fn animate(&mut self, ctx: &egui::Context, target: T) -> T {
+        self.set_target(ctx, target);
+        self.value(ctx)
+    }
+
+    pub fn is_animating(&self, ctx: &egui::Context) -> bool {
+        self.is_animating_at(ctx.input(|input| input.time))
+    }
+
+    fn is_animating_at(&self, now: f64) -> bool {
+        now < self.start + f64::from(self.duration)
+    }
+
+    fn value_at(&self, now: f64) -> T {
+        if !self.is_animating_at(now) || self.duration <= 0.0 {
+            return self.to;
+        }
+        let t = ((now - self.start) / f64::from(self.duration)) as f32;
+        self.from.lerp(self.to, self.easing.apply(t))
+    }
+}
+
+impl<T: Lerp + Default> Default for Animated<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
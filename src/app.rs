@@ -0,0 +1,42 @@
+use std::time::Duration;
+
+use crate::{compute::ComputeHook, Frame, SceneRenderer};
+
+/// User-provided state and UI that is plugged into the render loop.
+pub trait App {
+    /// Called after the wgpu device and queue have been created.
+    ///
+    /// Called again if the device is recreated after it was lost, resources of the previous
+    /// device, including textures registered with [`crate::Frame::textures`], have to be
+    /// recreated then. Textures created through egui are restored automatically.
+    fn setup(&mut self, _device: &wgpu::Device, _queue: &wgpu::Queue) {}
+
+    /// Advances the simulation by `step`, if a fixed update rate is set with
+    /// [`crate::AppBuilder::with_fixed_update_rate`]. Runs as many times per frame as steps are
+    /// due, before [`Self::update`].
+    fn fixed_update(&mut self, _step: Duration) {}
+
+    /// Called every frame to build the egui UI.
+    fn update(&mut self, ctx: &egui::Context, frame: &mut Frame);
+
+    /// Called once before the event loop ends, after the persisted state was saved, e.g. to flush
+    /// files or release GPU resources. Work submitted to `queue` is waited for afterwards.
+    fn on_exit(&mut self, _device: &wgpu::Device, _queue: &wgpu::Queue) {}
+
+    /// Called when the window is asked to close, by its close button, the quit shortcut or
+    /// [`crate::Frame::request_close`]. Returning `false`, e.g. with unsaved changes, asks the
+    /// user to confirm first.
+    fn can_close(&mut self) -> bool {
+        true
+    }
+
+    /// Returns the compute hooks to run this frame, before the scene and egui are rendered.
+    fn compute_hooks(&mut self) -> Vec<&mut dyn ComputeHook> {
+        Vec::new()
+    }
+
+    /// Returns the renderer for custom content drawn underneath the egui layer, if any.
+    fn scene_renderer(&mut self) -> Option<&mut dyn SceneRenderer> {
+        None
+    }
+}
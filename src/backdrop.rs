@@ -0,0 +1,139 @@
+//! Effects drawn by the OS behind the transparent parts of the window.
+//!
+//! Supported on Windows and macOS, on other platforms the window stays plainly transparent.
+
+use winit::window::Window;
+
+/// Effect shown behind the transparent egui content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Backdrop {
+    #[default]
+    None,
+    /// Blurs whatever is behind the window. Windows 7 and Windows 10 v1809 or newer.
+    Blur,
+    /// Tinted blur of the fluent design system. Windows 10 v1809 or newer, moving and resizing
+    /// the window may lag on some versions.
+    Acrylic,
+    /// Tinted with the desktop wallpaper. Windows 11 only.
+    Mica,
+    /// An `NSVisualEffectView` with the given material. macOS 10.14 or newer, it can not be
+    /// removed again once applied.
+    Vibrancy(VibrancyMaterial),
+}
+
+/// Semantic materials of the macOS `NSVisualEffectView`, which adapt to the system appearance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VibrancyMaterial {
+    Titlebar,
+    Selection,
+    Menu,
+    Popover,
+    Sidebar,
+    HeaderView,
+    Sheet,
+    #[default]
+    WindowBackground,
+    HudWindow,
+    FullScreenUi,
+    Tooltip,
+    ContentBackground,
+    UnderWindowBackground,
+    UnderPageBackground,
+}
+
+impl VibrancyMaterial {
+    pub const ALL: [VibrancyMaterial; 14] = [
+        VibrancyMaterial::Titlebar,
+        VibrancyMaterial::Selection,
+        VibrancyMaterial::Menu,
+        VibrancyMaterial::Popover,
+        VibrancyMaterial::Sidebar,
+        VibrancyMaterial::HeaderView,
+        VibrancyMaterial::Sheet,
+        VibrancyMaterial::WindowBackground,
+        VibrancyMaterial::HudWindow,
+        VibrancyMaterial::FullScreenUi,
+        VibrancyMaterial::Tooltip,
+        VibrancyMaterial::ContentBackground,
+        VibrancyMaterial::UnderWindowBackground,
+        VibrancyMaterial::UnderPageBackground,
+    ];
+
+    #[cfg(target_os = "macos")]
+    fn to_ns(self) -> window_vibrancy::NSVisualEffectMaterial {
+        use window_vibrancy::NSVisualEffectMaterial as Ns;
+
+        match self {
+            VibrancyMaterial::Titlebar => Ns::Titlebar,
+            VibrancyMaterial::Selection => Ns::Selection,
+            VibrancyMaterial::Menu => Ns::Menu,
+            VibrancyMaterial::Popover => Ns::Popover,
+            VibrancyMaterial::Sidebar => Ns::Sidebar,
+            VibrancyMaterial::HeaderView => Ns::HeaderView,
+            VibrancyMaterial::Sheet => Ns::Sheet,
+            VibrancyMaterial::WindowBackground => Ns::WindowBackground,
+            VibrancyMaterial::HudWindow => Ns::HudWindow,
+            VibrancyMaterial::FullScreenUi => Ns::FullScreenUI,
+            VibrancyMaterial::Tooltip => Ns::Tooltip,
+            VibrancyMaterial::ContentBackground => Ns::ContentBackground,
+            VibrancyMaterial::UnderWindowBackground => Ns::UnderWindowBackground,
+            VibrancyMaterial::UnderPageBackground => Ns::UnderPageBackground,
+        }
+    }
+}
+
+impl Backdrop {
+    /// Removes the `previous` backdrop from `window` and applies this one.
+    pub(crate) fn apply(self, previous: Backdrop, window: &Window) {
+        #[cfg(target_os = "windows")]
+        {
+            let dark = window
+                .theme()
+                .map(|theme| theme == winit::window::Theme::Dark);
+            let cleared = match previous {
+                Backdrop::None | Backdrop::Vibrancy(_) => Ok(()),
+                Backdrop::Blur => window_vibrancy::clear_blur(window),
+                Backdrop::Acrylic => window_vibrancy::clear_acrylic(window),
+                Backdrop::Mica => window_vibrancy::clear_mica(window),
+            };
+            let applied = cleared.and_then(|()| match self {
+                Backdrop::None => Ok(()),
+                Backdrop::Blur => window_vibrancy::apply_blur(window, None),
+                Backdrop::Acrylic => window_vibrancy::apply_acrylic(window, None),
+                Backdrop::Mica => window_vibrancy::apply_mica(window, dark),
+                Backdrop::Vibrancy(_) => Err(window_vibrancy::Error::UnsupportedPlatform(
+                    "vibrancy is only supported on macOS",
+                )),
+            });
+            if let Err(err) = applied {
+                log::warn!("Failed to apply the {self:?} backdrop: {err}");
+            }
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            if let Backdrop::Vibrancy(_) = previous {
+                log::warn!("The previous vibrancy can not be removed");
+            }
+            match self {
+                Backdrop::None => {}
+                Backdrop::Vibrancy(material) => {
+                    if let Err(err) =
+                        window_vibrancy::apply_vibrancy(window, material.to_ns(), None, None)
+                    {
+                        log::warn!("Failed to apply the {self:?} backdrop: {err}");
+                    }
+                }
+                _ => log::warn!("The {self:?} backdrop is only supported on Windows"),
+            }
+        }
+
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        {
+            let _ = (previous, window);
+            if self != Backdrop::None {
+                log::warn!("The {self:?} backdrop is not supported on this platform");
+            }
+        }
+    }
+}
@@ -0,0 +1,306 @@
+use winit::dpi::LogicalSize;
+
+use crate::{
+    graphics::GraphicsConfig, theme::ThemePreference, ui::UiConfig, window::WindowConfig, App,
+};
+
+/// Configures the window and wgpu setup before running an [`App`].
+///
+/// ```no_run
+/// # struct MyApp;
+/// # impl egui_tests::App for MyApp { fn update(&mut self, _ctx: &egui::Context, _frame: &mut egui_tests::Frame) {} }
+/// egui_tests::AppBuilder::new()
+///     .with_title("My app")
+///     .with_inner_size(800.0, 600.0)
+///     .with_present_mode(wgpu::PresentMode::AutoNoVsync)
+///     .run(MyApp);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AppBuilder {
+    window: WindowConfig,
+    graphics: GraphicsConfig,
+    ui: UiConfig,
+}
+
+impl AppBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.window.title = title.into();
+        self
+    }
+
+    pub fn with_transparent(mut self, transparent: bool) -> Self {
+        self.window.transparent = transparent;
+        self
+    }
+
+    /// Disables the title bar and borders drawn by the OS, see [`crate::title_bar`].
+    pub fn with_decorations(mut self, decorations: bool) -> Self {
+        self.window.decorations = decorations;
+        self
+    }
+
+    /// Rounds the corners of the transparent window, see [`crate::Frame::set_corner_radius`].
+    pub fn with_corner_radius(mut self, corner_radius: f32) -> Self {
+        self.window.corner_radius = corner_radius;
+        self
+    }
+
+    /// Draws a blur or acrylic effect behind the transparent window on Windows.
+    pub fn with_backdrop(mut self, backdrop: crate::backdrop::Backdrop) -> Self {
+        self.window.backdrop = backdrop;
+        self
+    }
+
+    /// Draws the content behind a transparent titlebar on macOS, see
+    /// [`crate::Frame::safe_area_insets`].
+    pub fn with_transparent_titlebar(mut self, transparent_titlebar: bool) -> Self {
+        self.window.transparent_titlebar = transparent_titlebar;
+        self
+    }
+
+    /// Sets the icon of the window and its taskbar entry, see [`crate::icon::WindowIcon::from_png`].
+    pub fn with_icon(mut self, icon: crate::icon::WindowIcon) -> Self {
+        self.window.icon = Some(icon);
+        self
+    }
+
+    /// Sets the initial inner size of the window in logical pixels.
+    pub fn with_inner_size(mut self, width: f64, height: f64) -> Self {
+        self.window.inner_size = Some(LogicalSize::new(width, height));
+        self
+    }
+
+    pub fn with_theme(mut self, theme: ThemePreference) -> Self {
+        self.ui.theme = theme;
+        self
+    }
+
+    /// Replaces egui's default fonts, see [`crate::fonts::FontLoader`].
+    pub fn with_fonts(mut self, fonts: impl Into<egui::FontDefinitions>) -> Self {
+        self.ui.fonts = Some(fonts.into());
+        self
+    }
+
+    /// Scales the UI on top of the scale factor of the monitor, overriding the persisted zoom.
+    pub fn with_zoom_factor(mut self, zoom_factor: f32) -> Self {
+        self.ui.zoom_factor = Some(zoom_factor);
+        self
+    }
+
+    /// Limits the memory used by the textures of images loaded by URI, see [`crate::images`].
+    #[cfg(feature = "image-loaders")]
+    pub fn with_image_cache_size(mut self, bytes: usize) -> Self {
+        self.ui.image_cache_size = Some(bytes);
+        self
+    }
+
+    /// Chooses which multi-touch gestures zoom and scroll the UI, all are enabled by default.
+    pub fn with_touch_gestures(mut self, touch_gestures: crate::gestures::TouchGestures) -> Self {
+        self.ui.touch_gestures = touch_gestures;
+        self
+    }
+
+    /// Runs [`crate::App::fixed_update`] `rate` times per second, independent of the frame rate.
+    pub fn with_fixed_update_rate(mut self, rate: Option<u32>) -> Self {
+        self.window.fixed_update_rate = rate;
+        self
+    }
+
+    /// Sets the identifier used to persist state like the window geometry between runs.
+    pub fn with_app_id(mut self, app_id: impl Into<String>) -> Self {
+        self.window.app_id = Some(app_id.into());
+        self
+    }
+
+    /// Turns the window into an always-on-top overlay that lets mouse input pass through
+    /// except where egui widgets are hovered.
+    pub fn with_click_through(mut self, click_through: bool) -> Self {
+        self.window.click_through = click_through;
+        self
+    }
+
+    /// Keeps the window above or below all other windows.
+    pub fn with_window_level(mut self, window_level: winit::window::WindowLevel) -> Self {
+        self.window.window_level = window_level;
+        self
+    }
+
+    /// Starts the window in fullscreen.
+    /// Places the window on a monitor before it is shown, see [`crate::Frame::place_window`].
+    pub fn with_monitor(mut self, placement: crate::monitors::MonitorPlacement) -> Self {
+        self.window.monitor = Some(placement);
+        self
+    }
+
+    pub fn with_fullscreen(mut self, fullscreen: crate::window::FullscreenMode) -> Self {
+        self.window.fullscreen = fullscreen;
+        self
+    }
+
+    /// Caps the redraw rate to `max_fps` frames per second, `None` disables the limit.
+    pub fn with_max_fps(mut self, max_fps: Option<u32>) -> Self {
+        self.window.max_fps = max_fps;
+        self
+    }
+
+    /// Draws frames back to back instead of only when egui requests it, see
+    /// [`crate::RenderMode`].
+    pub fn with_render_mode(mut self, render_mode: crate::RenderMode) -> Self {
+        self.window.render_mode = render_mode;
+        self
+    }
+
+    /// Color the window is cleared with, transparent by default. See
+    /// [`crate::Frame::set_clear_color`].
+    pub fn with_clear_color(mut self, clear_color: egui::Rgba) -> Self {
+        self.window.clear_color = clear_color;
+        self
+    }
+
+    /// Fades the UI in over `seconds` after launch.
+    pub fn with_fade_in(mut self, seconds: f32) -> Self {
+        self.window.fade_in = Some(seconds);
+        self
+    }
+
+    /// Lowers the redraw rate while the window is not focused, see [`crate::UnfocusedPolicy`].
+    pub fn with_unfocused_policy(mut self, unfocused: crate::UnfocusedPolicy) -> Self {
+        self.window.unfocused = unfocused;
+        self
+    }
+
+    /// Registers a system-wide hotkey that hides and shows the window, e.g. to toggle an overlay
+    /// while another application is focused.
+    #[cfg(all(feature = "global-hotkey", not(target_arch = "wasm32")))]
+    pub fn with_visibility_hotkey(mut self, hotkey: crate::hotkeys::HotKey) -> Self {
+        self.window.visibility_hotkey = Some(hotkey);
+        self
+    }
+
+    /// Shows an icon in the system tray with a menu to show, hide and quit the app. Minimizing
+    /// the window hides it in the tray.
+    #[cfg(all(feature = "tray", not(target_arch = "wasm32")))]
+    pub fn with_tray(mut self, tray: bool) -> Self {
+        self.window.tray = tray;
+        self
+    }
+
+    /// Lets gamepads move the keyboard focus and activate widgets, see [`crate::gamepad`].
+    #[cfg(all(feature = "gamepad", not(target_arch = "wasm32")))]
+    pub fn with_gamepad(mut self, gamepad: bool) -> Self {
+        self.window.gamepad = gamepad;
+        self
+    }
+
+    /// Keeps downscaled frames of the last `duration` in memory, which can be exported as GIF
+    /// with [`crate::Frame::save_replay`] (Ctrl+Shift+F12 by default).
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_replay_buffer(mut self, duration: std::time::Duration) -> Self {
+        self.window.replay_duration = Some(duration);
+        self
+    }
+
+    /// Loads settings from a TOML file, which are applied again whenever it changes, see
+    /// [`crate::config`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_config_file(mut self, path: impl Into<std::path::PathBuf>) -> Self {
+        self.window.config_path = Some(path.into());
+        self
+    }
+
+    /// Runs in the activity passed to `android_main`, which is required on Android:
+    ///
+    /// ```ignore
+    /// #[no_mangle]
+    /// fn android_main(app: egui_tests::AndroidApp) {
+    ///     egui_tests::AppBuilder::new().with_android_app(app).run(MyApp::default());
+    /// }
+    /// ```
+    #[cfg(target_os = "android")]
+    pub fn with_android_app(mut self, app: crate::AndroidApp) -> Self {
+        self.window.android_app = Some(app);
+        self
+    }
+
+    /// Applies the options given on the command line, see [`crate::cli`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_cli_args(mut self, args: &crate::cli::CliArgs) -> Self {
+        if let Some(backends) = args.backend {
+            self = self.with_backends(backends);
+        }
+        if let Some(vsync) = args.vsync {
+            self = self.with_present_mode(vsync.present_mode());
+        }
+        if let Some(scale) = args.scale {
+            self = self.with_zoom_factor(scale);
+        }
+        if args.fullscreen {
+            self = self.with_fullscreen(crate::window::FullscreenMode::Borderless);
+        }
+        if let Some(transparent) = args.transparent {
+            self = self.with_transparent(transparent);
+        }
+        if let Some(monitor) = args.monitor {
+            self = self.with_monitor(crate::monitors::MonitorPlacement::Center(monitor));
+        }
+        self
+    }
+
+    pub fn with_backends(mut self, backends: wgpu::Backends) -> Self {
+        self.graphics.backends = backends;
+        self
+    }
+
+    pub fn with_power_preference(mut self, power_preference: wgpu::PowerPreference) -> Self {
+        self.graphics.power_preference = power_preference;
+        self
+    }
+
+    pub fn with_present_mode(mut self, present_mode: wgpu::PresentMode) -> Self {
+        self.graphics.present_mode = present_mode;
+        self
+    }
+
+    /// Enables multisample anti-aliasing with the given sample count (e.g. 2, 4 or 8).
+    ///
+    /// Falls back to no MSAA if the count is not supported by the adapter.
+    pub fn with_msaa_samples(mut self, msaa_samples: u32) -> Self {
+        self.graphics.msaa_samples = msaa_samples;
+        self
+    }
+
+    /// Chooses between an sRGB (`true`, the default) and a linear framebuffer.
+    pub fn with_gamma_correction(mut self, gamma_correction: bool) -> Self {
+        self.graphics.gamma_correction = gamma_correction;
+        self
+    }
+
+    /// Creates a depth buffer in `format` for the scene, see [`crate::SceneFrame::depth_view`].
+    pub fn with_depth_buffer(mut self, format: Option<wgpu::TextureFormat>) -> Self {
+        self.graphics.depth_format = format;
+        self
+    }
+
+    /// Renders the scene in HDR and tone maps it before egui is drawn, see
+    /// [`crate::Frame::hdr_settings_mut`]. Falls back to SDR if the adapter does not support it.
+    pub fn with_hdr(mut self, hdr: bool) -> Self {
+        self.graphics.hdr = hdr;
+        self
+    }
+
+    /// Requests the downlevel limits and disables MSAA for GLES3-only devices, on by default with
+    /// the `gles` feature.
+    pub fn with_downlevel(mut self, downlevel: bool) -> Self {
+        self.graphics.downlevel = downlevel;
+        self
+    }
+
+    /// Creates the window and runs `app` until the window is closed.
+    pub fn run(self, app: impl App + 'static) {
+        crate::window::run(self.window, self.graphics, self.ui, app);
+    }
+}
@@ -0,0 +1,66 @@
+//! Command line options overriding the startup configuration of the [`crate::AppBuilder`].
+//!
+//! ```no_run
+//! use clap::Parser;
+//! use egui_tests::cli::CliArgs;
+//!
+//! let args = CliArgs::parse();
+//! let builder = egui_tests::AppBuilder::new().with_cli_args(&args);
+//! ```
+//!
+//! Apps with their own options can embed them with `#[command(flatten)]`.
+
+use clap::{Parser, ValueEnum};
+use log::LevelFilter;
+use wgpu::{Backends, PresentMode};
+
+use crate::adapter::parse_backends;
+
+/// Vertical synchronization of the presented frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Vsync {
+    On,
+    Off,
+    /// Presents the newest frame at the next vertical blank without blocking, if supported.
+    Mailbox,
+}
+
+impl Vsync {
+    pub fn present_mode(self) -> PresentMode {
+        match self {
+            Self::On => PresentMode::AutoVsync,
+            Self::Off => PresentMode::AutoNoVsync,
+            Self::Mailbox => PresentMode::Mailbox,
+        }
+    }
+}
+
+/// Startup options, unset options keep the configuration of the builder.
+#[derive(Debug, Clone, Default, PartialEq, Parser)]
+pub struct CliArgs {
+    /// Graphics backends to use, a comma separated list of vulkan, dx12, dx11, metal and gl.
+    #[arg(long, value_parser = parse_backend_arg)]
+    pub backend: Option<Backends>,
+    #[arg(long, value_enum)]
+    pub vsync: Option<Vsync>,
+    /// Scale of the UI, on top of the scale factor of the monitor.
+    #[arg(long)]
+    pub scale: Option<f32>,
+    /// Starts in borderless fullscreen.
+    #[arg(long)]
+    pub fullscreen: bool,
+    /// Whether the window background is transparent.
+    #[arg(long)]
+    pub transparent: Option<bool>,
+    /// Index of the monitor to center the window on.
+    #[arg(long)]
+    pub monitor: Option<usize>,
+    /// Maximum level of log messages, one of off, error, warn, info, debug and trace.
+    // Not applied by the builder, the app passes it to `log_console::init`
+    #[arg(long)]
+    pub log_level: Option<LevelFilter>,
+}
+
+fn parse_backend_arg(names: &str) -> Result<Backends, String> {
+    parse_backends(names).ok_or_else(|| format!("unknown backend in {names:?}"))
+}
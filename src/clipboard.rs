@@ -0,0 +1,90 @@
+use egui::ColorImage;
+
+/// Images larger than this in either dimension are scaled down when pasted.
+pub const MAX_IMAGE_SIZE: u32 = 2048;
+
+/// Reads images from the OS clipboard.
+///
+/// Text is already handled by egui-winit, which turns the paste shortcut into
+/// [`egui::Event::Paste`] if the clipboard contains text.
+pub struct Clipboard {
+    #[cfg(not(any(target_arch = "wasm32", target_os = "android", target_os = "ios")))]
+    inner: Option<arboard::Clipboard>,
+}
+
+impl Clipboard {
+    pub fn new() -> Self {
+        #[cfg(not(any(target_arch = "wasm32", target_os = "android", target_os = "ios")))]
+        {
+            let inner = arboard::Clipboard::new()
+                .map_err(|err| log::warn!("Failed to access the clipboard: {err}"))
+                .ok();
+            Self { inner }
+        }
+
+        #[cfg(any(target_arch = "wasm32", target_os = "android", target_os = "ios"))]
+        Self {}
+    }
+
+    /// Whether the paste shortcut (e.g. Ctrl+V) was pressed this frame.
+    pub fn paste_pressed(ctx: &egui::Context) -> bool {
+        ctx.input(|i| i.modifiers.command && i.key_pressed(egui::Key::V))
+    }
+
+    /// The image currently in the clipboard, scaled down to at most [`MAX_IMAGE_SIZE`].
+    #[cfg(not(any(target_arch = "wasm32", target_os = "android", target_os = "ios")))]
+    pub fn image(&mut self) -> Option<ColorImage> {
+        let image = match self.inner.as_mut()?.get_image() {
+            Ok(image) => image,
+            Err(arboard::Error::ContentNotAvailable) => return None,
+            Err(err) => {
+                log::warn!("Failed to read an image from the clipboard: {err}");
+                return None;
+            }
+        };
+        rgba_to_color_image(image.width, image.height, &image.bytes, MAX_IMAGE_SIZE)
+    }
+
+    #[cfg(any(target_arch = "wasm32", target_os = "android", target_os = "ios"))]
+    pub fn image(&mut self) -> Option<ColorImage> {
+        None
+    }
+
+    /// Loads the clipboard image into a texture named `name`.
+    pub fn paste_texture(
+        &mut self,
+        ctx: &egui::Context,
+        name: &str,
+    ) -> Option<egui::TextureHandle> {
+        let image = self.image()?;
+        Some(ctx.load_texture(name, image, egui::TextureOptions::LINEAR))
+    }
+}
+
+impl Default for Clipboard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Converts tightly packed, unmultiplied RGBA8 pixels into an egui image, scaling it down to fit
+/// into `max_size` while keeping the aspect ratio.
+///
+/// Returns `None` if `rgba` does not match the dimensions.
+pub fn rgba_to_color_image(
+    width: usize,
+    height: usize,
+    rgba: &[u8],
+    max_size: u32,
+) -> Option<ColorImage> {
+    let image = image::RgbaImage::from_raw(width as u32, height as u32, rgba.to_vec())?;
+    let image = if image.width() > max_size || image.height() > max_size {
+        image::DynamicImage::ImageRgba8(image)
+            .resize(max_size, max_size, image::imageops::FilterType::Triangle)
+            .to_rgba8()
+    } else {
+        image
+    };
+    let size = [image.width() as usize, image.height() as usize];
+    Some(ColorImage::from_rgba_unmultiplied(size, image.as_raw()))
+}
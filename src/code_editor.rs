@@ -0,0 +1,135 @@
+//! A multiline code editor with syntax highlighting and line numbers, e.g. for editing scripts.
+
+use std::hash::Hash;
+
+use egui::{Id, Response, ScrollArea, TextEdit, TextStyle, Ui, Widget};
+use egui_extras::syntax_highlighting::{self, CodeTheme};
+
+/// Edits `code` as a language known to syntect, highlighted in the [`CodeTheme`] stored in egui's
+/// memory, which follows dark and light mode unless set with [`CodeTheme::store_in_memory`].
+///
+/// Long lines scroll horizontally instead of wrapping. The returned response is the one of the
+/// text edit, e.g. to check whether the code `changed()`.
+///
+/// ```no_run
+/// # fn ui(ui: &mut egui::Ui, script: &mut String) {
+/// ui.add(egui_tests::code_editor::CodeEditor::new(script, "rs").desired_rows(20));
+/// # }
+/// ```
+pub struct CodeEditor<'a> {
+    code: &'a mut String,
+    language: String,
+    id: Option<Id>,
+    line_numbers: bool,
+    desired_rows: usize,
+    max_height: f32,
+}
+
+impl<'a> CodeEditor<'a> {
+    /// `language` is a file extension or name of a syntax, e.g. `"rs"` or `"Python"`.
+    pub fn new(code: &'a mut String, language: impl Into<String>) -> Self {
+        Self {
+            code,
+            language: language.into(),
+            id: None,
+            line_numbers: true,
+            desired_rows: 10,
+            max_height: f32::INFINITY,
+        }
+    }
+
+    /// Needed to tell apart several editors in the same [`Ui`].
+    pub fn id_source(mut self, id_source: impl Hash) -> Self {
+        self.id = Some(Id::new(id_source));
+        self
+    }
+
+    pub fn line_numbers(mut self, line_numbers: bool) -> Self {
+        self.line_numbers = line_numbers;
+        self
+    }
+
+    /// Minimum height in lines.
+    pub fn desired_rows(mut self, desired_rows: usize) -> Self {
+        self.desired_rows = desired_rows;
+        self
+    }
+
+    /// Scrolls vertically beyond this height.
+    pub fn max_height(mut self, max_height: f32) -> Self {
+        self.max_height = max_height;
+        self
+    }
+}
+
+impl Widget for CodeEditor<'_> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let Self {
+            code,
+            language,
+            id,
+            line_numbers,
+            desired_rows,
+            max_height,
+        } = self;
+        let id = id.map_or_else(|| ui.next_auto_id(), |id| ui.make_persistent_id(id));
+        let theme = CodeTheme::from_memory(ui.ctx());
+        let mut layouter = |ui: &Ui, code: &str, _wrap_width: f32| {
+            let mut job = syntax_highlighting::highlight(ui.ctx(), &theme, code, &language);
+            // Scrolls horizontally instead
+            job.wrap.max_width = f32::INFINITY;
+            ui.fonts(|fonts| fonts.layout_job(job))
+        };
+
+        ScrollArea::vertical()
+            .id_source(id.with("vertical"))
+            .max_height(max_height)
+            .show(ui, |ui| {
+                ui.horizontal_top(|ui| {
+                    ui.spacing_mut().item_spacing.x = 0.0;
+                    if line_numbers {
+                        let lines = code.split('\n').count();
+                        let numbers = (1..=lines)
+                            .map(|line| format!("{line:>3}"))
+                            .collect::<Vec<_>>()
+                            .join("\n");
+                        let width = ui.fonts(|fonts| {
+                            fonts
+                                .layout_no_wrap(
+                                    numbers.lines().last().unwrap_or_default().to_owned(),
+                                    TextStyle::Monospace.resolve(ui.style()),
+                                    Default::default(),
+                                )
+                                .size()
+                                .x
+                        });
+                        ui.add(
+                            TextEdit::multiline(&mut numbers.as_str())
+                                .id(id.with("line_numbers"))
+                                .font(TextStyle::Monospace)
+                                .interactive(false)
+                                .frame(false)
+                                .desired_rows(desired_rows)
+                                .desired_width(width),
+                        );
+                    }
+                    let width = ui.available_width();
+                    ScrollArea::horizontal()
+                        .id_source(id.with("horizontal"))
+                        .show(ui, |ui| {
+                            ui.add(
+                                TextEdit::multiline(code)
+                                    .id(id)
+                                    .code_editor()
+                                    .desired_rows(desired_rows)
+                                    .desired_width(width)
+                                    .layouter(&mut layouter),
+                            )
+                        })
+                        .inner
+                })
+                .inner
+            })
+            .inner
+    }
+}
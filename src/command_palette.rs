@@ -0,0 +1,219 @@
+//! A searchable list of the actions registered in [`Shortcuts`], opened with
+//! [`Action::CommandPalette`] (Ctrl+Shift+P by default).
+//!
+//! Apps and plugins add commands by registering a custom action, a shortcut is optional:
+//!
+//! ```no_run
+//! # fn update(frame: &mut egui_tests::Frame) {
+//! use egui_tests::shortcuts::Action;
+//!
+//! let new_file = Action::Custom("new_file".to_owned());
+//! frame.shortcuts_mut().register(new_file.clone(), "New file", None);
+//! if frame.action_triggered(&new_file) {
+//!     // ...
+//! }
+//! # }
+//! ```
+//!
+//! Commands run from the palette are ranked higher the next time it opens, persisted in the egui
+//! memory if an app id is set with [`crate::AppBuilder::with_app_id`].
+
+use egui::{Key, Modifiers};
+
+use crate::{
+    shortcuts::{Action, Shortcuts},
+    Frame,
+};
+
+/// How many recently run commands are ranked higher.
+const MAX_RECENT: usize = 8;
+/// Most commands shown at once.
+const MAX_RESULTS: usize = 12;
+
+/// Scores how well `query` matches `text`, ignoring case and whitespace in the query.
+///
+/// All characters of the query have to appear in `text` in the same order. Consecutive
+/// characters and characters at the start of a word score higher. An empty query matches
+/// everything with a score of `0`.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<u32> {
+    let text: Vec<char> = text.chars().flat_map(char::to_lowercase).collect();
+    let mut position = 0;
+    let mut previous = None;
+    let mut score = 0;
+    for query_char in query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+    {
+        let index = position + text[position..].iter().position(|&c| c == query_char)?;
+        score += 1;
+        if previous.is_some_and(|previous| previous + 1 == index) {
+            score += 4;
+        }
+        if index == 0 || !text[index - 1].is_alphanumeric() {
+            score += 8;
+        }
+        previous = Some(index);
+        position = index + 1;
+    }
+    Some(score)
+}
+
+/// The command palette, drawn after [`crate::App::update`].
+#[derive(Debug, Default)]
+pub struct CommandPalette {
+    visible: bool,
+    query: String,
+    selected: usize,
+    /// Ids of the commands run from the palette, most recent first.
+    recent: Vec<String>,
+    /// Whether [`Self::recent`] was read from the egui memory.
+    loaded: bool,
+}
+
+impl CommandPalette {
+    pub fn visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Opens the palette with an empty query, or closes it.
+    pub fn set_visible(&mut self, visible: bool) {
+        if visible && !self.visible {
+            self.query.clear();
+            self.selected = 0;
+        }
+        self.visible = visible;
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn set_query(&mut self, query: impl Into<String>) {
+        self.query = query.into();
+        self.selected = 0;
+    }
+
+    /// Ids of the recently run commands, see [`Action::id`], most recent first.
+    pub fn recent(&self) -> &[String] {
+        &self.recent
+    }
+
+    /// Ranks `action` first among the recent commands.
+    pub fn record(&mut self, action: &Action) {
+        self.recent.retain(|id| id != action.id());
+        self.recent.insert(0, action.id().to_owned());
+        self.recent.truncate(MAX_RECENT);
+    }
+
+    /// The commands matching the query, best match first. Recently run commands rank higher,
+    /// ties keep the order of registration.
+    pub fn matches<'a>(&self, shortcuts: &'a Shortcuts) -> Vec<&'a Action> {
+        let mut matches: Vec<_> = shortcuts
+            .commands()
+            .filter(|(action, _)| **action != Action::CommandPalette)
+            .filter_map(|(action, name)| {
+                let score = fuzzy_score(&self.query, name)?;
+                let recency = self
+                    .recent
+                    .iter()
+                    .position(|id| id == action.id())
+                    .map_or(0, |index| (MAX_RECENT - index) as u32 * 2);
+                Some((action, score + recency))
+            })
+            .collect();
+        matches.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+        matches.into_iter().map(|(action, _)| action).collect()
+    }
+
+    pub(crate) fn show(frame: &mut Frame, ctx: &egui::Context) {
+        let id = egui::Id::new("egui_tests::command_palette");
+        let palette = &mut frame.command_palette;
+        if !palette.loaded {
+            palette.loaded = true;
+            if let Some(recent) = ctx.data_mut(|data| data.get_persisted(id)) {
+                palette.recent = recent;
+            }
+        }
+        if !palette.visible {
+            return;
+        }
+
+        let (up, down, enter, escape) = ctx.input_mut(|input| {
+            (
+                input.consume_key(Modifiers::NONE, Key::ArrowUp),
+                input.consume_key(Modifiers::NONE, Key::ArrowDown),
+                input.consume_key(Modifiers::NONE, Key::Enter),
+                input.consume_key(Modifiers::NONE, Key::Escape),
+            )
+        });
+        let matches: Vec<Action> = palette
+            .matches(&frame.shortcuts)
+            .into_iter()
+            .take(MAX_RESULTS)
+            .cloned()
+            .collect();
+        if down {
+            palette.selected += 1;
+        }
+        if up {
+            palette.selected = palette.selected.saturating_sub(1);
+        }
+        palette.selected = palette.selected.min(matches.len().saturating_sub(1));
+
+        let mut run = enter
+            .then(|| matches.get(palette.selected).cloned())
+            .flatten();
+        let response = egui::Window::new("Command palette")
+            .id(id)
+            .title_bar(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_TOP, [0.0, 48.0])
+            .fixed_size([360.0, 0.0])
+            .show(ctx, |ui| {
+                let edit = ui.add(
+                    egui::TextEdit::singleline(&mut palette.query)
+                        .hint_text("Type a command…")
+                        .desired_width(f32::INFINITY),
+                );
+                edit.request_focus();
+                if edit.changed() {
+                    palette.selected = 0;
+                }
+                ui.separator();
+                if matches.is_empty() {
+                    ui.weak("No matching commands");
+                }
+                for (index, action) in matches.iter().enumerate() {
+                    let name = frame.shortcuts.name(action).unwrap_or(action.id());
+                    let response = ui
+                        .horizontal(|ui| {
+                            let response = ui.selectable_label(index == palette.selected, name);
+                            if let Some(shortcut) = frame.shortcuts.shortcut(action) {
+                                ui.with_layout(
+                                    egui::Layout::right_to_left(egui::Align::Center),
+                                    |ui| ui.weak(ctx.format_shortcut(&shortcut)),
+                                );
+                            }
+                            response
+                        })
+                        .inner;
+                    if response.clicked() {
+                        run = Some(action.clone());
+                    }
+                }
+            });
+
+        let clicked_outside = response.is_some_and(|response| {
+            response.response.clicked_elsewhere() && ctx.input(|input| input.pointer.any_pressed())
+        });
+        if let Some(action) = run {
+            palette.record(&action);
+            ctx.data_mut(|data| data.insert_persisted(id, palette.recent.clone()));
+            palette.visible = false;
+            frame.trigger_action(action);
+        } else if escape || clicked_outside {
+            palette.visible = false;
+        }
+    }
+}
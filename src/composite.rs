@@ -0,0 +1,214 @@
+//! The final pass copying the frame of a transparent window to its surface.
+
+use crate::uploads::Uploads;
+
+/// Output is converted to straight alpha for compositors which do not expect premultiplied alpha.
+const STRAIGHT_ALPHA: u32 = 1;
+/// The frame is premultiplied in linear space, the compositor expects it premultiplied in gamma
+/// space. Otherwise anti-aliased edges get a dark or bright fringe.
+const PREMULTIPLY_IN_GAMMA: u32 = 2;
+
+/// Whether the frame has to go through a `Compositor` for the compositor of the OS to blend it
+/// correctly with what is behind the window.
+pub fn needs_alpha_conversion(
+    alpha_mode: wgpu::CompositeAlphaMode,
+    format: wgpu::TextureFormat,
+) -> bool {
+    alpha_flags(alpha_mode, format) != 0
+}
+
+fn alpha_flags(alpha_mode: wgpu::CompositeAlphaMode, format: wgpu::TextureFormat) -> u32 {
+    match alpha_mode {
+        wgpu::CompositeAlphaMode::PostMultiplied => STRAIGHT_ALPHA,
+        wgpu::CompositeAlphaMode::PreMultiplied if format.is_srgb() => PREMULTIPLY_IN_GAMMA,
+        _ => 0,
+    }
+}
+
+/// Rounds the corners of a transparent window and converts its alpha to what the compositor of
+/// the OS expects.
+///
+/// The frame is rendered into an offscreen texture first, which is then copied to the surface
+/// with the corners masked out. The copy replaces the surface contents instead of blending, so
+/// the alpha written is exactly the converted one. winit offers no way to change the input
+/// region of a window, so the cut-off corners still receive mouse input.
+pub(crate) struct Compositor {
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    params: wgpu::Buffer,
+    target: Option<OffscreenTarget>,
+}
+
+struct OffscreenTarget {
+    view: wgpu::TextureView,
+    bind_group: wgpu::BindGroup,
+    size: [u32; 2],
+}
+
+impl Compositor {
+    pub fn new(device: &wgpu::Device, format: wgpu::TextureFormat) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("composite"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/composite.wgsl").into()),
+        });
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("composite"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("composite"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("composite"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(format.into())],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+        });
+        let params = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("composite params"),
+            size: 16,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            pipeline,
+            bind_group_layout,
+            params,
+            target: None,
+        }
+    }
+
+    /// The view to render the frame into, recreated if the size changed.
+    pub fn target_view(
+        &mut self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        size: [u32; 2],
+    ) -> &wgpu::TextureView {
+        if !matches!(&self.target, Some(target) if target.size == size) {
+            self.target = Some(self.create_target(device, format, size));
+        }
+        &self.target.as_ref().unwrap().view
+    }
+
+    /// Copies the frame rendered into [`Self::target_view`] to `view` with rounded corners of
+    /// `radius` pixels, `0.0` keeping them square. `format` is the one the frame was rendered
+    /// with.
+    #[allow(clippy::too_many_arguments)]
+    pub fn apply(
+        &self,
+        device: &wgpu::Device,
+        uploads: &mut Uploads,
+        encoder: &mut wgpu::CommandEncoder,
+        view: &wgpu::TextureView,
+        radius: f32,
+        alpha_mode: wgpu::CompositeAlphaMode,
+        format: wgpu::TextureFormat,
+    ) {
+        let Some(target) = &self.target else {
+            return;
+        };
+        let mut params: Vec<u8> = [target.size[0] as f32, target.size[1] as f32, radius]
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect();
+        params.extend(alpha_flags(alpha_mode, format).to_le_bytes());
+        uploads.write(device, encoder, &self.params, 0, &params);
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("composite"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &target.bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+
+    fn create_target(
+        &self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        size: [u32; 2],
+    ) -> OffscreenTarget {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("composite frame"),
+            size: wgpu::Extent3d {
+                width: size[0],
+                height: size[1],
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("composite"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: self.params.as_entire_binding(),
+                },
+            ],
+        });
+        OffscreenTarget {
+            view,
+            bind_group,
+            size,
+        }
+    }
+}
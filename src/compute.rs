@@ -0,0 +1,145 @@
+use std::time::Duration;
+
+use crate::uploads::Uploads;
+
+/// Values shared with every compute hook through the globals bind group.
+///
+/// Declared in WGSL by [`FrameUniforms::WGSL`] and bound at binding 0 of
+/// [`ComputeFrame::globals`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FrameUniforms {
+    /// Seconds since the first frame.
+    pub time: f32,
+    /// Seconds since the previous frame.
+    pub delta_time: f32,
+    /// Size of the surface in physical pixels.
+    pub resolution: [f32; 2],
+}
+
+impl FrameUniforms {
+    /// WGSL declaration matching the layout of the uniform buffer, to be pasted into shaders:
+    ///
+    /// ```wgsl
+    /// @group(0) @binding(0) var<uniform> frame: FrameUniforms;
+    /// ```
+    pub const WGSL: &'static str =
+        "struct FrameUniforms {\n    time: f32,\n    delta_time: f32,\n    resolution: vec2<f32>,\n}\n";
+
+    const SIZE: u64 = 16;
+
+    fn to_bytes(self) -> [u8; Self::SIZE as usize] {
+        let mut bytes = [0; Self::SIZE as usize];
+        let values = [
+            self.time,
+            self.delta_time,
+            self.resolution[0],
+            self.resolution[1],
+        ];
+        for (chunk, value) in bytes.chunks_exact_mut(4).zip(values) {
+            chunk.copy_from_slice(&value.to_le_bytes());
+        }
+        bytes
+    }
+}
+
+/// Everything a [`ComputeHook`] needs to record its compute passes for one frame.
+pub struct ComputeFrame<'a> {
+    pub device: &'a wgpu::Device,
+    pub queue: &'a wgpu::Queue,
+    /// Encoder of the frame. Passes recorded here run before the scene and egui are drawn.
+    pub encoder: &'a mut wgpu::CommandEncoder,
+    /// Bind group holding the [`FrameUniforms`] buffer at binding 0.
+    pub globals: &'a wgpu::BindGroup,
+    /// Layout of [`Self::globals`], to create pipeline layouts with.
+    pub globals_layout: &'a wgpu::BindGroupLayout,
+    pub uniforms: FrameUniforms,
+}
+
+/// Runs compute passes every frame before the render pass, e.g. to simulate particles that
+/// the [`crate::SceneRenderer`] then draws underneath the egui layer.
+pub trait ComputeHook {
+    fn compute(&mut self, frame: &mut ComputeFrame);
+}
+
+/// Owns the per-frame uniforms and runs the compute hooks of the app.
+pub(crate) struct ComputeStage {
+    buffer: wgpu::Buffer,
+    layout: wgpu::BindGroupLayout,
+    bind_group: wgpu::BindGroup,
+    elapsed: Duration,
+}
+
+impl ComputeStage {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("frame uniforms"),
+            size: FrameUniforms::SIZE,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("frame uniforms"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: wgpu::BufferSize::new(FrameUniforms::SIZE),
+                },
+                count: None,
+            }],
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("frame uniforms"),
+            layout: &layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: buffer.as_entire_binding(),
+            }],
+        });
+
+        Self {
+            buffer,
+            layout,
+            bind_group,
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    /// Uploads the uniforms of this frame and lets every hook record its passes.
+    #[allow(clippy::too_many_arguments)]
+    pub fn run(
+        &mut self,
+        hooks: Vec<&mut dyn ComputeHook>,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        uploads: &mut Uploads,
+        encoder: &mut wgpu::CommandEncoder,
+        frame_time: Duration,
+        size_in_pixels: [u32; 2],
+    ) {
+        self.elapsed += frame_time;
+        if hooks.is_empty() {
+            return;
+        }
+
+        let uniforms = FrameUniforms {
+            time: self.elapsed.as_secs_f32(),
+            delta_time: frame_time.as_secs_f32(),
+            resolution: [size_in_pixels[0] as f32, size_in_pixels[1] as f32],
+        };
+        uploads.write(device, encoder, &self.buffer, 0, &uniforms.to_bytes());
+
+        for hook in hooks {
+            hook.compute(&mut ComputeFrame {
+                device,
+                queue,
+                encoder,
+                globals: &self.bind_group,
+                globals_layout: &self.layout,
+                uniforms,
+            });
+        }
+    }
+}
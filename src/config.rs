@@ -0,0 +1,221 @@
+//! Settings loaded from a TOML file, e.g. `app.toml`, which are applied again whenever the file
+//! changes.
+//!
+//! ```toml
+//! theme = "Dark"
+//! max_fps = 60
+//!
+//! [window]
+//! decorations = false
+//! always_on_top = true
+//!
+//! [keybindings]
+//! quit = "Ctrl+Q"
+//! toggle_fullscreen = "F11"
+//! screenshot = ""
+//! ```
+//!
+//! Settings missing from the file keep the configuration of the [`crate::AppBuilder`] or the
+//! changes made at runtime. Only the settings which changed are applied on a reload.
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use web_time::{Instant, SystemTime};
+use winit::window::WindowLevel;
+
+use crate::{
+    shortcuts::{parse_shortcut, Action, Shortcuts},
+    theme::ThemePreference,
+    window::FullscreenMode,
+    Frame,
+};
+
+/// How often the modification time of the file is checked.
+pub(crate) const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Contents of the config file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<ThemePreference>,
+    /// Caps the redraw rate, `0` removes the limit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_fps: Option<u32>,
+    pub window: WindowOptions,
+    /// Shortcuts by action, see [`Action::id`] and [`crate::shortcuts::parse_shortcut`]. An
+    /// empty string removes the shortcut, actions not listed keep their default.
+    pub keybindings: BTreeMap<String, String>,
+}
+
+/// The `[window]` table of the config file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decorations: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub always_on_top: Option<bool>,
+    /// Borderless fullscreen.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fullscreen: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub click_through: Option<bool>,
+}
+
+impl AppConfig {
+    pub fn from_toml(text: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(text)
+    }
+
+    pub fn to_toml(&self) -> String {
+        toml::to_string_pretty(self).expect("The config only contains TOML compatible types")
+    }
+
+    /// Applies the settings which differ from `previous`, or all settings without one.
+    pub fn apply(&self, previous: Option<&AppConfig>, frame: &mut Frame) {
+        let unset = AppConfig::default();
+        let previous = previous.unwrap_or(&unset);
+
+        if let Some(theme) = changed(self.theme, previous.theme) {
+            frame.set_theme_preference(theme);
+        }
+        if let Some(max_fps) = changed(self.max_fps, previous.max_fps) {
+            frame.set_max_fps((max_fps > 0).then_some(max_fps));
+        }
+
+        let (window, previous_window) = (&self.window, &previous.window);
+        if let Some(decorations) = changed(window.decorations, previous_window.decorations) {
+            frame.set_decorations(decorations);
+        }
+        if let Some(always_on_top) = changed(window.always_on_top, previous_window.always_on_top) {
+            frame.set_window_level(if always_on_top {
+                WindowLevel::AlwaysOnTop
+            } else {
+                WindowLevel::Normal
+            });
+        }
+        if let Some(fullscreen) = changed(window.fullscreen, previous_window.fullscreen) {
+            frame.set_fullscreen_mode(if fullscreen {
+                FullscreenMode::Borderless
+            } else {
+                FullscreenMode::Windowed
+            });
+        }
+        if let Some(click_through) = changed(window.click_through, previous_window.click_through) {
+            frame.set_click_through(click_through);
+        }
+
+        if self.keybindings != previous.keybindings {
+            self.apply_keybindings(frame.shortcuts_mut());
+        }
+    }
+
+    /// Resets `shortcuts` and applies the keybindings. Actions have to be registered first.
+    pub fn apply_keybindings(&self, shortcuts: &mut Shortcuts) {
+        shortcuts.reset();
+        for (action, shortcut) in &self.keybindings {
+            let shortcut = if shortcut.is_empty() {
+                None
+            } else if let Some(shortcut) = parse_shortcut(shortcut) {
+                Some(shortcut)
+            } else {
+                log::warn!("Ignoring invalid shortcut {shortcut:?} for {action}");
+                continue;
+            };
+            shortcuts.set_shortcut(&Action::from_id(action), shortcut);
+        }
+    }
+}
+
+/// The config file, reloaded when its modification time changes.
+#[derive(Debug)]
+pub struct ConfigFile {
+    path: PathBuf,
+    config: AppConfig,
+    modified: Option<SystemTime>,
+    last_poll: Instant,
+}
+
+impl ConfigFile {
+    /// Loads the config from `path`, using the defaults if it does not exist or is invalid.
+    pub fn load(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let modified = modified(&path);
+        let config = read(&path).unwrap_or_default();
+        Self {
+            path,
+            config,
+            modified,
+            last_poll: Instant::now(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn config(&self) -> &AppConfig {
+        &self.config
+    }
+
+    /// Rereads the file if it changed since it was last read, at most once per second.
+    ///
+    /// Returns the previous config if the file was reloaded. An invalid file keeps the
+    /// current config.
+    pub fn poll(&mut self) -> Option<AppConfig> {
+        if self.last_poll.elapsed() < POLL_INTERVAL {
+            return None;
+        }
+        self.last_poll = Instant::now();
+
+        let modified = modified(&self.path);
+        if modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+        let config = read(&self.path)?;
+        log::info!("Reloaded {}", self.path.display());
+        Some(std::mem::replace(&mut self.config, config))
+    }
+
+    /// Writes `config` to the file, e.g. after it was changed in the UI.
+    pub fn save(&mut self, config: AppConfig) -> std::io::Result<()> {
+        std::fs::write(&self.path, config.to_toml())?;
+        self.modified = modified(&self.path);
+        self.config = config;
+        Ok(())
+    }
+}
+
+/// `current` if it is set and differs from `previous`.
+fn changed<T: PartialEq>(current: Option<T>, previous: Option<T>) -> Option<T> {
+    if current != previous {
+        current
+    } else {
+        None
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn read(path: &Path) -> Option<AppConfig> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return None,
+        Err(err) => {
+            log::warn!("Failed to read {}: {err}", path.display());
+            return None;
+        }
+    };
+    AppConfig::from_toml(&text)
+        .map_err(|err| log::warn!("Invalid config in {}: {err}", path.display()))
+        .ok()
+}
@@ -0,0 +1,389 @@
+use std::{collections::VecDeque, sync::mpsc, time::Duration};
+
+use egui::{Align2, Color32, Order, Pos2, Sense, Stroke};
+
+use crate::gpu_memory::{AllocationStats, GpuMemoryStats};
+
+/// Number of frames kept in the history.
+const HISTORY_LEN: usize = 240;
+
+/// Frame time the plot is scaled to at least, so a steady 60 FPS does not fill it completely.
+const MIN_PLOT_RANGE: Duration = Duration::from_millis(33);
+
+const FRAME_TIME_COLOR: Color32 = Color32::from_rgb(100, 200, 100);
+const CPU_TIME_COLOR: Color32 = Color32::from_rgb(100, 150, 250);
+const GPU_TIME_COLOR: Color32 = Color32::from_rgb(250, 150, 100);
+
+/// GPU time of the individual stages of a frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GpuPassTimes {
+    /// Compute hooks, the scene and its post processing.
+    pub scene: Duration,
+    /// Texture and buffer uploads of egui, including the copies of paint callbacks.
+    pub upload: Duration,
+    /// The egui render pass.
+    pub egui: Duration,
+}
+
+/// Measurements of a single frame.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FrameSample {
+    /// Time between the start of this and the previous frame.
+    pub frame_time: Duration,
+    /// CPU time spent building and submitting the frame.
+    pub cpu_time: Duration,
+    /// Latest GPU time measured when the frame was recorded, if timestamp queries are supported.
+    pub gpu_time: Option<Duration>,
+    /// Breakdown of [`Self::gpu_time`] into the stages of the frame.
+    pub gpu_passes: Option<GpuPassTimes>,
+    /// Number of egui draw calls (clipped primitives).
+    pub draw_calls: usize,
+    /// Bytes staged through the upload belt: uniforms and partial egui texture updates.
+    pub upload_bytes: u64,
+}
+
+/// Rolling history of frame measurements, shown as a small plot in the top right corner.
+#[derive(Debug, Default)]
+pub struct Diagnostics {
+    samples: VecDeque<FrameSample>,
+    gpu_time: Option<Duration>,
+    gpu_passes: Option<GpuPassTimes>,
+    memory: GpuMemoryStats,
+    visible: bool,
+}
+
+impl Diagnostics {
+    pub fn visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    /// Recorded frames, oldest first.
+    pub fn samples(&self) -> impl ExactSizeIterator<Item = &FrameSample> {
+        self.samples.iter()
+    }
+
+    pub fn latest(&self) -> Option<&FrameSample> {
+        self.samples.back()
+    }
+
+    /// GPU memory of the UI as of the last frame rendered while the overlay was visible.
+    pub fn memory(&self) -> &GpuMemoryStats {
+        &self.memory
+    }
+
+    /// Frames per second averaged over the history.
+    pub fn average_fps(&self) -> Option<f64> {
+        let total: Duration = self.samples.iter().map(|sample| sample.frame_time).sum();
+        (!total.is_zero()).then(|| self.samples.len() as f64 / total.as_secs_f64())
+    }
+
+    pub(crate) fn record(
+        &mut self,
+        frame_time: Duration,
+        cpu_time: Duration,
+        draw_calls: usize,
+        upload_bytes: u64,
+    ) {
+        if self.samples.len() == HISTORY_LEN {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(FrameSample {
+            frame_time,
+            cpu_time,
+            gpu_time: self.gpu_time,
+            gpu_passes: self.gpu_passes,
+            draw_calls,
+            upload_bytes,
+        });
+    }
+
+    /// Stores a GPU time read back from a previous frame.
+    pub(crate) fn set_gpu_time(&mut self, gpu_time: GpuTime) {
+        self.gpu_time = Some(gpu_time.total);
+        self.gpu_passes = Some(gpu_time.passes);
+    }
+
+    pub(crate) fn set_memory(&mut self, memory: GpuMemoryStats) {
+        self.memory = memory;
+    }
+
+    /// Shows the plot if visible.
+    pub fn show(&mut self, ctx: &egui::Context) {
+        if !self.visible {
+            return;
+        }
+
+        egui::Area::new("diagnostics")
+            .anchor(Align2::RIGHT_TOP, [-8.0, 8.0])
+            .order(Order::Foreground)
+            .interactable(false)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    let latest = self.latest().copied().unwrap_or_default();
+                    ui.label(format!(
+                        "FPS: {:.1}",
+                        self.average_fps().unwrap_or_default()
+                    ));
+                    ui.colored_label(
+                        FRAME_TIME_COLOR,
+                        format!("Frame: {:.2} ms", millis(latest.frame_time)),
+                    );
+                    ui.colored_label(
+                        CPU_TIME_COLOR,
+                        format!("CPU: {:.2} ms", millis(latest.cpu_time)),
+                    );
+                    ui.colored_label(
+                        GPU_TIME_COLOR,
+                        match latest.gpu_time {
+                            Some(gpu_time) => format!("GPU: {:.2} ms", millis(gpu_time)),
+                            None => "GPU: n/a".to_owned(),
+                        },
+                    );
+                    if let Some(passes) = latest.gpu_passes {
+                        for (name, time) in [
+                            ("Scene", passes.scene),
+                            ("Upload", passes.upload),
+                            ("egui", passes.egui),
+                        ] {
+                            ui.colored_label(
+                                GPU_TIME_COLOR.gamma_multiply(0.7),
+                                format!("  {name}: {:.2} ms", millis(time)),
+                            );
+                        }
+                    }
+                    ui.label(format!("Draw calls: {}", latest.draw_calls));
+                    ui.label(format!(
+                        "Staged uploads: {:.1} KiB",
+                        latest.upload_bytes as f64 / 1024.0
+                    ));
+                    self.plot(ui);
+                    self.memory_ui(ui);
+                });
+            });
+        // Keep the plot moving even if nothing else changes
+        ctx.request_repaint();
+    }
+
+    fn plot(&self, ui: &mut egui::Ui) {
+        let (rect, _) =
+            ui.allocate_exact_size(egui::vec2(HISTORY_LEN as f32, 60.0), Sense::hover());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+
+        let range = self
+            .samples
+            .iter()
+            .map(|sample| sample.frame_time)
+            .max()
+            .unwrap_or_default()
+            .max(MIN_PLOT_RANGE);
+        let to_pos = |index: usize, time: Duration| {
+            let x = rect.right() - (self.samples.len() - index) as f32;
+            let y = rect.bottom() - rect.height() * (millis(time) / millis(range)) as f32;
+            Pos2::new(x, y.max(rect.top()))
+        };
+        let line = |time: fn(&FrameSample) -> Option<Duration>, color: Color32| {
+            let points: Vec<Pos2> = self
+                .samples
+                .iter()
+                .enumerate()
+                .filter_map(|(index, sample)| Some(to_pos(index, time(sample)?)))
+                .collect();
+            painter.add(egui::Shape::line(points, Stroke::new(1.0, color)));
+        };
+        line(|sample| Some(sample.frame_time), FRAME_TIME_COLOR);
+        line(|sample| Some(sample.cpu_time), CPU_TIME_COLOR);
+        line(|sample| sample.gpu_time, GPU_TIME_COLOR);
+
+        painter.text(
+            rect.left_top() + egui::vec2(2.0, 2.0),
+            Align2::LEFT_TOP,
+            format!("{:.0} ms", millis(range)),
+            egui::FontId::monospace(10.0),
+            ui.visuals().weak_text_color(),
+        );
+    }
+
+    fn memory_ui(&self, ui: &mut egui::Ui) {
+        let allocations = |ui: &mut egui::Ui, name: &str, stats: AllocationStats| {
+            ui.label(format!(
+                "{name}: {} ({:.2} MiB)",
+                stats.count,
+                stats.bytes as f64 / (1024.0 * 1024.0)
+            ));
+        };
+        allocations(ui, "egui textures", self.memory.egui_textures);
+        allocations(ui, "Native textures", self.memory.native_textures);
+        allocations(ui, "Meshes", self.memory.buffers);
+        for id in &self.memory.unused_textures {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                format!("{id:?} is unused but not freed"),
+            );
+        }
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// A point in the frame at which [`GpuTimer`] writes a timestamp, in the order they are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GpuMark {
+    Begin,
+    /// After the compute hooks, the scene and its post processing.
+    SceneDone,
+    /// After egui uploaded its textures and buffers.
+    UploadDone,
+    /// After the egui render pass.
+    EguiDone,
+    End,
+}
+
+/// GPU time of a measured frame.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct GpuTime {
+    pub total: Duration,
+    pub passes: GpuPassTimes,
+}
+
+/// Measures the GPU time of a frame with timestamp queries written at its start, its end and
+/// between its stages.
+///
+/// The result is read back asynchronously, so while a measurement is in flight the following
+/// frames are not measured.
+pub struct GpuTimer {
+    query_set: wgpu::QuerySet,
+    resolve_buffer: wgpu::Buffer,
+    readback_buffer: wgpu::Buffer,
+    period: f32,
+    recording: bool,
+    pending: Option<mpsc::Receiver<Result<(), wgpu::BufferAsyncError>>>,
+}
+
+impl GpuTimer {
+    const QUERY_COUNT: u32 = GpuMark::End as u32 + 1;
+    const BUFFER_SIZE: u64 = Self::QUERY_COUNT as u64 * std::mem::size_of::<u64>() as u64;
+
+    /// Returns `None` if the device was created without [`wgpu::Features::TIMESTAMP_QUERY`].
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> Option<Self> {
+        if !device.features().contains(wgpu::Features::TIMESTAMP_QUERY) {
+            return None;
+        }
+
+        let query_set = device.create_query_set(&wgpu::QuerySetDescriptor {
+            label: Some("frame timestamps"),
+            ty: wgpu::QueryType::Timestamp,
+            count: Self::QUERY_COUNT,
+        });
+        let resolve_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("frame timestamps resolve"),
+            size: Self::BUFFER_SIZE,
+            usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let readback_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("frame timestamps readback"),
+            size: Self::BUFFER_SIZE,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        Some(Self {
+            query_set,
+            resolve_buffer,
+            readback_buffer,
+            period: queue.get_timestamp_period(),
+            recording: false,
+            pending: None,
+        })
+    }
+
+    /// Writes the start timestamp, unless the previous measurement is still being read back.
+    pub fn begin(&mut self, encoder: &mut wgpu::CommandEncoder) {
+        self.recording = self.pending.is_none();
+        self.mark(encoder, GpuMark::Begin);
+    }
+
+    /// Writes the timestamp of a point between the stages of the frame.
+    ///
+    /// Every mark has to be written once between [`Self::begin`] and [`Self::end`].
+    pub fn mark(&mut self, encoder: &mut wgpu::CommandEncoder, mark: GpuMark) {
+        if self.recording {
+            encoder.write_timestamp(&self.query_set, mark as u32);
+        }
+    }
+
+    /// Writes the end timestamp and copies all of them into the readback buffer.
+    pub fn end(&mut self, encoder: &mut wgpu::CommandEncoder) {
+        self.mark(encoder, GpuMark::End);
+        if !self.recording {
+            return;
+        }
+        encoder.resolve_query_set(
+            &self.query_set,
+            0..Self::QUERY_COUNT,
+            &self.resolve_buffer,
+            0,
+        );
+        encoder.copy_buffer_to_buffer(
+            &self.resolve_buffer,
+            0,
+            &self.readback_buffer,
+            0,
+            Self::BUFFER_SIZE,
+        );
+    }
+
+    /// Starts reading back the measurement. Must be called after the encoder has been submitted.
+    pub fn after_submit(&mut self) {
+        if !std::mem::take(&mut self.recording) {
+            return;
+        }
+        let (sender, receiver) = mpsc::channel();
+        self.readback_buffer
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                let _ = sender.send(result);
+            });
+        self.pending = Some(receiver);
+    }
+
+    /// Returns the GPU time of the last measured frame once it has been read back.
+    pub(crate) fn read(&mut self, device: &wgpu::Device) -> Option<GpuTime> {
+        let receiver = self.pending.as_ref()?;
+        device.poll(wgpu::Maintain::Poll);
+        let result = receiver.try_recv().ok()?;
+        self.pending = None;
+        if let Err(err) = result {
+            log::error!("Failed to map timestamp buffer: {err}");
+            return None;
+        }
+
+        let slice = self.readback_buffer.slice(..);
+        let timestamps: Vec<u64> = slice
+            .get_mapped_range()
+            .chunks_exact(8)
+            .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+            .collect();
+        self.readback_buffer.unmap();
+
+        let between = |from: GpuMark, to: GpuMark| {
+            let ticks = timestamps[to as usize].saturating_sub(timestamps[from as usize]);
+            Duration::from_nanos((ticks as f64 * self.period as f64) as u64)
+        };
+        Some(GpuTime {
+            total: between(GpuMark::Begin, GpuMark::End),
+            passes: GpuPassTimes {
+                scene: between(GpuMark::Begin, GpuMark::SceneDone),
+                upload: between(GpuMark::SceneDone, GpuMark::UploadDone),
+                egui: between(GpuMark::UploadDone, GpuMark::EguiDone),
+            },
+        })
+    }
+}
@@ -0,0 +1,278 @@
+//! Modal alert, confirmation and prompt dialogs.
+//!
+//! Dialogs are queued in [`crate::Frame::dialogs_mut`] and shown one at a time above a dimmed
+//! backdrop which blocks the rest of the UI. Enter accepts and Escape cancels. The answer arrives
+//! through the returned [`DialogHandle`], either by polling it during updates, as a future or by
+//! a callback:
+//!
+//! ```no_run
+//! # use egui_tests::{dialogs::DialogHandle, Frame};
+//! # fn update(frame: &mut Frame, discard: &mut Option<DialogHandle<bool>>) {
+//! if discard.is_none() {
+//!     *discard = Some(frame.dialogs_mut().confirm("Discard changes?"));
+//! }
+//! if let Some(confirmed) = discard.as_ref().and_then(|handle| handle.take_result()) {
+//!     // ...
+//! }
+//! # }
+//! ```
+
+use std::{
+    collections::VecDeque,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context as TaskContext, Poll, Waker},
+};
+
+use egui::{mutex::Mutex, Align2, Color32, Id, Key, Modifiers, Order, Sense};
+
+use crate::Frame;
+
+const BACKDROP: Color32 = Color32::from_black_alpha(128);
+
+type Callback<T> = Box<dyn FnOnce(T) + Send>;
+
+struct Slot<T> {
+    result: Option<T>,
+    answered: bool,
+    waker: Option<Waker>,
+    callback: Option<Callback<T>>,
+}
+
+/// The answer to a dialog, once the user closed it.
+pub struct DialogHandle<T> {
+    slot: Arc<Mutex<Slot<T>>>,
+}
+
+impl<T: Send + 'static> DialogHandle<T> {
+    fn new() -> Self {
+        Self {
+            slot: Arc::new(Mutex::new(Slot {
+                result: None,
+                answered: false,
+                waker: None,
+                callback: None,
+            })),
+        }
+    }
+
+    /// Whether the dialog is still waiting for the user.
+    pub fn is_open(&self) -> bool {
+        !self.slot.lock().answered
+    }
+
+    /// The answer, returned once after the dialog closed.
+    pub fn take_result(&self) -> Option<T> {
+        self.slot.lock().result.take()
+    }
+
+    /// Calls `callback` with the answer instead, right away if the dialog already closed.
+    pub fn on_result(self, callback: impl FnOnce(T) + Send + 'static) {
+        let mut slot = self.slot.lock();
+        match slot.result.take() {
+            Some(result) => {
+                drop(slot);
+                callback(result);
+            }
+            None => slot.callback = Some(Box::new(callback)),
+        }
+    }
+
+    fn respond(&self, result: T) {
+        let mut slot = self.slot.lock();
+        slot.answered = true;
+        if let Some(callback) = slot.callback.take() {
+            drop(slot);
+            callback(result);
+            return;
+        }
+        slot.result = Some(result);
+        if let Some(waker) = slot.waker.take() {
+            waker.wake();
+        }
+    }
+
+    fn share(&self) -> Self {
+        Self {
+            slot: self.slot.clone(),
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for DialogHandle<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DialogHandle")
+            .field("answered", &self.slot.lock().answered)
+            .finish()
+    }
+}
+
+impl<T: Send + 'static> Future for DialogHandle<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<T> {
+        let mut slot = self.slot.lock();
+        match slot.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                slot.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+enum Kind {
+    Alert(DialogHandle<()>),
+    Confirm(DialogHandle<bool>),
+    Prompt(DialogHandle<Option<String>>, String),
+}
+
+struct Dialog {
+    id: Id,
+    message: String,
+    kind: Kind,
+}
+
+/// The queue of modal dialogs of a window.
+#[derive(Default)]
+pub struct Dialogs {
+    queue: VecDeque<Dialog>,
+    next_id: u64,
+}
+
+impl std::fmt::Debug for Dialogs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.queue.iter().map(|dialog| &dialog.message))
+            .finish()
+    }
+}
+
+impl Dialogs {
+    /// Shows `message` with an OK button.
+    pub fn alert(&mut self, message: impl Into<String>) -> DialogHandle<()> {
+        let handle = DialogHandle::new();
+        self.push(message.into(), Kind::Alert(handle.share()));
+        handle
+    }
+
+    /// Asks to confirm `message`, answering `true` for OK and `false` for Cancel.
+    pub fn confirm(&mut self, message: impl Into<String>) -> DialogHandle<bool> {
+        let handle = DialogHandle::new();
+        self.push(message.into(), Kind::Confirm(handle.share()));
+        handle
+    }
+
+    /// Asks for a line of text, starting with `text`. Answers `None` if cancelled.
+    pub fn prompt(
+        &mut self,
+        message: impl Into<String>,
+        text: impl Into<String>,
+    ) -> DialogHandle<Option<String>> {
+        let handle = DialogHandle::new();
+        self.push(message.into(), Kind::Prompt(handle.share(), text.into()));
+        handle
+    }
+
+    /// Whether a dialog is shown, blocking the rest of the UI.
+    pub fn is_open(&self) -> bool {
+        !self.queue.is_empty()
+    }
+
+    fn push(&mut self, message: String, kind: Kind) {
+        self.next_id += 1;
+        self.queue.push_back(Dialog {
+            id: Id::new("egui_tests::dialog").with(self.next_id),
+            message,
+            kind,
+        });
+    }
+
+    /// Shows the first dialog of the queue. Runs before the app's update, so Enter and Escape
+    /// reach the dialog first.
+    pub(crate) fn show(frame: &mut Frame, ctx: &egui::Context) {
+        let Some(dialog) = frame.dialogs.queue.front_mut() else {
+            return;
+        };
+
+        // Catches clicks on the UI underneath
+        let screen = ctx.screen_rect();
+        egui::Area::new(dialog.id.with("backdrop"))
+            .order(Order::Foreground)
+            .fixed_pos(screen.min)
+            .show(ctx, |ui| {
+                ui.painter().rect_filled(screen, 0.0, BACKDROP);
+                ui.allocate_rect(screen, Sense::click_and_drag());
+            });
+
+        let (enter, escape) = ctx.input_mut(|input| {
+            (
+                input.consume_key(Modifiers::NONE, Key::Enter),
+                input.consume_key(Modifiers::NONE, Key::Escape),
+            )
+        });
+        let mut accepted = enter;
+        let mut cancelled = escape;
+        let area = egui::Area::new(dialog.id)
+            .order(Order::Foreground)
+            .anchor(Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                egui::Frame::window(ui.style())
+                    .show(ui, |ui| {
+                        ui.set_max_width(360.0);
+                        ui.label(&dialog.message);
+                        ui.add_space(8.0);
+                        let focus = match &mut dialog.kind {
+                            Kind::Prompt(_, text) => Some(ui.add(
+                                egui::TextEdit::singleline(text).desired_width(f32::INFINITY),
+                            )),
+                            _ => None,
+                        };
+                        ui.add_space(8.0);
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            let ok = ui.button("OK");
+                            accepted |= ok.clicked();
+                            let mut widgets = vec![ok.id];
+                            if !matches!(dialog.kind, Kind::Alert(_)) {
+                                let cancel = ui.button("Cancel");
+                                cancelled |= cancel.clicked();
+                                widgets.push(cancel.id);
+                            }
+                            let focus = match focus {
+                                Some(edit) => {
+                                    widgets.push(edit.id);
+                                    edit
+                                }
+                                None => ok,
+                            };
+                            (focus, widgets)
+                        })
+                        .inner
+                    })
+                    .inner
+            });
+        ctx.move_to_top(area.response.layer_id);
+
+        // Keeps the keyboard focus inside the dialog, moving it to the text field or OK button
+        let (focus, widgets) = area.inner;
+        let focused = ctx.memory(|memory| memory.focus());
+        if focused.is_none_or(|id| !widgets.contains(&id)) {
+            focus.request_focus();
+        }
+
+        if accepted || cancelled {
+            let dialog = frame
+                .dialogs
+                .queue
+                .pop_front()
+                .expect("the dialog was shown");
+            match dialog.kind {
+                Kind::Alert(handle) => handle.respond(()),
+                Kind::Confirm(handle) => handle.respond(accepted),
+                Kind::Prompt(handle, text) => handle.respond(accepted.then_some(text)),
+            }
+        }
+    }
+}
@@ -0,0 +1,234 @@
+//! Dockable tabs on top of [`egui_dock`], for editor-style tools.
+//!
+//! Tabs implement [`Tab`] and are registered with a [`Dock`] under a stable id. The layout refers
+//! to tabs by that id, so it is persisted in the egui memory between runs if an app id is set with
+//! [`crate::AppBuilder::with_app_id`].
+//!
+//! ```no_run
+//! use egui_tests::{dock::{Dock, DockState, Tab}, Frame};
+//!
+//! struct Inspector;
+//!
+//! impl Tab for Inspector {
+//!     fn title(&self) -> egui::WidgetText {
+//!         "Inspector".into()
+//!     }
+//!
+//!     fn ui(&mut self, ui: &mut egui::Ui, _frame: &mut Frame) {
+//!         ui.label("Nothing selected");
+//!     }
+//! }
+//!
+//! let mut layout = DockState::new(vec!["viewport".to_owned()]);
+//! layout
+//!     .main_surface_mut()
+//!     .split_right(egui_dock::NodeIndex::root(), 0.75, vec!["inspector".to_owned()]);
+//! let dock = Dock::new("editor", layout).with_tab("inspector", Inspector);
+//! ```
+
+use std::collections::BTreeMap;
+
+pub use egui_dock::DockState;
+use egui_dock::{DockArea, TabViewer};
+
+use crate::Frame;
+
+/// Content of a dockable tab.
+pub trait Tab {
+    fn title(&self) -> egui::WidgetText;
+
+    fn ui(&mut self, ui: &mut egui::Ui, frame: &mut Frame);
+
+    /// Whether the tab shows a close button, closed tabs can be opened again with
+    /// [`Dock::open_tab`].
+    fn closeable(&self) -> bool {
+        true
+    }
+}
+
+type Tabs = BTreeMap<String, Box<dyn Tab>>;
+
+/// Registered tabs and their layout.
+pub struct Dock {
+    id: egui::Id,
+    tabs: Tabs,
+    default_layout: DockState<String>,
+    /// Loaded from the egui memory on the first [`Self::show`].
+    layout: Option<DockState<String>>,
+}
+
+impl Dock {
+    /// A dock with `default_layout`, which holds the ids of the tabs.
+    ///
+    /// `id_source` has to be unique among the docks of the app, the layout is persisted under it.
+    pub fn new(id_source: impl std::hash::Hash, default_layout: DockState<String>) -> Self {
+        Self {
+            id: egui::Id::new(("egui_tests::dock", id_source)),
+            tabs: BTreeMap::new(),
+            default_layout,
+            layout: None,
+        }
+    }
+
+    pub fn with_tab(mut self, id: impl Into<String>, tab: impl Tab + 'static) -> Self {
+        self.add_tab(id, tab);
+        self
+    }
+
+    /// Registers `tab` under `id`, replacing a tab with the same id. Tabs not in the layout are
+    /// opened with [`Self::open_tab`].
+    pub fn add_tab(&mut self, id: impl Into<String>, tab: impl Tab + 'static) {
+        self.tabs.insert(id.into(), Box::new(tab));
+    }
+
+    pub fn tab(&self, id: &str) -> Option<&dyn Tab> {
+        self.tabs.get(id).map(|tab| &**tab)
+    }
+
+    pub fn tab_mut(&mut self, id: &str) -> Option<&mut (dyn Tab + 'static)> {
+        self.tabs.get_mut(id).map(|tab| &mut **tab)
+    }
+
+    /// The current layout, `None` before it was loaded by the first [`Self::show`].
+    pub fn layout(&self) -> Option<&DockState<String>> {
+        self.layout.as_ref()
+    }
+
+    pub fn is_open(&self, id: &str) -> bool {
+        self.layout
+            .as_ref()
+            .unwrap_or(&self.default_layout)
+            .find_tab(&id.to_owned())
+            .is_some()
+    }
+
+    /// Focuses the tab, or adds it to the focused node if it is closed.
+    pub fn open_tab(&mut self, id: &str) {
+        let layout = self
+            .layout
+            .get_or_insert_with(|| self.default_layout.clone());
+        let id = id.to_owned();
+        match layout.find_tab(&id) {
+            Some(tab) => layout.set_active_tab(tab),
+            None => layout.push_to_focused_leaf(id),
+        }
+    }
+
+    pub fn close_tab(&mut self, id: &str) {
+        let layout = self
+            .layout
+            .get_or_insert_with(|| self.default_layout.clone());
+        if let Some(tab) = layout.find_tab(&id.to_owned()) {
+            layout.remove_tab(tab);
+        }
+    }
+
+    /// Goes back to the layout passed to [`Self::new`].
+    pub fn reset_layout(&mut self) {
+        self.layout = Some(self.default_layout.clone());
+    }
+
+    /// Shows the dock in the remaining space of the window.
+    pub fn show(&mut self, ctx: &egui::Context, frame: &mut Frame) {
+        let (id, layout, tabs) = self.load_layout(ctx);
+        DockArea::new(layout)
+            .id(id)
+            .show(ctx, &mut Viewer::new(tabs, frame));
+        self.store_layout(ctx);
+    }
+
+    /// Shows the dock inside `ui`, e.g. inside a panel.
+    pub fn show_inside(&mut self, ui: &mut egui::Ui, frame: &mut Frame) {
+        let (id, layout, tabs) = self.load_layout(ui.ctx());
+        DockArea::new(layout)
+            .id(id)
+            .show_inside(ui, &mut Viewer::new(tabs, frame));
+        self.store_layout(ui.ctx());
+    }
+
+    /// Checkboxes to open and close the tabs, and a button to reset the layout, e.g. for a
+    /// "View" menu.
+    pub fn menu_ui(&mut self, ui: &mut egui::Ui) {
+        let tabs: Vec<(String, egui::WidgetText)> = self
+            .tabs
+            .iter()
+            .map(|(id, tab)| (id.clone(), tab.title()))
+            .collect();
+        for (id, title) in tabs {
+            let mut open = self.is_open(&id);
+            if ui.checkbox(&mut open, title).changed() {
+                if open {
+                    self.open_tab(&id);
+                } else {
+                    self.close_tab(&id);
+                }
+            }
+        }
+        ui.separator();
+        if ui.button("Reset layout").clicked() {
+            self.reset_layout();
+            ui.close_menu();
+        }
+    }
+
+    /// The id, the layout and the tabs, to be borrowed at the same time.
+    fn load_layout(
+        &mut self,
+        ctx: &egui::Context,
+    ) -> (egui::Id, &mut DockState<String>, &mut Tabs) {
+        if self.layout.is_none() {
+            self.layout = ctx.data_mut(|data| data.get_persisted(self.id));
+        }
+        let layout = self
+            .layout
+            .get_or_insert_with(|| self.default_layout.clone());
+        (self.id, layout, &mut self.tabs)
+    }
+
+    fn store_layout(&self, ctx: &egui::Context) {
+        if let Some(layout) = &self.layout {
+            ctx.data_mut(|data| data.insert_persisted(self.id, layout.clone()));
+        }
+    }
+}
+
+/// Looks up the tabs of the layout by id.
+struct Viewer<'a> {
+    tabs: &'a mut Tabs,
+    frame: &'a mut Frame,
+}
+
+impl<'a> Viewer<'a> {
+    fn new(tabs: &'a mut Tabs, frame: &'a mut Frame) -> Self {
+        Self { tabs, frame }
+    }
+}
+
+impl TabViewer for Viewer<'_> {
+    type Tab = String;
+
+    fn title(&mut self, id: &mut String) -> egui::WidgetText {
+        self.tabs
+            .get(id)
+            .map_or_else(|| id.as_str().into(), |tab| tab.title())
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, id: &mut String) {
+        if let Some(tab) = self.tabs.get_mut(id) {
+            tab.ui(ui, self.frame);
+        }
+    }
+
+    fn id(&mut self, id: &mut String) -> egui::Id {
+        egui::Id::new(("egui_tests::dock::tab", id.as_str()))
+    }
+
+    fn closeable(&mut self, id: &mut String) -> bool {
+        self.tabs.get(id).is_none_or(|tab| tab.closeable())
+    }
+
+    /// Removes tabs of a persisted layout which are no longer registered.
+    fn force_close(&mut self, id: &mut String) -> bool {
+        !self.tabs.contains_key(id)
+    }
+}
@@ -0,0 +1,164 @@
+use std::{io::Read, path::PathBuf};
+
+use egui::{Align2, Color32, Id, LayerId, Order, TextStyle, TextureHandle};
+
+/// Maximum width and height of image previews in pixels.
+const PREVIEW_SIZE: u32 = 128;
+/// Number of bytes read from a file for a text preview.
+const TEXT_PREVIEW_BYTES: u64 = 512;
+
+/// A short look into the content of a dropped file.
+#[derive(Clone)]
+pub enum FilePreview {
+    Image(TextureHandle),
+    Text(String),
+    None,
+}
+
+/// A file dropped onto the window.
+#[derive(Clone)]
+pub struct DroppedFile {
+    pub path: PathBuf,
+    /// Size in bytes, if the file could be read.
+    pub size: Option<u64>,
+    pub preview: FilePreview,
+}
+
+impl DroppedFile {
+    fn load(ctx: &egui::Context, path: PathBuf) -> Self {
+        let size = std::fs::metadata(&path).ok().map(|metadata| metadata.len());
+        let preview = image_preview(ctx, &path)
+            .map(FilePreview::Image)
+            .or_else(|| text_preview(&path).map(FilePreview::Text))
+            .unwrap_or(FilePreview::None);
+        Self {
+            path,
+            size,
+            preview,
+        }
+    }
+}
+
+/// Collects the files dropped onto the window over multiple frames.
+///
+/// winit reports drag-and-drop through `WindowEvent::HoveredFile` and `WindowEvent::DroppedFile`,
+/// which end up in [`egui::RawInput::hovered_files`] and [`egui::RawInput::dropped_files`].
+#[derive(Default)]
+pub struct DroppedFiles {
+    files: Vec<DroppedFile>,
+}
+
+impl DroppedFiles {
+    /// Takes the files dropped this frame from the egui input and returns how many were added.
+    pub fn update(&mut self, ctx: &egui::Context) -> usize {
+        let dropped: Vec<PathBuf> = ctx.input(|i| {
+            i.raw
+                .dropped_files
+                .iter()
+                .filter_map(|file| file.path.clone())
+                .collect()
+        });
+        let count = dropped.len();
+        for path in dropped {
+            self.add(ctx, path);
+        }
+        count
+    }
+
+    /// Adds a file obtained some other way, e.g. from a file dialog.
+    pub fn add(&mut self, ctx: &egui::Context, path: PathBuf) {
+        self.files.push(DroppedFile::load(ctx, path));
+    }
+
+    pub fn files(&self) -> &[DroppedFile] {
+        &self.files
+    }
+
+    pub fn clear(&mut self) {
+        self.files.clear();
+    }
+
+    /// Lists the dropped files with their previews.
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        if self.files.is_empty() {
+            ui.label("Drop files onto the window");
+            return;
+        }
+        if ui.button("Clear").clicked() {
+            self.clear();
+        }
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for file in &self.files {
+                ui.separator();
+                ui.label(file.path.display().to_string());
+                if let Some(size) = file.size {
+                    ui.weak(format!("{size} bytes"));
+                }
+                match &file.preview {
+                    FilePreview::Image(texture) => {
+                        ui.image((texture.id(), texture.size_vec2()));
+                    }
+                    FilePreview::Text(text) => {
+                        ui.monospace(text);
+                    }
+                    FilePreview::None => {}
+                }
+            }
+        });
+    }
+}
+
+/// Darkens the window and lists the files while they are dragged over it.
+pub fn paint_hovered_files(ctx: &egui::Context) {
+    let hovered: Vec<String> = ctx.input(|i| {
+        i.raw
+            .hovered_files
+            .iter()
+            .filter_map(|file| Some(file.path.as_ref()?.display().to_string()))
+            .collect()
+    });
+    if hovered.is_empty() {
+        return;
+    }
+
+    let painter = ctx.layer_painter(LayerId::new(Order::Foreground, Id::new("hovered_files")));
+    let screen_rect = ctx.screen_rect();
+    painter.rect_filled(screen_rect, 0.0, Color32::from_black_alpha(192));
+    painter.text(
+        screen_rect.center(),
+        Align2::CENTER_CENTER,
+        format!("Dropping files:\n{}", hovered.join("\n")),
+        TextStyle::Body.resolve(&ctx.style()),
+        Color32::WHITE,
+    );
+}
+
+fn image_preview(ctx: &egui::Context, path: &std::path::Path) -> Option<TextureHandle> {
+    let image = image::open(path).ok()?;
+    let thumbnail = image.thumbnail(PREVIEW_SIZE, PREVIEW_SIZE).to_rgba8();
+    let size = [thumbnail.width() as usize, thumbnail.height() as usize];
+    let color_image = egui::ColorImage::from_rgba_unmultiplied(size, thumbnail.as_raw());
+    Some(ctx.load_texture(
+        path.display().to_string(),
+        color_image,
+        egui::TextureOptions::LINEAR,
+    ))
+}
+
+fn text_preview(path: &std::path::Path) -> Option<String> {
+    let mut bytes = Vec::new();
+    std::fs::File::open(path)
+        .ok()?
+        .take(TEXT_PREVIEW_BYTES)
+        .read_to_end(&mut bytes)
+        .ok()?;
+    // The cut may split a multi-byte character, so only the valid part is used
+    let text = match std::str::from_utf8(&bytes) {
+        Ok(text) => text,
+        Err(err) if err.error_len().is_none() => {
+            std::str::from_utf8(&bytes[..err.valid_up_to()]).ok()?
+        }
+        Err(_) => return None,
+    };
+    Some(text.lines().take(5).collect::<Vec<_>>().join("\n"))
+}
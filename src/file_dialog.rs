@@ -0,0 +1,87 @@
+use std::{
+    path::PathBuf,
+    sync::mpsc::{self, Receiver, Sender},
+    time::Duration,
+};
+
+/// How often pending dialogs are checked for a result.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileDialogKind {
+    Open,
+    Save,
+}
+
+/// The outcome of a dialog, `path` is `None` if it was cancelled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileDialogResult {
+    pub kind: FileDialogKind,
+    pub path: Option<PathBuf>,
+}
+
+/// Shows native file dialogs without blocking the event loop.
+///
+/// Each dialog runs on its own thread and sends its result back through a channel, which is
+/// drained by [`Self::poll`] during an update.
+pub struct FileDialogs {
+    sender: Sender<FileDialogResult>,
+    receiver: Receiver<FileDialogResult>,
+    pending: usize,
+}
+
+impl FileDialogs {
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            sender,
+            receiver,
+            pending: 0,
+        }
+    }
+
+    /// Shows `dialog` to pick a single file to open.
+    pub fn open(&mut self, dialog: rfd::AsyncFileDialog) {
+        self.spawn(FileDialogKind::Open, dialog.pick_file());
+    }
+
+    /// Shows `dialog` to choose where to save a file.
+    pub fn save(&mut self, dialog: rfd::AsyncFileDialog) {
+        self.spawn(FileDialogKind::Save, dialog.save_file());
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.pending > 0
+    }
+
+    /// Returns the results of the dialogs closed since the last call.
+    ///
+    /// Keeps requesting repaints while a dialog is open, so results arrive without other input.
+    pub fn poll(&mut self, ctx: &egui::Context) -> Vec<FileDialogResult> {
+        let results: Vec<_> = self.receiver.try_iter().collect();
+        self.pending -= results.len();
+        if self.is_pending() {
+            ctx.request_repaint_after(POLL_INTERVAL);
+        }
+        results
+    }
+
+    fn spawn(
+        &mut self,
+        kind: FileDialogKind,
+        dialog: impl std::future::Future<Output = Option<rfd::FileHandle>> + Send + 'static,
+    ) {
+        self.pending += 1;
+        let sender = self.sender.clone();
+        std::thread::spawn(move || {
+            let path = pollster::block_on(dialog).map(|file| file.path().to_path_buf());
+            let _ = sender.send(FileDialogResult { kind, path });
+        });
+    }
+}
+
+impl Default for FileDialogs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,162 @@
+use std::{fs, io, path::Path};
+
+use egui::{FontData, FontDefinitions, FontFamily};
+
+/// Common locations of fonts covering Chinese, Japanese and Korean glyphs.
+const SYSTEM_CJK_FONTS: &[&str] = &[
+    // Windows
+    "C:\\Windows\\Fonts\\msyh.ttc",
+    "C:\\Windows\\Fonts\\YuGothM.ttc",
+    "C:\\Windows\\Fonts\\malgun.ttf",
+    // macOS
+    "/System/Library/Fonts/PingFang.ttc",
+    "/System/Library/Fonts/Hiragino Sans GB.ttc",
+    "/System/Library/Fonts/AppleSDGothicNeo.ttc",
+    // Linux
+    "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/google-noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/truetype/wqy/wqy-microhei.ttc",
+];
+
+/// Builds [`FontDefinitions`] from egui's default fonts plus user supplied TTF/OTF files.
+///
+/// Fonts added with [`Self::with_font`] take precedence over egui's fonts, fonts added with
+/// [`Self::with_fallback`] are only used for glyphs no other font provides.
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// let fonts = egui_tests::fonts::FontLoader::new()
+///     .with_font_file("assets/Inter.ttf", &[egui::FontFamily::Proportional])?
+///     .with_system_cjk_fallback()
+///     .build();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct FontLoader {
+    definitions: FontDefinitions,
+}
+
+impl Default for FontLoader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FontLoader {
+    pub fn new() -> Self {
+        Self {
+            definitions: FontDefinitions::default(),
+        }
+    }
+
+    /// Starts from an empty set of fonts instead of egui's defaults.
+    pub fn empty() -> Self {
+        Self {
+            definitions: FontDefinitions::empty(),
+        }
+    }
+
+    fn insert(&mut self, name: &str, data: Vec<u8>) {
+        self.definitions
+            .font_data
+            .insert(name.to_owned(), FontData::from_owned(data));
+    }
+
+    /// Adds a font that is preferred over all previously added fonts of `families`.
+    pub fn with_font(mut self, name: &str, data: Vec<u8>, families: &[FontFamily]) -> Self {
+        self.insert(name, data);
+        for family in families {
+            self.family_mut(family).insert(0, name.to_owned());
+        }
+        self
+    }
+
+    /// Adds a font that is only used for glyphs missing in all previously added fonts of
+    /// `families`.
+    pub fn with_fallback(mut self, name: &str, data: Vec<u8>, families: &[FontFamily]) -> Self {
+        self.insert(name, data);
+        for family in families {
+            self.family_mut(family).push(name.to_owned());
+        }
+        self
+    }
+
+    /// Adds a font from a file as the preferred font of `families`, named after the file stem.
+    pub fn with_font_file(
+        self,
+        path: impl AsRef<Path>,
+        families: &[FontFamily],
+    ) -> io::Result<Self> {
+        let path = path.as_ref();
+        let data = fs::read(path)?;
+        Ok(self.with_font(&font_name(path), data, families))
+    }
+
+    /// Adds all TTF/OTF/TTC files in `dir` as proportional fonts, in alphabetical order with the
+    /// first file being the most preferred.
+    pub fn with_font_dir(mut self, dir: impl AsRef<Path>) -> io::Result<Self> {
+        let mut paths: Vec<_> = fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| is_font_file(path))
+            .collect();
+        paths.sort();
+
+        for path in paths.into_iter().rev() {
+            self = self.with_font_file(&path, &[FontFamily::Proportional])?;
+        }
+        Ok(self)
+    }
+
+    /// Adds the first CJK font found on the system as fallback for all families, so Chinese,
+    /// Japanese and Korean text renders instead of showing placeholder boxes.
+    pub fn with_system_cjk_fallback(self) -> Self {
+        let Some((path, data)) = SYSTEM_CJK_FONTS
+            .iter()
+            .find_map(|path| fs::read(path).ok().map(|data| (path, data)))
+        else {
+            log::warn!("No CJK font found on this system");
+            return self;
+        };
+
+        log::debug!("Using {path} as CJK fallback font");
+        self.with_fallback(
+            "system-cjk",
+            data,
+            &[FontFamily::Proportional, FontFamily::Monospace],
+        )
+    }
+
+    /// Replaces the fallback order of `family` with `names`.
+    pub fn with_family_order(mut self, family: FontFamily, names: Vec<String>) -> Self {
+        self.definitions.families.insert(family, names);
+        self
+    }
+
+    fn family_mut(&mut self, family: &FontFamily) -> &mut Vec<String> {
+        self.definitions.families.entry(family.clone()).or_default()
+    }
+
+    pub fn build(self) -> FontDefinitions {
+        self.definitions
+    }
+}
+
+impl From<FontLoader> for FontDefinitions {
+    fn from(loader: FontLoader) -> Self {
+        loader.build()
+    }
+}
+
+fn is_font_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| matches!(ext.to_ascii_lowercase().as_str(), "ttf" | "otf" | "ttc"))
+}
+
+fn font_name(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
@@ -0,0 +1,695 @@
+use std::{any::Any, path::PathBuf, time::Duration};
+
+use wgpu::PresentMode;
+use winit::window::{ResizeDirection, WindowLevel};
+
+use crate::{
+    animations::Animated,
+    backdrop::Backdrop,
+    command_palette::CommandPalette,
+    diagnostics::Diagnostics,
+    dialogs::{DialogHandle, Dialogs},
+    frosted_glass::FrostedGlass,
+    gestures::TouchGestures,
+    gpu::GpuContext,
+    gpu_errors::GpuErrors,
+    hdr::HdrSettings,
+    icon::WindowIcon,
+    monitors::{MonitorInfo, MonitorPlacement, MonitorTracker},
+    overlay::ClickThrough,
+    plugin::Plugins,
+    post_process::PostProcess,
+    screenshot,
+    settings::SettingsWindow,
+    shortcuts::{Action, Shortcuts},
+    textures::TextureRegistry,
+    theme::{Theme, ThemePreference},
+    timestep::FixedTimestep,
+    window::{FullscreenMode, WindowCommand},
+    EventLoopProxy, FramePacer, FrameTime, RenderMode, UnfocusedPolicy,
+};
+
+/// Asked before closing when [`crate::App::can_close`] returns `false`.
+const CLOSE_CONFIRMATION: &str = "There are unsaved changes. Quit anyway?";
+
+/// Height of the standard macOS titlebar in points.
+const MACOS_TITLEBAR_HEIGHT: f32 = 28.0;
+
+/// Per-window state handed to [`crate::App::update`] alongside the egui context.
+#[derive(Debug)]
+pub struct Frame {
+    pub(crate) pacer: FramePacer,
+    pub(crate) timestep: Option<FixedTimestep>,
+    /// Opacity of the UI, multiplied into everything egui paints.
+    pub(crate) opacity: Animated<f32>,
+    /// Target opacity and duration in seconds of a fade, started in the next frame.
+    pub(crate) fade_request: Option<(f32, f32)>,
+    pub(crate) hide_after_fade: bool,
+    pub(crate) clear_color: egui::Rgba,
+    pub(crate) theme: Theme,
+    pub(crate) screenshot_request: Option<PathBuf>,
+    pub(crate) click_through: ClickThrough,
+    pub(crate) window_level: WindowLevel,
+    pub(crate) fullscreen_mode: FullscreenMode,
+    pub(crate) decorations: bool,
+    pub(crate) corner_radius: f32,
+    pub(crate) backdrop: Backdrop,
+    pub(crate) transparent_titlebar: bool,
+    pub(crate) monitors: MonitorTracker,
+    pub(crate) touch_gestures: TouchGestures,
+    pub(crate) window_commands: Vec<WindowCommand>,
+    pub(crate) present_mode: PresentMode,
+    pub(crate) supported_present_modes: Vec<PresentMode>,
+    pub(crate) requested_present_mode: Option<PresentMode>,
+    pub(crate) diagnostics: Diagnostics,
+    pub(crate) settings: SettingsWindow,
+    pub(crate) command_palette: CommandPalette,
+    pub(crate) dialogs: Dialogs,
+    pub(crate) plugins: Plugins,
+    pub(crate) textures: TextureRegistry,
+    pub(crate) gpu: GpuContext,
+    pub(crate) gpu_errors: GpuErrors,
+    pub(crate) hdr: bool,
+    pub(crate) hdr_settings: HdrSettings,
+    pub(crate) post_process: PostProcess,
+    pub(crate) frosted_glass: FrostedGlass,
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) recorder: crate::recorder::Recorder,
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) replay: crate::recorder::ReplayBuffer,
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) config_file: Option<crate::config::ConfigFile>,
+    pub(crate) proxy: EventLoopProxy,
+    /// Messages received since the last update, dropped after it if not taken.
+    pub(crate) messages: Vec<Box<dyn Any + Send>>,
+    pub(crate) shortcuts: Shortcuts,
+    /// Actions whose shortcuts were pressed this frame.
+    pub(crate) triggered_actions: Vec<Action>,
+    /// Actions triggered with [`Frame::trigger_action`], performed at the start of the next frame.
+    pub(crate) pending_actions: Vec<Action>,
+    pub(crate) quit_requested: bool,
+    pub(crate) close_requested: bool,
+    /// Open while the user is asked whether to close without saving.
+    pub(crate) close_confirmation: Option<DialogHandle<bool>>,
+    pub(crate) gpu_recreate_requested: bool,
+    #[cfg(feature = "puffin")]
+    pub(crate) profiler: crate::profiler::Profiler,
+}
+
+impl Frame {
+    /// Points the handles given to the app to the recreated device. The texture registry is
+    /// shared with `egui` and switches by itself.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn gpu_recreated(
+        &mut self,
+        egui: &crate::ui::EguiState,
+        graphics: &crate::graphics::Graphics,
+    ) {
+        self.gpu = egui.gpu_context(graphics);
+        self.gpu_errors.attach(&graphics.device);
+        self.hdr = graphics.hdr;
+        self.post_process.invalidate();
+        self.supported_present_modes = graphics.supported_present_modes();
+        // Keep a present mode chosen at runtime
+        self.requested_present_mode = Some(self.present_mode);
+        self.present_mode = graphics.config.present_mode;
+    }
+
+    /// Time between the start of the last two frames.
+    pub fn frame_time(&self) -> Duration {
+        self.pacer.frame_time()
+    }
+
+    /// Delta, smoothed delta and elapsed time of this frame, the same the scene renderer gets.
+    pub fn time(&self) -> FrameTime {
+        self.pacer.time()
+    }
+
+    /// The fixed update loop, if a rate is set with
+    /// [`crate::AppBuilder::with_fixed_update_rate`].
+    pub fn fixed_timestep(&self) -> Option<&FixedTimestep> {
+        self.timestep.as_ref()
+    }
+
+    /// CPU time spent building and submitting the last frame.
+    pub fn work_time(&self) -> Duration {
+        self.pacer.work_time()
+    }
+
+    pub fn max_fps(&self) -> Option<u32> {
+        self.pacer.max_fps()
+    }
+
+    /// Caps the redraw rate, `None` removes the limit.
+    pub fn set_max_fps(&mut self, max_fps: Option<u32>) {
+        self.pacer.set_max_fps(max_fps);
+    }
+
+    pub fn render_mode(&self) -> RenderMode {
+        self.pacer.render_mode()
+    }
+
+    /// Switches between drawing frames back to back and only when egui requests it, starting
+    /// with the next frame.
+    pub fn set_render_mode(&mut self, render_mode: RenderMode) {
+        self.pacer.set_render_mode(render_mode);
+    }
+
+    pub fn unfocused_policy(&self) -> UnfocusedPolicy {
+        self.pacer.unfocused_policy()
+    }
+
+    /// Lowers the redraw rate while the window is not focused.
+    pub fn set_unfocused_policy(&mut self, unfocused: UnfocusedPolicy) {
+        self.pacer.set_unfocused_policy(unfocused);
+    }
+
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    /// Switches between dark, light and system theme, applied from the next frame on.
+    pub fn set_theme_preference(&mut self, preference: ThemePreference) {
+        self.theme.set_preference(preference);
+    }
+
+    pub fn transparent_panels(&self) -> bool {
+        self.theme.transparent_panels()
+    }
+
+    /// Leaves panel backgrounds transparent, so a transparent window stays see-through.
+    pub fn set_transparent_panels(&mut self, transparent_panels: bool) {
+        self.theme.set_transparent_panels(transparent_panels);
+    }
+
+    pub fn clear_color(&self) -> egui::Rgba {
+        self.clear_color
+    }
+
+    /// Clears the window with `clear_color` before egui paints, e.g. a half transparent black
+    /// to dim what is behind a transparent window. Not used while the app renders a scene.
+    pub fn set_clear_color(&mut self, clear_color: egui::Rgba) {
+        self.clear_color = clear_color;
+    }
+
+    /// Saves the next rendered frame as PNG to `path`.
+    pub fn request_screenshot(&mut self, path: impl Into<PathBuf>) {
+        self.screenshot_request = Some(path.into());
+    }
+
+    /// Records the presented frames into a GIF or video, see [`crate::recorder`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn recorder(&self) -> &crate::recorder::Recorder {
+        &self.recorder
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn recorder_mut(&mut self) -> &mut crate::recorder::Recorder {
+        &mut self.recorder
+    }
+
+    /// Starts recording to a new file in the current working directory or stops recording.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn toggle_recording(&mut self) {
+        self.recorder.toggle();
+    }
+
+    /// The last seconds of presented frames, if enabled with
+    /// [`crate::AppBuilder::with_replay_buffer`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn replay_buffer(&self) -> &crate::recorder::ReplayBuffer {
+        &self.replay
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn replay_buffer_mut(&mut self) -> &mut crate::recorder::ReplayBuffer {
+        &mut self.replay
+    }
+
+    /// Exports the replay buffer to a new GIF in the current working directory.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save_replay(&mut self) {
+        self.replay.export(crate::recorder::default_replay_path());
+    }
+
+    /// The config file set with [`crate::AppBuilder::with_config_file`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn config_file(&self) -> Option<&crate::config::ConfigFile> {
+        self.config_file.as_ref()
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn config_file_mut(&mut self) -> Option<&mut crate::config::ConfigFile> {
+        self.config_file.as_mut()
+    }
+
+    /// Applies the whole config file, before the first frame.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn apply_config(&mut self) {
+        if let Some(file) = self.config_file.take() {
+            file.config().apply(None, self);
+            self.config_file = Some(file);
+        }
+    }
+
+    /// Applies the config file after it changed on disk. After the first update, when the app
+    /// has registered its actions, the keybindings are applied again.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn reload_config(&mut self, ctx: &egui::Context, first_frame: bool) {
+        let Some(mut file) = self.config_file.take() else {
+            return;
+        };
+        if first_frame {
+            file.config().apply_keybindings(&mut self.shortcuts);
+        } else if let Some(previous) = file.poll() {
+            file.config().apply(Some(&previous), self);
+        }
+        self.config_file = Some(file);
+        // Changes are only noticed while redrawing
+        ctx.request_repaint_after(crate::config::POLL_INTERVAL);
+    }
+
+    pub fn click_through(&self) -> bool {
+        self.click_through.enabled()
+    }
+
+    /// Lets mouse input pass through the window except where egui widgets are hovered.
+    pub fn set_click_through(&mut self, click_through: bool) {
+        self.click_through.set_enabled(click_through);
+    }
+
+    pub fn window_level(&self) -> WindowLevel {
+        self.window_level
+    }
+
+    /// Keeps the window above or below all other windows, applied after this update.
+    pub fn set_window_level(&mut self, level: WindowLevel) {
+        if level != self.window_level {
+            self.window_level = level;
+            self.window_commands
+                .push(WindowCommand::SetWindowLevel(level));
+        }
+    }
+
+    pub fn fullscreen_mode(&self) -> FullscreenMode {
+        self.fullscreen_mode
+    }
+
+    /// Switches between windowed, borderless and exclusive fullscreen after this update.
+    pub fn set_fullscreen_mode(&mut self, mode: FullscreenMode) {
+        if mode != self.fullscreen_mode {
+            self.fullscreen_mode = mode;
+            self.window_commands
+                .push(WindowCommand::SetFullscreen(mode));
+        }
+    }
+
+    pub fn decorations(&self) -> bool {
+        self.decorations
+    }
+
+    /// Shows or hides the title bar and borders drawn by the OS, see [`crate::title_bar`] for
+    /// drawing them with egui instead.
+    pub fn set_decorations(&mut self, decorations: bool) {
+        if decorations != self.decorations {
+            self.decorations = decorations;
+            self.window_commands
+                .push(WindowCommand::SetDecorations(decorations));
+        }
+    }
+
+    pub fn corner_radius(&self) -> f32 {
+        self.corner_radius
+    }
+
+    /// Rounds the corners of a transparent window with the given radius in points, `0.0` keeps
+    /// them square. Corners are not rounded in fullscreen or while the window is maximized.
+    pub fn set_corner_radius(&mut self, corner_radius: f32) {
+        self.corner_radius = corner_radius.max(0.0);
+    }
+
+    /// Opacity the UI is at or fading towards.
+    pub fn opacity(&self) -> f32 {
+        self.opacity.target()
+    }
+
+    /// Fades the UI to `opacity` in `0.0..=1.0` over `seconds`.
+    ///
+    /// Only what egui paints fades, the scene rendered with [`crate::App::scene_renderer`] and the
+    /// clear color stay as they are.
+    pub fn fade_to(&mut self, opacity: f32, seconds: f32) {
+        self.fade_request = Some((opacity.clamp(0.0, 1.0), seconds));
+        self.hide_after_fade = false;
+    }
+
+    /// Sets the opacity of the UI right away, stopping a fade.
+    pub fn set_opacity(&mut self, opacity: f32) {
+        self.fade_to(opacity, 0.0);
+    }
+
+    /// Fades the UI out and hides the window once it is invisible.
+    pub fn fade_out_and_hide(&mut self, seconds: f32) {
+        self.fade_to(0.0, seconds);
+        self.hide_after_fade = true;
+    }
+
+    /// Shows the window again and fades the UI in, e.g. after [`Self::fade_out_and_hide`].
+    pub fn show_and_fade_in(&mut self, seconds: f32) {
+        self.window_commands.push(WindowCommand::SetVisible(true));
+        self.fade_to(1.0, seconds);
+    }
+
+    /// Starts a requested fade and returns the opacity of this frame. Hides the window once a
+    /// fade out finished.
+    pub(crate) fn update_opacity(&mut self, ctx: &egui::Context) -> f32 {
+        if let Some((opacity, seconds)) = self.fade_request.take() {
+            self.opacity.set_duration(seconds);
+            self.opacity.set_target(ctx, opacity);
+        }
+        if self.hide_after_fade && !self.opacity.is_animating(ctx) {
+            self.hide_after_fade = false;
+            self.window_commands.push(WindowCommand::SetVisible(false));
+        }
+        self.opacity.value(ctx)
+    }
+
+    /// Space at the edges of the window covered by OS controls, which content should keep clear
+    /// of, e.g. with [`egui::Frame::inner_margin`] of the central panel.
+    ///
+    /// Only non-zero with a transparent titlebar on macOS, where the window buttons are drawn on
+    /// top of the content. On iOS, the screen rect of egui already excludes the notch and home
+    /// indicator, so panels stay clear of them without any insets.
+    pub fn safe_area_insets(&self) -> egui::Margin {
+        if self.transparent_titlebar
+            && self.decorations
+            && self.fullscreen_mode == FullscreenMode::Windowed
+        {
+            egui::Margin {
+                top: MACOS_TITLEBAR_HEIGHT,
+                ..Default::default()
+            }
+        } else {
+            egui::Margin::ZERO
+        }
+    }
+
+    pub fn touch_gestures(&self) -> TouchGestures {
+        self.touch_gestures
+    }
+
+    /// Chooses which multi-touch gestures zoom and scroll the UI, starting with the next frame.
+    pub fn set_touch_gestures(&mut self, touch_gestures: TouchGestures) {
+        self.touch_gestures = touch_gestures;
+    }
+
+    /// The connected monitors, refreshed once per second while frames are drawn.
+    pub fn monitors(&self) -> &[MonitorInfo] {
+        self.monitors.monitors()
+    }
+
+    /// Index into [`Self::monitors`] of the monitor the window center is on.
+    pub fn current_monitor(&self) -> Option<usize> {
+        self.monitors.current()
+    }
+
+    /// Moves the window onto a monitor after this update, leaving fullscreen.
+    pub fn place_window(&mut self, placement: MonitorPlacement) {
+        self.window_commands.push(WindowCommand::Place(placement));
+    }
+
+    /// Replaces the icon of the window and its taskbar entry after this update, e.g. with one
+    /// from [`WindowIcon::with_badge`]. `None` restores the platform default.
+    pub fn set_icon(&mut self, icon: Option<WindowIcon>) {
+        self.window_commands.push(WindowCommand::SetIcon(icon));
+    }
+
+    pub fn backdrop(&self) -> Backdrop {
+        self.backdrop
+    }
+
+    /// Changes the effect drawn by the OS behind the transparent parts of the window after this
+    /// update.
+    pub fn set_backdrop(&mut self, backdrop: Backdrop) {
+        if backdrop != self.backdrop {
+            self.window_commands.push(WindowCommand::SetBackdrop {
+                previous: self.backdrop,
+                backdrop,
+            });
+            self.backdrop = backdrop;
+        }
+    }
+
+    /// Starts resizing the window with the mouse from the given edge. Must be called while the
+    /// primary mouse button is pressed.
+    pub fn begin_resize(&mut self, direction: ResizeDirection) {
+        self.window_commands
+            .push(WindowCommand::BeginResize(direction));
+    }
+
+    /// The present mode currently used by the surface.
+    pub fn present_mode(&self) -> PresentMode {
+        self.present_mode
+    }
+
+    pub fn supported_present_modes(&self) -> &[PresentMode] {
+        &self.supported_present_modes
+    }
+
+    /// Switches the present mode (e.g. to toggle vsync) after this update, falling back to a
+    /// similar mode if it is not supported.
+    pub fn set_present_mode(&mut self, present_mode: PresentMode) {
+        if present_mode != self.present_mode {
+            self.requested_present_mode = Some(present_mode);
+        }
+    }
+
+    pub fn shortcuts(&self) -> &Shortcuts {
+        &self.shortcuts
+    }
+
+    /// Registers and remaps shortcuts, changes apply from the next frame on.
+    pub fn shortcuts_mut(&mut self) -> &mut Shortcuts {
+        &mut self.shortcuts
+    }
+
+    /// Whether the shortcut of `action` was pressed this frame.
+    pub fn action_triggered(&self, action: &Action) -> bool {
+        self.triggered_actions.contains(action)
+    }
+
+    /// Performs `action` at the start of the next frame as if its shortcut was pressed.
+    pub fn trigger_action(&mut self, action: Action) {
+        self.pending_actions.push(action);
+    }
+
+    /// Saves the persisted state and closes the window after this update, without asking
+    /// [`crate::App::can_close`].
+    pub fn quit(&mut self) {
+        self.quit_requested = true;
+    }
+
+    /// Closes the window like its close button, if [`crate::App::can_close`] allows it or the user
+    /// confirms.
+    pub fn request_close(&mut self) {
+        self.close_requested = true;
+    }
+
+    /// Quits after a close request if `can_close`, otherwise once the user confirmed.
+    pub(crate) fn handle_close_request(
+        &mut self,
+        ctx: &egui::Context,
+        can_close: impl FnOnce() -> bool,
+    ) {
+        if std::mem::take(&mut self.close_requested) && self.close_confirmation.is_none() {
+            if can_close() {
+                self.quit();
+            } else {
+                self.close_confirmation = Some(self.dialogs.confirm(CLOSE_CONFIRMATION));
+                ctx.request_repaint();
+            }
+        }
+        let confirmed = self
+            .close_confirmation
+            .as_ref()
+            .and_then(|confirmation| confirmation.take_result());
+        if let Some(confirmed) = confirmed {
+            self.close_confirmation = None;
+            if confirmed {
+                self.quit();
+            }
+        }
+    }
+
+    /// Consumes the pressed shortcuts and performs the built-in actions, including the ones
+    /// triggered with [`Self::trigger_action`].
+    pub(crate) fn handle_shortcuts(&mut self, ctx: &egui::Context) {
+        self.triggered_actions = std::mem::take(&mut self.pending_actions);
+        self.triggered_actions.extend(self.shortcuts.consume(ctx));
+        for action in self.triggered_actions.clone() {
+            match action {
+                Action::Quit => self.request_close(),
+                Action::ToggleFullscreen => self.set_fullscreen_mode(match self.fullscreen_mode {
+                    FullscreenMode::Windowed => FullscreenMode::Borderless,
+                    FullscreenMode::Borderless | FullscreenMode::Exclusive => {
+                        FullscreenMode::Windowed
+                    }
+                }),
+                Action::Screenshot => {
+                    self.request_screenshot(screenshot::default_screenshot_path())
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                Action::ToggleRecording => self.toggle_recording(),
+                #[cfg(not(target_arch = "wasm32"))]
+                Action::SaveReplay => self.save_replay(),
+                #[cfg(target_arch = "wasm32")]
+                Action::ToggleRecording | Action::SaveReplay => {}
+                Action::ToggleClickThrough => self.set_click_through(!self.click_through()),
+                Action::ToggleSettings => self.settings.set_visible(!self.settings.visible()),
+                Action::CommandPalette => self
+                    .command_palette
+                    .set_visible(!self.command_palette.visible()),
+                Action::ToggleDiagnostics => {
+                    self.set_diagnostics_visible(!self.diagnostics.visible())
+                }
+                Action::ZoomIn => egui::gui_zoom::zoom_in(ctx),
+                Action::ZoomOut => egui::gui_zoom::zoom_out(ctx),
+                Action::ZoomReset => ctx.set_zoom_factor(1.0),
+                Action::Undo | Action::Redo | Action::Custom(_) => {}
+            }
+        }
+    }
+
+    /// A handle to wake up the UI and send messages to the app from other threads.
+    pub fn proxy(&self) -> EventLoopProxy {
+        self.proxy.clone()
+    }
+
+    /// Takes the messages of type `T` sent through the [`EventLoopProxy`] since the last update.
+    pub fn take_messages<T: Any>(&mut self) -> Vec<T> {
+        let mut taken = Vec::new();
+        let mut remaining = Vec::new();
+        for message in self.messages.drain(..) {
+            match message.downcast::<T>() {
+                Ok(message) => taken.push(*message),
+                Err(message) => remaining.push(message),
+            }
+        }
+        self.messages = remaining;
+        taken
+    }
+
+    /// Tears down and recreates the device, surface and egui renderer before the next frame, as
+    /// happens automatically when the device is lost. Useful to test that the app recreates
+    /// its resources in [`crate::App::setup`].
+    pub fn recreate_gpu(&mut self) {
+        self.gpu_recreate_requested = true;
+    }
+
+    /// Validation and out of memory errors reported by wgpu.
+    pub fn gpu_errors(&self) -> &GpuErrors {
+        &self.gpu_errors
+    }
+
+    pub fn gpu_errors_mut(&mut self) -> &mut GpuErrors {
+        &mut self.gpu_errors
+    }
+
+    /// Whether the scene is rendered in HDR, requested with [`crate::AppBuilder::with_hdr`].
+    pub fn hdr(&self) -> bool {
+        self.hdr
+    }
+
+    pub fn hdr_settings(&self) -> &HdrSettings {
+        &self.hdr_settings
+    }
+
+    /// Exposure and tone mapping of the HDR scene, applied to the next rendered frame.
+    pub fn hdr_settings_mut(&mut self) -> &mut HdrSettings {
+        &mut self.hdr_settings
+    }
+
+    pub fn post_process(&self) -> &PostProcess {
+        &self.post_process
+    }
+
+    /// Full-screen effects applied to the scene before egui is drawn, see
+    /// [`crate::post_process`].
+    pub fn post_process_mut(&mut self) -> &mut PostProcess {
+        &mut self.post_process
+    }
+
+    pub fn frosted_glass(&self) -> &FrostedGlass {
+        &self.frosted_glass
+    }
+
+    /// The areas of this frame which blur the scene behind them, see [`crate::frosted_glass`].
+    pub fn frosted_glass_mut(&mut self) -> &mut FrostedGlass {
+        &mut self.frosted_glass
+    }
+
+    /// Registers wgpu textures with egui so they can be shown with `ui.image`.
+    pub fn textures(&self) -> &TextureRegistry {
+        &self.textures
+    }
+
+    /// The device, queue, adapter and egui renderer, for custom pipelines and compute passes.
+    pub fn gpu(&self) -> &GpuContext {
+        &self.gpu
+    }
+
+    /// The built-in settings window, see [`crate::settings`].
+    pub fn settings(&self) -> &SettingsWindow {
+        &self.settings
+    }
+
+    pub fn settings_mut(&mut self) -> &mut SettingsWindow {
+        &mut self.settings
+    }
+
+    /// The built-in and app plugins, see [`crate::plugin`].
+    pub fn plugins(&self) -> &Plugins {
+        &self.plugins
+    }
+
+    pub fn plugins_mut(&mut self) -> &mut Plugins {
+        &mut self.plugins
+    }
+
+    /// The built-in command palette, see [`crate::command_palette`].
+    pub fn command_palette(&self) -> &CommandPalette {
+        &self.command_palette
+    }
+
+    pub fn command_palette_mut(&mut self) -> &mut CommandPalette {
+        &mut self.command_palette
+    }
+
+    /// Modal dialogs, see [`crate::dialogs`].
+    pub fn dialogs(&self) -> &Dialogs {
+        &self.dialogs
+    }
+
+    pub fn dialogs_mut(&mut self) -> &mut Dialogs {
+        &mut self.dialogs
+    }
+
+    /// Frame time history, shown as a plot that can also be toggled with a shortcut (F3).
+    pub fn diagnostics(&self) -> &Diagnostics {
+        &self.diagnostics
+    }
+
+    pub fn set_diagnostics_visible(&mut self, visible: bool) {
+        self.diagnostics.set_visible(visible);
+    }
+
+    #[cfg(feature = "puffin")]
+    pub fn profiler_visible(&self) -> bool {
+        self.profiler.visible()
+    }
+
+    /// Shows or hides the puffin flamegraph window.
+    #[cfg(feature = "puffin")]
+    pub fn set_profiler_visible(&mut self, visible: bool) {
+        self.profiler.set_visible(visible);
+    }
+}
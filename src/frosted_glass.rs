@@ -0,0 +1,443 @@
+//! Blurs the scene behind UI panels, like frosted glass.
+//!
+//! Panels register the area they cover each frame. The scene rendered with
+//! [`crate::App::scene_renderer`] is blurred or pixelated there before egui draws on top, so
+//! panels should have a transparent or translucent fill:
+//!
+//! ```no_run
+//! # fn update(ctx: &egui::Context, frame: &mut egui_tests::Frame) {
+//! let panel = egui::SidePanel::left("tools")
+//!     .frame(egui::Frame::side_top_panel(&ctx.style()).fill(egui::Color32::TRANSPARENT))
+//!     .show(ctx, |ui| ui.label("Over a blurred scene"));
+//! frame.frosted_glass_mut().add(panel.response.rect);
+//! # }
+//! ```
+//!
+//! The areas are rectangles, rounded corners of windows are not cut out. With HDR enabled, the
+//! frosted areas are clamped to the displayable range instead of being tone mapped.
+
+use egui::{Color32, Rect};
+
+use crate::uploads::Uploads;
+
+/// Half the resolution of the scene, which also widens the blur.
+const DOWNSAMPLE: u32 = 2;
+
+/// How the scene behind panels is obscured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FrostStyle {
+    #[default]
+    Blur,
+    Pixelate,
+}
+
+/// The areas of the current frame which obscure the scene, see [`crate::Frame::frosted_glass_mut`].
+#[derive(Debug, Clone)]
+pub struct FrostedGlass {
+    rects: Vec<Rect>,
+    style: FrostStyle,
+    radius: f32,
+    tint: Color32,
+}
+
+impl Default for FrostedGlass {
+    fn default() -> Self {
+        Self {
+            rects: Vec::new(),
+            style: FrostStyle::Blur,
+            radius: 16.0,
+            tint: Color32::from_white_alpha(16),
+        }
+    }
+}
+
+impl FrostedGlass {
+    /// Obscures the scene behind `rect` in points for this frame.
+    pub fn add(&mut self, rect: Rect) {
+        if rect.is_positive() {
+            self.rects.push(rect);
+        }
+    }
+
+    pub fn rects(&self) -> &[Rect] {
+        &self.rects
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rects.is_empty()
+    }
+
+    pub fn style(&self) -> FrostStyle {
+        self.style
+    }
+
+    pub fn set_style(&mut self, style: FrostStyle) {
+        self.style = style;
+    }
+
+    /// Blur radius, or block size when pixelating, in pixels. 16 by default.
+    pub fn radius(&self) -> f32 {
+        self.radius
+    }
+
+    pub fn set_radius(&mut self, radius: f32) {
+        self.radius = radius.max(1.0);
+    }
+
+    /// Color laid over the frosted areas, a faint white by default.
+    pub fn tint(&self) -> Color32 {
+        self.tint
+    }
+
+    pub fn set_tint(&mut self, tint: Color32) {
+        self.tint = tint;
+    }
+
+    /// Drops the areas of the previous frame.
+    pub(crate) fn begin_frame(&mut self) {
+        self.rects.clear();
+    }
+}
+
+/// Blurs the scene texture and draws it into the render target at the frosted areas.
+pub(crate) struct FrostRenderer {
+    blur_pipeline: wgpu::RenderPipeline,
+    draw_pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    /// Parameters of the horizontal and vertical blur and of drawing, written separately since
+    /// all passes are submitted together.
+    params: [wgpu::Buffer; 3],
+    scene_format: wgpu::TextureFormat,
+    target: Option<BlurTarget>,
+}
+
+struct BlurTarget {
+    /// Horizontally and then fully blurred scene at a reduced resolution.
+    views: [wgpu::TextureView; 2],
+    size: [u32; 2],
+}
+
+impl FrostRenderer {
+    /// Creates the pipelines for a scene in `scene_format`, drawn into the render target in
+    /// `format`.
+    pub fn new(
+        device: &wgpu::Device,
+        scene_format: wgpu::TextureFormat,
+        format: wgpu::TextureFormat,
+        msaa_samples: u32,
+    ) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("frosted glass"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/frosted_glass.wgsl").into()),
+        });
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("frosted glass"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("frosted glass"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let create_pipeline = |label, entry_point, format: wgpu::TextureFormat, count| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: "vs_main",
+                    buffers: &[],
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point,
+                    targets: &[Some(format.into())],
+                }),
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState {
+                    count,
+                    ..Default::default()
+                },
+                multiview: None,
+            })
+        };
+        let blur_pipeline = create_pipeline("frosted glass blur", "fs_blur", scene_format, 1);
+        let draw_pipeline = create_pipeline("frosted glass draw", "fs_draw", format, msaa_samples);
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("frosted glass"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        let params = [(); 3].map(|_| {
+            device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("frosted glass params"),
+                size: 48,
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            })
+        });
+
+        Self {
+            blur_pipeline,
+            draw_pipeline,
+            bind_group_layout,
+            sampler,
+            params,
+            scene_format,
+            target: None,
+        }
+    }
+
+    /// Obscures the areas of `frosted_glass` in `output` with the blurred `scene`, which is
+    /// `size` pixels large. Rects are converted to pixels with `pixels_per_point`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn render(
+        &mut self,
+        device: &wgpu::Device,
+        uploads: &mut Uploads,
+        encoder: &mut wgpu::CommandEncoder,
+        scene: &wgpu::TextureView,
+        output: &wgpu::TextureView,
+        size: [u32; 2],
+        pixels_per_point: f32,
+        frosted_glass: &FrostedGlass,
+    ) {
+        let scissors: Vec<_> = frosted_glass
+            .rects
+            .iter()
+            .filter_map(|rect| scissor_rect(*rect, pixels_per_point, size))
+            .collect();
+        if scissors.is_empty() {
+            return;
+        }
+
+        let (input, input_size) = match frosted_glass.style {
+            FrostStyle::Blur => {
+                self.blur(device, uploads, encoder, scene, size, frosted_glass.radius);
+                let target = self.target.as_ref().unwrap();
+                (&target.views[1], target.size)
+            }
+            FrostStyle::Pixelate => (scene, size),
+        };
+        let mode: u32 = match frosted_glass.style {
+            FrostStyle::Blur => 0,
+            FrostStyle::Pixelate => 1,
+        };
+        let tint = egui::Rgba::from(frosted_glass.tint).to_array();
+        self.write_params(
+            device,
+            uploads,
+            encoder,
+            2,
+            input_size,
+            [0.0, 0.0],
+            tint,
+            frosted_glass.radius,
+            mode,
+        );
+        let bind_group = self.bind_group(device, input, 2);
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("frosted glass draw"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: output,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        render_pass.set_pipeline(&self.draw_pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[]);
+        for [x, y, width, height] in scissors {
+            render_pass.set_scissor_rect(x, y, width, height);
+            render_pass.draw(0..3, 0..1);
+        }
+    }
+
+    /// Blurs `scene` horizontally into the first and vertically into the second view of the
+    /// target.
+    fn blur(
+        &mut self,
+        device: &wgpu::Device,
+        uploads: &mut Uploads,
+        encoder: &mut wgpu::CommandEncoder,
+        scene: &wgpu::TextureView,
+        size: [u32; 2],
+        radius: f32,
+    ) {
+        let target_size = size.map(|side| (side / DOWNSAMPLE).max(1));
+        if !matches!(&self.target, Some(target) if target.size == target_size) {
+            self.target = Some(self.create_target(device, target_size));
+        }
+        // The 9 taps reach 4 steps to either side
+        let step = radius / 4.0;
+        self.write_params(
+            device,
+            uploads,
+            encoder,
+            0,
+            size,
+            [step, 0.0],
+            [0.0; 4],
+            0.0,
+            0,
+        );
+        self.write_params(
+            device,
+            uploads,
+            encoder,
+            1,
+            target_size,
+            [0.0, step / DOWNSAMPLE as f32],
+            [0.0; 4],
+            0.0,
+            0,
+        );
+
+        let target = self.target.as_ref().unwrap();
+        for (pass, input) in [scene, &target.views[0]].into_iter().enumerate() {
+            let bind_group = self.bind_group(device, input, pass);
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("frosted glass blur"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &target.views[pass],
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            render_pass.set_pipeline(&self.blur_pipeline);
+            render_pass.set_bind_group(0, &bind_group, &[]);
+            render_pass.draw(0..3, 0..1);
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn write_params(
+        &self,
+        device: &wgpu::Device,
+        uploads: &mut Uploads,
+        encoder: &mut wgpu::CommandEncoder,
+        index: usize,
+        size: [u32; 2],
+        direction: [f32; 2],
+        tint: [f32; 4],
+        block_size: f32,
+        mode: u32,
+    ) {
+        let mut params = Vec::with_capacity(48);
+        for value in [size[0] as f32, size[1] as f32]
+            .into_iter()
+            .chain(direction)
+            .chain(tint)
+            .chain([block_size])
+        {
+            params.extend_from_slice(&value.to_le_bytes());
+        }
+        params.extend_from_slice(&mode.to_le_bytes());
+        params.extend_from_slice(&[0; 8]);
+        uploads.write(device, encoder, &self.params[index], 0, &params);
+    }
+
+    fn bind_group(
+        &self,
+        device: &wgpu::Device,
+        input: &wgpu::TextureView,
+        params: usize,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("frosted glass"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(input),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: self.params[params].as_entire_binding(),
+                },
+            ],
+        })
+    }
+
+    fn create_target(&self, device: &wgpu::Device, size: [u32; 2]) -> BlurTarget {
+        let views = [(); 2].map(|_| {
+            device
+                .create_texture(&wgpu::TextureDescriptor {
+                    label: Some("frosted glass blur"),
+                    size: wgpu::Extent3d {
+                        width: size[0],
+                        height: size[1],
+                        depth_or_array_layers: 1,
+                    },
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format: self.scene_format,
+                    usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                        | wgpu::TextureUsages::TEXTURE_BINDING,
+                    view_formats: &[],
+                })
+                .create_view(&wgpu::TextureViewDescriptor::default())
+        });
+        BlurTarget { views, size }
+    }
+}
+
+/// `rect` in points as a scissor rect `[x, y, width, height]` in pixels, clipped to `size`.
+pub fn scissor_rect(rect: Rect, pixels_per_point: f32, size: [u32; 2]) -> Option<[u32; 4]> {
+    let min = (rect.min.to_vec2() * pixels_per_point).round();
+    let max = (rect.max.to_vec2() * pixels_per_point).round();
+    let x = (min.x.max(0.0) as u32).min(size[0]);
+    let y = (min.y.max(0.0) as u32).min(size[1]);
+    let width = (max.x.max(0.0) as u32).min(size[0]).saturating_sub(x);
+    let height = (max.y.max(0.0) as u32).min(size[1]).saturating_sub(y);
+    (width > 0 && height > 0).then_some([x, y, width, height])
+}
@@ -0,0 +1,114 @@
+//! Gamepad input translated into egui keyboard navigation, for setups without mouse and keyboard.
+//!
+//! | Gamepad                         | egui                                      |
+//! |---------------------------------|-------------------------------------------|
+//! | D-pad or left stick down, right | `Tab`, focuses the next widget            |
+//! | D-pad or left stick up, left    | `Shift+Tab`, focuses the previous widget  |
+//! | South (A / Cross)               | `Enter`, activates the focused widget     |
+//! | East (B / Circle)               | `Escape`, closes popups                   |
+//! | Right stick                     | Scrolling                                 |
+
+use egui::{Key, Modifiers, Vec2};
+use gilrs::{Axis, Button, EventType, Gilrs};
+
+use crate::{proxy::UserEvent, EventLoopProxy};
+
+/// How far a stick has to be tilted to count as a direction.
+const STICK_THRESHOLD: f32 = 0.5;
+
+/// Points scrolled per gamepad event at full right stick tilt.
+const SCROLL_SPEED: f32 = 40.0;
+
+/// Starts reading gamepads on a background thread, sending the translated egui events to the
+/// event loop.
+pub(crate) fn spawn(proxy: EventLoopProxy) {
+    std::thread::Builder::new()
+        .name("gamepad".to_owned())
+        .spawn(move || {
+            let mut gilrs = match Gilrs::new() {
+                Ok(gilrs) => gilrs,
+                Err(err) => {
+                    log::warn!("Gamepads are not available: {err}");
+                    return;
+                }
+            };
+            let mut sticks = StickState::default();
+            while let Some(event) = gilrs.next_event_blocking(None) {
+                let events = sticks.translate(event.event);
+                if !events.is_empty() {
+                    proxy.send_event(UserEvent::Gamepad(events));
+                }
+            }
+        })
+        .expect("Failed to spawn the gamepad thread");
+}
+
+/// Direction the left stick currently points to, so tilting it only moves the focus once.
+#[derive(Debug, Default)]
+struct StickState {
+    x: i8,
+    y: i8,
+}
+
+impl StickState {
+    fn translate(&mut self, event: EventType) -> Vec<egui::Event> {
+        match event {
+            EventType::ButtonPressed(button, _) => match button {
+                Button::DPadDown | Button::DPadRight => key_press(Key::Tab, Modifiers::NONE),
+                Button::DPadUp | Button::DPadLeft => key_press(Key::Tab, Modifiers::SHIFT),
+                Button::South => key_press(Key::Enter, Modifiers::NONE),
+                Button::East => key_press(Key::Escape, Modifiers::NONE),
+                _ => Vec::new(),
+            },
+            EventType::AxisChanged(axis @ (Axis::LeftStickX | Axis::LeftStickY), value, _) => {
+                let direction = if value > STICK_THRESHOLD {
+                    1
+                } else if value < -STICK_THRESHOLD {
+                    -1
+                } else {
+                    0
+                };
+                let previous = match axis {
+                    Axis::LeftStickX => &mut self.x,
+                    _ => &mut self.y,
+                };
+                if std::mem::replace(previous, direction) == direction {
+                    return Vec::new();
+                }
+                // Up on the stick is positive, unlike in egui
+                let forward = match axis {
+                    Axis::LeftStickX => direction > 0,
+                    _ => direction < 0,
+                };
+                match direction {
+                    0 => Vec::new(),
+                    _ if forward => key_press(Key::Tab, Modifiers::NONE),
+                    _ => key_press(Key::Tab, Modifiers::SHIFT),
+                }
+            }
+            EventType::AxisChanged(Axis::RightStickX, value, _) => {
+                vec![egui::Event::Scroll(Vec2::new(-value * SCROLL_SPEED, 0.0))]
+            }
+            EventType::AxisChanged(Axis::RightStickY, value, _) => {
+                vec![egui::Event::Scroll(Vec2::new(0.0, value * SCROLL_SPEED))]
+            }
+            EventType::Connected => {
+                log::info!("Gamepad connected");
+                Vec::new()
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+fn key_press(key: Key, modifiers: Modifiers) -> Vec<egui::Event> {
+    [true, false]
+        .into_iter()
+        .map(|pressed| egui::Event::Key {
+            key,
+            pressed,
+            repeat: false,
+            modifiers,
+        })
+        .collect()
+}
@@ -0,0 +1,49 @@
+//! Multi-touch gestures mapped to egui zoom and scrolling.
+//!
+//! winit forwards touch events to egui, which only turns the first touch into pointer input.
+//! Gestures with more fingers are picked up from [`egui::InputState::multi_touch`].
+
+use egui::Context;
+
+/// Zoom factors the pinch gesture is limited to, like egui's keyboard zoom.
+const MIN_ZOOM_FACTOR: f32 = 0.2;
+const MAX_ZOOM_FACTOR: f32 = 5.0;
+
+/// Which gestures change the whole UI, see [`crate::Frame::set_touch_gestures`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TouchGestures {
+    /// Pinching changes [`egui::Context::zoom_factor`].
+    pub pinch_to_zoom: bool,
+    /// Moving two fingers scrolls the scroll area under them.
+    ///
+    /// Only applies while the first finger is not dragging anything, which already scrolls
+    /// scroll areas by itself.
+    pub two_finger_scroll: bool,
+}
+
+impl Default for TouchGestures {
+    fn default() -> Self {
+        Self {
+            pinch_to_zoom: true,
+            two_finger_scroll: true,
+        }
+    }
+}
+
+impl TouchGestures {
+    /// Applies the gestures of the current frame, before the UI is built.
+    pub(crate) fn apply(&self, ctx: &Context) {
+        let Some(touch) = ctx.input(|input| input.multi_touch()) else {
+            return;
+        };
+
+        if self.pinch_to_zoom && touch.zoom_delta != 1.0 {
+            let zoom_factor = ctx.zoom_factor() * touch.zoom_delta;
+            ctx.set_zoom_factor(zoom_factor.clamp(MIN_ZOOM_FACTOR, MAX_ZOOM_FACTOR));
+        }
+
+        if self.two_finger_scroll && touch.num_touches == 2 && !ctx.is_using_pointer() {
+            ctx.input_mut(|input| input.scroll_delta += touch.translation_delta);
+        }
+    }
+}
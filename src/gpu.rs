@@ -0,0 +1,88 @@
+use std::sync::Arc;
+
+use egui::mutex::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// The wgpu objects used to render the window, for creating custom pipelines, buffers and
+/// compute passes.
+///
+/// ```no_run
+/// # fn example(frame: &egui_tests::Frame) {
+/// let gpu = frame.gpu();
+/// let buffer = gpu.device().create_buffer(&wgpu::BufferDescriptor {
+///     label: Some("particles"),
+///     size: 1024,
+///     usage: wgpu::BufferUsages::STORAGE,
+///     mapped_at_creation: false,
+/// });
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct GpuContext {
+    pub(crate) adapter: Arc<wgpu::Adapter>,
+    pub(crate) device: Arc<wgpu::Device>,
+    pub(crate) queue: Arc<wgpu::Queue>,
+    pub(crate) renderer: Arc<RwLock<egui_wgpu::Renderer>>,
+    pub(crate) surface_config: wgpu::SurfaceConfiguration,
+    pub(crate) format: wgpu::TextureFormat,
+    pub(crate) msaa_samples: u32,
+}
+
+impl std::fmt::Debug for GpuContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GpuContext")
+            .field("surface_config", &self.surface_config)
+            .field("format", &self.format)
+            .field("msaa_samples", &self.msaa_samples)
+            .finish_non_exhaustive()
+    }
+}
+
+impl GpuContext {
+    pub fn adapter(&self) -> &wgpu::Adapter {
+        &self.adapter
+    }
+
+    pub fn device(&self) -> &wgpu::Device {
+        &self.device
+    }
+
+    pub fn queue(&self) -> &wgpu::Queue {
+        &self.queue
+    }
+
+    /// Configuration of the window surface as of the start of the current frame.
+    pub fn surface_config(&self) -> &wgpu::SurfaceConfiguration {
+        &self.surface_config
+    }
+
+    /// Format everything is rendered with, which may be an sRGB view of the surface format.
+    pub fn target_format(&self) -> wgpu::TextureFormat {
+        self.format
+    }
+
+    /// Sample count of the color target scenes and egui are drawn into.
+    pub fn msaa_samples(&self) -> u32 {
+        self.msaa_samples
+    }
+
+    /// The renderer drawing egui, e.g. to register custom paint callback resources.
+    pub fn renderer(&self) -> RwLockReadGuard<'_, egui_wgpu::Renderer> {
+        self.renderer.read()
+    }
+
+    /// Locks the egui renderer for writing. Must not be held while the frame is rendered.
+    pub fn renderer_mut(&self) -> RwLockWriteGuard<'_, egui_wgpu::Renderer> {
+        self.renderer.write()
+    }
+
+    /// Stores `resources` for paint callbacks to look up by type, replacing earlier ones of the
+    /// same type. See [`crate::paint_callback`].
+    pub fn insert_callback_resources<T: Send + Sync + 'static>(&self, resources: T) {
+        self.renderer.write().callback_resources.insert(resources);
+    }
+
+    /// Whether resources of type `T` are stored. They are dropped when the device is recreated.
+    pub fn has_callback_resources<T: Send + Sync + 'static>(&self) -> bool {
+        self.renderer.read().callback_resources.contains::<T>()
+    }
+}
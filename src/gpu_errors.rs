@@ -0,0 +1,216 @@
+use std::{collections::VecDeque, sync::Arc};
+
+use egui::mutex::Mutex;
+
+/// Number of errors kept in the history, older ones are dropped.
+const HISTORY_LEN: usize = 100;
+
+/// How wgpu-core describes operations on a lost device. wgpu 0.18 has no device lost callback,
+/// so errors are the only way to notice the loss.
+const DEVICE_LOST_MESSAGE: &str = "Parent device is lost";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GpuErrorKind {
+    Validation,
+    OutOfMemory,
+}
+
+/// An error reported by wgpu.
+#[derive(Debug, Clone)]
+pub struct GpuError {
+    pub kind: GpuErrorKind,
+    pub message: String,
+}
+
+impl GpuError {
+    /// Whether the error was caused by the device being lost.
+    pub fn is_device_lost(&self) -> bool {
+        self.message.contains(DEVICE_LOST_MESSAGE)
+    }
+}
+
+impl From<wgpu::Error> for GpuError {
+    fn from(err: wgpu::Error) -> Self {
+        let kind = match err {
+            wgpu::Error::OutOfMemory { .. } => GpuErrorKind::OutOfMemory,
+            wgpu::Error::Validation { .. } => GpuErrorKind::Validation,
+        };
+        Self {
+            kind,
+            message: err.to_string(),
+        }
+    }
+}
+
+type ErrorCallback = Box<dyn FnMut(&GpuError)>;
+
+/// Errors reported by wgpu, collected into a panel instead of panicking.
+///
+/// The GPU work of each frame is wrapped in error scopes for validation and out of memory
+/// errors, errors outside of them are caught by the uncaptured error handler.
+pub struct GpuErrors {
+    /// Filled by the error handler and the error scopes, which may complete asynchronously.
+    pending: Arc<Mutex<Vec<GpuError>>>,
+    errors: VecDeque<GpuError>,
+    break_on_error: bool,
+    visible: bool,
+    callback: Option<ErrorCallback>,
+    device_lost: bool,
+}
+
+impl std::fmt::Debug for GpuErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GpuErrors")
+            .field("errors", &self.errors)
+            .field("break_on_error", &self.break_on_error)
+            .field("visible", &self.visible)
+            .finish_non_exhaustive()
+    }
+}
+
+impl GpuErrors {
+    /// Replaces the default handler of `device`, which panics on uncaptured errors.
+    pub(crate) fn new(device: &wgpu::Device) -> Self {
+        let errors = Self {
+            pending: Arc::new(Mutex::new(Vec::new())),
+            errors: VecDeque::new(),
+            break_on_error: false,
+            visible: false,
+            callback: None,
+            device_lost: false,
+        };
+        errors.attach(device);
+        errors
+    }
+
+    /// Installs the error handler on a recreated device, keeping the history.
+    pub(crate) fn attach(&self, device: &wgpu::Device) {
+        let sink = self.pending.clone();
+        device.on_uncaptured_error(Box::new(move |err| sink.lock().push(err.into())));
+    }
+
+    /// Whether an error since the last call reported that the device was lost.
+    pub(crate) fn take_device_lost(&mut self) -> bool {
+        std::mem::take(&mut self.device_lost)
+    }
+
+    /// Starts capturing the errors of a frame.
+    pub(crate) fn push_scopes(&self, device: &wgpu::Device) {
+        device.push_error_scope(wgpu::ErrorFilter::OutOfMemory);
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+    }
+
+    /// Stops capturing the errors of a frame. They are reported by the next [`Self::collect`]
+    /// once the scopes resolved.
+    pub(crate) fn pop_scopes(&self, device: &wgpu::Device) {
+        for scope in [device.pop_error_scope(), device.pop_error_scope()] {
+            let pending = self.pending.clone();
+            let report = async move {
+                if let Some(err) = scope.await {
+                    pending.lock().push(err.into());
+                }
+            };
+            #[cfg(not(target_arch = "wasm32"))]
+            pollster::block_on(report);
+            #[cfg(target_arch = "wasm32")]
+            wasm_bindgen_futures::spawn_local(report);
+        }
+    }
+
+    /// Whether errors arrived which have not been collected yet.
+    pub(crate) fn has_pending(&self) -> bool {
+        !self.pending.lock().is_empty()
+    }
+
+    /// Moves newly reported errors into the history, opening the panel.
+    ///
+    /// # Panics
+    ///
+    /// If [`Self::break_on_error`] is set and an error was reported.
+    pub(crate) fn collect(&mut self) {
+        let pending = std::mem::take(&mut *self.pending.lock());
+        for error in pending {
+            log::error!("wgpu {:?} error: {}", error.kind, error.message);
+            if error.is_device_lost() {
+                self.device_lost = true;
+            }
+            if self.break_on_error {
+                panic!("wgpu error: {}", error.message);
+            }
+            if let Some(callback) = &mut self.callback {
+                callback(&error);
+            }
+            if self.errors.len() == HISTORY_LEN {
+                self.errors.pop_front();
+            }
+            self.errors.push_back(error);
+            self.visible = true;
+        }
+    }
+
+    /// Reported errors, oldest first.
+    pub fn errors(&self) -> impl ExactSizeIterator<Item = &GpuError> {
+        self.errors.iter()
+    }
+
+    pub fn clear(&mut self) {
+        self.errors.clear();
+    }
+
+    pub fn break_on_error(&self) -> bool {
+        self.break_on_error
+    }
+
+    /// Panics on the next error like wgpu does by default, e.g. to inspect it in a debugger.
+    pub fn set_break_on_error(&mut self, break_on_error: bool) {
+        self.break_on_error = break_on_error;
+    }
+
+    /// Calls `callback` for every error, on the main thread before the next update.
+    pub fn set_callback(&mut self, callback: impl FnMut(&GpuError) + 'static) {
+        self.callback = Some(Box::new(callback));
+    }
+
+    pub fn visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Shows or hides the panel, it opens by itself when an error is reported.
+    pub fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    /// Shows the panel if visible.
+    pub fn show(&mut self, ctx: &egui::Context) {
+        let mut visible = self.visible;
+        egui::Window::new("GPU errors")
+            .open(&mut visible)
+            .default_width(400.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.break_on_error, "Break on error");
+                    if ui.button("Clear").clicked() {
+                        self.errors.clear();
+                    }
+                });
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .stick_to_bottom(true)
+                    .show(ui, |ui| {
+                        if self.errors.is_empty() {
+                            ui.weak("No errors");
+                        }
+                        for error in &self.errors {
+                            let color = match error.kind {
+                                GpuErrorKind::Validation => ui.visuals().warn_fg_color,
+                                GpuErrorKind::OutOfMemory => ui.visuals().error_fg_color,
+                            };
+                            ui.colored_label(color, format!("{:?}", error.kind));
+                            ui.monospace(&error.message);
+                            ui.separator();
+                        }
+                    });
+            });
+        self.visible = visible;
+    }
+}
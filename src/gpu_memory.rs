@@ -0,0 +1,157 @@
+//! Counts the GPU textures and buffers allocated for the UI.
+//!
+//! Textures managed by egui are tracked through its texture deltas, native textures through the
+//! [`crate::textures::TextureRegistry`]. Native textures are registered by the app and have to
+//! be freed by it, so one that has not been drawn for a while is reported as possibly leaked.
+
+use std::{collections::HashMap, sync::Arc};
+
+use egui::{epaint::ImageDelta, mutex::Mutex, ClippedPrimitive, TextureId};
+
+/// Number of painted frames after which a native texture that was not drawn counts as unused.
+pub const UNUSED_AFTER_FRAMES: u64 = 600;
+
+/// Number and total size of allocations of one kind.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocationStats {
+    pub count: usize,
+    pub bytes: u64,
+}
+
+/// Snapshot of the memory tracked by [`GpuMemory`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GpuMemoryStats {
+    /// Font atlas and images uploaded by egui.
+    pub egui_textures: AllocationStats,
+    /// Textures of the app registered with the renderer. Their size is only known for
+    /// [`crate::textures::NativeTexture`]s.
+    pub native_textures: AllocationStats,
+    /// Vertex and index data of the last frame, which the buffers of the renderer have to hold.
+    pub buffers: AllocationStats,
+    /// Native textures that are registered but were not drawn for [`UNUSED_AFTER_FRAMES`].
+    pub unused_textures: Vec<TextureId>,
+}
+
+struct NativeEntry {
+    bytes: Option<u64>,
+    last_used: u64,
+    reported: bool,
+}
+
+#[derive(Default)]
+struct Tracker {
+    egui_textures: HashMap<TextureId, u64>,
+    native_textures: HashMap<TextureId, NativeEntry>,
+    buffers: AllocationStats,
+    frame: u64,
+}
+
+/// Shared tracker of the GPU memory of the UI, see the [module docs](self).
+#[derive(Clone, Default)]
+pub struct GpuMemory {
+    tracker: Arc<Mutex<Tracker>>,
+}
+
+impl std::fmt::Debug for GpuMemory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GpuMemory").finish_non_exhaustive()
+    }
+}
+
+impl GpuMemory {
+    /// Applies a texture update sent by egui. Partial updates do not change the size.
+    pub fn set_texture(&self, id: TextureId, delta: &ImageDelta) {
+        if delta.pos.is_none() {
+            // egui_wgpu stores fonts and images as RGBA8
+            let bytes = (delta.image.width() * delta.image.height() * 4) as u64;
+            self.tracker.lock().egui_textures.insert(id, bytes);
+        }
+    }
+
+    pub fn free_texture(&self, id: TextureId) {
+        self.tracker.lock().egui_textures.remove(&id);
+    }
+
+    pub fn register_native(&self, id: TextureId) {
+        let mut tracker = self.tracker.lock();
+        let last_used = tracker.frame;
+        tracker.native_textures.insert(
+            id,
+            NativeEntry {
+                bytes: None,
+                last_used,
+                reported: false,
+            },
+        );
+    }
+
+    /// Sets the size of a registered native texture, e.g. after it was resized.
+    pub fn set_native_size(&self, id: TextureId, bytes: u64) {
+        if let Some(entry) = self.tracker.lock().native_textures.get_mut(&id) {
+            entry.bytes = Some(bytes);
+        }
+    }
+
+    pub fn free_native(&self, id: TextureId) {
+        self.tracker.lock().native_textures.remove(&id);
+    }
+
+    /// Records the buffer sizes and the textures drawn by a painted frame, and warns once about
+    /// every native texture that became unused.
+    pub fn record_frame(&self, paint_jobs: &[ClippedPrimitive]) {
+        let mut tracker = self.tracker.lock();
+        tracker.frame += 1;
+        let frame = tracker.frame;
+
+        let mut buffers = AllocationStats::default();
+        for job in paint_jobs {
+            if let egui::epaint::Primitive::Mesh(mesh) = &job.primitive {
+                if let Some(entry) = tracker.native_textures.get_mut(&mesh.texture_id) {
+                    entry.last_used = frame;
+                    entry.reported = false;
+                }
+                buffers.count += 1;
+                buffers.bytes += std::mem::size_of_val(mesh.vertices.as_slice()) as u64
+                    + std::mem::size_of_val(mesh.indices.as_slice()) as u64;
+            }
+        }
+        tracker.buffers = buffers;
+
+        for (id, entry) in &mut tracker.native_textures {
+            if !entry.reported && frame - entry.last_used >= UNUSED_AFTER_FRAMES {
+                entry.reported = true;
+                log::warn!(
+                    "Native texture {id:?} was not drawn for {UNUSED_AFTER_FRAMES} frames, \
+                     but is still registered. Free it when it is not needed anymore."
+                );
+            }
+        }
+    }
+
+    pub fn stats(&self) -> GpuMemoryStats {
+        let tracker = self.tracker.lock();
+        let mut unused_textures: Vec<TextureId> = tracker
+            .native_textures
+            .iter()
+            .filter(|(_, entry)| tracker.frame - entry.last_used >= UNUSED_AFTER_FRAMES)
+            .map(|(&id, _)| id)
+            .collect();
+        unused_textures.sort();
+        GpuMemoryStats {
+            egui_textures: AllocationStats {
+                count: tracker.egui_textures.len(),
+                bytes: tracker.egui_textures.values().sum(),
+            },
+            native_textures: AllocationStats {
+                count: tracker.native_textures.len(),
+                bytes: tracker
+                    .native_textures
+                    .values()
+                    .filter_map(|entry| entry.bytes)
+                    .sum(),
+            },
+            buffers: tracker.buffers,
+            unused_textures,
+        }
+    }
+}
@@ -0,0 +1,485 @@
+use std::sync::Arc;
+
+use wgpu::{Backends, InstanceDescriptor, PowerPreference, PresentMode};
+use winit::{dpi::PhysicalSize, window::Window};
+
+use crate::{
+    adapter::{backends_from_env, request_adapter},
+    hdr::{ToneMapper, HDR_FORMAT},
+    surface::ConfiguredSurface,
+};
+
+/// Settings used when creating the wgpu instance, device and surface.
+#[derive(Debug, Clone)]
+pub struct GraphicsConfig {
+    /// Backends to choose the adapter from, overridden by [`crate::adapter::BACKEND_ENV_VAR`].
+    pub backends: Backends,
+    /// Prefers discrete GPUs for [`PowerPreference::HighPerformance`] and integrated GPUs for
+    /// [`PowerPreference::LowPower`], see [`crate::adapter::select_adapter`].
+    pub power_preference: PowerPreference,
+    pub present_mode: PresentMode,
+    /// Number of samples per pixel used for multisample anti-aliasing, `1` disables MSAA.
+    pub msaa_samples: u32,
+    /// Render into an sRGB framebuffer, so the GPU converts egui's output to gamma space.
+    ///
+    /// If disabled, a linear (`Unorm`) framebuffer is used instead.
+    pub gamma_correction: bool,
+    /// Format of the depth buffer created for the scene, `None` disables it.
+    pub depth_format: Option<wgpu::TextureFormat>,
+    /// Renders the scene into a floating point target which is tone mapped before egui is drawn.
+    pub hdr: bool,
+    /// Requests [`wgpu::Limits::downlevel_defaults`] instead of the WebGL2 limits and disables
+    /// MSAA, for GLES3-only devices like the Raspberry Pi. Enabled by the `gles` feature.
+    pub downlevel: bool,
+    /// Configures the surface to be blended with what is behind the window, see
+    /// [`select_alpha_mode`]. Set from [`crate::window::WindowConfig::transparent`].
+    pub transparent: bool,
+}
+
+impl Default for GraphicsConfig {
+    fn default() -> Self {
+        Self {
+            backends: if cfg!(any(target_arch = "wasm32", feature = "gles")) {
+                Backends::GL
+            } else if cfg!(target_os = "ios") {
+                Backends::METAL
+            } else {
+                Backends::PRIMARY
+            },
+            power_preference: PowerPreference::default(),
+            present_mode: PresentMode::Fifo,
+            msaa_samples: 1,
+            gamma_correction: true,
+            depth_format: None,
+            hdr: false,
+            downlevel: cfg!(feature = "gles"),
+            transparent: false,
+        }
+    }
+}
+
+/// Owns the wgpu instance, device and the surface of the main window.
+pub struct Graphics {
+    pub instance: wgpu::Instance,
+    /// Shared with the app through [`crate::gpu::GpuContext`].
+    pub adapter: Arc<wgpu::Adapter>,
+    /// Shared with the [`crate::textures::TextureRegistry`] and [`crate::gpu::GpuContext`].
+    pub device: Arc<wgpu::Device>,
+    pub queue: Arc<wgpu::Queue>,
+    pub surface: wgpu::Surface,
+    pub config: wgpu::SurfaceConfiguration,
+    /// Format everything is rendered with, which may be an sRGB view of the surface format.
+    pub format: wgpu::TextureFormat,
+    pub msaa_samples: u32,
+    msaa_view: Option<wgpu::TextureView>,
+    pub depth_format: Option<wgpu::TextureFormat>,
+    depth_view: Option<wgpu::TextureView>,
+    /// Whether the scene is rendered in HDR, see [`crate::hdr`].
+    pub hdr: bool,
+    /// A surface format able to show colors beyond the SDR range, if the display supports one.
+    ///
+    /// The surface is still configured with an SDR format, HDR scenes are tone mapped into it.
+    pub hdr_surface_format: Option<wgpu::TextureFormat>,
+}
+
+/// Why the GPU context could not be created.
+#[derive(Debug)]
+pub enum GraphicsError {
+    CreateSurface(wgpu::CreateSurfaceError),
+    NoAdapter,
+    RequestDevice(wgpu::RequestDeviceError),
+    UnsupportedSurface,
+}
+
+impl std::fmt::Display for GraphicsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::CreateSurface(err) => write!(f, "failed to create surface: {err}"),
+            Self::NoAdapter => f.write_str("failed to find an appropriate adapter"),
+            Self::RequestDevice(err) => write!(f, "failed to create device: {err}"),
+            Self::UnsupportedSurface => f.write_str("the surface is not supported by the adapter"),
+        }
+    }
+}
+
+impl std::error::Error for GraphicsError {}
+
+impl Graphics {
+    /// Same as [`Self::try_new`], but panics if the GPU context can not be created.
+    pub async fn new(window: &Window, graphics_config: &GraphicsConfig) -> Self {
+        Self::try_new(window, graphics_config)
+            .await
+            .unwrap_or_else(|err| panic!("Failed to initialize graphics: {err}"))
+    }
+
+    pub async fn try_new(
+        window: &Window,
+        graphics_config: &GraphicsConfig,
+    ) -> Result<Self, GraphicsError> {
+        let mut size = surface_size(window);
+        size.width = size.width.max(1);
+        size.height = size.height.max(1);
+
+        let backends = backends_from_env().unwrap_or(graphics_config.backends);
+        let instance = wgpu::Instance::new(InstanceDescriptor {
+            backends,
+            ..Default::default()
+        });
+
+        let surface =
+            unsafe { instance.create_surface(window) }.map_err(GraphicsError::CreateSurface)?;
+
+        let adapter = request_adapter(
+            &instance,
+            backends,
+            Some(&surface),
+            graphics_config.power_preference,
+        )
+        .await
+        .ok_or(GraphicsError::NoAdapter)?;
+
+        // Create the logical device and command queue
+        let (device, queue) = if graphics_config.downlevel {
+            // GLES 3.0 devices lack the compute shaders required by the downlevel limits
+            match request_device(&adapter, wgpu::Limits::downlevel_defaults()).await {
+                Ok(device) => device,
+                Err(err) => {
+                    log::warn!("Falling back to the WebGL2 limits: {err}");
+                    request_device(&adapter, wgpu::Limits::downlevel_webgl2_defaults()).await?
+                }
+            }
+        } else {
+            request_device(&adapter, wgpu::Limits::downlevel_webgl2_defaults()).await?
+        };
+
+        let hdr_surface_format =
+            select_hdr_surface_format(&surface.get_capabilities(&adapter).formats);
+        if let Some(hdr_surface_format) = hdr_surface_format {
+            log::info!("The surface supports the HDR format {hdr_surface_format:?}");
+        }
+
+        let mut config = surface
+            .get_default_config(&adapter, size.width, size.height)
+            .ok_or(GraphicsError::UnsupportedSurface)?;
+        let (surface_format, format) = select_surface_format(
+            &surface.get_capabilities(&adapter).formats,
+            graphics_config.gamma_correction,
+        );
+        config.format = surface_format;
+        if format != surface_format {
+            config.view_formats.push(format);
+        }
+        config.alpha_mode = select_alpha_mode(
+            &surface.get_capabilities(&adapter).alpha_modes,
+            graphics_config.transparent,
+        );
+        config.present_mode = resolve_present_mode(
+            graphics_config.present_mode,
+            &surface.get_capabilities(&adapter).present_modes,
+        );
+        // Allows copying frames out of the surface, e.g. for screenshots
+        if surface
+            .get_capabilities(&adapter)
+            .usages
+            .contains(wgpu::TextureUsages::COPY_SRC)
+        {
+            config.usage |= wgpu::TextureUsages::COPY_SRC;
+        }
+        surface.configure(&device, &config);
+
+        let msaa_samples = if graphics_config.downlevel && graphics_config.msaa_samples > 1 {
+            log::info!("MSAA is disabled on downlevel devices");
+            1
+        } else {
+            supported_msaa_samples(&adapter, format, graphics_config.msaa_samples)
+        };
+        let msaa_view = create_msaa_view(&device, &config, format, msaa_samples);
+        let hdr = graphics_config.hdr && ToneMapper::is_supported(&adapter, msaa_samples);
+        if graphics_config.hdr && !hdr {
+            log::warn!("Rendering to {HDR_FORMAT:?} is not supported, disabling HDR");
+        }
+        let depth_format = graphics_config.depth_format;
+        let depth_view = depth_format
+            .map(|depth_format| create_depth_view(&device, &config, depth_format, msaa_samples));
+
+        Ok(Self {
+            instance,
+            adapter: Arc::new(adapter),
+            device: Arc::new(device),
+            queue: Arc::new(queue),
+            surface,
+            config,
+            format,
+            msaa_samples,
+            msaa_view,
+            depth_format,
+            depth_view,
+            hdr,
+            hdr_surface_format,
+        })
+    }
+
+    /// The largest width and height of a texture, which may be as small as 2048 on downlevel
+    /// devices.
+    pub fn max_texture_side(&self) -> usize {
+        self.device.limits().max_texture_dimension_2d as usize
+    }
+
+    /// Creates a view of a surface texture in the render [`Self::format`].
+    pub fn create_surface_view(&self, texture: &wgpu::Texture) -> wgpu::TextureView {
+        texture.create_view(&wgpu::TextureViewDescriptor {
+            format: Some(self.format),
+            ..Default::default()
+        })
+    }
+
+    /// The view to render into and the view to resolve to, which is `surface_view` itself when
+    /// MSAA is disabled.
+    pub fn render_target<'a>(
+        &'a self,
+        surface_view: &'a wgpu::TextureView,
+    ) -> (&'a wgpu::TextureView, Option<&'a wgpu::TextureView>) {
+        match &self.msaa_view {
+            Some(msaa_view) => (msaa_view, Some(surface_view)),
+            None => (surface_view, None),
+        }
+    }
+
+    /// The depth buffer matching the size and sample count of the render target, if enabled.
+    pub fn depth_view(&self) -> Option<&wgpu::TextureView> {
+        self.depth_view.as_ref()
+    }
+
+    pub fn configured_surface(&self) -> ConfiguredSurface<'_> {
+        ConfiguredSurface {
+            surface: &self.surface,
+            device: &self.device,
+            config: &self.config,
+        }
+    }
+
+    /// Present modes supported by the surface on this adapter.
+    pub fn supported_present_modes(&self) -> Vec<wgpu::PresentMode> {
+        self.surface.get_capabilities(&self.adapter).present_modes
+    }
+
+    /// Switches to `present_mode`, falling back to a similar mode if it is not supported.
+    ///
+    /// Returns the mode that is actually used.
+    pub fn set_present_mode(&mut self, present_mode: PresentMode) -> PresentMode {
+        self.config.present_mode =
+            resolve_present_mode(present_mode, &self.supported_present_modes());
+        self.surface.configure(&self.device, &self.config);
+        self.config.present_mode
+    }
+
+    /// Replaces the surface with a new one for `window`, whose native window was recreated, e.g.
+    /// when an Android app returns from the background.
+    pub fn recreate_surface(&mut self, window: &Window) -> Result<(), wgpu::CreateSurfaceError> {
+        self.surface = unsafe { self.instance.create_surface(window) }?;
+        let size = surface_size(window);
+        self.resize(size.width, size.height);
+        Ok(())
+    }
+
+    /// Reconfigures the surface with the new size
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.config.width = width.max(1);
+        self.config.height = height.max(1);
+        self.surface.configure(&self.device, &self.config);
+        self.msaa_view =
+            create_msaa_view(&self.device, &self.config, self.format, self.msaa_samples);
+        self.depth_view = self.depth_format.map(|depth_format| {
+            create_depth_view(&self.device, &self.config, depth_format, self.msaa_samples)
+        });
+    }
+}
+
+/// Requests the device with `limits`, raising the texture size limits to what the adapter
+/// supports so images the size of the swapchain fit.
+async fn request_device(
+    adapter: &wgpu::Adapter,
+    limits: wgpu::Limits,
+) -> Result<(wgpu::Device, wgpu::Queue), GraphicsError> {
+    adapter
+        .request_device(
+            &wgpu::DeviceDescriptor {
+                label: None,
+                // Used to measure GPU frame times if available
+                features: adapter.features() & wgpu::Features::TIMESTAMP_QUERY,
+                limits: limits.using_resolution(adapter.limits()),
+            },
+            None,
+        )
+        .await
+        .map_err(GraphicsError::RequestDevice)
+}
+
+/// Size of the surface covering `window` in physical pixels.
+///
+/// On iOS, winit reports the safe area as the inner size, while the surface covers the whole
+/// screen.
+pub fn surface_size(window: &Window) -> PhysicalSize<u32> {
+    if cfg!(target_os = "ios") {
+        window.outer_size()
+    } else {
+        window.inner_size()
+    }
+}
+
+/// Picks the surface format and the format to render with.
+///
+/// Prefers a supported format matching `srgb`. If there is none, the first supported format is
+/// used for the surface and rendered to through a view with(out) the sRGB suffix.
+pub fn select_surface_format(
+    supported: &[wgpu::TextureFormat],
+    srgb: bool,
+) -> (wgpu::TextureFormat, wgpu::TextureFormat) {
+    if let Some(&format) = supported.iter().find(|format| format.is_srgb() == srgb) {
+        return (format, format);
+    }
+
+    let surface_format = supported
+        .first()
+        .copied()
+        .unwrap_or(wgpu::TextureFormat::Bgra8Unorm);
+    let format = if srgb {
+        surface_format.add_srgb_suffix()
+    } else {
+        surface_format.remove_srgb_suffix()
+    };
+    if format.is_srgb() != srgb {
+        log::warn!(
+            "No {} format available for the surface, using {surface_format:?}",
+            if srgb { "sRGB" } else { "linear" }
+        );
+    }
+    (surface_format, format)
+}
+
+/// Picks how the compositor of the OS blends the surface with what is behind the window.
+///
+/// Transparent windows prefer premultiplied alpha, which egui renders, over straight alpha, which
+/// needs a conversion in the `Compositor`. Opaque windows ignore the alpha of the
+/// frame if possible.
+pub fn select_alpha_mode(
+    supported: &[wgpu::CompositeAlphaMode],
+    transparent: bool,
+) -> wgpu::CompositeAlphaMode {
+    use wgpu::CompositeAlphaMode;
+
+    let preferred: &[CompositeAlphaMode] = if transparent {
+        &[
+            CompositeAlphaMode::PreMultiplied,
+            CompositeAlphaMode::PostMultiplied,
+            CompositeAlphaMode::Inherit,
+        ]
+    } else {
+        &[CompositeAlphaMode::Opaque]
+    };
+    preferred
+        .iter()
+        .find(|alpha_mode| supported.contains(alpha_mode))
+        .or(supported.first())
+        .copied()
+        .unwrap_or(CompositeAlphaMode::Auto)
+}
+
+/// Returns the first supported surface format with more range or precision than 8 bit SDR,
+/// preferring floating point formats.
+pub fn select_hdr_surface_format(supported: &[wgpu::TextureFormat]) -> Option<wgpu::TextureFormat> {
+    [
+        wgpu::TextureFormat::Rgba16Float,
+        wgpu::TextureFormat::Rgb10a2Unorm,
+    ]
+    .into_iter()
+    .find(|format| supported.contains(format))
+}
+
+/// Picks `requested` if it is supported, otherwise the closest supported mode.
+///
+/// `Fifo` is supported everywhere, so it is the final fallback.
+pub fn resolve_present_mode(requested: PresentMode, supported: &[PresentMode]) -> PresentMode {
+    let fallbacks: &[PresentMode] = match requested {
+        // wgpu resolves these by itself
+        PresentMode::AutoVsync | PresentMode::AutoNoVsync => return requested,
+        PresentMode::Immediate => &[PresentMode::Mailbox],
+        PresentMode::Mailbox => &[PresentMode::Immediate],
+        PresentMode::FifoRelaxed | PresentMode::Fifo => &[],
+    };
+
+    let resolved = std::iter::once(requested)
+        .chain(fallbacks.iter().copied())
+        .find(|mode| supported.contains(mode))
+        .unwrap_or(PresentMode::Fifo);
+    if resolved != requested {
+        log::warn!("Present mode {requested:?} is not supported, using {resolved:?}");
+    }
+    resolved
+}
+
+/// Returns `requested` if the adapter supports it for `format`, otherwise falls back to `1`.
+fn supported_msaa_samples(
+    adapter: &wgpu::Adapter,
+    format: wgpu::TextureFormat,
+    requested: u32,
+) -> u32 {
+    let flags = adapter.get_texture_format_features(format).flags;
+    if requested > 1 && !flags.sample_count_supported(requested) {
+        log::warn!("{requested}x MSAA is not supported for {format:?}, disabling MSAA");
+        return 1;
+    }
+    requested.max(1)
+}
+
+/// Creates the multisampled color target matching the surface, if MSAA is enabled.
+pub(crate) fn create_msaa_view(
+    device: &wgpu::Device,
+    config: &wgpu::SurfaceConfiguration,
+    format: wgpu::TextureFormat,
+    msaa_samples: u32,
+) -> Option<wgpu::TextureView> {
+    if msaa_samples <= 1 {
+        return None;
+    }
+
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("msaa color target"),
+        size: wgpu::Extent3d {
+            width: config.width,
+            height: config.height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: msaa_samples,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+        view_formats: &[],
+    });
+    Some(texture.create_view(&wgpu::TextureViewDescriptor::default()))
+}
+
+/// Creates the depth buffer for the scene, egui itself is drawn without depth testing.
+fn create_depth_view(
+    device: &wgpu::Device,
+    config: &wgpu::SurfaceConfiguration,
+    depth_format: wgpu::TextureFormat,
+    msaa_samples: u32,
+) -> wgpu::TextureView {
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("scene depth buffer"),
+        size: wgpu::Extent3d {
+            width: config.width,
+            height: config.height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: msaa_samples,
+        dimension: wgpu::TextureDimension::D2,
+        format: depth_format,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+        view_formats: &[],
+    });
+    texture.create_view(&wgpu::TextureViewDescriptor::default())
+}
@@ -0,0 +1,180 @@
+use crate::uploads::Uploads;
+
+/// How scene colors above `1.0` are mapped into the displayable range.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Tonemapping {
+    /// Clamps each channel, bright areas lose their detail.
+    Clamp,
+    Reinhard,
+    /// Filmic curve with a slight contrast boost.
+    #[default]
+    Aces,
+}
+
+/// Adjusts how the HDR scene is mapped to the surface, see [`crate::AppBuilder::with_hdr`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HdrSettings {
+    /// Multiplier applied to the scene colors before tone mapping.
+    pub exposure: f32,
+    pub tonemapping: Tonemapping,
+}
+
+impl Default for HdrSettings {
+    fn default() -> Self {
+        Self {
+            exposure: 1.0,
+            tonemapping: Tonemapping::default(),
+        }
+    }
+}
+
+/// Format of the offscreen texture the scene is rendered into with HDR enabled.
+pub const HDR_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+/// Tone maps the HDR scene into the render target before egui is drawn, so the UI itself is not
+/// affected by the exposure.
+pub(crate) struct ToneMapper {
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    params: wgpu::Buffer,
+    encode_srgb: bool,
+}
+
+impl ToneMapper {
+    /// Whether the adapter can render to [`HDR_FORMAT`] with the given sample count.
+    pub fn is_supported(adapter: &wgpu::Adapter, msaa_samples: u32) -> bool {
+        let features = adapter.get_texture_format_features(HDR_FORMAT);
+        features
+            .allowed_usages
+            .contains(wgpu::TextureUsages::RENDER_ATTACHMENT)
+            && features.flags.sample_count_supported(msaa_samples)
+    }
+
+    /// Creates the tone mapping pipeline writing into the render target in `format`.
+    pub fn new(device: &wgpu::Device, format: wgpu::TextureFormat, msaa_samples: u32) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("tonemap"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/tonemap.wgsl").into()),
+        });
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("tonemap"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("tonemap"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("tonemap"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(format.into())],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: msaa_samples,
+                ..Default::default()
+            },
+            multiview: None,
+        });
+        let params = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("tonemap params"),
+            size: 16,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            pipeline,
+            bind_group_layout,
+            params,
+            encode_srgb: !format.is_srgb(),
+        }
+    }
+
+    /// Tone maps the resolved HDR scene in `input` into `output`, replacing its content.
+    pub fn apply(
+        &self,
+        device: &wgpu::Device,
+        uploads: &mut Uploads,
+        encoder: &mut wgpu::CommandEncoder,
+        input: &wgpu::TextureView,
+        output: &wgpu::TextureView,
+        settings: &HdrSettings,
+    ) {
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("tonemap"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(input),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: self.params.as_entire_binding(),
+                },
+            ],
+        });
+
+        let tonemapping: u32 = match settings.tonemapping {
+            Tonemapping::Clamp => 0,
+            Tonemapping::Reinhard => 1,
+            Tonemapping::Aces => 2,
+        };
+        let mut params = Vec::with_capacity(16);
+        params.extend_from_slice(&settings.exposure.to_le_bytes());
+        params.extend_from_slice(&tonemapping.to_le_bytes());
+        params.extend_from_slice(&u32::from(self.encode_srgb).to_le_bytes());
+        params.extend_from_slice(&0.0f32.to_le_bytes());
+        uploads.write(device, encoder, &self.params, 0, &params);
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("tonemap"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: output,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
@@ -0,0 +1,294 @@
+//! Renders egui into an offscreen texture without creating a window, e.g. for screenshot tests
+//! of the UI in CI.
+//!
+//! ```no_run
+//! use egui_tests::headless::{HeadlessConfig, HeadlessRenderer};
+//!
+//! let mut renderer = HeadlessRenderer::new(&HeadlessConfig::default()).unwrap();
+//! renderer
+//!     .render_to_png("hello.png".as_ref(), |ctx| {
+//!         egui::CentralPanel::default().show(ctx, |ui| ui.label("Hello"));
+//!     })
+//!     .unwrap();
+//! ```
+
+use std::path::Path;
+
+use egui::{Context, Pos2, Rect, ViewportId, ViewportInfo};
+use egui_wgpu::renderer::ScreenDescriptor;
+use image::RgbaImage;
+
+use crate::{
+    adapter::{backends_from_env, request_adapter},
+    gpu_memory::GpuMemory,
+    graphics::{GraphicsConfig, GraphicsError},
+    screenshot::TextureCapture,
+    textures::free_textures,
+};
+
+/// Time that passes between two rendered frames, so animations are deterministic.
+pub const FRAME_TIME: f32 = 1.0 / 60.0;
+
+/// Environment variable which tests rendering with a [`HeadlessRenderer`] check to skip instead
+/// of failing, for machines without any adapter, not even a software one.
+pub const SKIP_ENV_VAR: &str = "SKIP_GPU_TESTS";
+
+/// Settings of a [`HeadlessRenderer`].
+#[derive(Debug, Clone)]
+pub struct HeadlessConfig {
+    /// Size of the rendered images.
+    pub size_in_pixels: [u32; 2],
+    pub pixels_per_point: f32,
+    /// Only the backends, power preference and gamma correction are used.
+    pub graphics: GraphicsConfig,
+}
+
+impl Default for HeadlessConfig {
+    fn default() -> Self {
+        Self {
+            size_in_pixels: [800, 600],
+            pixels_per_point: 1.0,
+            graphics: GraphicsConfig::default(),
+        }
+    }
+}
+
+/// Why a frame could not be rendered or written.
+#[derive(Debug)]
+pub enum HeadlessError {
+    /// Reading the rendered texture back failed.
+    Capture,
+    Image(image::ImageError),
+}
+
+impl std::fmt::Display for HeadlessError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Capture => f.write_str("failed to read back the rendered frame"),
+            Self::Image(err) => write!(f, "failed to encode the rendered frame: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for HeadlessError {}
+
+impl From<image::ImageError> for HeadlessError {
+    fn from(err: image::ImageError) -> Self {
+        Self::Image(err)
+    }
+}
+
+/// Owns an egui context and a GPU context rendering into an offscreen texture.
+pub struct HeadlessRenderer {
+    context: Context,
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    renderer: egui_wgpu::Renderer,
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+    screen_descriptor: ScreenDescriptor,
+    time: f64,
+    memory: GpuMemory,
+}
+
+impl HeadlessRenderer {
+    /// Creates the GPU context, blocking until it is ready.
+    pub fn new(config: &HeadlessConfig) -> Result<Self, GraphicsError> {
+        pollster::block_on(Self::new_async(config))
+    }
+
+    pub async fn new_async(config: &HeadlessConfig) -> Result<Self, GraphicsError> {
+        let backends = backends_from_env().unwrap_or(config.graphics.backends);
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+            backends,
+            ..Default::default()
+        });
+        let adapter = request_adapter(&instance, backends, None, config.graphics.power_preference)
+            .await
+            .ok_or(GraphicsError::NoAdapter)?;
+        let (device, queue) = adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    label: None,
+                    features: wgpu::Features::empty(),
+                    limits: wgpu::Limits::downlevel_webgl2_defaults()
+                        .using_resolution(adapter.limits()),
+                },
+                None,
+            )
+            .await
+            .map_err(GraphicsError::RequestDevice)?;
+
+        let format = if config.graphics.gamma_correction {
+            wgpu::TextureFormat::Rgba8UnormSrgb
+        } else {
+            wgpu::TextureFormat::Rgba8Unorm
+        };
+        let [width, height] = config.size_in_pixels;
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("headless target"),
+            size: wgpu::Extent3d {
+                width: width.max(1),
+                height: height.max(1),
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let renderer = egui_wgpu::Renderer::new(&device, format, None, 1);
+        let context = Context::default();
+        #[cfg(feature = "icons")]
+        {
+            let mut fonts = egui::FontDefinitions::default();
+            crate::icons::add_to_fonts(&mut fonts);
+            context.set_fonts(fonts);
+        }
+
+        Ok(Self {
+            context,
+            device,
+            queue,
+            renderer,
+            screen_descriptor: ScreenDescriptor {
+                size_in_pixels: [texture.width(), texture.height()],
+                pixels_per_point: config.pixels_per_point,
+            },
+            texture,
+            view,
+            time: 0.0,
+            memory: GpuMemory::default(),
+        })
+    }
+
+    /// The egui context, e.g. to set fonts or the theme before rendering.
+    pub fn context(&self) -> &Context {
+        &self.context
+    }
+
+    /// Textures uploaded by egui and still held by the renderer.
+    pub fn memory(&self) -> &GpuMemory {
+        &self.memory
+    }
+
+    /// Whether the renderer holds a texture for `id`.
+    pub fn has_texture(&self, id: egui::TextureId) -> bool {
+        self.renderer.texture(&id).is_some()
+    }
+
+    /// Runs one egui frame with `run_ui` and returns the rendered image.
+    ///
+    /// Some layouts only settle after a few frames, e.g. windows sizing themselves to their
+    /// content, so call this repeatedly and keep the last image in that case.
+    pub fn render(&mut self, run_ui: impl FnOnce(&Context)) -> Result<RgbaImage, HeadlessError> {
+        let output = self.context.run(self.raw_input(), run_ui);
+        self.time += f64::from(FRAME_TIME);
+
+        let paint_jobs = self
+            .context
+            .tessellate(output.shapes, output.pixels_per_point);
+        for (id, delta) in &output.textures_delta.set {
+            self.renderer
+                .update_texture(&self.device, &self.queue, *id, delta);
+            self.memory.set_texture(*id, delta);
+        }
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("headless"),
+            });
+        let callback_buffers = self.renderer.update_buffers(
+            &self.device,
+            &self.queue,
+            &mut encoder,
+            &paint_jobs,
+            &self.screen_descriptor,
+        );
+        if !callback_buffers.is_empty() {
+            self.queue.submit(callback_buffers);
+        }
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("headless egui"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &self.view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+            self.renderer
+                .render(&mut render_pass, &paint_jobs, &self.screen_descriptor);
+        }
+        let capture = TextureCapture::record(&self.device, &mut encoder, &self.texture);
+        self.queue.submit(Some(encoder.finish()));
+
+        free_textures(
+            &mut self.renderer,
+            &self.memory,
+            &output.textures_delta.free,
+        );
+
+        capture.read(&self.device).ok_or(HeadlessError::Capture)
+    }
+
+    /// Same as [`Self::render`], but returns the image encoded as PNG.
+    pub fn render_png(&mut self, run_ui: impl FnOnce(&Context)) -> Result<Vec<u8>, HeadlessError> {
+        let image = self.render(run_ui)?;
+        let mut bytes = Vec::new();
+        image.write_to(
+            &mut std::io::Cursor::new(&mut bytes),
+            image::ImageOutputFormat::Png,
+        )?;
+        Ok(bytes)
+    }
+
+    /// Same as [`Self::render`], but writes the image to `path` as PNG.
+    pub fn render_to_png(
+        &mut self,
+        path: &Path,
+        run_ui: impl FnOnce(&Context),
+    ) -> Result<(), HeadlessError> {
+        let image = self.render(run_ui)?;
+        image.save_with_format(path, image::ImageFormat::Png)?;
+        Ok(())
+    }
+
+    fn raw_input(&self) -> egui::RawInput {
+        let [width, height] = self.screen_descriptor.size_in_pixels;
+        let pixels_per_point = self.screen_descriptor.pixels_per_point;
+        let screen_rect = Rect::from_min_size(
+            Pos2::ZERO,
+            egui::vec2(width as f32, height as f32) / pixels_per_point,
+        );
+
+        let mut raw_input = egui::RawInput {
+            screen_rect: Some(screen_rect),
+            max_texture_side: Some(self.device.limits().max_texture_dimension_2d as usize),
+            time: Some(self.time),
+            predicted_dt: FRAME_TIME,
+            focused: true,
+            ..Default::default()
+        };
+        raw_input.viewports.insert(
+            ViewportId::ROOT,
+            ViewportInfo {
+                native_pixels_per_point: Some(pixels_per_point),
+                inner_rect: Some(screen_rect),
+                focused: Some(true),
+                ..Default::default()
+            },
+        );
+        raw_input
+    }
+}
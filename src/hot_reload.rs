@@ -0,0 +1,116 @@
+//! Reloads WGSL shaders when their files change, so pipelines can be tweaked while the app runs.
+//!
+//! Effects of the [`crate::post_process::PostProcess`] chain added with
+//! [`crate::post_process::PostProcess::add_file`] are reloaded automatically. Scene renderers
+//! can use the [`ShaderWatcher`] and [`try_create`] directly:
+//!
+//! ```no_run
+//! # fn create_pipeline(device: &wgpu::Device, source: &str) -> wgpu::RenderPipeline { todo!() }
+//! # fn example(device: &wgpu::Device, pipeline: &mut wgpu::RenderPipeline) -> notify::Result<()> {
+//! use egui_tests::hot_reload::{try_create, ShaderWatcher};
+//!
+//! let mut watcher = ShaderWatcher::new()?;
+//! watcher.watch("shaders/scene.wgsl")?;
+//! // Every frame
+//! for path in watcher.changed() {
+//!     let Ok(source) = std::fs::read_to_string(&path) else { continue };
+//!     match try_create(device, || create_pipeline(device, &source)) {
+//!         Ok(new_pipeline) => *pipeline = new_pipeline,
+//!         Err(err) => log::error!("Failed to reload {}: {err}", path.display()),
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::mpsc,
+};
+
+use notify::{RecursiveMode, Watcher};
+
+/// Watches shader files and reports which of them changed.
+///
+/// The directories containing the files are watched instead of the files themselves, so
+/// editors which save by replacing the file are picked up as well.
+pub struct ShaderWatcher {
+    watcher: notify::RecommendedWatcher,
+    changes: mpsc::Receiver<PathBuf>,
+    directories: HashSet<PathBuf>,
+    files: HashSet<PathBuf>,
+}
+
+impl std::fmt::Debug for ShaderWatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ShaderWatcher")
+            .field("files", &self.files)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ShaderWatcher {
+    pub fn new() -> notify::Result<Self> {
+        let (sender, changes) = mpsc::channel();
+        let watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| match event {
+                Ok(event) if event.kind.is_modify() || event.kind.is_create() => {
+                    for path in event.paths {
+                        let _ = sender.send(path);
+                    }
+                }
+                Ok(_) => {}
+                Err(err) => log::warn!("Shader watcher error: {err}"),
+            })?;
+        Ok(Self {
+            watcher,
+            changes,
+            directories: HashSet::new(),
+            files: HashSet::new(),
+        })
+    }
+
+    /// Starts watching the file at `path`. Returns the absolute path reported by
+    /// [`Self::changed`] for it.
+    pub fn watch(&mut self, path: impl AsRef<Path>) -> notify::Result<PathBuf> {
+        let path = path.as_ref();
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| notify::Error::generic("the shader path has no file name"))?;
+        let directory = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.canonicalize()?,
+            _ => std::env::current_dir()?,
+        };
+
+        if self.directories.insert(directory.clone()) {
+            self.watcher
+                .watch(&directory, RecursiveMode::NonRecursive)?;
+        }
+        let path = directory.join(file_name);
+        self.files.insert(path.clone());
+        Ok(path)
+    }
+
+    /// Returns the watched files which changed since the last call, each one only once.
+    pub fn changed(&self) -> Vec<PathBuf> {
+        let mut changed = Vec::new();
+        for path in self.changes.try_iter() {
+            if self.files.contains(&path) && !changed.contains(&path) {
+                changed.push(path);
+            }
+        }
+        changed
+    }
+}
+
+/// Runs `create` inside a validation error scope, returning the error instead of panicking if
+/// e.g. a shader fails to compile.
+pub fn try_create<T>(device: &wgpu::Device, create: impl FnOnce() -> T) -> Result<T, wgpu::Error> {
+    device.push_error_scope(wgpu::ErrorFilter::Validation);
+    let value = create();
+    match pollster::block_on(device.pop_error_scope()) {
+        Some(err) => Err(err),
+        None => Ok(value),
+    }
+}
@@ -0,0 +1,38 @@
+use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState};
+
+pub use global_hotkey::hotkey::{Code, HotKey, Modifiers};
+
+use crate::{proxy::UserEvent, EventLoopProxy};
+
+/// System-wide hotkeys which work even while the window is unfocused or hidden.
+pub(crate) struct GlobalHotkeys {
+    _manager: GlobalHotKeyManager,
+    toggle_visibility: HotKey,
+}
+
+impl GlobalHotkeys {
+    /// Registers `toggle_visibility` and forwards its presses to the event loop.
+    pub(crate) fn new(proxy: EventLoopProxy, toggle_visibility: HotKey) -> Option<Self> {
+        let manager = GlobalHotKeyManager::new()
+            .map_err(|err| log::warn!("Global hotkeys are not available: {err}"))
+            .ok()?;
+        if let Err(err) = manager.register(toggle_visibility) {
+            log::warn!("Failed to register the global hotkey {toggle_visibility:?}: {err}");
+            return None;
+        }
+        // The events arrive on another thread on some platforms, so they wake up the event loop
+        GlobalHotKeyEvent::set_event_handler(Some(move |event: GlobalHotKeyEvent| {
+            if event.state() == HotKeyState::Pressed {
+                proxy.send_event(UserEvent::GlobalHotKey(event.id()));
+            }
+        }));
+        Some(Self {
+            _manager: manager,
+            toggle_visibility,
+        })
+    }
+
+    pub(crate) fn is_toggle_visibility(&self, id: u32) -> bool {
+        self.toggle_visibility.id() == id
+    }
+}
@@ -0,0 +1,106 @@
+//! Window and taskbar icons, see [`crate::Frame::set_icon`].
+
+use winit::window::Window;
+
+/// An RGBA icon for the window and its taskbar entry.
+///
+/// macOS takes the icon from the app bundle instead and ignores it, on the web it is not
+/// supported either.
+#[derive(Clone, PartialEq, Eq)]
+pub struct WindowIcon {
+    rgba: Vec<u8>,
+    width: u32,
+    height: u32,
+}
+
+impl std::fmt::Debug for WindowIcon {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WindowIcon")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .finish_non_exhaustive()
+    }
+}
+
+impl WindowIcon {
+    /// Decodes a PNG image, e.g. embedded with [`include_bytes!`].
+    pub fn from_png(bytes: &[u8]) -> Result<Self, image::ImageError> {
+        let image = image::load_from_memory_with_format(bytes, image::ImageFormat::Png)?;
+        let image = image.into_rgba8();
+        Ok(Self {
+            width: image.width(),
+            height: image.height(),
+            rgba: image.into_raw(),
+        })
+    }
+
+    /// Creates an icon from unmultiplied RGBA pixels.
+    ///
+    /// # Panics
+    ///
+    /// If `rgba` does not contain `width * height` pixels.
+    pub fn from_rgba(rgba: Vec<u8>, width: u32, height: u32) -> Self {
+        assert_eq!(
+            rgba.len(),
+            width as usize * height as usize * 4,
+            "The icon data does not match its size"
+        );
+        Self {
+            rgba,
+            width,
+            height,
+        }
+    }
+
+    pub fn size(&self) -> [u32; 2] {
+        [self.width, self.height]
+    }
+
+    pub fn rgba(&self) -> &[u8] {
+        &self.rgba
+    }
+
+    /// Returns a copy of the icon with a filled circle in the top right corner, e.g. to signal
+    /// unread notifications.
+    pub fn with_badge(&self, color: egui::Color32) -> Self {
+        let mut icon = self.clone();
+        let radius = self.width.min(self.height) as f32 / 4.0;
+        let center = (self.width as f32 - radius, radius);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let distance = ((x as f32 + 0.5 - center.0).powi(2)
+                    + (y as f32 + 0.5 - center.1).powi(2))
+                .sqrt();
+                let coverage = (radius - distance + 0.5).clamp(0.0, 1.0);
+                if coverage > 0.0 {
+                    let i = (y * self.width + x) as usize * 4;
+                    let pixel = &mut icon.rgba[i..i + 4];
+                    let [r, g, b, a] = color.to_srgba_unmultiplied();
+                    for (channel, value) in pixel.iter_mut().zip([r, g, b, a]) {
+                        *channel = (*channel as f32 * (1.0 - coverage) + value as f32 * coverage)
+                            .round() as u8;
+                    }
+                }
+            }
+        }
+        icon
+    }
+
+    fn to_winit(&self) -> Option<winit::window::Icon> {
+        winit::window::Icon::from_rgba(self.rgba.clone(), self.width, self.height)
+            .map_err(|err| log::warn!("Invalid window icon: {err}"))
+            .ok()
+    }
+}
+
+/// Sets the icon of the title bar and, on Windows, the larger one of the taskbar.
+pub(crate) fn apply(window: &Window, icon: Option<&WindowIcon>) {
+    let icon = icon.and_then(WindowIcon::to_winit);
+    #[cfg(target_os = "windows")]
+    {
+        use winit::platform::windows::WindowExtWindows;
+
+        window.set_taskbar_icon(icon.clone());
+    }
+    window.set_window_icon(icon);
+}
@@ -0,0 +1,148 @@
+//! A built-in set of icons from the Phosphor icon font, which is added to the fonts of every
+//! window.
+//!
+//! ```no_run
+//! use egui_tests::icons::{Icon, IconUi};
+//! # fn ui(ui: &mut egui::Ui) {
+//! ui.icon(Icon::Save);
+//! if ui.icon_button(Icon::Open, "Open").clicked() {}
+//! ui.label(format!("{} Saved", Icon::Check));
+//! # }
+//! ```
+
+use std::fmt;
+
+use egui::{FontDefinitions, FontFamily, Response, RichText, Ui, WidgetText};
+use egui_phosphor::regular as phosphor;
+
+const FONT_NAME: &str = "phosphor";
+
+/// Adds the icon font as the last fallback of the proportional and monospace families, so
+/// regular text keeps its glyphs.
+pub fn add_to_fonts(fonts: &mut FontDefinitions) {
+    fonts.font_data.insert(
+        FONT_NAME.to_owned(),
+        egui_phosphor::Variant::Regular.font_data(),
+    );
+    for family in [FontFamily::Proportional, FontFamily::Monospace] {
+        let names = fonts.families.entry(family).or_default();
+        if !names.iter().any(|name| name == FONT_NAME) {
+            names.push(FONT_NAME.to_owned());
+        }
+    }
+}
+
+macro_rules! icons {
+    ($($icon:ident => $glyph:ident,)*) => {
+        /// An icon of the built-in icon font.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum Icon {
+            $($icon,)*
+        }
+
+        impl Icon {
+            /// All icons, in declaration order.
+            pub const ALL: &'static [Icon] = &[$(Icon::$icon,)*];
+
+            /// The character of the icon in the icon font.
+            pub fn glyph(self) -> &'static str {
+                match self {
+                    $(Icon::$icon => phosphor::$glyph,)*
+                }
+            }
+
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(Icon::$icon => stringify!($icon),)*
+                }
+            }
+        }
+    };
+}
+
+icons! {
+    New => FILE_PLUS,
+    Open => FOLDER_OPEN,
+    Save => FLOPPY_DISK,
+    Undo => ARROW_COUNTER_CLOCKWISE,
+    Redo => ARROW_CLOCKWISE,
+    Cut => SCISSORS,
+    Copy => COPY,
+    Paste => CLIPBOARD,
+    Delete => TRASH,
+    Edit => PENCIL_SIMPLE,
+    Search => MAGNIFYING_GLASS,
+    Settings => GEAR,
+    Close => X,
+    Add => PLUS,
+    Remove => MINUS,
+    Check => CHECK,
+    Play => PLAY,
+    Pause => PAUSE,
+    Stop => STOP,
+    Refresh => ARROWS_CLOCKWISE,
+    Info => INFO,
+    Warning => WARNING,
+    Error => X_CIRCLE,
+    File => FILE,
+    Folder => FOLDER,
+    Image => IMAGE,
+    Home => HOUSE,
+    Menu => LIST,
+    Show => EYE,
+    Hide => EYE_SLASH,
+    Lock => LOCK,
+    Unlock => LOCK_OPEN,
+    Download => DOWNLOAD_SIMPLE,
+    Upload => UPLOAD_SIMPLE,
+    Expand => CARET_DOWN,
+    Collapse => CARET_RIGHT,
+    Light => SUN,
+    Dark => MOON,
+    Command => COMMAND,
+    Terminal => TERMINAL_WINDOW,
+}
+
+impl Icon {
+    /// The icon followed by `text`, e.g. for buttons and menu entries.
+    pub fn with_text(self, text: &str) -> String {
+        format!("{} {text}", self.glyph())
+    }
+}
+
+impl fmt::Display for Icon {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.glyph())
+    }
+}
+
+impl From<Icon> for RichText {
+    fn from(icon: Icon) -> Self {
+        RichText::new(icon.glyph())
+    }
+}
+
+impl From<Icon> for WidgetText {
+    fn from(icon: Icon) -> Self {
+        RichText::from(icon).into()
+    }
+}
+
+/// Shows icons in a [`Ui`].
+pub trait IconUi {
+    /// An icon in the size of the body text.
+    fn icon(&mut self, icon: Icon) -> Response;
+
+    /// A button with the icon in front of `text`.
+    fn icon_button(&mut self, icon: Icon, text: &str) -> Response;
+}
+
+impl IconUi for Ui {
+    fn icon(&mut self, icon: Icon) -> Response {
+        self.label(icon)
+    }
+
+    fn icon_button(&mut self, icon: Icon, text: &str) -> Response {
+        self.button(icon.with_text(text))
+    }
+}
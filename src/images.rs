@@ -0,0 +1,203 @@
+//! Images loaded by URI, e.g. `ui.image("file://assets/icon.png")` or
+//! `ui.image("https://example.com/image.png")`.
+//!
+//! Files are read and downloads run in the background, the image shows up once it is decoded.
+//! Textures which were not shown for a while are dropped when the cache grows beyond its size,
+//! see [`crate::AppBuilder::with_image_cache_size`], and are loaded again when needed.
+//!
+//! With the `svg` feature, `SvgImage` shows `.svg` files rasterized for the current scale.
+
+use std::{collections::HashMap, sync::Arc};
+
+use egui::{
+    load::{ImagePoll, SizeHint, SizedTexture, TextureLoadResult, TextureLoader, TexturePoll},
+    mutex::Mutex,
+    Context, TextureHandle, TextureOptions,
+};
+#[cfg(feature = "svg")]
+use egui::{Color32, Response, Ui, Vec2, Widget};
+
+/// Size of the texture cache if none is set, in bytes.
+pub const DEFAULT_CACHE_SIZE: usize = 256 * 1024 * 1024;
+
+/// Installs the file, HTTP and image decoding loaders of `egui_extras` and a texture cache
+/// holding at most `cache_size` bytes.
+pub(crate) fn install(ctx: &Context, cache_size: usize) -> Arc<BoundedTextureLoader> {
+    egui_extras::install_image_loaders(ctx);
+    let loader = Arc::new(BoundedTextureLoader::new(cache_size));
+    ctx.add_texture_loader(loader.clone());
+    loader
+}
+
+struct CachedTexture {
+    handle: TextureHandle,
+    /// Index of the frame the texture was last shown in.
+    last_used: usize,
+}
+
+/// Caches the textures of loaded images like egui's default loader, but drops the least recently
+/// used ones beyond a size limit.
+pub struct BoundedTextureLoader {
+    max_bytes: usize,
+    /// Images rasterized at several sizes, like SVGs, are cached per size.
+    cache: Mutex<HashMap<(String, TextureOptions, SizeHint), CachedTexture>>,
+}
+
+impl BoundedTextureLoader {
+    pub fn new(max_bytes: usize) -> Self {
+        Self {
+            max_bytes,
+            cache: Default::default(),
+        }
+    }
+
+    /// Drops the least recently used textures beyond the size limit, except the ones shown in
+    /// the frame which just ended. Their bytes and decoded images are forgotten as well.
+    // egui does not call `TextureLoader::end_frame` itself
+    pub(crate) fn end_frame(&self, ctx: &Context) {
+        // Already counts the coming frame
+        let ended_frame = (ctx.frame_nr() as usize).saturating_sub(1);
+        let mut evicted = Vec::new();
+        {
+            let mut cache = self.cache.lock();
+            let mut bytes: usize = cache.values().map(|cached| cached.handle.byte_size()).sum();
+            if bytes <= self.max_bytes {
+                return;
+            }
+            let mut unused: Vec<_> = cache
+                .iter()
+                .filter(|(_, cached)| cached.last_used < ended_frame)
+                .map(|(key, cached)| (cached.last_used, key.clone()))
+                .collect();
+            unused.sort_by_key(|(last_used, _)| *last_used);
+            for (_, key) in unused {
+                if bytes <= self.max_bytes {
+                    break;
+                }
+                if let Some(cached) = cache.remove(&key) {
+                    bytes -= cached.handle.byte_size();
+                    evicted.push(key.0);
+                }
+            }
+        }
+        // Also calls `forget`, which locks the cache
+        for uri in evicted {
+            log::debug!("Dropping {uri} from the image cache");
+            ctx.forget_image(&uri);
+        }
+    }
+}
+
+impl TextureLoader for BoundedTextureLoader {
+    fn id(&self) -> &str {
+        egui::generate_loader_id!(BoundedTextureLoader)
+    }
+
+    fn load(
+        &self,
+        ctx: &Context,
+        uri: &str,
+        texture_options: TextureOptions,
+        size_hint: SizeHint,
+    ) -> TextureLoadResult {
+        let frame_index = ctx.frame_nr() as usize;
+        let mut cache = self.cache.lock();
+        if let Some(cached) = cache.get_mut(&(uri.to_owned(), texture_options, size_hint)) {
+            cached.last_used = frame_index;
+            let texture = SizedTexture::from_handle(&cached.handle);
+            return Ok(TexturePoll::Ready { texture });
+        }
+        match ctx.try_load_image(uri, size_hint)? {
+            ImagePoll::Pending { size } => Ok(TexturePoll::Pending { size }),
+            ImagePoll::Ready { image } => {
+                let handle = ctx.load_texture(uri, image, texture_options);
+                let texture = SizedTexture::from_handle(&handle);
+                cache.insert(
+                    (uri.to_owned(), texture_options, size_hint),
+                    CachedTexture {
+                        handle,
+                        last_used: frame_index,
+                    },
+                );
+                Ok(TexturePoll::Ready { texture })
+            }
+        }
+    }
+
+    fn forget(&self, uri: &str) {
+        self.cache.lock().retain(|(cached, _, _), _| cached != uri);
+    }
+
+    fn forget_all(&self) {
+        self.cache.lock().clear();
+    }
+
+    fn byte_size(&self) -> usize {
+        self.cache
+            .lock()
+            .values()
+            .map(|cached| cached.handle.byte_size())
+            .sum()
+    }
+}
+
+/// An SVG image, e.g. `"file://assets/icon.svg"`, rasterized at the scale of the display and the
+/// zoom factor so it stays crisp.
+///
+/// It is rasterized again when the scale changes, each size is cached separately.
+#[cfg(feature = "svg")]
+pub struct SvgImage {
+    uri: String,
+    size: Vec2,
+    tint: Color32,
+}
+
+#[cfg(feature = "svg")]
+impl SvgImage {
+    /// Shows the image at `size` in points.
+    pub fn new(uri: impl Into<String>, size: impl Into<Vec2>) -> Self {
+        Self {
+            uri: uri.into(),
+            size: size.into(),
+            tint: Color32::WHITE,
+        }
+    }
+
+    /// Multiplies the colors of the image, e.g. to color white icons like text.
+    pub fn tint(mut self, tint: impl Into<Color32>) -> Self {
+        self.tint = tint.into();
+        self
+    }
+}
+
+#[cfg(feature = "svg")]
+impl Widget for SvgImage {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let pixels = self.size * ui.ctx().pixels_per_point();
+        let size_hint = SizeHint::Size(pixels.x.round() as u32, pixels.y.round() as u32);
+        match ui
+            .ctx()
+            .try_load_texture(&self.uri, TextureOptions::LINEAR, size_hint)
+        {
+            Ok(TexturePoll::Ready { texture }) => ui.add(
+                egui::Image::from_texture(SizedTexture::new(texture.id, self.size)).tint(self.tint),
+            ),
+            Ok(TexturePoll::Pending { .. }) => {
+                let (rect, response) = ui.allocate_exact_size(self.size, egui::Sense::hover());
+                egui::Spinner::new().paint_at(ui, rect);
+                response
+            }
+            Err(err) => {
+                let (rect, response) = ui.allocate_exact_size(self.size, egui::Sense::hover());
+                ui.painter().text(
+                    rect.center(),
+                    egui::Align2::CENTER_CENTER,
+                    "⚠",
+                    egui::TextStyle::Body.resolve(ui.style()),
+                    ui.visuals().error_fg_color,
+                );
+                response.on_hover_text(err.to_string())
+            }
+        }
+    }
+}
@@ -0,0 +1,114 @@
+/// Opens a profiling scope that lasts until the end of the enclosing block, if the `puffin`
+/// feature is enabled.
+macro_rules! profile_scope {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "puffin")]
+        puffin::profile_scope!($($arg)*);
+    };
+}
+
+pub mod adapter;
+pub mod animations;
+mod app;
+pub mod backdrop;
+mod builder;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod cli;
+pub mod clipboard;
+#[cfg(feature = "code-editor")]
+pub mod code_editor;
+pub mod command_palette;
+pub mod composite;
+pub mod compute;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod config;
+pub mod diagnostics;
+pub mod dialogs;
+#[cfg(feature = "dock")]
+pub mod dock;
+pub mod dropped_files;
+#[cfg(not(any(target_arch = "wasm32", target_os = "android", target_os = "ios")))]
+pub mod file_dialog;
+pub mod fonts;
+mod frame;
+pub mod frosted_glass;
+#[cfg(all(feature = "gamepad", not(target_arch = "wasm32")))]
+pub mod gamepad;
+pub mod gestures;
+pub mod gpu;
+pub mod gpu_errors;
+pub mod gpu_memory;
+pub mod graphics;
+pub mod hdr;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod headless;
+#[cfg(all(feature = "hot-reload", not(target_arch = "wasm32")))]
+pub mod hot_reload;
+#[cfg(all(feature = "global-hotkey", not(target_arch = "wasm32")))]
+pub mod hotkeys;
+pub mod icon;
+#[cfg(feature = "icons")]
+pub mod icons;
+#[cfg(feature = "image-loaders")]
+pub mod images;
+pub mod log_console;
+#[cfg(feature = "markdown")]
+pub mod markdown;
+#[cfg(feature = "plot")]
+pub mod metrics;
+pub mod monitors;
+pub mod notifications;
+pub mod overlay;
+mod pacer;
+pub mod paint_callback;
+pub mod persistence;
+pub mod plugin;
+pub mod post_process;
+#[cfg(feature = "puffin")]
+pub mod profiler;
+mod proxy;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod recorder;
+#[cfg(all(feature = "tokio", not(target_arch = "wasm32")))]
+pub mod runtime;
+mod scene;
+pub mod scene_view;
+pub mod screenshot;
+pub mod settings;
+#[cfg(feature = "shader-playground")]
+pub mod shader_playground;
+pub mod shortcuts;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod snapshot;
+pub mod surface;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod tasks;
+#[cfg(all(feature = "threaded-tessellation", not(target_arch = "wasm32")))]
+pub mod tessellation;
+pub mod textures;
+pub mod theme;
+pub mod timestep;
+pub mod title_bar;
+#[cfg(all(feature = "tray", not(target_arch = "wasm32")))]
+mod tray;
+pub mod ui;
+pub mod undo;
+mod uploads;
+pub mod viewport;
+pub mod virtual_table;
+pub mod window;
+
+#[cfg(target_os = "android")]
+pub use winit::platform::android::activity::AndroidApp;
+
+pub use app::App;
+pub use builder::AppBuilder;
+pub use frame::Frame;
+pub use pacer::{FramePacer, FrameTime, RenderMode, UnfocusedPolicy};
+pub use proxy::EventLoopProxy;
+pub use scene::{SceneFrame, SceneRenderer};
+
+/// Runs `app` with the default [`AppBuilder`] configuration.
+pub fn run(app: impl App + 'static) {
+    AppBuilder::new().run(app);
+}
@@ -0,0 +1,235 @@
+use std::{collections::VecDeque, sync::Arc};
+
+use egui::mutex::Mutex;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+/// Number of records kept in the buffer, older ones are dropped.
+const CAPACITY: usize = 1000;
+
+/// A log message captured by the [`TeeLogger`].
+#[derive(Debug, Clone)]
+pub struct LogRecord {
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+}
+
+/// Ring buffer of the most recent log records, shared between the logger and the console.
+#[derive(Clone, Default)]
+pub struct LogBuffer {
+    records: Arc<Mutex<VecDeque<LogRecord>>>,
+}
+
+impl LogBuffer {
+    fn push(&self, record: LogRecord) {
+        let mut records = self.records.lock();
+        if records.len() == CAPACITY {
+            records.pop_front();
+        }
+        records.push_back(record);
+    }
+
+    pub fn clear(&self) {
+        self.records.lock().clear();
+    }
+
+    /// Calls `f` with all records, oldest first.
+    pub fn read<R>(&self, f: impl FnOnce(&VecDeque<LogRecord>) -> R) -> R {
+        f(&self.records.lock())
+    }
+}
+
+/// Forwards records to the platform logger and keeps a copy of them in a [`LogBuffer`].
+pub struct TeeLogger {
+    inner: Box<dyn Log>,
+    buffer: LogBuffer,
+    level: LevelFilter,
+}
+
+impl Log for TeeLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level || self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if record.level() <= self.level {
+            self.buffer.push(LogRecord {
+                level: record.level(),
+                target: record.target().to_owned(),
+                message: record.args().to_string(),
+            });
+        }
+        if self.inner.enabled(record.metadata()) {
+            self.inner.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+/// Installs a [`TeeLogger`] capturing records up to `level` as the global logger.
+///
+/// Records are still printed by `env_logger` (configured through `RUST_LOG`) on native, to
+/// logcat on Android and to the browser console on the web.
+pub fn init(level: LevelFilter) -> LogBuffer {
+    #[cfg(target_os = "android")]
+    let (inner, inner_level): (Box<dyn Log>, _) = (
+        Box::new(android_logger::AndroidLogger::new(
+            android_logger::Config::default().with_max_level(LevelFilter::Info),
+        )),
+        LevelFilter::Info,
+    );
+    #[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+    let (inner, inner_level): (Box<dyn Log>, _) = {
+        let logger = env_logger::Builder::from_default_env().build();
+        let inner_level = logger.filter();
+        (Box::new(logger), inner_level)
+    };
+    #[cfg(target_arch = "wasm32")]
+    let (inner, inner_level): (Box<dyn Log>, _) = (Box::new(ConsoleLogger), LevelFilter::Info);
+
+    let buffer = LogBuffer::default();
+    let logger = TeeLogger {
+        inner,
+        buffer: buffer.clone(),
+        level,
+    };
+    match log::set_boxed_logger(Box::new(logger)) {
+        Ok(()) => log::set_max_level(level.max(inner_level)),
+        Err(err) => eprintln!("Failed to install the logger: {err}"),
+    }
+    buffer
+}
+
+/// Logs to the browser console.
+#[cfg(target_arch = "wasm32")]
+struct ConsoleLogger;
+
+#[cfg(target_arch = "wasm32")]
+impl Log for ConsoleLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= Level::Info
+    }
+
+    fn log(&self, record: &Record) {
+        console_log::log(record);
+    }
+
+    fn flush(&self) {}
+}
+
+/// Shows the captured log records with level filtering and search.
+///
+/// The console is either docked to the bottom of the window or floats in its own window.
+pub struct LogConsole {
+    buffer: LogBuffer,
+    level: LevelFilter,
+    search: String,
+    docked: bool,
+}
+
+impl LogConsole {
+    pub fn new(buffer: LogBuffer) -> Self {
+        Self {
+            buffer,
+            level: LevelFilter::Info,
+            search: String::new(),
+            docked: true,
+        }
+    }
+
+    /// Shows the console while `open` is set. Must be called before the central panel is added.
+    pub fn show(&mut self, ctx: &egui::Context, open: &mut bool) {
+        if !*open {
+            return;
+        }
+        if self.docked {
+            egui::TopBottomPanel::bottom("log console")
+                .resizable(true)
+                .default_height(160.0)
+                .show(ctx, |ui| self.ui(ui));
+        } else {
+            egui::Window::new("Log")
+                .open(open)
+                .default_size([500.0, 250.0])
+                .show(ctx, |ui| self.ui(ui));
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_source("log level")
+                .selected_text(self.level.to_string())
+                .show_ui(ui, |ui| {
+                    for level in LevelFilter::iter().skip(1) {
+                        ui.selectable_value(&mut self.level, level, level.to_string());
+                    }
+                });
+            ui.add(egui::TextEdit::singleline(&mut self.search).hint_text("Search"));
+            if ui.button("Copy").clicked() {
+                let text = self.filtered(|records| {
+                    records
+                        .map(|record| {
+                            format!("[{} {}] {}", record.level, record.target, record.message)
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                });
+                ui.output_mut(|output| output.copied_text = text);
+            }
+            if ui.button("Clear").clicked() {
+                self.buffer.clear();
+            }
+            ui.toggle_value(&mut self.docked, "Docked");
+        });
+        ui.separator();
+
+        egui::ScrollArea::vertical()
+            .auto_shrink([false, false])
+            .stick_to_bottom(true)
+            .show(ui, |ui| {
+                self.filtered(|records| {
+                    for record in records {
+                        ui.horizontal_wrapped(|ui| {
+                            ui.colored_label(
+                                level_color(ui.visuals(), record.level),
+                                record.level.as_str(),
+                            );
+                            ui.weak(&record.target);
+                            ui.label(&record.message);
+                        });
+                    }
+                });
+            });
+    }
+
+    fn filtered<R>(&self, f: impl FnOnce(&mut dyn Iterator<Item = &LogRecord>) -> R) -> R {
+        let search = self.search.to_lowercase();
+        self.buffer.read(|records| {
+            f(&mut records.iter().filter(|record| {
+                record.level <= self.level
+                    && (search.is_empty()
+                        || record.message.to_lowercase().contains(&search)
+                        || record.target.to_lowercase().contains(&search))
+            }))
+        })
+    }
+}
+
+impl Default for LogConsole {
+    /// A console that is not connected to a logger, see [`init`].
+    fn default() -> Self {
+        Self::new(LogBuffer::default())
+    }
+}
+
+fn level_color(visuals: &egui::Visuals, level: Level) -> egui::Color32 {
+    match level {
+        Level::Error => visuals.error_fg_color,
+        Level::Warn => visuals.warn_fg_color,
+        Level::Info => visuals.text_color(),
+        Level::Debug | Level::Trace => visuals.weak_text_color(),
+    }
+}
@@ -1,212 +1,1003 @@
-use egui::{Color32, Context, Visuals};
-use egui_wgpu::renderer::ScreenDescriptor;
-use wgpu::{Backends, Color, InstanceDescriptor, LoadOp, StoreOp};
-use winit::{
-    event::{Event, WindowEvent},
-    event_loop::EventLoop,
-    window::Window,
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
 };
-use winit::event_loop::ControlFlow;
-
-async fn run(event_loop: EventLoop<()>, window: Window) {
-    let mut size = window.inner_size();
-    size.width = size.width.max(1);
-    size.height = size.height.max(1);
-
-    let instance = wgpu::Instance::new(InstanceDescriptor { backends: Backends::PRIMARY, ..Default::default() });
-
-    let surface = unsafe { instance.create_surface(&window) }.unwrap();
-
-    let adapter = instance
-        .request_adapter(&wgpu::RequestAdapterOptions {
-            power_preference: wgpu::PowerPreference::default(),
-            force_fallback_adapter: false,
-            // Request an adapter which can render to our surface
-            compatible_surface: Some(&surface),
-        })
-        .await
-        .expect("Failed to find an appropriate adapter");
-
-    // Create the logical device and command queue
-    let (device, queue) = adapter
-        .request_device(
-            &wgpu::DeviceDescriptor {
-                label: None,
-                features: wgpu::Features::empty(),
-                // Make sure we use the texture resolution limits from the adapter, so we can support images the size of the swapchain.
-                limits: wgpu::Limits::downlevel_webgl2_defaults()
-                    .using_resolution(adapter.limits()),
-            },
-            None,
-        )
-        .await
-        .expect("Failed to create device");
-
-    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-        label: None,
-        bind_group_layouts: &[],
-        push_constant_ranges: &[],
-    });
 
-    let mut config = surface
-        .get_default_config(&adapter, size.width, size.height)
-        .unwrap();
-    surface.configure(&device, &config);
-
-    // Egui stuff
-    let context = Context::default();
-    let mut winit_state = egui_winit::State::new(context.viewport_id(), &window, Some(window.scale_factor() as f32), None);
-
-    let mut egui_renderer = egui_wgpu::Renderer::new(
-        &device,
-        config.format,
-        None,
-        1,
-    );
-
-    let mut screen_descriptor = ScreenDescriptor {
-        pixels_per_point: window.scale_factor() as f32,
-        size_in_pixels: [window.inner_size().width, window.inner_size().height],
-    };
+use egui::{ViewportBuilder, ViewportId};
+use egui_tests::{
+    animations::Animated,
+    backdrop::{Backdrop, VibrancyMaterial},
+    clipboard::Clipboard,
+    dropped_files::{self, DroppedFiles},
+    fonts::FontLoader,
+    icon::WindowIcon,
+    log_console::{self, LogConsole},
+    monitors::MonitorPlacement,
+    notifications,
+    plugin::LogConsolePlugin,
+    post_process::effects,
+    scene_view::SceneView,
+    title_bar,
+    undo::{Command, CommandStack},
+    virtual_table::{Column, VirtualTable},
+    window::FullscreenMode,
+    App, AppBuilder, Frame, RenderMode, SceneFrame, SceneRenderer, UnfocusedPolicy,
+};
+use winit::window::WindowLevel;
+
+/// Clears the window with a slowly pulsing, half transparent background.
+///
+/// The preview is cleared with the inverse color and shown inside the UI.
+struct BackgroundScene {
+    /// Simulated in fixed steps, the previous value is kept for interpolating between steps.
+    phase: f64,
+    previous_phase: f64,
+    preview: SceneView,
+}
+
+impl Default for BackgroundScene {
+    fn default() -> Self {
+        Self {
+            phase: 0.0,
+            previous_phase: 0.0,
+            preview: SceneView::new(wgpu::TextureFormat::Rgba8UnormSrgb),
+        }
+    }
+}
+
+impl SceneRenderer for BackgroundScene {
+    fn render(&mut self, frame: &mut SceneFrame) {
+        let phase = self.previous_phase
+            + (self.phase - self.previous_phase) * f64::from(frame.interpolation);
+        let intensity = (phase.sin() * 0.5 + 0.5) * 0.3;
+
+        frame
+            .encoder
+            .begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("background scene"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: frame.view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color {
+                            r: 0.0,
+                            g: 0.0,
+                            b: intensity,
+                            a: 0.5,
+                        }),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+
+        if let Some(preview) = self.preview.view() {
+            frame
+                .encoder
+                .begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("preview scene"),
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view: preview,
+                        resolve_target: None,
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(wgpu::Color {
+                                r: 0.3 - intensity,
+                                g: 0.0,
+                                b: 0.0,
+                                a: 1.0,
+                            }),
+                            store: wgpu::StoreOp::Store,
+                        },
+                    })],
+                    depth_stencil_attachment: None,
+                    timestamp_writes: None,
+                    occlusion_query_set: None,
+                });
+        }
+    }
+}
+
+/// Sent from a background thread through the event loop proxy.
+struct BackgroundTaskDone(String);
+
+/// The document edited in the undo demo.
+#[derive(Clone, PartialEq)]
+struct Document {
+    title: String,
+    size: f32,
+    color: egui::Color32,
+}
+
+impl Default for Document {
+    fn default() -> Self {
+        Self {
+            title: "Untitled".to_owned(),
+            size: 1.0,
+            color: egui::Color32::LIGHT_BLUE,
+        }
+    }
+}
+
+/// An edit of one field of the [`Document`], storing the values before and after.
+enum DocumentEdit {
+    Title(String, String),
+    Size(f32, f32),
+    Color(egui::Color32, egui::Color32),
+}
+
+impl Command for DocumentEdit {
+    type Target = Document;
+
+    fn apply(&mut self, document: &mut Document) {
+        match self {
+            Self::Title(_, after) => document.title = after.clone(),
+            Self::Size(_, after) => document.size = *after,
+            Self::Color(_, after) => document.color = *after,
+        }
+    }
+
+    fn undo(&mut self, document: &mut Document) {
+        match self {
+            Self::Title(before, _) => document.title = before.clone(),
+            Self::Size(before, _) => document.size = *before,
+            Self::Color(before, _) => document.color = *before,
+        }
+    }
+
+    fn merge(&mut self, next: &Self) -> bool {
+        match (self, next) {
+            (Self::Title(_, after), Self::Title(_, next)) => *after = next.clone(),
+            (Self::Size(_, after), Self::Size(_, next)) => *after = *next,
+            (Self::Color(_, after), Self::Color(_, next)) => *after = *next,
+            _ => return false,
+        }
+        true
+    }
+}
+
+#[derive(Default)]
+struct DocumentDemo {
+    document: Document,
+    history: CommandStack<DocumentEdit>,
+}
+
+impl DocumentDemo {
+    fn show(&mut self, ctx: &egui::Context, frame: &Frame) {
+        self.history.handle_actions(frame, &mut self.document);
+        egui::Window::new("Document").show(ctx, |ui| {
+            self.history.ui(ui, &mut self.document);
+            let before = self.document.clone();
+            let document = &mut self.document;
+            ui.text_edit_singleline(&mut document.title);
+            let size = ui.add(egui::Slider::new(&mut document.size, 0.5..=4.0).text("Size"));
+            ui.color_edit_button_srgba(&mut document.color);
+
+            if document.title != before.title {
+                let edit = DocumentEdit::Title(before.title, document.title.clone());
+                self.history.push(edit);
+            } else if document.size != before.size {
+                self.history
+                    .push(DocumentEdit::Size(before.size, document.size));
+            } else if document.color != before.color {
+                self.history
+                    .push(DocumentEdit::Color(before.color, document.color));
+            }
+            if size.drag_released() {
+                self.history.seal();
+            }
+            ui.label(format!(
+                "{} undo and {} redo steps",
+                self.history.undo_len(),
+                self.history.redo_len()
+            ));
+        });
+    }
+}
+
+#[derive(Default)]
+struct DemoApp {
+    scene: BackgroundScene,
+    show_viewport: Arc<AtomicBool>,
+    dropped_files: DroppedFiles,
+    #[cfg(not(any(target_arch = "wasm32", target_os = "android", target_os = "ios")))]
+    file_dialogs: egui_tests::file_dialog::FileDialogs,
+    clipboard: Clipboard,
+    pasted_image: Option<egui::TextureHandle>,
+    background_task_status: String,
+    #[cfg(not(target_arch = "wasm32"))]
+    tasks: egui_tests::tasks::TaskManager,
+    #[cfg(all(feature = "tokio", not(target_arch = "wasm32")))]
+    async_tasks: egui_tests::runtime::AsyncTasks<String>,
+    /// Added as a plugin on the first update.
+    log_console: Option<LogConsole>,
+    icon: Option<WindowIcon>,
+    icon_badge: bool,
+    ime_text: String,
+    accessibility: AccessibilityDemo,
+    document: DocumentDemo,
+    #[cfg(feature = "markdown")]
+    help: Option<egui_tests::markdown::Markdown>,
+    #[cfg(feature = "code-editor")]
+    script: String,
+    #[cfg(feature = "shader-playground")]
+    shader_playground: egui_tests::shader_playground::ShaderPlayground,
+    #[cfg(feature = "plot")]
+    metrics: Option<(
+        egui_tests::metrics::MetricsStore,
+        egui_tests::metrics::MetricsChart,
+    )>,
+    /// Created when the window is first opened.
+    large_table: Option<(VirtualTable, Vec<TableRow>)>,
+    sidebar_open: bool,
+    sidebar_width: Animated<f32>,
+    highlight: Animated<egui::Color32>,
+}
+
+struct TableRow {
+    id: u32,
+    group: char,
+    value: f32,
+}
+
+impl TableRow {
+    /// Rows with pseudo random values.
+    fn generate(count: u32) -> Vec<Self> {
+        (0..count)
+            .map(|id| {
+                let hash = id.wrapping_mul(2_654_435_761);
+                Self {
+                    id,
+                    group: char::from(b'A' + (hash % 26) as u8),
+                    value: (hash >> 8) as f32 / (1 << 24) as f32,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Controls with labels attached, as read by screen readers with the `accesskit` feature.
+#[derive(Default)]
+struct AccessibilityDemo {
+    open: bool,
+    name: String,
+    volume: u8,
+    subscribed: bool,
+}
+
+impl AccessibilityDemo {
+    fn show(&mut self, ctx: &egui::Context) {
+        egui::Window::new("Accessibility")
+            .open(&mut self.open)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let label = ui.label("Name:");
+                    ui.text_edit_singleline(&mut self.name)
+                        .labelled_by(label.id);
+                });
+                ui.horizontal(|ui| {
+                    let label = ui.label("Volume:");
+                    ui.add(egui::Slider::new(&mut self.volume, 0..=100))
+                        .labelled_by(label.id);
+                });
+                ui.checkbox(&mut self.subscribed, "Subscribe to updates");
+                if ui.button("Submit").clicked() {
+                    notifications::notify_info(ui.ctx(), format!("Submitted {}", self.name));
+                }
+            });
+    }
+}
+
+impl DemoApp {
+    fn settings_ui(ui: &mut egui::Ui, frame: &mut Frame) {
+        if ui.button("Open preferences").clicked() {
+            frame.settings_mut().set_visible(true);
+        }
+        #[cfg(feature = "demo-lib")]
+        {
+            use egui_tests::plugin::EguiDemoPlugin;
+
+            let mut demo = frame.plugins().is_enabled(EguiDemoPlugin::NAME);
+            if ui.checkbox(&mut demo, "egui demo windows").changed() {
+                frame.plugins_mut().set_enabled(EguiDemoPlugin::NAME, demo);
+            }
+        }
+        ui.collapsing("Plugins", |ui| frame.plugins_mut().ui(ui));
+
+        let mut theme = frame.theme().preference();
+        if theme.ui(ui) {
+            frame.set_theme_preference(theme);
+        }
+
+        let mut zoom_factor = ui.ctx().zoom_factor();
+        if ui
+            .add(
+                egui::Slider::new(&mut zoom_factor, 0.5..=3.0)
+                    .step_by(0.1)
+                    .text("UI zoom"),
+            )
+            .changed()
+        {
+            ui.ctx().set_zoom_factor(zoom_factor);
+        }
+
+        ui.label(format!(
+            "Frame time: {:.2} ms (work: {:.2} ms)",
+            frame.frame_time().as_secs_f64() * 1000.0,
+            frame.work_time().as_secs_f64() * 1000.0,
+        ));
+        if let Some(timestep) = frame.fixed_timestep() {
+            ui.label(format!(
+                "Fixed updates: {} (interpolation {:.2})",
+                timestep.ticks(),
+                timestep.interpolation()
+            ));
+        }
+        let mut max_fps = frame.max_fps();
+        egui::ComboBox::from_label("FPS limit")
+            .selected_text(max_fps.map_or("Unlimited".to_owned(), |fps| fps.to_string()))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut max_fps, Some(60), "60");
+                ui.selectable_value(&mut max_fps, Some(120), "120");
+                ui.selectable_value(&mut max_fps, None, "Unlimited");
+            });
+        frame.set_max_fps(max_fps);
+        let mut render_mode = frame.render_mode();
+        ui.horizontal(|ui| {
+            ui.label("Rendering:");
+            ui.selectable_value(&mut render_mode, RenderMode::Reactive, "Reactive");
+            ui.selectable_value(&mut render_mode, RenderMode::Continuous, "Continuous");
+        });
+        frame.set_render_mode(render_mode);
+
+        let mut opacity = frame.opacity();
+        if ui
+            .add(egui::Slider::new(&mut opacity, 0.2..=1.0).text("UI opacity"))
+            .changed()
+        {
+            frame.set_opacity(opacity);
+        }
+
+        let mut click_through = frame.click_through();
+        if ui
+            .checkbox(&mut click_through, "Click-through overlay")
+            .changed()
+        {
+            frame.set_click_through(click_through);
+        }
+
+        let mut window_level = frame.window_level();
+        ui.horizontal(|ui| {
+            ui.label("Window level:");
+            ui.selectable_value(&mut window_level, WindowLevel::AlwaysOnBottom, "Bottom");
+            ui.selectable_value(&mut window_level, WindowLevel::Normal, "Normal");
+            ui.selectable_value(&mut window_level, WindowLevel::AlwaysOnTop, "On top");
+        });
+        frame.set_window_level(window_level);
+
+        let mut decorations = frame.decorations();
+        if ui
+            .checkbox(&mut decorations, "OS window decorations")
+            .changed()
+        {
+            frame.set_decorations(decorations);
+        }
+
+        let mut corner_radius = frame.corner_radius();
+        if ui
+            .add(egui::Slider::new(&mut corner_radius, 0.0..=32.0).text("Corner radius"))
+            .changed()
+        {
+            frame.set_corner_radius(corner_radius);
+        }
+
+        let mut backdrop = frame.backdrop();
+        ui.horizontal(|ui| {
+            ui.label("Backdrop:");
+            ui.selectable_value(&mut backdrop, Backdrop::None, "None");
+            ui.selectable_value(&mut backdrop, Backdrop::Blur, "Blur");
+            ui.selectable_value(&mut backdrop, Backdrop::Acrylic, "Acrylic");
+            ui.selectable_value(&mut backdrop, Backdrop::Mica, "Mica");
+            let vibrancy = Backdrop::Vibrancy(VibrancyMaterial::default());
+            if ui
+                .selectable_label(matches!(backdrop, Backdrop::Vibrancy(_)), "Vibrancy")
+                .clicked()
+            {
+                backdrop = vibrancy;
+            }
+        });
+        if let Backdrop::Vibrancy(material) = &mut backdrop {
+            egui::ComboBox::from_label("Vibrancy material")
+                .selected_text(format!("{material:?}"))
+                .show_ui(ui, |ui| {
+                    for option in VibrancyMaterial::ALL {
+                        ui.selectable_value(material, option, format!("{option:?}"));
+                    }
+                });
+        }
+        frame.set_backdrop(backdrop);
+
+        let mut fullscreen_mode = frame.fullscreen_mode();
+        ui.horizontal(|ui| {
+            ui.label("Fullscreen:");
+            ui.selectable_value(&mut fullscreen_mode, FullscreenMode::Windowed, "Off");
+            ui.selectable_value(
+                &mut fullscreen_mode,
+                FullscreenMode::Borderless,
+                "Borderless",
+            );
+            ui.selectable_value(&mut fullscreen_mode, FullscreenMode::Exclusive, "Exclusive");
+        });
+        frame.set_fullscreen_mode(fullscreen_mode);
+
+        let mut touch_gestures = frame.touch_gestures();
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut touch_gestures.pinch_to_zoom, "Pinch to zoom");
+            ui.checkbox(&mut touch_gestures.two_finger_scroll, "Two finger scroll");
+        });
+        frame.set_touch_gestures(touch_gestures);
+
+        ui.collapsing("Monitors", |ui| {
+            let current = frame.current_monitor();
+            let mut placement = None;
+            for (index, monitor) in frame.monitors().iter().enumerate() {
+                ui.horizontal(|ui| {
+                    let marker = if current == Some(index) { "▶" } else { " " };
+                    ui.label(format!("{marker} {}", monitor.label()));
+                    if ui.button("Center").clicked() {
+                        placement = Some(MonitorPlacement::Center(index));
+                    }
+                    if ui.button("Fill").clicked() {
+                        placement = Some(MonitorPlacement::Fill(index));
+                    }
+                });
+            }
+            if ui.button("Span all monitors").clicked() {
+                placement = Some(MonitorPlacement::SpanAll);
+            }
+            if let Some(placement) = placement {
+                frame.place_window(placement);
+            }
+        });
+
+        let mut present_mode = frame.present_mode();
+        egui::ComboBox::from_label("Present mode")
+            .selected_text(format!("{present_mode:?}"))
+            .show_ui(ui, |ui| {
+                for &mode in frame.supported_present_modes() {
+                    ui.selectable_value(&mut present_mode, mode, format!("{mode:?}"));
+                }
+            });
+        frame.set_present_mode(present_mode);
+
+        let mut diagnostics_visible = frame.diagnostics().visible();
+        if ui
+            .checkbox(&mut diagnostics_visible, "Frame time graph")
+            .changed()
+        {
+            frame.set_diagnostics_visible(diagnostics_visible);
+        }
+
+        let mut gpu_errors_visible = frame.gpu_errors().visible();
+        if ui.checkbox(&mut gpu_errors_visible, "GPU errors").changed() {
+            frame.gpu_errors_mut().set_visible(gpu_errors_visible);
+        }
+        if ui.button("Simulate device loss").clicked() {
+            frame.recreate_gpu();
+        }
+
+        #[cfg(feature = "puffin")]
+        {
+            let mut profiler_visible = frame.profiler_visible();
+            if ui.checkbox(&mut profiler_visible, "Profiler").changed() {
+                frame.set_profiler_visible(profiler_visible);
+            }
+        }
+
+        ui.collapsing("Post processing", |ui| frame.post_process_mut().ui(ui));
+        ui.collapsing("Shortcuts", |ui| frame.shortcuts_mut().ui(ui));
 
-    let window = Box::leak(Box::new(window));
-
-    let mut first_resize_happened = cfg!(not(target_os = "windows"));
-
-    event_loop
-        .run(move |event, target, control_flow| {
-            // Have the closure take ownership of the resources.
-            // `event_loop.run` never returns, therefore we must do this to ensure
-            // the resources are properly cleaned up.
-            let _ = (&instance, &adapter, &pipeline_layout);
-
-            match event {
-                Event::RedrawRequested(_) => {
-                    // egui
-                    let raw_input = winit_state.take_egui_input(&window);
-                    context.begin_frame(raw_input);
-                    context.set_visuals(Visuals {
-                        window_fill: Color32::TRANSPARENT,
-                        panel_fill: Color32::TRANSPARENT,
-                        override_text_color: Some(Color32::RED),
-                        faint_bg_color: Color32::RED,
-                        extreme_bg_color: Color32::BLUE,
-                        ..Default::default()
+        ui.collapsing("About", |ui| {
+            ui.label("Position, size and collapsed state of this window survive restarts.");
+        });
+    }
+}
+
+impl App for DemoApp {
+    fn setup(&mut self, _device: &wgpu::Device, _queue: &wgpu::Queue) {
+        // Also called after the device was recreated, the preview belongs to the old one
+        self.scene.preview = SceneView::new(wgpu::TextureFormat::Rgba8UnormSrgb);
+    }
+
+    fn fixed_update(&mut self, step: std::time::Duration) {
+        self.scene.previous_phase = self.scene.phase;
+        self.scene.phase += step.as_secs_f64();
+    }
+
+    fn update(&mut self, ctx: &egui::Context, frame: &mut Frame) {
+        if frame.post_process().effects().is_empty() {
+            let post_process = frame.post_process_mut();
+            for (name, source) in [
+                ("Bloom", effects::BLOOM),
+                ("FXAA", effects::FXAA),
+                ("Vignette", effects::VIGNETTE),
+            ] {
+                post_process.add(name, source);
+                post_process.set_enabled(name, false);
+            }
+        }
+        for BackgroundTaskDone(status) in frame.take_messages() {
+            self.background_task_status = status;
+        }
+        #[cfg(all(feature = "tokio", not(target_arch = "wasm32")))]
+        for status in self.async_tasks.poll() {
+            self.background_task_status = status;
+        }
+
+        if !frame.decorations() {
+            title_bar::resize_grips(ctx, frame);
+            title_bar::title_bar(ctx, frame, "egui example");
+        }
+        if let Some(console) = self.log_console.take() {
+            frame.plugins_mut().add(LogConsolePlugin::new(console));
+        }
+
+        let sidebar_width = self
+            .sidebar_width
+            .animate(ctx, if self.sidebar_open { 200.0 } else { 0.0 });
+        if sidebar_width > 0.0 {
+            let panel = egui::SidePanel::right("animated sidebar")
+                .resizable(false)
+                .exact_width(sidebar_width)
+                .frame(egui::Frame::side_top_panel(&ctx.style()).fill(egui::Color32::TRANSPARENT))
+                .show(ctx, |ui| {
+                    ui.heading("Sidebar");
+                    ui.label("Slides in and out without redrawing while it rests.");
+                    ui.label("The scene behind it is blurred.");
+                });
+            frame.frosted_glass_mut().add(panel.response.rect);
+        }
+
+        let central_frame = egui::Frame::central_panel(&ctx.style());
+        let central_frame =
+            central_frame.inner_margin(central_frame.inner_margin + frame.safe_area_insets());
+        egui::CentralPanel::default()
+            .frame(central_frame)
+            .show(ctx, |ui| {
+                ui.label("Hello world".to_owned());
+                ui.label("こんにちは世界 / 你好，世界 / 안녕하세요 세계");
+                ui.checkbox(&mut self.accessibility.open, "Accessibility demo");
+                ui.horizontal(|ui| {
+                    ui.label("IME input:");
+                    ui.text_edit_singleline(&mut self.ime_text);
+                });
+
+                ui.scope(|ui| {
+                    let _ = ui.button("aaa");
+                });
+
+                if ui.button("Click me").clicked() {
+                    println!("Clicked");
+                }
+
+                let mut show_log_console = frame.plugins().is_enabled("log_console");
+                if ui
+                    .checkbox(&mut show_log_console, "Show log console")
+                    .changed()
+                {
+                    frame
+                        .plugins_mut()
+                        .set_enabled("log_console", show_log_console);
+                }
+
+                if ui.button("Take screenshot").clicked() {
+                    frame.request_screenshot("screenshot.png");
+                }
+
+                #[cfg(not(target_arch = "wasm32"))]
+                ui.horizontal(|ui| {
+                    use egui_tests::recorder::RecordingFormat;
+
+                    let recording = frame.recorder().is_recording();
+                    let label = if recording {
+                        "Stop recording"
+                    } else {
+                        "Start recording"
+                    };
+                    if ui.button(label).clicked() {
+                        frame.toggle_recording();
+                    }
+                    let recorder = frame.recorder_mut();
+                    let mut format = recorder.format();
+                    ui.add_enabled_ui(!recording, |ui| {
+                        ui.selectable_value(&mut format, RecordingFormat::Gif, "GIF");
+                        ui.selectable_value(&mut format, RecordingFormat::Mp4, "MP4 (ffmpeg)");
                     });
-                    egui::CentralPanel::default().show(&context, |ui| {
-                        ui.label("Hello world".to_owned());
+                    recorder.set_format(format);
+                });
 
-                        ui.scope(|ui| {
-                            ui.button("aaa");
+                #[cfg(not(target_arch = "wasm32"))]
+                if ui
+                    .add_enabled(
+                        !frame.replay_buffer().is_empty(),
+                        egui::Button::new("Save last 10 seconds"),
+                    )
+                    .clicked()
+                {
+                    frame.save_replay();
+                }
+
+                #[cfg(not(target_arch = "wasm32"))]
+                ui.horizontal(|ui| {
+                    if ui.button("Run background task").clicked() {
+                        self.background_task_status = "Running…".to_owned();
+                        let proxy = frame.proxy();
+                        std::thread::spawn(move || {
+                            std::thread::sleep(std::time::Duration::from_secs(2));
+                            proxy.send(BackgroundTaskDone("Done".to_owned()));
                         });
+                    }
+                    #[cfg(all(feature = "tokio", not(target_arch = "wasm32")))]
+                    if ui.button("Run async task").clicked() {
+                        self.background_task_status = "Running async…".to_owned();
+                        self.async_tasks.spawn(ctx, async {
+                            tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+                            "Async task done".to_owned()
+                        });
+                    }
+                    ui.label(&self.background_task_status);
+                });
 
-                        if ui.button("Click me").clicked() {
-                            println!("Clicked");
+                ui.horizontal(|ui| {
+                    if ui.button("Info").clicked() {
+                        notifications::notify_info(ctx, "Something happened");
+                    }
+                    if ui.button("Warning").clicked() {
+                        notifications::notify_warn(ctx, "Something looks off");
+                    }
+                    if ui.button("Error").clicked() {
+                        notifications::notify_error(ctx, "Something went wrong");
+                    }
+                });
+
+                if let Some(icon) = &self.icon {
+                    if ui.checkbox(&mut self.icon_badge, "Icon badge").changed() {
+                        frame.set_icon(Some(if self.icon_badge {
+                            icon.with_badge(egui::Color32::RED)
+                        } else {
+                            icon.clone()
+                        }));
+                    }
+                }
+
+                #[cfg(not(target_arch = "wasm32"))]
+                if ui.button("Start long task").clicked() {
+                    self.tasks.spawn(ctx, "Long task", |task| {
+                        for step in 0..100 {
+                            if task.is_cancelled() {
+                                return;
+                            }
+                            task.set_message(format!("Step {step} of 100"));
+                            task.set_progress(step as f32 / 100.0);
+                            std::thread::sleep(std::time::Duration::from_millis(50));
                         }
                     });
-                    let output = context.end_frame();
-
-
-                    let frame = surface
-                        .get_current_texture()
-                        .expect("Failed to acquire next swap chain texture");
-                    let view = frame
-                        .texture
-                        .create_view(&wgpu::TextureViewDescriptor::default());
-                    let mut encoder =
-                        device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
-                            label: None,
+                }
+
+                let mut show_viewport = self.show_viewport.load(Ordering::Relaxed);
+                ui.checkbox(&mut show_viewport, "Show deferred viewport");
+                self.show_viewport.store(show_viewport, Ordering::Relaxed);
+            });
+
+        egui::Window::new("Settings").show(ctx, |ui| Self::settings_ui(ui, frame));
+
+        self.dropped_files.update(ctx);
+        dropped_files::paint_hovered_files(ctx);
+        #[cfg(not(any(target_arch = "wasm32", target_os = "android", target_os = "ios")))]
+        for result in self.file_dialogs.poll(ctx) {
+            if let Some(path) = result.path {
+                self.dropped_files.add(ctx, path);
+            }
+        }
+        egui::Window::new("Files").show(ctx, |ui| {
+            #[cfg(not(any(target_arch = "wasm32", target_os = "android", target_os = "ios")))]
+            if ui
+                .add_enabled(!self.file_dialogs.is_pending(), egui::Button::new("Open…"))
+                .clicked()
+            {
+                self.file_dialogs.open(rfd::AsyncFileDialog::new());
+            }
+            self.dropped_files.ui(ui);
+        });
+
+        if Clipboard::paste_pressed(ctx) {
+            if let Some(texture) = self.clipboard.paste_texture(ctx, "pasted image") {
+                self.pasted_image = Some(texture);
+            }
+        }
+        egui::Window::new("Clipboard image").show(ctx, |ui| match &self.pasted_image {
+            Some(texture) => {
+                ui.label(format!("{} x {}", texture.size()[0], texture.size()[1]));
+                ui.add(egui::Image::new(texture).max_width(256.0));
+            }
+            None => {
+                ui.label("Copy an image and press Ctrl+V");
+            }
+        });
+
+        #[cfg(feature = "icons")]
+        egui::Window::new("Icons")
+            .default_open(false)
+            .show(ctx, |ui| {
+                use egui_tests::icons::{Icon, IconUi};
+                ui.horizontal_wrapped(|ui| {
+                    for &icon in Icon::ALL {
+                        ui.icon(icon).on_hover_text(icon.name());
+                    }
+                });
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.icon_button(Icon::Save, "Save");
+                    ui.icon_button(Icon::Open, "Open");
+                    ui.label(egui::RichText::from(Icon::Warning).color(ui.visuals().warn_fg_color));
+                });
+            });
+
+        #[cfg(feature = "plot")]
+        egui::Window::new("Metrics")
+            .default_open(false)
+            .show(ctx, |ui| {
+                use egui_tests::metrics::{MetricsChart, MetricsStore};
+                let (store, chart) = self.metrics.get_or_insert_with(|| {
+                    let store = MetricsStore::default();
+                    #[cfg(not(target_arch = "wasm32"))]
+                    {
+                        // Samples from a background thread, running until the app quits
+                        let worker = store.clone();
+                        std::thread::spawn(move || loop {
+                            let time = worker.now();
+                            worker.push("sine", time.sin());
+                            worker
+                                .push("load", (time * 0.37).sin().abs() * (time * 3.1).cos().abs());
+                            std::thread::sleep(std::time::Duration::from_millis(50));
                         });
+                    }
+                    (store, MetricsChart::new("metrics").with_height(200.0))
+                });
+                store.push("frame time (ms)", frame.frame_time().as_secs_f64() * 1000.0);
+                chart.ui(ui, store);
+            });
 
-                    // prepare egui frame
-                    let paint_jobs = context
-                        .tessellate(output.shapes, output.pixels_per_point);
-                    let tdelta = output.textures_delta;
+        egui::Window::new("Animations")
+            .default_open(false)
+            .show(ctx, |ui| {
+                ui.checkbox(&mut self.sidebar_open, "Sidebar");
+                // Painted behind the label once its size is known
+                let background = ui.painter().add(egui::Shape::Noop);
+                let response =
+                    ui.add(egui::Label::new("Hover to highlight").sense(egui::Sense::hover()));
+                let color = if response.hovered() {
+                    ui.visuals().selection.bg_fill
+                } else {
+                    egui::Color32::TRANSPARENT
+                };
+                let color = self.highlight.animate(ctx, color);
+                ui.painter().set(
+                    background,
+                    egui::Shape::rect_filled(response.rect.expand(2.0), 2.0, color),
+                );
+                let mut dimmed = frame.opacity() < 1.0;
+                if ui.checkbox(&mut dimmed, "Dim UI").changed() {
+                    frame.fade_to(if dimmed { 0.5 } else { 1.0 }, 0.3);
+                }
+            });
 
-                    for (t_id, tdelta) in tdelta.set {
-                        egui_renderer
-                            .update_texture(&device, &queue, t_id, &tdelta);
+        egui::Window::new("Dialogs")
+            .default_open(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("Alert").clicked() {
+                        frame.dialogs_mut().alert("Something happened.");
+                    }
+                    if ui.button("Confirm").clicked() {
+                        let ctx = ctx.clone();
+                        frame
+                            .dialogs_mut()
+                            .confirm("Discard changes?")
+                            .on_result(move |discard| {
+                                let message = if discard { "Discarded" } else { "Kept" };
+                                notifications::notify_info(&ctx, message);
+                            });
                     }
+                    if ui.button("Prompt").clicked() {
+                        let ctx = ctx.clone();
+                        frame
+                            .dialogs_mut()
+                            .prompt("Name of the new layer:", "Layer 1")
+                            .on_result(move |name| {
+                                if let Some(name) = name {
+                                    notifications::notify_info(&ctx, format!("Added {name}"));
+                                }
+                            });
+                    }
+                });
+            });
 
-                    egui_renderer.update_buffers(
-                        &device,
-                        &queue,
-                        &mut encoder,
-                        &paint_jobs,
-                        &screen_descriptor,
+        egui::Window::new("Large table")
+            .default_open(false)
+            .default_size([360.0, 300.0])
+            .show(ctx, |ui| {
+                let (table, rows) = self.large_table.get_or_insert_with(|| {
+                    let table = VirtualTable::new(
+                        "large_table",
+                        [
+                            Column::new("Id", 80.0),
+                            Column::new("Group", 60.0),
+                            Column::new("Value", 120.0),
+                        ],
                     );
+                    (table, TableRow::generate(1_000_000))
+                });
+                ui.label(format!("{} rows", rows.len()));
+                table.show(
+                    ui,
+                    rows,
+                    |a, b, column| match column {
+                        0 => a.id.cmp(&b.id),
+                        1 => a.group.cmp(&b.group),
+                        _ => a.value.total_cmp(&b.value),
+                    },
+                    |ui, row, column| {
+                        match column {
+                            0 => ui.label(row.id.to_string()),
+                            1 => ui.label(row.group.to_string()),
+                            _ => ui.add(egui::ProgressBar::new(row.value)),
+                        };
+                    },
+                );
+            });
 
-                    {
-                        let mut egui_render_pass = encoder
-                            .begin_render_pass(&wgpu::RenderPassDescriptor {
-                                label: None,
-                                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                                    view: &view,
-                                    ops: wgpu::Operations {
-                                        load: LoadOp::Clear(Color::TRANSPARENT),
-                                        store: StoreOp::Store,
-                                    },
-                                    resolve_target: None,
-                                })],
-                                depth_stencil_attachment: None,
-                                occlusion_query_set: None,
-                                timestamp_writes: None,
-                            });
+        #[cfg(feature = "code-editor")]
+        egui::Window::new("Script")
+            .default_open(false)
+            .show(ctx, |ui| {
+                ui.add(
+                    egui_tests::code_editor::CodeEditor::new(&mut self.script, "rs")
+                        .id_source("script")
+                        .max_height(300.0),
+                );
+            });
 
-                        egui_renderer
-                            .render(&mut egui_render_pass, &paint_jobs, &screen_descriptor);
-                    }
+        #[cfg(feature = "shader-playground")]
+        egui::Window::new("Shader playground")
+            .default_open(false)
+            .show(ctx, |ui| self.shader_playground.ui(ui, frame.gpu()));
 
-                    queue.submit(Some(encoder.finish()));
-                    frame.present();
+        #[cfg(feature = "markdown")]
+        egui::Window::new("Help")
+            .default_open(false)
+            .default_size([360.0, 420.0])
+            .show(ctx, |ui| {
+                let help = self.help.get_or_insert_with(|| {
+                    egui_tests::markdown::Markdown::new("help")
+                        .with_max_image_width(64)
+                        .with_link_hook("settings")
+                });
+                help.show_scrollable(ui, include_str!("../assets/help.md"));
+                if help.link_clicked("settings") {
+                    frame.settings_mut().set_visible(true);
                 }
+            });
 
-                Event::WindowEvent {
-                    event: window_event, window_id: _
-                } => {
-                    match window_event {
-                        WindowEvent::Resized(new_size) => {
-                            if !first_resize_happened {
-                                first_resize_happened = true;
-                                return;
-                            }
-                            // egui resize
-                            screen_descriptor.size_in_pixels = [new_size.width, new_size.height];
-                            screen_descriptor.pixels_per_point = egui_winit::pixels_per_point(&context, &window);
-
-                            // Reconfigure the surface with the new size
-                            config.width = new_size.width.max(1);
-                            config.height = new_size.height.max(1);
-                            surface.configure(&device, &config);
-                            // On macos the window needs to be redrawn manually after resizing
-                            window.request_redraw();
-                        }
-                        WindowEvent::CloseRequested => {
-                            *control_flow = ControlFlow::ExitWithCode(0);
-                        }
-                        other => {
-                            let result =
-                                winit_state
-                                    .on_window_event(&context, &other);
-                            if result.repaint {
-                                window.request_redraw();
-                            }
-                        }
-                    };
+        #[cfg(feature = "image-loaders")]
+        egui::Window::new("Images").show(ctx, |ui| {
+            #[cfg(not(target_arch = "wasm32"))]
+            ui.add(egui::Image::new("file://assets/icon.png").max_width(64.0));
+            ui.add(egui::Image::new("https://picsum.photos/seed/egui/320/200").max_width(320.0));
+            #[cfg(all(feature = "svg", not(target_arch = "wasm32")))]
+            ui.horizontal(|ui| {
+                for size in [16.0, 32.0, 64.0] {
+                    ui.add(egui_tests::images::SvgImage::new(
+                        "file://assets/icon.svg",
+                        [size, size],
+                    ));
                 }
-                _ => {}
-            }
+            });
         });
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            egui::Window::new("Tasks").show(ctx, |ui| self.tasks.ui(ui));
+            self.tasks.show_toast(ctx);
+        }
+
+        self.accessibility.show(ctx);
+        self.document.show(ctx, frame);
+
+        egui::Window::new("Scene view")
+            .default_size([160.0, 120.0])
+            .resizable(true)
+            .show(ctx, |ui| {
+                self.scene.preview.show(ui, frame.textures());
+            });
+
+        if self.show_viewport.load(Ordering::Relaxed) {
+            let show_viewport = self.show_viewport.clone();
+            ctx.show_viewport_deferred(
+                ViewportId::from_hash_of("deferred_viewport"),
+                ViewportBuilder::default()
+                    .with_title("Deferred viewport")
+                    .with_inner_size([300.0, 200.0]),
+                move |ctx, _class| {
+                    egui::CentralPanel::default().show(ctx, |ui| {
+                        ui.label("Hello from a native window");
+                    });
+                    if ctx.input(|i| i.viewport().close_requested()) {
+                        show_viewport.store(false, Ordering::Relaxed);
+                        ctx.request_repaint_of(ViewportId::ROOT);
+                    }
+                },
+            );
+        }
+    }
+
+    // Edits of the document count as unsaved
+    fn can_close(&mut self) -> bool {
+        !self.document.history.can_undo()
+    }
+
+    fn scene_renderer(&mut self) -> Option<&mut dyn SceneRenderer> {
+        Some(&mut self.scene)
+    }
 }
 
 pub fn main() {
-    env_logger::init();
+    #[cfg(target_arch = "wasm32")]
+    std::panic::set_hook(Box::new(console_error_panic_hook::hook));
+    #[cfg(not(target_arch = "wasm32"))]
+    let args = <egui_tests::cli::CliArgs as clap::Parser>::parse();
+    #[cfg(not(target_arch = "wasm32"))]
+    let log_buffer = log_console::init(args.log_level.unwrap_or(log::LevelFilter::Info));
+    #[cfg(target_arch = "wasm32")]
+    let log_buffer = log_console::init(log::LevelFilter::Info);
+    let icon = WindowIcon::from_png(include_bytes!("../assets/icon.png"))
+        .expect("The embedded icon is a valid PNG");
 
-    let event_loop = EventLoop::new();
-    let window = winit::window::WindowBuilder::new()
+    let builder = AppBuilder::new()
+        .with_title("egui example")
+        .with_app_id("egui-example")
+        .with_icon(icon.clone())
         .with_transparent(true)
-        .build(&event_loop)
-        .unwrap();
-
-    pollster::block_on(run(event_loop, window));
+        .with_transparent_titlebar(true)
+        .with_max_fps(Some(60))
+        .with_unfocused_policy(UnfocusedPolicy::MaxFps(10))
+        .with_fade_in(0.3)
+        .with_fixed_update_rate(Some(30))
+        .with_msaa_samples(4)
+        .with_fonts(FontLoader::new().with_system_cjk_fallback());
+    #[cfg(all(feature = "global-hotkey", not(target_arch = "wasm32")))]
+    let builder = {
+        use egui_tests::hotkeys::{Code, HotKey, Modifiers};
+        builder.with_visibility_hotkey(HotKey::new(
+            Some(Modifiers::CONTROL | Modifiers::SHIFT),
+            Code::Space,
+        ))
+    };
+    #[cfg(all(feature = "tray", not(target_arch = "wasm32")))]
+    let builder = builder.with_tray(true);
+    #[cfg(all(feature = "gamepad", not(target_arch = "wasm32")))]
+    let builder = builder.with_gamepad(true);
+    #[cfg(not(target_arch = "wasm32"))]
+    let builder = builder
+        .with_replay_buffer(std::time::Duration::from_secs(10))
+        .with_config_file("app.toml")
+        .with_cli_args(&args);
+    builder.run(DemoApp {
+        log_console: Some(LogConsole::new(log_buffer)),
+        icon: Some(icon),
+        #[cfg(feature = "code-editor")]
+        script: "fn main() {\n    println!(\"Hello, world!\");\n}\n".to_owned(),
+        ..Default::default()
+    });
 }
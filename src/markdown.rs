@@ -0,0 +1,64 @@
+//! CommonMark rendering through `egui_commonmark`, e.g. for help screens and changelogs.
+//!
+//! Code blocks are highlighted with syntect in the colors of the current theme, images are
+//! loaded by URI like in [`crate::images`] and links open in the browser.
+
+use std::hash::Hash;
+
+use egui::{Id, Ui};
+use egui_commonmark::{CommonMarkCache, CommonMarkViewer};
+
+/// Renders markdown text, keeping the parsed syntax definitions and scroll state between frames.
+///
+/// ```no_run
+/// # fn ui(ui: &mut egui::Ui, help: &mut egui_tests::markdown::Markdown) {
+/// help.show(ui, "# Help\nPress `Ctrl+Shift+P` to open the [command palette](#commands).");
+/// # }
+/// ```
+pub struct Markdown {
+    id: Id,
+    cache: CommonMarkCache,
+    max_image_width: Option<usize>,
+}
+
+impl Markdown {
+    pub fn new(id_source: impl Hash) -> Self {
+        Self {
+            id: Id::new(id_source),
+            cache: CommonMarkCache::default(),
+            max_image_width: None,
+        }
+    }
+
+    /// Scales down images wider than `width` points.
+    pub fn with_max_image_width(mut self, width: usize) -> Self {
+        self.max_image_width = Some(width);
+        self
+    }
+
+    /// Handles clicks on links to `target` in the app instead of opening them, see
+    /// [`Self::link_clicked`].
+    pub fn with_link_hook(mut self, target: impl Into<String>) -> Self {
+        self.cache.add_link_hook(target);
+        self
+    }
+
+    /// Whether a link to a `target` added with [`Self::with_link_hook`] was clicked in the last
+    /// call to [`Self::show`].
+    pub fn link_clicked(&self, target: &str) -> bool {
+        self.cache.get_link_hook(target).unwrap_or(false)
+    }
+
+    fn viewer(&self) -> CommonMarkViewer {
+        CommonMarkViewer::new(self.id).max_image_width(self.max_image_width)
+    }
+
+    pub fn show(&mut self, ui: &mut Ui, text: &str) {
+        self.viewer().show(ui, &mut self.cache, text);
+    }
+
+    /// Shows `text` in a scroll area, only laying out the visible part of long documents.
+    pub fn show_scrollable(&mut self, ui: &mut Ui, text: &str) {
+        self.viewer().show_scrollable(ui, &mut self.cache, text);
+    }
+}
@@ -0,0 +1,227 @@
+//! Time series which any thread can push samples into, shown as live scrolling charts.
+//!
+//! ```no_run
+//! use egui_tests::metrics::MetricsStore;
+//!
+//! let metrics = MetricsStore::default();
+//! let worker = metrics.clone();
+//! std::thread::spawn(move || loop {
+//!     worker.push("queue length", 42.0);
+//!     std::thread::sleep(std::time::Duration::from_millis(100));
+//! });
+//! ```
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    hash::Hash,
+    sync::Arc,
+    time::Duration,
+};
+
+use egui::{mutex::Mutex, Id, Ui};
+use egui_plot::{Legend, Line, Plot, PlotBounds};
+use web_time::Instant;
+
+/// Number of samples kept per series if none is set.
+pub const DEFAULT_CAPACITY: usize = 10_000;
+
+const LIVE_REPAINT_INTERVAL: Duration = Duration::from_millis(33);
+
+struct Inner {
+    capacity: usize,
+    series: BTreeMap<String, VecDeque<[f64; 2]>>,
+}
+
+/// Named series of `[time, value]` samples, dropping the oldest samples beyond a capacity.
+///
+/// Clones share the same series. Times are in seconds since the store was created.
+#[derive(Clone)]
+pub struct MetricsStore {
+    started: Instant,
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl Default for MetricsStore {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl MetricsStore {
+    /// Keeps up to `capacity` samples per series.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            started: Instant::now(),
+            inner: Arc::new(Mutex::new(Inner {
+                capacity: capacity.max(1),
+                series: BTreeMap::new(),
+            })),
+        }
+    }
+
+    /// Seconds since the store was created.
+    pub fn now(&self) -> f64 {
+        self.started.elapsed().as_secs_f64()
+    }
+
+    /// Adds a sample to the series `name` at the current time.
+    pub fn push(&self, name: &str, value: f64) {
+        self.push_at(name, self.now(), value);
+    }
+
+    /// Adds a sample at `time` in seconds. Samples of a series are expected in order of time.
+    pub fn push_at(&self, name: &str, time: f64, value: f64) {
+        let mut inner = self.inner.lock();
+        let capacity = inner.capacity;
+        let samples = match inner.series.get_mut(name) {
+            Some(samples) => samples,
+            None => inner.series.entry(name.to_owned()).or_default(),
+        };
+        if samples.len() == capacity {
+            samples.pop_front();
+        }
+        samples.push_back([time, value]);
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.inner.lock().series.keys().cloned().collect()
+    }
+
+    pub fn samples(&self, name: &str) -> Vec<[f64; 2]> {
+        self.samples_since(name, f64::NEG_INFINITY)
+    }
+
+    /// The samples of `name` from `time` on.
+    pub fn samples_since(&self, name: &str, time: f64) -> Vec<[f64; 2]> {
+        let inner = self.inner.lock();
+        let Some(samples) = inner.series.get(name) else {
+            return Vec::new();
+        };
+        let start = samples.partition_point(|[t, _]| *t < time);
+        samples.range(start..).copied().collect()
+    }
+
+    pub fn latest(&self, name: &str) -> Option<f64> {
+        let inner = self.inner.lock();
+        inner.series.get(name)?.back().map(|[_, value]| *value)
+    }
+
+    /// Removes all series.
+    pub fn clear(&self) {
+        self.inner.lock().series.clear();
+    }
+}
+
+/// A chart of all series of a [`MetricsStore`], scrolling along with the latest samples.
+///
+/// Dragging or zooming the chart pauses it, resuming jumps back to the latest samples. While live,
+/// the chart repaints at 30 FPS.
+pub struct MetricsChart {
+    id: Id,
+    /// Seconds shown while live.
+    window: f64,
+    paused: bool,
+    height: Option<f32>,
+}
+
+impl MetricsChart {
+    pub fn new(id_source: impl Hash) -> Self {
+        Self {
+            id: Id::new(id_source),
+            window: 10.0,
+            paused: false,
+            height: None,
+        }
+    }
+
+    /// Shows the last `seconds` while live, 10 seconds by default.
+    pub fn with_window(mut self, seconds: f64) -> Self {
+        self.window = seconds;
+        self
+    }
+
+    pub fn with_height(mut self, height: f32) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui, store: &MetricsStore) {
+        ui.horizontal(|ui| {
+            let label = if self.paused {
+                "▶ Resume"
+            } else {
+                "⏸ Pause"
+            };
+            if ui.button(label).clicked() {
+                self.paused = !self.paused;
+            }
+            ui.add(
+                egui::Slider::new(&mut self.window, 1.0..=120.0)
+                    .logarithmic(true)
+                    .suffix(" s")
+                    .text("Window"),
+            );
+        });
+
+        let now = store.now();
+        let start = now - self.window;
+        // While paused, the whole history can be explored
+        let series: Vec<_> = store
+            .names()
+            .into_iter()
+            .map(|name| {
+                let samples = if self.paused {
+                    store.samples(&name)
+                } else {
+                    store.samples_since(&name, start)
+                };
+                (name, samples)
+            })
+            .collect();
+
+        let mut plot = Plot::new(self.id)
+            .legend(Legend::default())
+            .x_axis_label("s");
+        if let Some(height) = self.height {
+            plot = plot.height(height);
+        }
+        let paused = self.paused;
+        let response = plot.show(ui, |plot_ui| {
+            if !paused {
+                let (min, max) = series
+                    .iter()
+                    .flat_map(|(_, samples)| samples)
+                    .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), [_, y]| {
+                        (min.min(*y), max.max(*y))
+                    });
+                let (min, max) = if min <= max { (min, max) } else { (0.0, 1.0) };
+                let margin = ((max - min) * 0.1).max(1e-3);
+                plot_ui.set_plot_bounds(PlotBounds::from_min_max(
+                    [start, min - margin],
+                    [now, max + margin],
+                ));
+            }
+            for (name, samples) in series {
+                plot_ui.line(Line::new(samples).name(name));
+            }
+        });
+
+        let zoomed = response.response.hovered()
+            && ui
+                .input(|input| input.zoom_delta() != 1.0 || input.scroll_delta != egui::Vec2::ZERO);
+        if response.response.dragged() || zoomed {
+            self.paused = true;
+        }
+        if !self.paused {
+            ui.ctx().request_repaint_after(LIVE_REPAINT_INTERVAL);
+        }
+    }
+}
@@ -0,0 +1,188 @@
+//! Enumerates the connected monitors and places the window onto them.
+
+use std::time::Duration;
+
+use web_time::Instant;
+use winit::{
+    dpi::{PhysicalPosition, PhysicalSize},
+    monitor::MonitorHandle,
+    window::Window,
+};
+
+/// How often the list of monitors is refreshed to notice hotplugged monitors, winit has no
+/// event for it.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A monitor connected to the system. Positions and sizes are in physical pixels of the
+/// virtual desktop, so they can be compared across monitors with different scale factors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonitorInfo {
+    pub name: Option<String>,
+    pub position: PhysicalPosition<i32>,
+    pub size: PhysicalSize<u32>,
+    pub scale_factor: f64,
+    pub refresh_rate_millihertz: Option<u32>,
+    pub primary: bool,
+}
+
+impl MonitorInfo {
+    fn new(handle: &MonitorHandle, primary: Option<&MonitorHandle>) -> Self {
+        Self {
+            name: handle.name(),
+            position: handle.position(),
+            size: handle.size(),
+            scale_factor: handle.scale_factor(),
+            refresh_rate_millihertz: handle.refresh_rate_millihertz(),
+            primary: primary == Some(handle),
+        }
+    }
+
+    /// A label for monitor pickers, e.g. `DP-1 (2560×1440 @ 1.5x)`.
+    pub fn label(&self) -> String {
+        format!(
+            "{} ({}×{} @ {}x)",
+            self.name.as_deref().unwrap_or("Unknown"),
+            self.size.width,
+            self.size.height,
+            self.scale_factor
+        )
+    }
+
+    fn contains(&self, point: PhysicalPosition<i32>) -> bool {
+        let x = i64::from(point.x) - i64::from(self.position.x);
+        let y = i64::from(point.y) - i64::from(self.position.y);
+        (0..i64::from(self.size.width)).contains(&x)
+            && (0..i64::from(self.size.height)).contains(&y)
+    }
+}
+
+/// Where to move the window, see [`crate::Frame::place_window`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MonitorPlacement {
+    /// Centers the window on the monitor with the given index, keeping its size.
+    Center(usize),
+    /// Covers the monitor with the given index, like a borderless fullscreen overlay.
+    Fill(usize),
+    /// Covers the bounding box of all monitors.
+    SpanAll,
+}
+
+impl MonitorPlacement {
+    /// Returns the outer position and, if it changes, the size of the window on `monitors`.
+    pub fn target(
+        self,
+        monitors: &[MonitorInfo],
+        window_size: PhysicalSize<u32>,
+    ) -> Option<(PhysicalPosition<i32>, Option<PhysicalSize<u32>>)> {
+        match self {
+            MonitorPlacement::Center(index) => {
+                let monitor = monitors.get(index)?;
+                let offset =
+                    |monitor: u32, window: u32| (i64::from(monitor) - i64::from(window)) / 2;
+                let position = PhysicalPosition::new(
+                    monitor.position.x + offset(monitor.size.width, window_size.width) as i32,
+                    monitor.position.y + offset(monitor.size.height, window_size.height) as i32,
+                );
+                Some((position, None))
+            }
+            MonitorPlacement::Fill(index) => {
+                let monitor = monitors.get(index)?;
+                Some((monitor.position, Some(monitor.size)))
+            }
+            MonitorPlacement::SpanAll => {
+                let min_x = monitors.iter().map(|m| m.position.x).min()?;
+                let min_y = monitors.iter().map(|m| m.position.y).min()?;
+                let max_x = monitors
+                    .iter()
+                    .map(|m| m.position.x + m.size.width as i32)
+                    .max()?;
+                let max_y = monitors
+                    .iter()
+                    .map(|m| m.position.y + m.size.height as i32)
+                    .max()?;
+                Some((
+                    PhysicalPosition::new(min_x, min_y),
+                    Some(PhysicalSize::new(
+                        (max_x - min_x) as u32,
+                        (max_y - min_y) as u32,
+                    )),
+                ))
+            }
+        }
+    }
+
+    pub(crate) fn apply(self, window: &Window) {
+        let monitors = available_monitors(window);
+        let Some((position, size)) = self.target(&monitors, window.outer_size()) else {
+            log::warn!("Can not place the window with {self:?}, the monitor is gone");
+            return;
+        };
+        // Leave fullscreen first, which would otherwise keep the window on its monitor
+        window.set_fullscreen(None);
+        window.set_outer_position(position);
+        if let Some(size) = size {
+            window.set_inner_size(size);
+        }
+    }
+}
+
+/// Keeps the list of monitors up to date while frames are drawn.
+#[derive(Debug)]
+pub(crate) struct MonitorTracker {
+    monitors: Vec<MonitorInfo>,
+    current: Option<usize>,
+    last_poll: Option<Instant>,
+}
+
+impl MonitorTracker {
+    pub(crate) fn new() -> Self {
+        Self {
+            monitors: Vec::new(),
+            current: None,
+            last_poll: None,
+        }
+    }
+
+    pub(crate) fn monitors(&self) -> &[MonitorInfo] {
+        &self.monitors
+    }
+
+    pub(crate) fn current(&self) -> Option<usize> {
+        self.current
+    }
+
+    /// Refreshes the monitors if due and finds the one the window is on.
+    pub(crate) fn update(&mut self, window: &Window) {
+        let now = Instant::now();
+        if !matches!(self.last_poll, Some(last_poll) if now - last_poll < POLL_INTERVAL) {
+            self.last_poll = Some(now);
+            let monitors = available_monitors(window);
+            if monitors != self.monitors {
+                if !self.monitors.is_empty() {
+                    log::info!("Monitors changed, {} connected", monitors.len());
+                }
+                self.monitors = monitors;
+            }
+        }
+
+        // The monitor containing the window center, like the OS decides it
+        let position = window.outer_position().unwrap_or_default();
+        let size = window.outer_size();
+        let center = PhysicalPosition::new(
+            position.x + size.width as i32 / 2,
+            position.y + size.height as i32 / 2,
+        );
+        self.current = self
+            .monitors
+            .iter()
+            .position(|monitor| monitor.contains(center));
+    }
+}
+
+fn available_monitors(window: &Window) -> Vec<MonitorInfo> {
+    let primary = window.primary_monitor();
+    window
+        .available_monitors()
+        .map(|handle| MonitorInfo::new(&handle, primary.as_ref()))
+        .collect()
+}
@@ -0,0 +1,114 @@
+use std::time::Duration;
+
+use egui::{Align2, Color32, Id, Order};
+
+/// Where the notifications of all toasts are stored in the egui context.
+const STORAGE_ID: &str = "notifications";
+/// Maximum number of toasts shown at once, older ones are dismissed first.
+const MAX_TOASTS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationLevel {
+    Info,
+    Warning,
+    Error,
+}
+
+impl NotificationLevel {
+    /// How long a toast of this level stays visible.
+    pub fn duration(self) -> Duration {
+        match self {
+            NotificationLevel::Info => Duration::from_secs(4),
+            NotificationLevel::Warning => Duration::from_secs(6),
+            NotificationLevel::Error => Duration::from_secs(8),
+        }
+    }
+
+    fn color(self, visuals: &egui::Visuals) -> Color32 {
+        match self {
+            NotificationLevel::Info => visuals.text_color(),
+            NotificationLevel::Warning => visuals.warn_fg_color,
+            NotificationLevel::Error => visuals.error_fg_color,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Toast {
+    level: NotificationLevel,
+    text: String,
+    /// egui input time after which the toast is dismissed.
+    expires_at: f64,
+}
+
+/// Shows `text` as a toast in the bottom left corner. Can be called from any thread.
+pub fn notify(ctx: &egui::Context, level: NotificationLevel, text: impl Into<String>) {
+    let expires_at = ctx.input(|i| i.time) + level.duration().as_secs_f64();
+    ctx.data_mut(|data| {
+        let toasts = data.get_temp_mut_or_default::<Vec<Toast>>(Id::new(STORAGE_ID));
+        toasts.push(Toast {
+            level,
+            text: text.into(),
+            expires_at,
+        });
+        if toasts.len() > MAX_TOASTS {
+            toasts.remove(0);
+        }
+    });
+    ctx.request_repaint();
+}
+
+pub fn notify_info(ctx: &egui::Context, text: impl Into<String>) {
+    notify(ctx, NotificationLevel::Info, text);
+}
+
+pub fn notify_warn(ctx: &egui::Context, text: impl Into<String>) {
+    notify(ctx, NotificationLevel::Warning, text);
+}
+
+pub fn notify_error(ctx: &egui::Context, text: impl Into<String>) {
+    notify(ctx, NotificationLevel::Error, text);
+}
+
+/// Draws the pending toasts stacked from the bottom up and dismisses expired or clicked ones.
+pub(crate) fn show(ctx: &egui::Context) {
+    let id = Id::new(STORAGE_ID);
+    let now = ctx.input(|i| i.time);
+    let mut toasts = ctx.data_mut(|data| data.get_temp::<Vec<Toast>>(id).unwrap_or_default());
+    toasts.retain(|toast| toast.expires_at > now);
+    if toasts.is_empty() {
+        ctx.data_mut(|data| data.remove::<Vec<Toast>>(id));
+        return;
+    }
+
+    let mut dismissed = None;
+    let mut offset = -8.0;
+    for (index, toast) in toasts.iter().enumerate().rev() {
+        let response = egui::Area::new(id.with(index))
+            .anchor(Align2::LEFT_BOTTOM, [8.0, offset])
+            .order(Order::Foreground)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.set_max_width(300.0);
+                    ui.colored_label(toast.level.color(ui.visuals()), &toast.text);
+                })
+            })
+            .response;
+        if response.interact(egui::Sense::click()).clicked() {
+            dismissed = Some(index);
+        }
+        offset -= response.rect.height() + 4.0;
+    }
+    if let Some(index) = dismissed {
+        toasts.remove(index);
+    }
+
+    if let Some(next_expiry) = toasts
+        .iter()
+        .map(|toast| toast.expires_at)
+        .min_by(f64::total_cmp)
+    {
+        ctx.request_repaint_after(Duration::from_secs_f64(next_expiry - now));
+    }
+    ctx.data_mut(|data| data.insert_temp(id, toasts));
+}
@@ -0,0 +1,94 @@
+use std::time::Duration;
+
+use egui::{Order, Pos2};
+use winit::window::Window;
+
+/// How often the global cursor position is polled while input passes through the window.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Lets mouse input pass through the window to whatever is underneath, except where egui
+/// widgets are hovered.
+///
+/// Like [`egui::Context::is_pointer_over_area`], the space left for the central panel counts as
+/// background, so the overlay UI should live in windows or side panels.
+///
+/// While input passes through, the window receives no cursor events, so the global cursor
+/// position is polled to notice when the cursor enters an egui area again.
+#[derive(Debug)]
+pub struct ClickThrough {
+    enabled: bool,
+    hittest: bool,
+    warned: bool,
+}
+
+impl ClickThrough {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            hittest: true,
+            warned: false,
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Updates the cursor hit test of `window` after an egui frame.
+    pub fn update(&mut self, ctx: &egui::Context, window: &Window) {
+        let hittest = !self.enabled
+            || ctx.wants_pointer_input()
+            || ctx.is_pointer_over_area()
+            || global_pointer_pos(ctx, window).is_some_and(|pos| is_over_area(ctx, pos));
+
+        if hittest != self.hittest {
+            match window.set_cursor_hittest(hittest) {
+                Ok(()) => self.hittest = hittest,
+                Err(err) if !self.warned => {
+                    log::warn!("Click-through is not supported on this platform: {err}");
+                    self.warned = true;
+                }
+                Err(_) => {}
+            }
+        }
+
+        if !self.hittest {
+            ctx.request_repaint_after(POLL_INTERVAL);
+        }
+    }
+}
+
+/// Same as [`egui::Context::is_pointer_over_area`], but for an arbitrary position.
+fn is_over_area(ctx: &egui::Context, pos: Pos2) -> bool {
+    match ctx.layer_id_at(pos) {
+        Some(layer) if layer.order == Order::Background => !ctx.available_rect().contains(pos),
+        Some(_) => true,
+        None => false,
+    }
+}
+
+/// Position of the cursor in egui points relative to the window, even if the window does not
+/// receive cursor events.
+#[cfg(not(any(target_arch = "wasm32", target_os = "android", target_os = "ios")))]
+fn global_pointer_pos(ctx: &egui::Context, window: &Window) -> Option<Pos2> {
+    use mouse_position::mouse_position::Mouse;
+
+    let Mouse::Position { x, y } = Mouse::get_mouse_position() else {
+        return None;
+    };
+    let origin = window.inner_position().ok()?;
+    let pixels_per_point = egui_winit::pixels_per_point(ctx, window);
+    Some(Pos2::new(
+        (x - origin.x) as f32 / pixels_per_point,
+        (y - origin.y) as f32 / pixels_per_point,
+    ))
+}
+
+#[cfg(any(target_arch = "wasm32", target_os = "android", target_os = "ios"))]
+fn global_pointer_pos(_ctx: &egui::Context, _window: &Window) -> Option<Pos2> {
+    None
+}
@@ -0,0 +1,238 @@
+use std::time::Duration;
+
+use web_time::Instant;
+
+/// Sleeping is only accurate to a few milliseconds on most platforms, so the last part of the
+/// wait is spent spinning.
+const SPIN_THRESHOLD: Duration = Duration::from_millis(2);
+
+/// Weight of the latest frame in [`FrameTime::smoothed_delta`].
+const SMOOTHING: f64 = 0.1;
+
+/// Timing of the current frame, for animations and simulations.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct FrameTime {
+    /// Time since the previous frame started, zero for the first frame.
+    pub delta: Duration,
+    /// Exponential moving average of [`Self::delta`], steadier for e.g. FPS counters.
+    pub smoothed_delta: Duration,
+    /// Time since the first frame started.
+    pub elapsed: Duration,
+    /// Number of frames before this one.
+    pub frame_index: u64,
+}
+
+impl FrameTime {
+    pub fn delta_secs(&self) -> f32 {
+        self.delta.as_secs_f32()
+    }
+
+    pub fn elapsed_secs(&self) -> f64 {
+        self.elapsed.as_secs_f64()
+    }
+}
+
+/// When frames are drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RenderMode {
+    /// Draws frames back to back, as fast as the frame rate limit and vsync allow, e.g. for games.
+    Continuous,
+    /// Only draws when egui asks for it, after input or [`egui::Context::request_repaint_after`],
+    /// e.g. for tools.
+    #[default]
+    Reactive,
+}
+
+/// How often frames are drawn while the window does not have the keyboard focus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnfocusedPolicy {
+    /// The same as while focused.
+    #[default]
+    Unchanged,
+    /// Caps the redraw rate, e.g. to keep an always running overlay cheap in the background.
+    MaxFps(u32),
+    /// Only redraws on input, e.g. the pointer moving over the window. Repaint requests,
+    /// including those of animations, wait until the window is focused again.
+    EventDriven,
+}
+
+/// Caps the redraw rate to a maximum number of frames per second and measures frame times.
+#[derive(Debug, Clone)]
+pub struct FramePacer {
+    max_fps: Option<u32>,
+    render_mode: RenderMode,
+    unfocused: UnfocusedPolicy,
+    focused: bool,
+    first_frame_start: Option<Instant>,
+    last_frame_start: Option<Instant>,
+    time: FrameTime,
+    work_time: Duration,
+}
+
+impl FramePacer {
+    /// Creates a pacer limiting redraws to `max_fps`, or not at all if `None`.
+    pub fn new(max_fps: Option<u32>) -> Self {
+        Self {
+            max_fps,
+            render_mode: RenderMode::Reactive,
+            unfocused: UnfocusedPolicy::Unchanged,
+            focused: true,
+            first_frame_start: None,
+            last_frame_start: None,
+            time: FrameTime::default(),
+            work_time: Duration::ZERO,
+        }
+    }
+
+    pub fn max_fps(&self) -> Option<u32> {
+        self.max_fps
+    }
+
+    pub fn set_max_fps(&mut self, max_fps: Option<u32>) {
+        self.max_fps = max_fps;
+    }
+
+    pub fn render_mode(&self) -> RenderMode {
+        self.render_mode
+    }
+
+    pub fn set_render_mode(&mut self, render_mode: RenderMode) {
+        self.render_mode = render_mode;
+    }
+
+    /// How long to wait before the next frame, given the delay egui asked for.
+    pub fn repaint_delay(&self, requested: Duration) -> Duration {
+        match self.render_mode {
+            RenderMode::Continuous => Duration::ZERO,
+            RenderMode::Reactive => requested,
+        }
+    }
+
+    pub fn unfocused_policy(&self) -> UnfocusedPolicy {
+        self.unfocused
+    }
+
+    pub fn set_unfocused_policy(&mut self, unfocused: UnfocusedPolicy) {
+        self.unfocused = unfocused;
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    /// Whether repaint requests are ignored, because the window is unfocused and
+    /// [`UnfocusedPolicy::EventDriven`].
+    pub fn is_event_driven(&self) -> bool {
+        !self.focused && self.unfocused == UnfocusedPolicy::EventDriven
+    }
+
+    /// Minimum time between the start of two frames, the lower of both caps while unfocused.
+    pub fn min_frame_interval(&self) -> Option<Duration> {
+        let max_fps = match self.unfocused {
+            UnfocusedPolicy::MaxFps(unfocused) if !self.focused => {
+                Some(self.max_fps.map_or(unfocused, |fps| fps.min(unfocused)))
+            }
+            _ => self.max_fps,
+        };
+        max_fps
+            .filter(|&fps| fps > 0)
+            .map(|fps| Duration::from_secs_f64(1.0 / fps as f64))
+    }
+
+    /// How long the event loop should sleep before the next frame while unfocused, instead of
+    /// blocking in [`Self::wait`]. `None` while focused or without an [`UnfocusedPolicy`].
+    pub fn unfocused_delay(&self) -> Option<Duration> {
+        if self.focused {
+            return None;
+        }
+        match self.unfocused {
+            UnfocusedPolicy::Unchanged => None,
+            UnfocusedPolicy::MaxFps(_) => Some(
+                self.next_frame_deadline()
+                    .map_or(Duration::ZERO, |deadline| {
+                        deadline.saturating_duration_since(Instant::now())
+                    }),
+            ),
+            UnfocusedPolicy::EventDriven => Some(Duration::MAX),
+        }
+    }
+
+    /// Time between the start of the last two frames.
+    pub fn frame_time(&self) -> Duration {
+        self.time.delta
+    }
+
+    /// Timing of the current frame, updated by [`Self::begin_frame`].
+    pub fn time(&self) -> FrameTime {
+        self.time
+    }
+
+    /// Time spent between [`Self::begin_frame`] and [`Self::end_frame`] of the last frame.
+    pub fn work_time(&self) -> Duration {
+        self.work_time
+    }
+
+    /// Earliest point in time the next frame should be started at.
+    pub fn next_frame_deadline(&self) -> Option<Instant> {
+        Some(self.last_frame_start? + self.min_frame_interval()?)
+    }
+
+    /// Blocks until the next frame may be started, sleeping first and spinning for the last
+    /// 2 ms to hit the deadline accurately.
+    pub fn wait(&self) {
+        // Blocking the main thread is not possible in the browser, which paces frames itself.
+        if cfg!(target_arch = "wasm32") {
+            return;
+        }
+        let Some(deadline) = self.next_frame_deadline() else {
+            return;
+        };
+
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining > SPIN_THRESHOLD {
+            std::thread::sleep(remaining - SPIN_THRESHOLD);
+        }
+        while Instant::now() < deadline {
+            std::hint::spin_loop();
+        }
+    }
+
+    /// Marks the start of a new frame.
+    pub fn begin_frame(&mut self) {
+        let now = Instant::now();
+        match self.last_frame_start {
+            Some(last_frame_start) => {
+                let delta = now - last_frame_start;
+                self.time.delta = delta;
+                self.time.smoothed_delta = if self.time.frame_index == 0 {
+                    delta
+                } else {
+                    self.time.smoothed_delta.mul_f64(1.0 - SMOOTHING) + delta.mul_f64(SMOOTHING)
+                };
+                self.time.frame_index += 1;
+            }
+            None => self.first_frame_start = Some(now),
+        }
+        self.time.elapsed = self
+            .first_frame_start
+            .map_or(Duration::ZERO, |first_frame_start| now - first_frame_start);
+        self.last_frame_start = Some(now);
+    }
+
+    /// Marks the end of the current frame.
+    pub fn end_frame(&mut self) {
+        if let Some(last_frame_start) = self.last_frame_start {
+            self.work_time = last_frame_start.elapsed();
+        }
+    }
+}
+
+impl Default for FramePacer {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
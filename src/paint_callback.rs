@@ -0,0 +1,65 @@
+//! Raw wgpu draws inside the rect of an egui widget, between the shapes painted before and after.
+//!
+//! Pipelines and buffers live in the [`CallbackResources`] of the renderer, stored once with
+//! [`crate::gpu::GpuContext::insert_callback_resources`]. Every frame, a [`CallbackTrait`] value
+//! carrying the per-frame data is added as a shape. Its [`CallbackTrait::prepare`] updates the
+//! resources before the egui render pass, [`CallbackTrait::paint`] draws inside it:
+//!
+//! ```no_run
+//! use egui_tests::paint_callback::{self, CallbackResources, CallbackTrait};
+//!
+//! struct Resources {
+//!     pipeline: wgpu::RenderPipeline,
+//!     uniforms: wgpu::Buffer,
+//!     bind_group: wgpu::BindGroup,
+//! }
+//!
+//! struct Draw {
+//!     time: f32,
+//! }
+//!
+//! impl CallbackTrait for Draw {
+//!     fn prepare(
+//!         &self,
+//!         _device: &wgpu::Device,
+//!         queue: &wgpu::Queue,
+//!         _encoder: &mut wgpu::CommandEncoder,
+//!         resources: &mut CallbackResources,
+//!     ) -> Vec<wgpu::CommandBuffer> {
+//!         let resources: &Resources = resources.get().unwrap();
+//!         queue.write_buffer(&resources.uniforms, 0, &self.time.to_le_bytes());
+//!         Vec::new()
+//!     }
+//!
+//!     fn paint<'a>(
+//!         &'a self,
+//!         _info: egui::PaintCallbackInfo,
+//!         render_pass: &mut wgpu::RenderPass<'a>,
+//!         resources: &'a CallbackResources,
+//!     ) {
+//!         let resources: &Resources = resources.get().unwrap();
+//!         render_pass.set_pipeline(&resources.pipeline);
+//!         render_pass.set_bind_group(0, &resources.bind_group, &[]);
+//!         render_pass.draw(0..3, 0..1);
+//!     }
+//! }
+//!
+//! # fn update(ui: &mut egui::Ui, time: f32) {
+//! let (rect, _) = ui.allocate_exact_size(egui::vec2(200.0, 200.0), egui::Sense::hover());
+//! ui.painter().add(paint_callback::shape(rect, Draw { time }));
+//! # }
+//! ```
+//!
+//! The viewport and scissor rect are set to the widget rect before `paint` is called. Pipelines
+//! have to target [`crate::gpu::GpuContext::target_format`] with
+//! [`crate::gpu::GpuContext::msaa_samples`]. The resources are dropped when the device is
+//! recreated, so check [`crate::gpu::GpuContext::has_callback_resources`] before relying on them.
+//! Callbacks are not faded by [`crate::Frame::fade_to`].
+
+pub use egui::PaintCallbackInfo;
+pub use egui_wgpu::{CallbackResources, CallbackTrait};
+
+/// A shape running `callback` inside `rect`, to be added to a painter.
+pub fn shape(rect: egui::Rect, callback: impl CallbackTrait + 'static) -> egui::Shape {
+    egui::Shape::Callback(egui_wgpu::Callback::new_paint_callback(rect, callback))
+}
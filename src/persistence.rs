@@ -0,0 +1,217 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use winit::{
+    dpi::{PhysicalPosition, PhysicalSize},
+    event_loop::EventLoopWindowTarget,
+    monitor::MonitorHandle,
+    window::{Window, WindowBuilder},
+};
+
+use crate::settings::Preferences;
+
+/// Directory application state of `app_id` is persisted to, e.g. `~/.config/<app_id>` on Linux.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn storage_dir(app_id: &str) -> Option<PathBuf> {
+    directories::ProjectDirs::from("", "", app_id).map(|dirs| dirs.config_dir().to_path_buf())
+}
+
+/// There is no file system to persist to in the browser.
+#[cfg(target_arch = "wasm32")]
+pub fn storage_dir(_app_id: &str) -> Option<PathBuf> {
+    None
+}
+
+pub(crate) fn load_ron<T: DeserializeOwned>(path: &Path) -> Option<T> {
+    let contents = fs::read_to_string(path).ok()?;
+    match ron::from_str(&contents) {
+        Ok(value) => Some(value),
+        Err(err) => {
+            log::warn!("Failed to parse {}: {err}", path.display());
+            None
+        }
+    }
+}
+
+pub(crate) fn save_ron<T: Serialize>(path: &Path, value: &T) {
+    let result = ron::ser::to_string_pretty(value, Default::default())
+        .map_err(|err| err.to_string())
+        .and_then(|contents| {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+            }
+            fs::write(path, contents).map_err(|err| err.to_string())
+        });
+    if let Err(err) = result {
+        log::warn!("Failed to save {}: {err}", path.display());
+    }
+}
+
+/// Geometry of a window, in physical pixels.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WindowState {
+    pub position: Option<(i32, i32)>,
+    pub size: (u32, u32),
+    pub maximized: bool,
+    /// Name of the monitor the window was on.
+    pub monitor: Option<String>,
+}
+
+impl WindowState {
+    pub fn from_window(window: &Window) -> Self {
+        let size = window.inner_size();
+        Self {
+            position: window
+                .outer_position()
+                .ok()
+                .map(|position| (position.x, position.y)),
+            size: (size.width, size.height),
+            maximized: window.is_maximized(),
+            monitor: window.current_monitor().and_then(|monitor| monitor.name()),
+        }
+    }
+
+    /// Makes sure the window ends up on a connected monitor, dropping the position and
+    /// shrinking the size if the monitor it was on is gone.
+    pub fn clamp_to_monitors(mut self, monitors: impl IntoIterator<Item = MonitorHandle>) -> Self {
+        let monitors: Vec<_> = monitors.into_iter().collect();
+        let monitor = monitors
+            .iter()
+            .find(|monitor| monitor.name().is_some() && monitor.name() == self.monitor)
+            .or_else(|| {
+                let (x, y) = self.position?;
+                monitors
+                    .iter()
+                    .find(|monitor| contains(monitor, PhysicalPosition::new(x, y)))
+            });
+
+        match monitor {
+            Some(monitor) => {
+                let monitor_size = monitor.size();
+                self.size.0 = self.size.0.min(monitor_size.width);
+                self.size.1 = self.size.1.min(monitor_size.height);
+                if let Some((x, y)) = self.position {
+                    if !contains(monitor, PhysicalPosition::new(x, y)) {
+                        self.position = None;
+                    }
+                }
+            }
+            None => {
+                self.position = None;
+                self.monitor = None;
+            }
+        }
+        self
+    }
+
+    pub fn apply_to_builder(&self, builder: WindowBuilder) -> WindowBuilder {
+        let mut builder = builder
+            .with_inner_size(PhysicalSize::new(self.size.0.max(1), self.size.1.max(1)))
+            .with_maximized(self.maximized);
+        if let Some((x, y)) = self.position {
+            builder = builder.with_position(PhysicalPosition::new(x, y));
+        }
+        builder
+    }
+}
+
+fn contains(monitor: &MonitorHandle, point: PhysicalPosition<i32>) -> bool {
+    let position = monitor.position();
+    let size = monitor.size();
+    point.x >= position.x
+        && point.y >= position.y
+        && point.x < position.x + size.width as i32
+        && point.y < position.y + size.height as i32
+}
+
+/// Saves the geometry of the main window on close and restores it on startup.
+#[derive(Debug, Clone)]
+pub struct WindowStatePersistence {
+    path: PathBuf,
+}
+
+impl WindowStatePersistence {
+    /// Persists the window state of `app_id` in its [`storage_dir`].
+    pub fn new(app_id: &str) -> Option<Self> {
+        Some(Self::with_path(storage_dir(app_id)?.join("window.ron")))
+    }
+
+    pub fn with_path(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Loads the stored window state, clamped to the currently connected monitors.
+    pub fn load<T>(&self, target: &EventLoopWindowTarget<T>) -> Option<WindowState> {
+        let state: WindowState = load_ron(&self.path)?;
+        Some(state.clamp_to_monitors(target.available_monitors()))
+    }
+
+    pub fn save(&self, window: &Window) {
+        save_ron(&self.path, &WindowState::from_window(window));
+    }
+}
+
+/// Saves egui's [`egui::Memory`] (window positions, collapsed headers, scroll offsets, ...) on
+/// exit and restores it on startup.
+#[derive(Debug, Clone)]
+pub struct EguiMemoryPersistence {
+    path: PathBuf,
+}
+
+impl EguiMemoryPersistence {
+    /// Persists the egui memory of `app_id` in its [`storage_dir`].
+    pub fn new(app_id: &str) -> Option<Self> {
+        Some(Self::with_path(
+            storage_dir(app_id)?.join("egui_memory.ron"),
+        ))
+    }
+
+    pub fn with_path(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Replaces the memory of `ctx` with the stored one, if there is any. The options are not
+    /// stored by egui, so the ones already set on `ctx` are kept.
+    pub fn load(&self, ctx: &egui::Context) {
+        if let Some(mut memory) = load_ron::<egui::Memory>(&self.path) {
+            ctx.memory_mut(|m| {
+                memory.options = m.options.clone();
+                *m = memory;
+            });
+        }
+    }
+
+    pub fn save(&self, ctx: &egui::Context) {
+        ctx.memory(|memory| save_ron(&self.path, memory));
+    }
+}
+
+/// Saves the [`Preferences`] of the settings window on exit and restores them on startup.
+#[derive(Debug, Clone)]
+pub struct PreferencesPersistence {
+    path: PathBuf,
+}
+
+impl PreferencesPersistence {
+    /// Persists the preferences of `app_id` in its [`storage_dir`].
+    pub fn new(app_id: &str) -> Option<Self> {
+        Some(Self::with_path(
+            storage_dir(app_id)?.join("preferences.ron"),
+        ))
+    }
+
+    pub fn with_path(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn load(&self) -> Option<Preferences> {
+        load_ron(&self.path)
+    }
+
+    pub fn save(&self, preferences: &Preferences) {
+        save_ron(&self.path, preferences);
+    }
+}
@@ -0,0 +1,322 @@
+//! Features which hook into every frame and can be toggled independently, like the built-in
+//! settings window, command palette, frame time graph and profiler.
+//!
+//! Apps add their own plugins with [`Plugins::add`], e.g. on the first update:
+//!
+//! ```no_run
+//! use egui_tests::{plugin::Plugin, Frame};
+//!
+//! struct Fps;
+//!
+//! impl Plugin for Fps {
+//!     fn name(&self) -> &str {
+//!         "fps"
+//!     }
+//!
+//!     fn ui(&mut self, ctx: &egui::Context, frame: &mut Frame) {
+//!         let fps = 1.0 / frame.frame_time().as_secs_f32();
+//!         egui::Area::new("fps").show(ctx, |ui| ui.label(format!("{fps:.0} FPS")));
+//!     }
+//! }
+//!
+//! # fn update(frame: &mut Frame) {
+//! frame.plugins_mut().add(Fps);
+//! # }
+//! ```
+
+use std::collections::BTreeMap;
+
+use crate::{
+    command_palette::CommandPalette, dialogs::Dialogs, log_console::LogConsole,
+    settings::SettingsWindow, Frame,
+};
+
+/// A feature run by the window alongside the [`crate::App`].
+pub trait Plugin {
+    /// Unique name, used to enable and disable the plugin.
+    fn name(&self) -> &str;
+
+    /// Called once before the first update while the plugin is enabled.
+    fn setup(&mut self, _ctx: &egui::Context, _frame: &mut Frame) {}
+
+    /// Called every frame before [`crate::App::update`], e.g. to add side panels.
+    fn update(&mut self, _ctx: &egui::Context, _frame: &mut Frame) {}
+
+    /// Called every frame after [`crate::App::update`], e.g. to show windows on top of the app.
+    fn ui(&mut self, _ctx: &egui::Context, _frame: &mut Frame) {}
+
+    /// Called before exiting if [`Self::setup`] was called.
+    fn shutdown(&mut self, _frame: &mut Frame) {}
+}
+
+struct Entry {
+    plugin: Box<dyn Plugin>,
+    enabled: bool,
+    started: bool,
+}
+
+/// The registered plugins, run in the order they were added.
+#[derive(Default)]
+pub struct Plugins {
+    entries: Vec<Entry>,
+    /// Enabled states set while the plugins were running, or before the plugin was added.
+    pending: BTreeMap<String, bool>,
+}
+
+impl std::fmt::Debug for Plugins {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map()
+            .entries(
+                self.entries
+                    .iter()
+                    .map(|entry| (entry.plugin.name(), entry.enabled)),
+            )
+            .finish()
+    }
+}
+
+impl Plugins {
+    /// The built-in plugins, all enabled.
+    pub(crate) fn builtin() -> Self {
+        let mut plugins = Self::default();
+        plugins.add(SettingsPlugin);
+        plugins.add(CommandPalettePlugin);
+        plugins.add(DialogsPlugin);
+        plugins.add(DiagnosticsPlugin);
+        #[cfg(feature = "puffin")]
+        plugins.add(ProfilerPlugin);
+        #[cfg(feature = "demo-lib")]
+        {
+            plugins.add(EguiDemoPlugin::default());
+            plugins.set_enabled(EguiDemoPlugin::NAME, false);
+        }
+        plugins
+    }
+
+    /// Adds an enabled plugin, replacing one with the same name.
+    pub fn add(&mut self, plugin: impl Plugin + 'static) {
+        let name = plugin.name().to_owned();
+        let entry = Entry {
+            enabled: self.pending.remove(&name).unwrap_or(true),
+            plugin: Box::new(plugin),
+            started: false,
+        };
+        match self
+            .entries
+            .iter_mut()
+            .find(|existing| existing.plugin.name() == name)
+        {
+            Some(existing) => *existing = entry,
+            None => self.entries.push(entry),
+        }
+    }
+
+    /// Names of the plugins in the order they run.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|entry| entry.plugin.name())
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.entry(name).is_some()
+    }
+
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.pending
+            .get(name)
+            .copied()
+            .or_else(|| self.entry(name).map(|entry| entry.enabled))
+            .unwrap_or(false)
+    }
+
+    /// Enables or disables the plugin, also if it is added later.
+    pub fn set_enabled(&mut self, name: &str, enabled: bool) {
+        match self
+            .entries
+            .iter_mut()
+            .find(|entry| entry.plugin.name() == name)
+        {
+            Some(entry) => entry.enabled = enabled,
+            None => {
+                self.pending.insert(name.to_owned(), enabled);
+            }
+        }
+    }
+
+    /// A checkbox per plugin to enable or disable it.
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        for entry in &mut self.entries {
+            ui.checkbox(&mut entry.enabled, entry.plugin.name());
+        }
+    }
+
+    /// Sets up the enabled plugins added since the last frame and runs [`Plugin::update`].
+    pub(crate) fn update(frame: &mut Frame, ctx: &egui::Context) {
+        Self::run(frame, |entry, frame| {
+            if !entry.started {
+                entry.plugin.setup(ctx, frame);
+                entry.started = true;
+            }
+            entry.plugin.update(ctx, frame);
+        });
+    }
+
+    pub(crate) fn ui_after_update(frame: &mut Frame, ctx: &egui::Context) {
+        Self::run(frame, |entry, frame| {
+            if entry.started {
+                entry.plugin.ui(ctx, frame);
+            }
+        });
+    }
+
+    pub(crate) fn shutdown(frame: &mut Frame) {
+        let mut plugins = std::mem::take(&mut frame.plugins);
+        for entry in plugins.entries.iter_mut().filter(|entry| entry.started) {
+            entry.plugin.shutdown(frame);
+        }
+    }
+
+    /// Runs `f` for the enabled plugins, which are taken out of the frame meanwhile. Plugins
+    /// added and toggled through the frame are merged back afterwards.
+    fn run(frame: &mut Frame, mut f: impl FnMut(&mut Entry, &mut Frame)) {
+        let mut plugins = std::mem::take(&mut frame.plugins);
+        for entry in plugins.entries.iter_mut().filter(|entry| entry.enabled) {
+            f(entry, frame);
+        }
+        let changes = std::mem::replace(&mut frame.plugins, plugins);
+        for entry in changes.entries {
+            let name = entry.plugin.name().to_owned();
+            frame
+                .plugins
+                .entries
+                .retain(|existing| existing.plugin.name() != name);
+            frame.plugins.entries.push(entry);
+        }
+        for (name, enabled) in changes.pending {
+            frame.plugins.set_enabled(&name, enabled);
+        }
+    }
+
+    fn entry(&self, name: &str) -> Option<&Entry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.plugin.name() == name)
+    }
+}
+
+/// Shows the [`SettingsWindow`].
+struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn name(&self) -> &str {
+        "settings"
+    }
+
+    fn ui(&mut self, ctx: &egui::Context, frame: &mut Frame) {
+        SettingsWindow::show(frame, ctx);
+    }
+}
+
+/// Shows the [`CommandPalette`].
+struct CommandPalettePlugin;
+
+impl Plugin for CommandPalettePlugin {
+    fn name(&self) -> &str {
+        "command_palette"
+    }
+
+    fn ui(&mut self, ctx: &egui::Context, frame: &mut Frame) {
+        CommandPalette::show(frame, ctx);
+    }
+}
+
+/// Shows the modal [`crate::dialogs::Dialogs`], before the app so they get Enter and Escape first.
+struct DialogsPlugin;
+
+impl Plugin for DialogsPlugin {
+    fn name(&self) -> &str {
+        "dialogs"
+    }
+
+    fn update(&mut self, ctx: &egui::Context, frame: &mut Frame) {
+        Dialogs::show(frame, ctx);
+    }
+}
+
+/// Shows the frame time graph of [`crate::diagnostics::Diagnostics`].
+struct DiagnosticsPlugin;
+
+impl Plugin for DiagnosticsPlugin {
+    fn name(&self) -> &str {
+        "diagnostics"
+    }
+
+    fn ui(&mut self, ctx: &egui::Context, frame: &mut Frame) {
+        frame.diagnostics.show(ctx);
+    }
+}
+
+/// Shows the flamegraph of [`crate::profiler::Profiler`].
+#[cfg(feature = "puffin")]
+struct ProfilerPlugin;
+
+#[cfg(feature = "puffin")]
+impl Plugin for ProfilerPlugin {
+    fn name(&self) -> &str {
+        "profiler"
+    }
+
+    fn ui(&mut self, ctx: &egui::Context, frame: &mut Frame) {
+        frame.profiler.show(ctx);
+    }
+}
+
+/// The demo windows of `egui_demo_lib`, to check how widgets, fonts and input behave with this
+/// window and renderer setup.
+#[cfg(feature = "demo-lib")]
+#[derive(Default)]
+pub struct EguiDemoPlugin {
+    windows: egui_demo_lib::DemoWindows,
+}
+
+#[cfg(feature = "demo-lib")]
+impl EguiDemoPlugin {
+    pub const NAME: &'static str = "egui_demo";
+}
+
+#[cfg(feature = "demo-lib")]
+impl Plugin for EguiDemoPlugin {
+    fn name(&self) -> &str {
+        Self::NAME
+    }
+
+    // Adds side panels, which have to come before the central panel of the app
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut Frame) {
+        self.windows.ui(ctx);
+    }
+}
+
+/// Docks a [`LogConsole`] to the bottom of the window while enabled, or floats it in a window
+/// which disables the plugin when closed.
+pub struct LogConsolePlugin {
+    console: LogConsole,
+}
+
+impl LogConsolePlugin {
+    pub fn new(console: LogConsole) -> Self {
+        Self { console }
+    }
+}
+
+impl Plugin for LogConsolePlugin {
+    fn name(&self) -> &str {
+        "log_console"
+    }
+
+    fn update(&mut self, ctx: &egui::Context, frame: &mut Frame) {
+        let mut open = true;
+        self.console.show(ctx, &mut open);
+        if !open {
+            frame.plugins_mut().set_enabled(self.name(), false);
+        }
+    }
+}
@@ -0,0 +1,528 @@
+//! Full-screen shader passes applied to the scene before egui is drawn on top.
+//!
+//! Effects are fragment shaders written against [`PostEffect::PRELUDE`], which declares the
+//! input texture, a linear sampler, the `params` uniform and the `VertexOutput` of the
+//! full-screen triangle. An effect only defines `fs_main`:
+//!
+//! ```wgsl
+//! @fragment
+//! fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+//!     let color = textureSample(input_texture, input_sampler, in.uv);
+//!     return vec4<f32>(1.0 - color.rgb, color.a);
+//! }
+//! ```
+
+use std::time::Duration;
+
+use crate::uploads::Uploads;
+
+/// Source of the built-in effects, to be registered with [`PostProcess::add`].
+pub mod effects {
+    /// Glow around bright parts of the scene.
+    pub const BLOOM: &str = include_str!("shaders/post/bloom.wgsl");
+    /// Fast approximate anti-aliasing.
+    pub const FXAA: &str = include_str!("shaders/post/fxaa.wgsl");
+    /// Darkens the edges of the scene.
+    pub const VIGNETTE: &str = include_str!("shaders/post/vignette.wgsl");
+}
+
+/// A full-screen pass in the [`PostProcess`] chain.
+#[derive(Debug)]
+pub struct PostEffect {
+    name: String,
+    source: String,
+    enabled: bool,
+    /// Compiled on first use for the format of the scene.
+    pipeline: Option<wgpu::RenderPipeline>,
+    /// Set when the source changed, the previous pipeline is kept until the new one compiles.
+    stale: bool,
+    /// File the source is reloaded from when it changes.
+    #[cfg(all(feature = "hot-reload", not(target_arch = "wasm32")))]
+    path: Option<std::path::PathBuf>,
+}
+
+impl PostEffect {
+    /// Declarations shared by all effects, prepended to their source.
+    pub const PRELUDE: &'static str = include_str!("shaders/post_process.wgsl");
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+}
+
+/// The chain of effects applied to the scene in order of registration, see
+/// [`crate::Frame::post_process_mut`].
+///
+/// While any effect is enabled the scene is rendered into an offscreen texture, which costs
+/// an additional full-screen copy even if all effects are cheap.
+#[derive(Debug, Default)]
+pub struct PostProcess {
+    effects: Vec<PostEffect>,
+    #[cfg(all(feature = "hot-reload", not(target_arch = "wasm32")))]
+    watcher: Option<crate::hot_reload::ShaderWatcher>,
+}
+
+impl PostProcess {
+    /// Appends an enabled effect with the WGSL fragment shader `source`, replacing an effect
+    /// with the same name.
+    pub fn add(&mut self, name: impl Into<String>, source: impl Into<String>) {
+        let name = name.into();
+        self.remove(&name);
+        self.effects.push(PostEffect {
+            name,
+            source: source.into(),
+            enabled: true,
+            pipeline: None,
+            stale: true,
+            #[cfg(all(feature = "hot-reload", not(target_arch = "wasm32")))]
+            path: None,
+        });
+    }
+
+    /// Same as [`Self::add`], but reads the source from a file and recompiles the effect whenever
+    /// the file changes. Compile errors are logged and keep the previous version running.
+    #[cfg(all(feature = "hot-reload", not(target_arch = "wasm32")))]
+    pub fn add_file(
+        &mut self,
+        name: impl Into<String>,
+        path: impl AsRef<std::path::Path>,
+    ) -> std::io::Result<()> {
+        let source = std::fs::read_to_string(path.as_ref())?;
+        let watcher = match &mut self.watcher {
+            Some(watcher) => watcher,
+            None => self
+                .watcher
+                .insert(crate::hot_reload::ShaderWatcher::new().map_err(std::io::Error::other)?),
+        };
+        let path = watcher.watch(path).map_err(std::io::Error::other)?;
+
+        let name = name.into();
+        self.add(name.clone(), source);
+        if let Some(effect) = self.effects.iter_mut().find(|effect| effect.name == name) {
+            effect.path = Some(path);
+        }
+        Ok(())
+    }
+
+    /// Reads the sources of effects whose files changed, to be recompiled before the next pass.
+    #[cfg(all(feature = "hot-reload", not(target_arch = "wasm32")))]
+    fn reload_changed(&mut self) {
+        let Some(watcher) = &self.watcher else {
+            return;
+        };
+        for path in watcher.changed() {
+            let source = match std::fs::read_to_string(&path) {
+                Ok(source) => source,
+                Err(err) => {
+                    log::error!("Failed to read {}: {err}", path.display());
+                    continue;
+                }
+            };
+            for effect in &mut self.effects {
+                if effect.path.as_ref() == Some(&path) {
+                    log::info!("Reloading post effect {}", effect.name);
+                    effect.source.clone_from(&source);
+                    effect.stale = true;
+                }
+            }
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.effects.retain(|effect| effect.name != name);
+    }
+
+    /// Turns an effect on or off without recompiling it.
+    pub fn set_enabled(&mut self, name: &str, enabled: bool) {
+        if let Some(effect) = self.effects.iter_mut().find(|effect| effect.name == name) {
+            effect.enabled = enabled;
+        }
+    }
+
+    pub fn effects(&self) -> &[PostEffect] {
+        &self.effects
+    }
+
+    /// Drops the compiled pipelines, e.g. after the device was recreated.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn invalidate(&mut self) {
+        for effect in &mut self.effects {
+            effect.pipeline = None;
+            effect.stale = true;
+        }
+    }
+
+    /// Whether any effect is enabled.
+    pub fn is_active(&self) -> bool {
+        self.effects.iter().any(|effect| effect.enabled)
+    }
+
+    /// Shows a checkbox for each effect.
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        for effect in &mut self.effects {
+            ui.checkbox(&mut effect.enabled, effect.name.as_str());
+        }
+    }
+}
+
+/// Renders the scene into an offscreen texture and runs the post processing chain on it.
+///
+/// The result is copied into the render target, or tone mapped into it if HDR is enabled.
+pub(crate) struct PostProcessor {
+    format: wgpu::TextureFormat,
+    msaa_samples: u32,
+    bind_group_layout: wgpu::BindGroupLayout,
+    pipeline_layout: wgpu::PipelineLayout,
+    sampler: wgpu::Sampler,
+    params: wgpu::Buffer,
+    /// Copies the result into the render target if it is not tone mapped.
+    copy_pipeline: wgpu::RenderPipeline,
+    target: Option<SceneTarget>,
+    /// Index of the view holding the result of the last run.
+    result: usize,
+    elapsed: Duration,
+}
+
+struct SceneTarget {
+    /// Multisampled texture the scene is drawn into, resolved into the first texture.
+    msaa_view: Option<wgpu::TextureView>,
+    /// Ping-pong textures the effects read from and write to in turn.
+    views: [wgpu::TextureView; 2],
+    bind_groups: [wgpu::BindGroup; 2],
+    size: [u32; 2],
+}
+
+impl PostProcessor {
+    /// Creates the post processor for a scene in `format`, whose result is written into the
+    /// render target in `output_format`.
+    pub fn new(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        output_format: wgpu::TextureFormat,
+        msaa_samples: u32,
+    ) -> Self {
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("post process"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("post process"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("post process"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        let params = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("post process params"),
+            size: 16,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let copy_pipeline = create_pipeline(
+            device,
+            &pipeline_layout,
+            "post process copy",
+            include_str!("shaders/post/copy.wgsl"),
+            output_format,
+            msaa_samples,
+        );
+
+        Self {
+            format,
+            msaa_samples,
+            bind_group_layout,
+            pipeline_layout,
+            sampler,
+            params,
+            copy_pipeline,
+            target: None,
+            result: 0,
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    pub fn format(&self) -> wgpu::TextureFormat {
+        self.format
+    }
+
+    /// The view the scene renders into, recreated if the size changed. It is multisampled if
+    /// MSAA is enabled.
+    pub fn scene_view(&mut self, device: &wgpu::Device, size: [u32; 2]) -> &wgpu::TextureView {
+        if !matches!(&self.target, Some(target) if target.size == size) {
+            self.target = Some(self.create_target(device, size));
+        }
+        let target = self.target.as_ref().unwrap();
+        target.msaa_view.as_ref().unwrap_or(&target.views[0])
+    }
+
+    /// The scene with the effects applied, as written into the render target by the last
+    /// [`Self::run`].
+    pub fn result_view(&self) -> Option<&wgpu::TextureView> {
+        Some(&self.target.as_ref()?.views[self.result])
+    }
+
+    /// Runs the enabled effects on the scene and writes the result into `output`, through
+    /// `tone_mapper` if given.
+    #[allow(clippy::too_many_arguments)]
+    pub fn run(
+        &mut self,
+        device: &wgpu::Device,
+        uploads: &mut Uploads,
+        encoder: &mut wgpu::CommandEncoder,
+        post_process: &mut PostProcess,
+        tone_mapper: Option<(&crate::hdr::ToneMapper, &crate::hdr::HdrSettings)>,
+        output: &wgpu::TextureView,
+        frame_time: Duration,
+    ) {
+        self.elapsed += frame_time;
+        let Some(target) = &self.target else {
+            return;
+        };
+        #[cfg(all(feature = "hot-reload", not(target_arch = "wasm32")))]
+        post_process.reload_changed();
+
+        if let Some(msaa_view) = &target.msaa_view {
+            // An empty pass only resolving the multisampled scene
+            encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("scene resolve"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: msaa_view,
+                    resolve_target: Some(&target.views[0]),
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Discard,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+        }
+
+        let params: Vec<u8> = [
+            target.size[0] as f32,
+            target.size[1] as f32,
+            self.elapsed.as_secs_f32(),
+            0.0,
+        ]
+        .iter()
+        .flat_map(|value| value.to_le_bytes())
+        .collect();
+        uploads.write(device, encoder, &self.params, 0, &params);
+
+        let mut current = 0;
+        for effect in post_process
+            .effects
+            .iter_mut()
+            .filter(|effect| effect.enabled)
+        {
+            if std::mem::take(&mut effect.stale) {
+                let create = || {
+                    create_pipeline(
+                        device,
+                        &self.pipeline_layout,
+                        &effect.name,
+                        &effect.source,
+                        self.format,
+                        1,
+                    )
+                };
+                #[cfg(all(feature = "hot-reload", not(target_arch = "wasm32")))]
+                match crate::hot_reload::try_create(device, create) {
+                    Ok(pipeline) => effect.pipeline = Some(pipeline),
+                    Err(err) => log::error!("Failed to compile post effect {}: {err}", effect.name),
+                }
+                #[cfg(not(all(feature = "hot-reload", not(target_arch = "wasm32"))))]
+                {
+                    effect.pipeline = Some(create());
+                }
+            }
+            let Some(pipeline) = &effect.pipeline else {
+                continue;
+            };
+            draw_fullscreen(
+                encoder,
+                &effect.name,
+                pipeline,
+                &target.bind_groups[current],
+                &target.views[1 - current],
+            );
+            current = 1 - current;
+        }
+        self.result = current;
+
+        match tone_mapper {
+            Some((tone_mapper, settings)) => tone_mapper.apply(
+                device,
+                uploads,
+                encoder,
+                &target.views[current],
+                output,
+                settings,
+            ),
+            None => draw_fullscreen(
+                encoder,
+                "post process copy",
+                &self.copy_pipeline,
+                &target.bind_groups[current],
+                output,
+            ),
+        }
+    }
+
+    fn create_target(&self, device: &wgpu::Device, size: [u32; 2]) -> SceneTarget {
+        let create_view = |label, sample_count, usage| {
+            device
+                .create_texture(&wgpu::TextureDescriptor {
+                    label: Some(label),
+                    size: wgpu::Extent3d {
+                        width: size[0],
+                        height: size[1],
+                        depth_or_array_layers: 1,
+                    },
+                    mip_level_count: 1,
+                    sample_count,
+                    dimension: wgpu::TextureDimension::D2,
+                    format: self.format,
+                    usage,
+                    view_formats: &[],
+                })
+                .create_view(&wgpu::TextureViewDescriptor::default())
+        };
+
+        let msaa_view = (self.msaa_samples > 1).then(|| {
+            create_view(
+                "scene msaa",
+                self.msaa_samples,
+                wgpu::TextureUsages::RENDER_ATTACHMENT,
+            )
+        });
+        let usage = wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING;
+        let views = [
+            create_view("scene", 1, usage),
+            create_view("scene", 1, usage),
+        ];
+        let bind_groups = views.each_ref().map(|view| {
+            device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("post process"),
+                layout: &self.bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::Sampler(&self.sampler),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: self.params.as_entire_binding(),
+                    },
+                ],
+            })
+        });
+        SceneTarget {
+            msaa_view,
+            views,
+            bind_groups,
+            size,
+        }
+    }
+}
+
+fn create_pipeline(
+    device: &wgpu::Device,
+    layout: &wgpu::PipelineLayout,
+    label: &str,
+    source: &str,
+    format: wgpu::TextureFormat,
+    sample_count: u32,
+) -> wgpu::RenderPipeline {
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some(label),
+        source: wgpu::ShaderSource::Wgsl(format!("{}\n{source}", PostEffect::PRELUDE).into()),
+    });
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some(label),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: &shader,
+            entry_point: "vs_main",
+            buffers: &[],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader,
+            entry_point: "fs_main",
+            targets: &[Some(format.into())],
+        }),
+        primitive: wgpu::PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
+        multiview: None,
+    })
+}
+
+fn draw_fullscreen(
+    encoder: &mut wgpu::CommandEncoder,
+    label: &str,
+    pipeline: &wgpu::RenderPipeline,
+    bind_group: &wgpu::BindGroup,
+    view: &wgpu::TextureView,
+) {
+    let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: Some(label),
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view,
+            resolve_target: None,
+            ops: wgpu::Operations {
+                load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                store: wgpu::StoreOp::Store,
+            },
+        })],
+        depth_stencil_attachment: None,
+        timestamp_writes: None,
+        occlusion_query_set: None,
+    });
+    render_pass.set_pipeline(pipeline);
+    render_pass.set_bind_group(0, bind_group, &[]);
+    render_pass.draw(0..3, 0..1);
+}
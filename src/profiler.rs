@@ -0,0 +1,35 @@
+/// Collects puffin profiling data and shows it in a flamegraph window.
+#[derive(Debug, Default)]
+pub struct Profiler {
+    visible: bool,
+}
+
+impl Profiler {
+    pub fn visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Shows or hides the flamegraph window. Profiling scopes are only recorded while it is shown.
+    pub fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+        puffin::set_scopes_on(visible);
+    }
+
+    /// Marks the start of a new frame for the profiler.
+    pub fn new_frame(&self) {
+        puffin::GlobalProfiler::lock().new_frame();
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) {
+        if self.visible {
+            let mut open = true;
+            egui::Window::new("Profiler")
+                .open(&mut open)
+                .default_size([800.0, 400.0])
+                .show(ctx, puffin_egui::profiler_ui);
+            if !open {
+                self.set_visible(false);
+            }
+        }
+    }
+}
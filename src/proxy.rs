@@ -0,0 +1,87 @@
+use std::{any::Any, sync::Arc, time::Duration};
+
+use egui::{mutex::Mutex, ViewportId};
+
+/// Events sent to the event loop from outside of it.
+#[derive(Debug)]
+pub(crate) enum UserEvent {
+    /// egui requested a repaint, possibly from another thread.
+    RequestRepaint {
+        viewport_id: ViewportId,
+        delay: Duration,
+    },
+    /// A global hotkey with the given id was pressed.
+    #[cfg(all(feature = "global-hotkey", not(target_arch = "wasm32")))]
+    GlobalHotKey(u32),
+    /// egui input translated from gamepad events.
+    #[cfg(all(feature = "gamepad", not(target_arch = "wasm32")))]
+    Gamepad(Vec<egui::Event>),
+    /// The tray icon or its menu was clicked.
+    #[cfg(all(feature = "tray", not(target_arch = "wasm32")))]
+    Tray(crate::tray::TrayEvent),
+    /// A screen reader wants to interact with a widget.
+    #[cfg(all(feature = "accesskit", not(target_arch = "wasm32")))]
+    AccessKitActionRequest(egui_winit::accesskit_winit::ActionRequestEvent),
+    /// A message for the app, handed out through [`crate::Frame::take_messages`].
+    Message(Box<dyn Any + Send>),
+}
+
+#[cfg(all(feature = "accesskit", not(target_arch = "wasm32")))]
+impl From<egui_winit::accesskit_winit::ActionRequestEvent> for UserEvent {
+    fn from(event: egui_winit::accesskit_winit::ActionRequestEvent) -> Self {
+        UserEvent::AccessKitActionRequest(event)
+    }
+}
+
+/// Wakes up the UI and delivers messages to the app, e.g. from background threads.
+///
+/// ```no_run
+/// # fn example(frame: &egui_tests::Frame) {
+/// let proxy = frame.proxy();
+/// std::thread::spawn(move || {
+///     let result = 42_u32;
+///     proxy.send(result);
+/// });
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct EventLoopProxy {
+    // The winit proxy is not `Sync` on all platforms
+    inner: Arc<Mutex<winit::event_loop::EventLoopProxy<UserEvent>>>,
+}
+
+impl std::fmt::Debug for EventLoopProxy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventLoopProxy").finish_non_exhaustive()
+    }
+}
+
+impl EventLoopProxy {
+    // There are no threads to share the proxy with on the web
+    #[cfg_attr(target_arch = "wasm32", allow(clippy::arc_with_non_send_sync))]
+    pub(crate) fn new(proxy: winit::event_loop::EventLoopProxy<UserEvent>) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(proxy)),
+        }
+    }
+
+    /// Sends `message` to the app, which receives it in the next update through
+    /// [`crate::Frame::take_messages`].
+    ///
+    /// Returns `false` if the event loop has already exited.
+    pub fn send<T: Any + Send>(&self, message: T) -> bool {
+        self.send_event(UserEvent::Message(Box::new(message)))
+    }
+
+    /// Redraws the main window as soon as possible.
+    pub fn request_repaint(&self) -> bool {
+        self.send_event(UserEvent::RequestRepaint {
+            viewport_id: ViewportId::ROOT,
+            delay: Duration::ZERO,
+        })
+    }
+
+    pub(crate) fn send_event(&self, event: UserEvent) -> bool {
+        self.inner.lock().send_event(event).is_ok()
+    }
+}
@@ -0,0 +1,467 @@
+//! Records the window into a GIF or, through an `ffmpeg` subprocess, into a video.
+//!
+//! Frames are copied out of the surface when it is presented and encoded on a background
+//! thread. Recording is toggled with [`crate::shortcuts::Action::ToggleRecording`] (Shift+F12 by
+//! default) or [`crate::Frame::toggle_recording`].
+//!
+//! The [`ReplayBuffer`] keeps the last seconds in memory instead, to attach them to bug reports
+//! after something went wrong.
+
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    sync::mpsc::{self, Receiver, SyncSender, TrySendError},
+    thread::JoinHandle,
+    time::Duration,
+};
+
+use image::{
+    codecs::gif::{GifEncoder, Repeat},
+    Delay, RgbaImage,
+};
+use web_time::{Instant, SystemTime};
+
+/// Number of captured frames which may wait for the encoder before frames are dropped.
+const QUEUE_LENGTH: usize = 8;
+
+/// What a recording is encoded to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RecordingFormat {
+    /// An animated GIF, encoded in-crate.
+    #[default]
+    Gif,
+    /// An H.264 MP4 video, encoded by piping the frames into `ffmpeg`, which has to be in the
+    /// `PATH`.
+    Mp4,
+}
+
+impl RecordingFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Gif => "gif",
+            Self::Mp4 => "mp4",
+        }
+    }
+}
+
+/// A captured frame, shown for `repeat` frame intervals.
+struct RecordedFrame {
+    image: RgbaImage,
+    repeat: u32,
+}
+
+struct Recording {
+    path: PathBuf,
+    sender: SyncSender<RecordedFrame>,
+    worker: JoinHandle<()>,
+    started: Instant,
+    /// Frame intervals since the start which have been captured.
+    captured: u32,
+    /// Intervals of dropped frames, added to the next frame that is sent.
+    skipped: u32,
+}
+
+/// Captures presented frames into a recording file.
+pub struct Recorder {
+    format: RecordingFormat,
+    fps: u32,
+    recording: Option<Recording>,
+    /// Encoder threads of stopped recordings which may still be writing their files.
+    finishing: Vec<JoinHandle<()>>,
+}
+
+impl Default for Recorder {
+    fn default() -> Self {
+        Self {
+            format: RecordingFormat::default(),
+            fps: 30,
+            recording: None,
+            finishing: Vec::new(),
+        }
+    }
+}
+
+impl std::fmt::Debug for Recorder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Recorder")
+            .field("format", &self.format)
+            .field("fps", &self.fps)
+            .field("path", &self.recording.as_ref().map(|r| &r.path))
+            .finish()
+    }
+}
+
+impl Recorder {
+    pub fn format(&self) -> RecordingFormat {
+        self.format
+    }
+
+    /// Format of the next recording, a running recording is not affected.
+    pub fn set_format(&mut self, format: RecordingFormat) {
+        self.format = format;
+    }
+
+    pub fn fps(&self) -> u32 {
+        self.fps
+    }
+
+    /// Frame rate of the next recording. Frames presented faster are skipped, if frames are
+    /// presented slower the last frame is repeated.
+    pub fn set_fps(&mut self, fps: u32) {
+        self.fps = fps.max(1);
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// Time since the running recording was started.
+    pub fn elapsed(&self) -> Option<Duration> {
+        Some(self.recording.as_ref()?.started.elapsed())
+    }
+
+    /// Starts recording to `path`, stopping a running recording first.
+    pub fn start(&mut self, path: impl Into<PathBuf>) {
+        self.stop();
+        let path = path.into();
+        let (sender, receiver) = mpsc::sync_channel(QUEUE_LENGTH);
+        let worker = {
+            let path = path.clone();
+            let format = self.format;
+            let fps = self.fps;
+            std::thread::spawn(move || encode(&path, format, fps, receiver))
+        };
+        log::info!("Recording to {}", path.display());
+        self.recording = Some(Recording {
+            path,
+            sender,
+            worker,
+            started: Instant::now(),
+            captured: 0,
+            skipped: 0,
+        });
+    }
+
+    /// Stops the running recording. The file is finished on the encoder thread.
+    pub fn stop(&mut self) {
+        self.finishing.retain(|worker| !worker.is_finished());
+        if let Some(recording) = self.recording.take() {
+            // Dropping the sender lets the encoder finish the file
+            drop(recording.sender);
+            self.finishing.push(recording.worker);
+        }
+    }
+
+    /// Stops recording and waits until all files are written, before exiting.
+    pub(crate) fn finish(&mut self) {
+        self.stop();
+        for worker in self.finishing.drain(..) {
+            let _ = worker.join();
+        }
+    }
+
+    /// Starts recording to [`default_recording_path`] or stops the running recording.
+    pub fn toggle(&mut self) {
+        if self.is_recording() {
+            self.stop();
+        } else {
+            self.start(default_recording_path(self.format));
+        }
+    }
+
+    /// Whether the frame presented now should be captured.
+    pub(crate) fn wants_frame(&self) -> bool {
+        self.recording
+            .as_ref()
+            .is_some_and(|recording| interval(recording.started, self.fps) > recording.captured)
+    }
+
+    /// Hands a captured frame to the encoder, dropping it if the encoder falls behind.
+    pub(crate) fn push(&mut self, image: RgbaImage) {
+        let Some(recording) = &mut self.recording else {
+            return;
+        };
+        let interval = interval(recording.started, self.fps);
+        let repeat = interval - recording.captured.min(interval);
+        recording.captured = interval;
+        let frame = RecordedFrame {
+            image,
+            repeat: repeat + recording.skipped,
+        };
+        match recording.sender.try_send(frame) {
+            Ok(()) => recording.skipped = 0,
+            Err(TrySendError::Full(frame)) => recording.skipped = frame.repeat,
+            Err(TrySendError::Disconnected(_)) => {
+                log::error!("The encoder stopped, ending the recording");
+                self.recording = None;
+            }
+        }
+    }
+
+    /// Shows a blinking indicator with the recording time in the top right corner.
+    pub fn show_indicator(&self, ctx: &egui::Context) {
+        let Some(elapsed) = self.elapsed() else {
+            return;
+        };
+        egui::Area::new("recording indicator")
+            .anchor(egui::Align2::RIGHT_TOP, [-8.0, 8.0])
+            .order(egui::Order::Foreground)
+            .interactable(false)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        let (rect, _) =
+                            ui.allocate_exact_size(egui::Vec2::splat(10.0), egui::Sense::hover());
+                        if elapsed.as_millis() % 1000 < 500 {
+                            ui.painter().circle_filled(
+                                rect.center(),
+                                5.0,
+                                egui::Color32::from_rgb(220, 40, 40),
+                            );
+                        }
+                        let seconds = elapsed.as_secs();
+                        ui.monospace(format!("REC {:02}:{:02}", seconds / 60, seconds % 60));
+                    });
+                });
+            });
+        ctx.request_repaint_after(Duration::from_millis(
+            500 - elapsed.as_millis() as u64 % 500,
+        ));
+    }
+}
+
+/// Keeps downscaled copies of the frames presented during the last seconds, which can be
+/// exported as an animated GIF with [`Self::export`].
+///
+/// Disabled by default, enabled with [`crate::AppBuilder::with_replay_buffer`] or
+/// [`Self::set_duration`].
+pub struct ReplayBuffer {
+    duration: Option<Duration>,
+    fps: u32,
+    max_width: u32,
+    frames: VecDeque<(Instant, RgbaImage)>,
+}
+
+impl Default for ReplayBuffer {
+    fn default() -> Self {
+        Self {
+            duration: None,
+            fps: 10,
+            max_width: 480,
+            frames: VecDeque::new(),
+        }
+    }
+}
+
+impl std::fmt::Debug for ReplayBuffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReplayBuffer")
+            .field("duration", &self.duration)
+            .field("fps", &self.fps)
+            .field("max_width", &self.max_width)
+            .field("frames", &self.frames.len())
+            .finish()
+    }
+}
+
+impl ReplayBuffer {
+    pub fn duration(&self) -> Option<Duration> {
+        self.duration
+    }
+
+    /// How far back frames are kept, `None` disables the buffer and drops its frames.
+    pub fn set_duration(&mut self, duration: Option<Duration>) {
+        self.duration = duration;
+        if duration.is_none() {
+            self.frames.clear();
+        }
+    }
+
+    /// Number of frames kept per second.
+    pub fn set_fps(&mut self, fps: u32) {
+        self.fps = fps.max(1);
+    }
+
+    /// Frames wider than `max_width` pixels are scaled down to it.
+    pub fn set_max_width(&mut self, max_width: u32) {
+        self.max_width = max_width.max(1);
+    }
+
+    /// Number of frames currently kept.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Whether the frame presented now should be captured.
+    pub(crate) fn wants_frame(&self) -> bool {
+        self.duration.is_some()
+            && self.frames.back().is_none_or(|(captured, _)| {
+                captured.elapsed().as_secs_f64() >= 1.0 / f64::from(self.fps)
+            })
+    }
+
+    /// Adds a captured frame, dropping frames older than the duration.
+    pub(crate) fn push(&mut self, image: &RgbaImage) {
+        let Some(duration) = self.duration else {
+            return;
+        };
+        let now = Instant::now();
+        while let Some((captured, _)) = self.frames.front() {
+            if now.duration_since(*captured) <= duration {
+                break;
+            }
+            self.frames.pop_front();
+        }
+
+        let (width, height) = image.dimensions();
+        let image = if width > self.max_width {
+            let height = (height as u64 * self.max_width as u64 / width as u64).max(1) as u32;
+            image::imageops::thumbnail(image, self.max_width, height)
+        } else {
+            image.clone()
+        };
+        self.frames.push_back((now, image));
+    }
+
+    /// Writes the kept frames to `path` as an animated GIF on a background thread.
+    pub fn export(&self, path: impl Into<PathBuf>) {
+        let path = path.into();
+        let Some((_, last)) = self.frames.back() else {
+            log::warn!("The replay buffer is empty, nothing to export");
+            return;
+        };
+        let size = last.dimensions();
+        let next_captures = self
+            .frames
+            .iter()
+            .skip(1)
+            .map(|(captured, _)| Some(*captured))
+            .chain(std::iter::once(None));
+        let frames: Vec<_> = self
+            .frames
+            .iter()
+            .zip(next_captures)
+            // Frames from before the window was resized can not be mixed in
+            .filter(|((_, image), _)| image.dimensions() == size)
+            .map(|((captured, image), next)| {
+                // Each frame is shown until the next one was captured, the last one briefly
+                let delay = next.map_or(Duration::from_secs(1) / self.fps, |next| {
+                    next.duration_since(*captured)
+                });
+                (image.clone(), Delay::from_saturating_duration(delay))
+            })
+            .collect();
+
+        std::thread::spawn(move || match write_gif(&path, frames) {
+            Ok(()) => log::info!("Saved replay to {}", path.display()),
+            Err(err) => log::error!("Failed to save replay to {}: {err}", path.display()),
+        });
+    }
+}
+
+/// `replay-<unix timestamp>.gif` in the current working directory.
+pub fn default_replay_path() -> PathBuf {
+    let timestamp = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|duration| duration.as_millis())
+        .unwrap_or_default();
+    PathBuf::from(format!("replay-{timestamp}.gif"))
+}
+
+/// Frame interval a recording started at `started` is in now, counting from 1.
+fn interval(started: Instant, fps: u32) -> u32 {
+    (started.elapsed().as_secs_f64() * f64::from(fps)) as u32 + 1
+}
+
+/// `recording-<unix timestamp>.<extension>` in the current working directory.
+pub fn default_recording_path(format: RecordingFormat) -> PathBuf {
+    let timestamp = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|duration| duration.as_millis())
+        .unwrap_or_default();
+    PathBuf::from(format!("recording-{timestamp}.{}", format.extension()))
+}
+
+/// Encodes the frames received until the recorder stops, runs on the encoder thread.
+fn encode(path: &Path, format: RecordingFormat, fps: u32, frames: Receiver<RecordedFrame>) {
+    let result = match format {
+        RecordingFormat::Gif => encode_gif(path, fps, frames),
+        RecordingFormat::Mp4 => encode_ffmpeg(path, fps, frames).map_err(image::ImageError::from),
+    };
+    match result {
+        Ok(()) => log::info!("Saved recording to {}", path.display()),
+        Err(err) => log::error!("Failed to record to {}: {err}", path.display()),
+    }
+}
+
+fn encode_gif(path: &Path, fps: u32, frames: Receiver<RecordedFrame>) -> image::ImageResult<()> {
+    write_gif(
+        path,
+        frames.into_iter().map(|frame| {
+            let delay = Delay::from_numer_denom_ms(1000 * frame.repeat, fps);
+            (frame.image, delay)
+        }),
+    )
+}
+
+fn write_gif(
+    path: &Path,
+    frames: impl IntoIterator<Item = (RgbaImage, Delay)>,
+) -> image::ImageResult<()> {
+    let mut encoder = GifEncoder::new_with_speed(BufWriter::new(File::create(path)?), 10);
+    encoder.set_repeat(Repeat::Infinite)?;
+    for (image, delay) in frames {
+        encoder.encode_frame(image::Frame::from_parts(image, 0, 0, delay))?;
+    }
+    Ok(())
+}
+
+fn encode_ffmpeg(path: &Path, fps: u32, frames: Receiver<RecordedFrame>) -> std::io::Result<()> {
+    // The size of the video is fixed by the first frame
+    let Ok(first) = frames.recv() else {
+        return Ok(());
+    };
+    let (width, height) = first.image.dimensions();
+    let mut ffmpeg = spawn_ffmpeg(path, fps, width, height)?;
+    let mut input = ffmpeg.stdin.take().expect("stdin is piped");
+    for frame in std::iter::once(first).chain(frames) {
+        if frame.image.dimensions() != (width, height) {
+            log::warn!("Skipping recorded frame, the window size changed");
+            continue;
+        }
+        for _ in 0..frame.repeat {
+            input.write_all(&frame.image)?;
+        }
+    }
+    drop(input);
+
+    let status = ffmpeg.wait()?;
+    if !status.success() {
+        return Err(std::io::Error::other(format!(
+            "ffmpeg exited with {status}"
+        )));
+    }
+    Ok(())
+}
+
+fn spawn_ffmpeg(path: &Path, fps: u32, width: u32, height: u32) -> std::io::Result<Child> {
+    Command::new("ffmpeg")
+        .args(["-loglevel", "error", "-y"])
+        .args(["-f", "rawvideo", "-pix_fmt", "rgba"])
+        .args(["-s", &format!("{width}x{height}")])
+        .args(["-r", &fps.to_string()])
+        .args(["-i", "-"])
+        // yuv420p needs even dimensions
+        .args(["-vf", "crop=trunc(iw/2)*2:trunc(ih/2)*2"])
+        .args(["-c:v", "libx264", "-pix_fmt", "yuv420p"])
+        .arg(path)
+        .stdin(Stdio::piped())
+        .spawn()
+}
@@ -0,0 +1,72 @@
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, Receiver, Sender},
+        Arc,
+    },
+};
+
+/// Creates the multi-threaded tokio runtime the event loop runs in.
+pub(crate) fn build() -> tokio::runtime::Runtime {
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .expect("Failed to create the tokio runtime")
+}
+
+/// Runs futures on the tokio runtime and collects their results for the UI.
+///
+/// Results are sent through a channel which is drained by [`Self::poll`], usually once per
+/// update. A repaint is requested whenever a task finishes, so the UI picks up results right
+/// away.
+pub struct AsyncTasks<T> {
+    sender: Sender<T>,
+    receiver: Receiver<T>,
+    running: Arc<AtomicUsize>,
+}
+
+impl<T: Send + 'static> AsyncTasks<T> {
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            sender,
+            receiver,
+            running: Arc::default(),
+        }
+    }
+
+    /// Spawns `future` on the runtime. Must be called from the UI thread or a runtime thread.
+    pub fn spawn(
+        &self,
+        ctx: &egui::Context,
+        future: impl Future<Output = T> + Send + 'static,
+    ) -> tokio::task::JoinHandle<()> {
+        let sender = self.sender.clone();
+        let running = self.running.clone();
+        let ctx = ctx.clone();
+        running.fetch_add(1, Ordering::Relaxed);
+        tokio::spawn(async move {
+            let result = future.await;
+            running.fetch_sub(1, Ordering::Relaxed);
+            let _ = sender.send(result);
+            ctx.request_repaint();
+        })
+    }
+
+    /// Number of spawned tasks which have not finished yet.
+    pub fn running(&self) -> usize {
+        self.running.load(Ordering::Relaxed)
+    }
+
+    /// Returns the results of the tasks finished since the last call.
+    pub fn poll(&self) -> Vec<T> {
+        self.receiver.try_iter().collect()
+    }
+}
+
+impl<T: Send + 'static> Default for AsyncTasks<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,62 @@
+use crate::FrameTime;
+
+/// Everything a [`SceneRenderer`] needs to record its draw calls for one frame.
+pub struct SceneFrame<'a> {
+    pub device: &'a wgpu::Device,
+    pub queue: &'a wgpu::Queue,
+    pub encoder: &'a mut wgpu::CommandEncoder,
+    /// View of the texture the egui layer is drawn on top of.
+    ///
+    /// With MSAA enabled this is the multisampled color target, which is resolved into the
+    /// surface after the egui pass, so scene passes should not resolve it themselves.
+    ///
+    /// With HDR enabled this is a floating point texture in [`crate::hdr::HDR_FORMAT`], which
+    /// is tone mapped after the scene was rendered. Also with effects in the
+    /// [`crate::post_process::PostProcess`] chain, it is an offscreen texture.
+    pub view: &'a wgpu::TextureView,
+    pub format: wgpu::TextureFormat,
+    /// Sample count of `view`, pipelines drawing into it have to use the same count.
+    pub sample_count: u32,
+    /// Depth buffer with the size and sample count of `view`, if enabled with
+    /// [`crate::AppBuilder::with_depth_buffer`]. egui is drawn on top without depth testing.
+    pub depth_view: Option<&'a wgpu::TextureView>,
+    pub depth_format: Option<wgpu::TextureFormat>,
+    pub size_in_pixels: [u32; 2],
+    /// Delta and elapsed time of this frame, also available to the UI as
+    /// [`crate::Frame::time`].
+    pub time: FrameTime,
+    /// Progress from the last towards the next fixed update, see
+    /// [`crate::timestep::FixedTimestep::interpolation`]. Always `1.0` without a fixed update
+    /// rate.
+    pub interpolation: f32,
+}
+
+impl<'a> SceneFrame<'a> {
+    /// Attachment clearing the depth buffer (and stencil, if the format has one), for the first
+    /// pass of the scene. Later passes should load the depth values instead.
+    pub fn depth_stencil_attachment(&self) -> Option<wgpu::RenderPassDepthStencilAttachment<'a>> {
+        let view = self.depth_view?;
+        let has_stencil = self
+            .depth_format
+            .is_some_and(|format| format.has_stencil_aspect());
+        Some(wgpu::RenderPassDepthStencilAttachment {
+            view,
+            depth_ops: Some(wgpu::Operations {
+                load: wgpu::LoadOp::Clear(1.0),
+                store: wgpu::StoreOp::Store,
+            }),
+            stencil_ops: has_stencil.then_some(wgpu::Operations {
+                load: wgpu::LoadOp::Clear(0),
+                store: wgpu::StoreOp::Store,
+            }),
+        })
+    }
+}
+
+/// Draws custom wgpu content underneath the egui layer.
+///
+/// The renderer is responsible for clearing the surface view, egui is drawn on top of whatever
+/// the scene leaves behind.
+pub trait SceneRenderer {
+    fn render(&mut self, frame: &mut SceneFrame);
+}
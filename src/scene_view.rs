@@ -0,0 +1,105 @@
+use egui::{Rect, Response, Sense, Ui, Vec2};
+
+use crate::textures::{NativeTexture, TextureRegistry};
+
+/// An offscreen render target shown as a widget, e.g. the 3D view of an editor.
+///
+/// [`Self::show`] sizes the texture to match the physical pixels covered by the widget, so the
+/// scene can render into [`Self::view`] from [`crate::SceneRenderer::render`] of the same frame
+/// without any scaling.
+///
+/// ```no_run
+/// # fn example(ui: &mut egui::Ui, frame: &egui_tests::Frame, view: &mut egui_tests::scene_view::SceneView) {
+/// let response = view.show(ui, frame.textures());
+/// if response.dragged() {
+///     // Orbit the camera by `response.drag_delta()`
+/// }
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct SceneView {
+    format: wgpu::TextureFormat,
+    filter: wgpu::FilterMode,
+    texture: Option<NativeTexture>,
+    pixels_per_point: f32,
+}
+
+impl SceneView {
+    pub fn new(format: wgpu::TextureFormat) -> Self {
+        Self {
+            format,
+            filter: wgpu::FilterMode::Linear,
+            texture: None,
+            pixels_per_point: 1.0,
+        }
+    }
+
+    /// Fills the available space of `ui` with the view.
+    pub fn show(&mut self, ui: &mut Ui, textures: &TextureRegistry) -> Response {
+        self.show_sized(ui, textures, ui.available_size())
+    }
+
+    /// Shows the view with a size in points, resizing the texture if needed. The response senses
+    /// clicks and drags for camera controls.
+    pub fn show_sized(&mut self, ui: &mut Ui, textures: &TextureRegistry, size: Vec2) -> Response {
+        let (rect, response) = ui.allocate_exact_size(size, Sense::click_and_drag());
+        // Snapped to the pixel grid, so every texel maps to exactly one physical pixel
+        let rect = Rect::from_min_max(
+            ui.painter().round_pos_to_pixels(rect.min),
+            ui.painter().round_pos_to_pixels(rect.max),
+        );
+        self.pixels_per_point = ui.ctx().pixels_per_point();
+        let size_in_pixels = [
+            (rect.width() * self.pixels_per_point).round() as u32,
+            (rect.height() * self.pixels_per_point).round() as u32,
+        ];
+
+        let texture = match &mut self.texture {
+            Some(texture) => {
+                texture.resize(size_in_pixels);
+                texture
+            }
+            None => self.texture.insert(NativeTexture::new(
+                textures,
+                size_in_pixels,
+                self.format,
+                self.filter,
+            )),
+        };
+
+        if ui.is_rect_visible(rect) {
+            ui.painter().image(
+                texture.id(),
+                rect,
+                Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+                egui::Color32::WHITE,
+            );
+        }
+        response
+    }
+
+    /// View of the texture to render into, `None` until the widget was shown once.
+    pub fn view(&self) -> Option<&wgpu::TextureView> {
+        self.texture.as_ref().map(NativeTexture::view)
+    }
+
+    pub fn format(&self) -> wgpu::TextureFormat {
+        self.format
+    }
+
+    /// Size of the texture in physical pixels, at least one pixel in each direction.
+    pub fn size_in_pixels(&self) -> [u32; 2] {
+        self.texture.as_ref().map_or([1, 1], NativeTexture::size)
+    }
+
+    /// Scale factor the view was last shown with, e.g. to size lines and text in the scene.
+    pub fn pixels_per_point(&self) -> f32 {
+        self.pixels_per_point
+    }
+
+    /// Aspect ratio of the texture, for the projection matrix of a camera.
+    pub fn aspect_ratio(&self) -> f32 {
+        let [width, height] = self.size_in_pixels();
+        width as f32 / height as f32
+    }
+}
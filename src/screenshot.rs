@@ -0,0 +1,122 @@
+use std::path::{Path, PathBuf};
+
+use image::RgbaImage;
+use web_time::SystemTime;
+
+/// A copy of a texture into a mappable buffer, recorded but not yet read back.
+pub struct TextureCapture {
+    buffer: wgpu::Buffer,
+    width: u32,
+    height: u32,
+    padded_bytes_per_row: u32,
+    format: wgpu::TextureFormat,
+}
+
+impl TextureCapture {
+    /// Records a copy of `texture` into a new buffer. The texture needs
+    /// [`wgpu::TextureUsages::COPY_SRC`].
+    pub fn record(
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        texture: &wgpu::Texture,
+    ) -> Self {
+        let width = texture.width();
+        let height = texture.height();
+        let format = texture.format();
+        let bytes_per_pixel = format.block_size(None).unwrap_or(4);
+        let padded_bytes_per_row =
+            (width * bytes_per_pixel).next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("texture capture"),
+            size: padded_bytes_per_row as u64 * height as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_bytes_per_row),
+                    rows_per_image: Some(height),
+                },
+            },
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+
+        Self {
+            buffer,
+            width,
+            height,
+            padded_bytes_per_row,
+            format,
+        }
+    }
+
+    /// Waits for the copy to finish and converts the pixels to RGBA.
+    ///
+    /// Must be called after the encoder the copy was recorded into has been submitted.
+    pub fn read(self, device: &wgpu::Device) -> Option<RgbaImage> {
+        let swap_red_blue = match self.format {
+            wgpu::TextureFormat::Rgba8Unorm | wgpu::TextureFormat::Rgba8UnormSrgb => false,
+            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb => true,
+            format => {
+                log::error!("Capturing {format:?} textures is not supported");
+                return None;
+            }
+        };
+
+        let slice = self.buffer.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        device.poll(wgpu::Maintain::Wait);
+        if let Err(err) = receiver.recv().ok()? {
+            log::error!("Failed to map capture buffer: {err}");
+            return None;
+        }
+
+        let unpadded_bytes_per_row = (self.width * 4) as usize;
+        let mut pixels = Vec::with_capacity(unpadded_bytes_per_row * self.height as usize);
+        for row in slice
+            .get_mapped_range()
+            .chunks_exact(self.padded_bytes_per_row as usize)
+        {
+            pixels.extend_from_slice(&row[..unpadded_bytes_per_row]);
+        }
+        self.buffer.unmap();
+
+        if swap_red_blue {
+            for pixel in pixels.chunks_exact_mut(4) {
+                pixel.swap(0, 2);
+            }
+        }
+
+        RgbaImage::from_raw(self.width, self.height, pixels)
+    }
+}
+
+/// Writes `image` to `path` as PNG, logging failures.
+pub fn save_png(image: &RgbaImage, path: &Path) {
+    match image.save_with_format(path, image::ImageFormat::Png) {
+        Ok(()) => log::info!("Saved screenshot to {}", path.display()),
+        Err(err) => log::error!("Failed to save screenshot to {}: {err}", path.display()),
+    }
+}
+
+/// `screenshot-<unix timestamp>.png` in the current working directory.
+pub fn default_screenshot_path() -> PathBuf {
+    let timestamp = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|duration| duration.as_millis())
+        .unwrap_or_default();
+    PathBuf::from(format!("screenshot-{timestamp}.png"))
+}
@@ -0,0 +1,216 @@
+//! A ready-made settings window for the common preferences, toggled with
+//! [`crate::shortcuts::Action::ToggleSettings`] (Ctrl+Shift+S by default).
+//!
+//! The [`Preferences`] shown in it are persisted between runs if an app id is set with
+//! [`crate::AppBuilder::with_app_id`].
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use wgpu::PresentMode;
+
+use crate::{
+    persistence::PreferencesPersistence,
+    shortcuts::{parse_shortcut, shortcut_to_string, Action, Shortcuts},
+    theme::ThemePreference,
+    Frame,
+};
+
+/// FPS caps offered in the settings window, besides no cap.
+const FPS_CAPS: [u32; 4] = [30, 60, 120, 144];
+
+/// The user preferences shown in the settings window.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preferences {
+    pub theme: ThemePreference,
+    pub vsync: bool,
+    pub max_fps: Option<u32>,
+    /// Zoom factor of the UI, see [`egui::Context::zoom_factor`].
+    pub scale: f32,
+    /// Leaves panel backgrounds transparent, if the window is transparent.
+    pub transparent: bool,
+    /// Shortcuts by [`Action::id`], formatted with
+    /// [`crate::shortcuts::shortcut_to_string`]. An empty string means no shortcut.
+    pub shortcuts: BTreeMap<String, String>,
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Self {
+            theme: ThemePreference::default(),
+            vsync: true,
+            max_fps: None,
+            scale: 1.0,
+            transparent: false,
+            shortcuts: BTreeMap::new(),
+        }
+    }
+}
+
+impl Preferences {
+    /// The preferences currently in effect.
+    pub fn capture(frame: &Frame, ctx: &egui::Context) -> Self {
+        Self {
+            theme: frame.theme().preference(),
+            vsync: matches!(
+                frame.present_mode(),
+                PresentMode::AutoVsync | PresentMode::Fifo | PresentMode::FifoRelaxed
+            ),
+            max_fps: frame.max_fps(),
+            scale: ctx.zoom_factor(),
+            transparent: frame.transparent_panels(),
+            shortcuts: frame
+                .shortcuts()
+                .bindings()
+                .map(|(action, shortcut)| {
+                    let shortcut = shortcut
+                        .map(|shortcut| shortcut_to_string(&shortcut))
+                        .unwrap_or_default();
+                    (action.id().to_owned(), shortcut)
+                })
+                .collect(),
+        }
+    }
+
+    /// Applies the preferences which differ from the ones in effect.
+    pub fn apply(&self, frame: &mut Frame, ctx: &egui::Context) {
+        let current = Self::capture(frame, ctx);
+        frame.set_theme_preference(self.theme);
+        if self.vsync != current.vsync {
+            frame.set_present_mode(if self.vsync {
+                PresentMode::AutoVsync
+            } else {
+                PresentMode::AutoNoVsync
+            });
+        }
+        frame.set_max_fps(self.max_fps);
+        if self.scale != current.scale {
+            ctx.set_zoom_factor(self.scale);
+        }
+        frame.set_transparent_panels(self.transparent);
+        self.apply_shortcuts(frame.shortcuts_mut());
+    }
+
+    /// Rebinds the actions in [`Self::shortcuts`], which have to be registered already.
+    pub fn apply_shortcuts(&self, shortcuts: &mut Shortcuts) {
+        for (action, shortcut) in &self.shortcuts {
+            shortcuts.set_shortcut(&Action::from_id(action), parse_shortcut(shortcut));
+        }
+    }
+
+    /// Shows the preferences except the shortcuts, returns `true` if one changed.
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let before = self.clone();
+        egui::Grid::new("preferences")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Theme");
+                self.theme.ui(ui);
+                ui.end_row();
+
+                ui.label("Vsync");
+                ui.checkbox(&mut self.vsync, "");
+                ui.end_row();
+
+                ui.label("FPS cap");
+                egui::ComboBox::from_id_source("fps cap")
+                    .selected_text(
+                        self.max_fps
+                            .map_or("Unlimited".to_owned(), |fps| fps.to_string()),
+                    )
+                    .show_ui(ui, |ui| {
+                        for fps in FPS_CAPS {
+                            ui.selectable_value(&mut self.max_fps, Some(fps), fps.to_string());
+                        }
+                        ui.selectable_value(&mut self.max_fps, None, "Unlimited");
+                    });
+                ui.end_row();
+
+                ui.label("Scale");
+                ui.add(egui::Slider::new(&mut self.scale, 0.5..=3.0).step_by(0.1));
+                ui.end_row();
+
+                ui.label("Transparent background");
+                ui.checkbox(&mut self.transparent, "");
+                ui.end_row();
+            });
+        *self != before
+    }
+}
+
+/// The settings window, drawn after [`crate::App::update`].
+#[derive(Debug, Default)]
+pub struct SettingsWindow {
+    visible: bool,
+    persistence: Option<PreferencesPersistence>,
+}
+
+impl SettingsWindow {
+    pub(crate) fn new(persistence: Option<PreferencesPersistence>) -> Self {
+        Self {
+            visible: false,
+            persistence,
+        }
+    }
+
+    pub fn visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    /// Applies the persisted preferences, before the first frame.
+    pub(crate) fn load(frame: &mut Frame, ctx: &egui::Context) {
+        if let Some(preferences) = frame.settings.load_preferences() {
+            preferences.apply(frame, ctx);
+        }
+    }
+
+    /// Applies the persisted shortcuts again, after the first update when the app has registered
+    /// its actions.
+    pub(crate) fn load_shortcuts(frame: &mut Frame) {
+        if let Some(preferences) = frame.settings.load_preferences() {
+            preferences.apply_shortcuts(frame.shortcuts_mut());
+        }
+    }
+
+    fn load_preferences(&self) -> Option<Preferences> {
+        self.persistence.as_ref()?.load()
+    }
+
+    /// Persists the preferences in effect, before exiting.
+    pub(crate) fn save(frame: &Frame, ctx: &egui::Context) {
+        if let Some(persistence) = &frame.settings.persistence {
+            persistence.save(&Preferences::capture(frame, ctx));
+        }
+    }
+
+    pub(crate) fn show(frame: &mut Frame, ctx: &egui::Context) {
+        let mut visible = frame.settings.visible;
+        egui::Window::new("Settings")
+            .id(egui::Id::new("egui_tests::settings"))
+            .open(&mut visible)
+            .default_width(320.0)
+            .show(ctx, |ui| {
+                let mut preferences = Preferences::capture(frame, ctx);
+                if preferences.ui(ui) {
+                    preferences.apply(frame, ctx);
+                }
+                ui.separator();
+                ui.collapsing("Shortcuts", |ui| frame.shortcuts_mut().ui(ui));
+                ui.separator();
+                if ui.button("Reset to defaults").clicked() {
+                    frame.shortcuts_mut().reset();
+                    Preferences {
+                        shortcuts: BTreeMap::new(),
+                        ..Default::default()
+                    }
+                    .apply(frame, ctx);
+                }
+            });
+        frame.settings.visible = visible;
+    }
+}
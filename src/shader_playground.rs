@@ -0,0 +1,366 @@
+//! A live editor for WGSL fragment shaders, previewed with a [`crate::paint_callback`].
+//!
+//! The edited source defines `fs_main`, written against [`PRELUDE`], which declares the
+//! `uniforms` with the resolution, pointer position and time, and the `VertexOutput` of the
+//! triangle covering the preview. The shader is checked with naga on every edit, errors are
+//! shown below the editor and highlighted in it while the last working shader keeps running:
+//!
+//! ```no_run
+//! # fn update(ui: &mut egui::Ui, frame: &egui_tests::Frame,
+//! #     playground: &mut egui_tests::shader_playground::ShaderPlayground) {
+//! playground.ui(ui, frame.gpu());
+//! # }
+//! ```
+
+use std::{collections::HashMap, hash::Hash};
+
+use egui::{text::LayoutJob, Color32, Id, Sense, TextFormat, TextStyle, Ui, Vec2};
+
+use crate::{
+    gpu::GpuContext,
+    paint_callback::{self, CallbackResources, CallbackTrait, PaintCallbackInfo},
+};
+
+/// Declarations shared by all playground shaders, prepended to the edited source.
+pub const PRELUDE: &str = include_str!("shaders/playground.wgsl");
+
+/// The shader a new playground starts with.
+pub const DEFAULT_SHADER: &str = "\
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let pointer = uniforms.mouse / uniforms.resolution;
+    let glow = 0.05 / distance(in.uv, pointer);
+    let color = 0.5 + 0.5 * cos(uniforms.time + in.uv.xyx + vec3<f32>(0.0, 2.0, 4.0));
+    return vec4<f32>(color + glow, 1.0);
+}
+";
+
+const UNIFORMS_SIZE: u64 = 32;
+
+/// Why a playground shader did not compile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShaderError {
+    /// 1-based line of the edited source, if the error points at it.
+    pub line: Option<u32>,
+    pub message: String,
+}
+
+impl std::fmt::Display for ShaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {line}: {}", self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+impl std::error::Error for ShaderError {}
+
+/// Parses and validates `source` after the [`PRELUDE`], with lines counted within `source`.
+pub fn validate(source: &str) -> Result<(), ShaderError> {
+    let full_source = format!("{PRELUDE}{source}");
+    let line = |location: Option<naga::SourceLocation>| {
+        location
+            .and_then(|location| {
+                location
+                    .line_number
+                    .checked_sub(PRELUDE.lines().count() as u32)
+            })
+            .filter(|&line| line > 0)
+    };
+    let module = naga::front::wgsl::parse_str(&full_source).map_err(|err| ShaderError {
+        line: line(err.location(&full_source)),
+        message: err.message().to_owned(),
+    })?;
+    naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::empty(),
+    )
+    .validate(&module)
+    .map_err(|err| ShaderError {
+        line: line(err.location(&full_source)),
+        message: err.as_inner().to_string(),
+    })?;
+    if !module
+        .entry_points
+        .iter()
+        .any(|entry_point| entry_point.name == "fs_main")
+    {
+        return Err(ShaderError {
+            line: None,
+            message: "the shader has no `fs_main` entry point".to_owned(),
+        });
+    }
+    Ok(())
+}
+
+/// An editor for a fragment shader next to a preview running it.
+pub struct ShaderPlayground {
+    id: Id,
+    source: String,
+    /// The source last compiled, successfully or not.
+    compiled: Option<String>,
+    error: Option<ShaderError>,
+    preview_size: Vec2,
+    mouse: Vec2,
+}
+
+impl std::fmt::Debug for ShaderPlayground {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ShaderPlayground")
+            .field("id", &self.id)
+            .field("error", &self.error)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for ShaderPlayground {
+    fn default() -> Self {
+        Self::new("egui_tests::shader_playground")
+    }
+}
+
+impl ShaderPlayground {
+    /// Starts with [`DEFAULT_SHADER`].
+    pub fn new(id_source: impl Hash) -> Self {
+        Self {
+            id: Id::new(id_source),
+            source: DEFAULT_SHADER.to_owned(),
+            compiled: None,
+            error: None,
+            preview_size: egui::vec2(320.0, 180.0),
+            mouse: Vec2::ZERO,
+        }
+    }
+
+    pub fn with_source(mut self, source: impl Into<String>) -> Self {
+        self.source = source.into();
+        self
+    }
+
+    /// Size of the preview in points, 320 by 180 by default.
+    pub fn with_preview_size(mut self, size: impl Into<Vec2>) -> Self {
+        self.preview_size = size.into();
+        self
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Why the current source did not compile, the previous shader is still shown then.
+    pub fn error(&self) -> Option<&ShaderError> {
+        self.error.as_ref()
+    }
+
+    /// Shows the preview above the editor, compiling the source if it changed.
+    pub fn ui(&mut self, ui: &mut Ui, gpu: &GpuContext) {
+        // Compiled again after the device was recreated
+        if self.compiled.as_ref() != Some(&self.source)
+            || (self.error.is_none() && !self.has_pipeline(gpu))
+        {
+            self.compile(gpu);
+        }
+
+        let (rect, response) = ui.allocate_exact_size(self.preview_size, Sense::hover());
+        let pixels_per_point = ui.ctx().pixels_per_point();
+        if let Some(pointer) = response.hover_pos() {
+            self.mouse = (pointer - rect.min) * pixels_per_point;
+        }
+        if self.has_pipeline(gpu) {
+            let resolution = rect.size() * pixels_per_point;
+            let mut uniforms = Vec::with_capacity(UNIFORMS_SIZE as usize);
+            for value in [
+                resolution.x,
+                resolution.y,
+                self.mouse.x,
+                self.mouse.y,
+                ui.input(|input| input.time) as f32,
+            ] {
+                uniforms.extend_from_slice(&value.to_le_bytes());
+            }
+            uniforms.extend_from_slice(&(ui.ctx().frame_nr() as u32).to_le_bytes());
+            uniforms.resize(UNIFORMS_SIZE as usize, 0);
+            ui.painter().add(paint_callback::shape(
+                rect,
+                PlaygroundCallback {
+                    id: self.id,
+                    uniforms,
+                },
+            ));
+            ui.ctx().request_repaint();
+        } else {
+            ui.painter()
+                .rect_filled(rect, 0.0, ui.visuals().extreme_bg_color);
+        }
+
+        let error_line = self.error.as_ref().and_then(|error| error.line);
+        let error_color = ui.visuals().error_fg_color;
+        let mut layouter = |ui: &Ui, text: &str, wrap_width: f32| {
+            let font_id = TextStyle::Monospace.resolve(ui.style());
+            let mut job = LayoutJob::default();
+            for (index, line) in text.split_inclusive('\n').enumerate() {
+                let mut format = TextFormat::simple(font_id.clone(), ui.visuals().text_color());
+                if error_line == Some(index as u32 + 1) {
+                    format.background = error_color.gamma_multiply(0.3);
+                }
+                job.append(line, 0.0, format);
+            }
+            job.wrap.max_width = wrap_width;
+            ui.fonts(|fonts| fonts.layout_job(job))
+        };
+        ui.add(
+            egui::TextEdit::multiline(&mut self.source)
+                .id(self.id.with("source"))
+                .code_editor()
+                .desired_width(f32::INFINITY)
+                .desired_rows(12)
+                .layouter(&mut layouter),
+        );
+        match &self.error {
+            Some(error) => ui.colored_label(error_color, error.to_string()),
+            None => ui.colored_label(Color32::GRAY, "Compiled"),
+        };
+    }
+
+    fn has_pipeline(&self, gpu: &GpuContext) -> bool {
+        pipeline(&gpu.renderer().callback_resources, self.id).is_some()
+    }
+
+    fn compile(&mut self, gpu: &GpuContext) {
+        self.compiled = Some(self.source.clone());
+        if let Err(err) = validate(&self.source) {
+            self.error = Some(err);
+            return;
+        }
+        self.error = None;
+        let pipeline = PlaygroundPipeline::new(gpu, &format!("{PRELUDE}{}", self.source));
+        let mut renderer = gpu.renderer_mut();
+        if !renderer
+            .callback_resources
+            .contains::<PlaygroundPipelines>()
+        {
+            renderer
+                .callback_resources
+                .insert(PlaygroundPipelines::default());
+        }
+        let pipelines: &mut PlaygroundPipelines = renderer.callback_resources.get_mut().unwrap();
+        pipelines.0.insert(self.id, pipeline);
+    }
+}
+
+/// The pipelines of all playgrounds by id, kept in the callback resources of the renderer.
+#[derive(Default)]
+struct PlaygroundPipelines(HashMap<Id, PlaygroundPipeline>);
+
+struct PlaygroundPipeline {
+    pipeline: wgpu::RenderPipeline,
+    uniforms: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+}
+
+impl PlaygroundPipeline {
+    fn new(gpu: &GpuContext, source: &str) -> Self {
+        let device = gpu.device();
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("shader playground"),
+            source: wgpu::ShaderSource::Wgsl(source.into()),
+        });
+        let uniforms = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("shader playground uniforms"),
+            size: UNIFORMS_SIZE,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("shader playground"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("shader playground"),
+            layout: &bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniforms.as_entire_binding(),
+            }],
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("shader playground"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("shader playground"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(gpu.target_format().into())],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: gpu.msaa_samples(),
+                ..Default::default()
+            },
+            multiview: None,
+        });
+
+        Self {
+            pipeline,
+            uniforms,
+            bind_group,
+        }
+    }
+}
+
+struct PlaygroundCallback {
+    id: Id,
+    uniforms: Vec<u8>,
+}
+
+impl CallbackTrait for PlaygroundCallback {
+    fn prepare(
+        &self,
+        _device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        _encoder: &mut wgpu::CommandEncoder,
+        resources: &mut CallbackResources,
+    ) -> Vec<wgpu::CommandBuffer> {
+        if let Some(pipeline) = pipeline(resources, self.id) {
+            queue.write_buffer(&pipeline.uniforms, 0, &self.uniforms);
+        }
+        Vec::new()
+    }
+
+    fn paint<'a>(
+        &'a self,
+        _info: PaintCallbackInfo,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        resources: &'a CallbackResources,
+    ) {
+        if let Some(pipeline) = pipeline(resources, self.id) {
+            render_pass.set_pipeline(&pipeline.pipeline);
+            render_pass.set_bind_group(0, &pipeline.bind_group, &[]);
+            render_pass.draw(0..3, 0..1);
+        }
+    }
+}
+
+fn pipeline(resources: &CallbackResources, id: Id) -> Option<&PlaygroundPipeline> {
+    resources.get::<PlaygroundPipelines>()?.0.get(&id)
+}
@@ -0,0 +1,63 @@
+// Copies the offscreen frame to the surface, cutting off the corners of the window and converting
+// its alpha to what the compositor of the OS expects.
+
+const STRAIGHT_ALPHA: u32 = 1u;
+const PREMULTIPLY_IN_GAMMA: u32 = 2u;
+
+struct Params {
+    size: vec2<f32>,
+    radius: f32,
+    flags: u32,
+}
+
+@group(0) @binding(0) var frame_texture: texture_2d<f32>;
+@group(0) @binding(1) var<uniform> params: Params;
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+    // A single triangle covering the whole viewport
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    return vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+
+fn linear_to_srgb(linear: vec3<f32>) -> vec3<f32> {
+    let cutoff = linear < vec3<f32>(0.0031308);
+    let lower = linear * 12.92;
+    let higher = 1.055 * pow(linear, vec3<f32>(1.0 / 2.4)) - 0.055;
+    return select(higher, lower, cutoff);
+}
+
+fn srgb_to_linear(srgb: vec3<f32>) -> vec3<f32> {
+    let cutoff = srgb < vec3<f32>(0.04045);
+    let lower = srgb / 12.92;
+    let higher = pow((srgb + 0.055) / 1.055, vec3<f32>(2.4));
+    return select(higher, lower, cutoff);
+}
+
+@fragment
+fn fs_main(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    var color = textureLoad(frame_texture, vec2<i32>(position.xy), 0);
+
+    // Signed distance to the rounded rectangle, negative inside
+    let half_size = params.size * 0.5;
+    let q = abs(position.xy - half_size) - half_size + params.radius;
+    let distance = length(max(q, vec2<f32>(0.0))) + min(max(q.x, q.y), 0.0) - params.radius;
+    let coverage = clamp(0.5 - distance, 0.0, 1.0);
+
+    // egui blends with premultiplied alpha, so all channels are scaled
+    color *= coverage;
+    if params.flags == 0u {
+        return color;
+    }
+    if color.a <= 0.0 {
+        return vec4<f32>(0.0);
+    }
+
+    let straight = color.rgb / color.a;
+    if (params.flags & STRAIGHT_ALPHA) != 0u {
+        return vec4<f32>(straight, color.a);
+    }
+    // The sRGB view encodes the output again, so the premultiplied gamma color is decoded here
+    let premultiplied = linear_to_srgb(straight) * color.a;
+    return vec4<f32>(srgb_to_linear(premultiplied), color.a);
+}
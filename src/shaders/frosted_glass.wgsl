@@ -0,0 +1,61 @@
+// Blurs the scene and draws it behind UI panels, tinted like frosted glass.
+
+const MODE_BLUR: u32 = 0u;
+const MODE_PIXELATE: u32 = 1u;
+
+struct Params {
+    // Size of the input texture in pixels
+    size: vec2<f32>,
+    // Step between the blur taps in texels of the input, along one axis
+    direction: vec2<f32>,
+    // Premultiplied color laid over the frosted area
+    tint: vec4<f32>,
+    // Pixel size of the blocks when pixelating
+    block_size: f32,
+    mode: u32,
+    _padding: vec2<f32>,
+}
+
+@group(0) @binding(0) var input_texture: texture_2d<f32>;
+@group(0) @binding(1) var input_sampler: sampler;
+@group(0) @binding(2) var<uniform> params: Params;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    // A single triangle covering the whole viewport
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    var out: VertexOutput;
+    out.position = vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+    out.uv = vec2<f32>(uv.x, 1.0 - uv.y);
+    return out;
+}
+
+// One direction of a separable gaussian blur with 9 taps
+@fragment
+fn fs_blur(in: VertexOutput) -> @location(0) vec4<f32> {
+    let step = params.direction / params.size;
+    var weights = array<f32, 5>(0.227027, 0.1945946, 0.1216216, 0.054054, 0.016216);
+    var color = textureSample(input_texture, input_sampler, in.uv) * weights[0];
+    for (var i = 1; i < 5; i++) {
+        let offset = step * f32(i);
+        color += textureSample(input_texture, input_sampler, in.uv + offset) * weights[i];
+        color += textureSample(input_texture, input_sampler, in.uv - offset) * weights[i];
+    }
+    return color;
+}
+
+@fragment
+fn fs_draw(in: VertexOutput) -> @location(0) vec4<f32> {
+    var uv = in.uv;
+    if params.mode == MODE_PIXELATE {
+        let block = params.block_size / params.size;
+        uv = (floor(uv / block) + 0.5) * block;
+    }
+    let color = textureSampleLevel(input_texture, input_sampler, uv, 0.0);
+    return params.tint + color * (1.0 - params.tint.a);
+}
@@ -0,0 +1,31 @@
+// Declarations of the shader playground, prepended to the edited fragment shader.
+
+struct Uniforms {
+    // Size of the preview in pixels
+    resolution: vec2<f32>,
+    // Last pointer position over the preview, in pixels from its top left corner
+    mouse: vec2<f32>,
+    // Seconds since the app started
+    time: f32,
+    // Number of the egui frame
+    frame: u32,
+    _padding: vec2<f32>,
+}
+
+@group(0) @binding(0) var<uniform> uniforms: Uniforms;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    // From (0, 0) at the top left to (1, 1) at the bottom right of the preview
+    @location(0) uv: vec2<f32>,
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    // A single triangle covering the preview
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    var out: VertexOutput;
+    out.position = vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+    out.uv = vec2<f32>(uv.x, 1.0 - uv.y);
+    return out;
+}
@@ -0,0 +1,26 @@
+// Adds a glow around bright parts of the scene in a single pass.
+
+const THRESHOLD: f32 = 0.8;
+const RADIUS: i32 = 4;
+const SPREAD: f32 = 3.0;
+const INTENSITY: f32 = 0.6;
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let texel = SPREAD / params.resolution;
+    let color = textureSampleLevel(input_texture, input_sampler, in.uv, 0.0);
+
+    var glow = vec3<f32>(0.0);
+    var total_weight = 0.0;
+    for (var x = -RADIUS; x <= RADIUS; x++) {
+        for (var y = -RADIUS; y <= RADIUS; y++) {
+            let offset = vec2<f32>(f32(x), f32(y));
+            let weight = exp(-dot(offset, offset) / f32(RADIUS * RADIUS));
+            let tap = textureSampleLevel(input_texture, input_sampler, in.uv + offset * texel, 0.0);
+            glow += max(tap.rgb - vec3<f32>(THRESHOLD), vec3<f32>(0.0)) * weight;
+            total_weight += weight;
+        }
+    }
+
+    return vec4<f32>(color.rgb + glow / total_weight * INTENSITY, color.a);
+}
@@ -0,0 +1,43 @@
+// Smooths aliased edges by blurring along them, based on FXAA 2 by Timothy Lottes.
+
+fn luma(color: vec3<f32>) -> f32 {
+    return dot(color, vec3<f32>(0.299, 0.587, 0.114));
+}
+
+fn sample_at(uv: vec2<f32>) -> vec4<f32> {
+    return textureSampleLevel(input_texture, input_sampler, uv, 0.0);
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let texel = 1.0 / params.resolution;
+    let center = sample_at(in.uv);
+    let luma_nw = luma(sample_at(in.uv + vec2<f32>(-1.0, -1.0) * texel).rgb);
+    let luma_ne = luma(sample_at(in.uv + vec2<f32>(1.0, -1.0) * texel).rgb);
+    let luma_sw = luma(sample_at(in.uv + vec2<f32>(-1.0, 1.0) * texel).rgb);
+    let luma_se = luma(sample_at(in.uv + vec2<f32>(1.0, 1.0) * texel).rgb);
+    let luma_m = luma(center.rgb);
+    let luma_min = min(luma_m, min(min(luma_nw, luma_ne), min(luma_sw, luma_se)));
+    let luma_max = max(luma_m, max(max(luma_nw, luma_ne), max(luma_sw, luma_se)));
+
+    // Direction along the edge
+    var dir = vec2<f32>(
+        -((luma_nw + luma_ne) - (luma_sw + luma_se)),
+        (luma_nw + luma_sw) - (luma_ne + luma_se),
+    );
+    let reduce = max((luma_nw + luma_ne + luma_sw + luma_se) * (0.25 / 8.0), 1.0 / 128.0);
+    let scale = 1.0 / (min(abs(dir.x), abs(dir.y)) + reduce);
+    dir = clamp(dir * scale, vec2<f32>(-8.0), vec2<f32>(8.0)) * texel;
+
+    let near = 0.5 * (sample_at(in.uv + dir * (1.0 / 3.0 - 0.5)).rgb
+        + sample_at(in.uv + dir * (2.0 / 3.0 - 0.5)).rgb);
+    let far = near * 0.5 + 0.25 * (sample_at(in.uv - dir * 0.5).rgb
+        + sample_at(in.uv + dir * 0.5).rgb);
+
+    // The wider blur overshoots on thin features, fall back to the narrow one there
+    let luma_far = luma(far);
+    if luma_far < luma_min || luma_far > luma_max {
+        return vec4<f32>(near, center.a);
+    }
+    return vec4<f32>(far, center.a);
+}
@@ -0,0 +1,9 @@
+// Darkens the edges of the scene.
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(input_texture, input_sampler, in.uv);
+    let distance = length(in.uv - vec2<f32>(0.5));
+    let vignette = 1.0 - smoothstep(0.3, 0.8, distance);
+    return vec4<f32>(color.rgb * vignette, color.a);
+}
@@ -0,0 +1,65 @@
+// Maps the HDR scene to the displayable range before egui is drawn on top.
+
+struct Params {
+    exposure: f32,
+    tonemapping: u32,
+    encode_srgb: u32,
+    _padding: f32,
+}
+
+@group(0) @binding(0) var hdr_texture: texture_2d<f32>;
+@group(0) @binding(1) var<uniform> params: Params;
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+    // A single triangle covering the whole viewport
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    return vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+
+fn reinhard(color: vec3<f32>) -> vec3<f32> {
+    return color / (color + vec3<f32>(1.0));
+}
+
+// Fit of the ACES filmic curve by Krzysztof Narkowicz
+fn aces(color: vec3<f32>) -> vec3<f32> {
+    let a = 2.51;
+    let b = 0.03;
+    let c = 2.43;
+    let d = 0.59;
+    let e = 0.14;
+    return (color * (a * color + b)) / (color * (c * color + d) + e);
+}
+
+fn linear_to_srgb(color: vec3<f32>) -> vec3<f32> {
+    let cutoff = color < vec3<f32>(0.0031308);
+    let lower = color * 12.92;
+    let higher = 1.055 * pow(color, vec3<f32>(1.0 / 2.4)) - 0.055;
+    return select(higher, lower, cutoff);
+}
+
+@fragment
+fn fs_main(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    let hdr = textureLoad(hdr_texture, vec2<i32>(position.xy), 0);
+    let exposed = max(hdr.rgb * params.exposure, vec3<f32>(0.0));
+
+    var color: vec3<f32>;
+    switch params.tonemapping {
+        case 1u: {
+            color = reinhard(exposed);
+        }
+        case 2u: {
+            color = aces(exposed);
+        }
+        default: {
+            color = exposed;
+        }
+    }
+    color = clamp(color, vec3<f32>(0.0), vec3<f32>(1.0));
+
+    // A linear framebuffer is displayed as if it contained gamma encoded values
+    if params.encode_srgb != 0u {
+        color = linear_to_srgb(color);
+    }
+    return vec4<f32>(color, clamp(hdr.a, 0.0, 1.0));
+}
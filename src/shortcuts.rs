@@ -0,0 +1,386 @@
+use egui::{Key, KeyboardShortcut, Modifiers};
+use serde::{de::value::StrDeserializer, Deserialize};
+
+/// Something that can be triggered with a keyboard shortcut.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    ToggleFullscreen,
+    Screenshot,
+    /// Starts or stops recording the window, see [`crate::recorder`].
+    ToggleRecording,
+    /// Exports the [`crate::recorder::ReplayBuffer`] if it is enabled.
+    SaveReplay,
+    ToggleClickThrough,
+    ToggleDiagnostics,
+    /// Shows or hides the [`crate::settings::SettingsWindow`].
+    ToggleSettings,
+    /// Shows the [`crate::command_palette::CommandPalette`].
+    CommandPalette,
+    ZoomIn,
+    ZoomOut,
+    ZoomReset,
+    /// Handled by the app, e.g. with [`crate::undo::CommandStack::handle_actions`]. Left to the
+    /// focused widget while text is edited.
+    Undo,
+    /// See [`Self::Undo`].
+    Redo,
+    /// An action of the app, checked with [`crate::Frame::action_triggered`].
+    Custom(String),
+}
+
+impl Action {
+    /// Name of the action in config files, e.g. `toggle_fullscreen`. Custom actions use their
+    /// own name.
+    pub fn id(&self) -> &str {
+        match self {
+            Self::Quit => "quit",
+            Self::ToggleFullscreen => "toggle_fullscreen",
+            Self::Screenshot => "screenshot",
+            Self::ToggleRecording => "toggle_recording",
+            Self::SaveReplay => "save_replay",
+            Self::ToggleClickThrough => "toggle_click_through",
+            Self::ToggleDiagnostics => "toggle_diagnostics",
+            Self::ToggleSettings => "toggle_settings",
+            Self::CommandPalette => "command_palette",
+            Self::ZoomIn => "zoom_in",
+            Self::ZoomOut => "zoom_out",
+            Self::ZoomReset => "zoom_reset",
+            Self::Undo => "undo",
+            Self::Redo => "redo",
+            Self::Custom(name) => name,
+        }
+    }
+
+    /// Inverse of [`Self::id`], unknown names are custom actions.
+    pub fn from_id(id: &str) -> Self {
+        match id {
+            "quit" => Self::Quit,
+            "toggle_fullscreen" => Self::ToggleFullscreen,
+            "screenshot" => Self::Screenshot,
+            "toggle_recording" => Self::ToggleRecording,
+            "save_replay" => Self::SaveReplay,
+            "toggle_click_through" => Self::ToggleClickThrough,
+            "toggle_diagnostics" => Self::ToggleDiagnostics,
+            "toggle_settings" => Self::ToggleSettings,
+            "command_palette" => Self::CommandPalette,
+            "zoom_in" => Self::ZoomIn,
+            "zoom_out" => Self::ZoomOut,
+            "zoom_reset" => Self::ZoomReset,
+            "undo" => Self::Undo,
+            "redo" => Self::Redo,
+            custom => Self::Custom(custom.to_owned()),
+        }
+    }
+}
+
+/// Parses a shortcut like `Ctrl+Shift+S` or `F11`. `Ctrl` is Cmd on macOS.
+///
+/// Keys are named like the variants of [`Key`], e.g. `Num1` or `ArrowUp`.
+pub fn parse_shortcut(text: &str) -> Option<KeyboardShortcut> {
+    let mut parts: Vec<_> = text.split('+').map(str::trim).collect();
+    let key = parts.pop()?;
+    let mut modifiers = Modifiers::NONE;
+    for part in parts {
+        modifiers = modifiers.plus(match part.to_lowercase().as_str() {
+            "ctrl" | "cmd" | "command" => Modifiers::COMMAND,
+            "shift" => Modifiers::SHIFT,
+            "alt" | "option" => Modifiers::ALT,
+            _ => return None,
+        });
+    }
+    let key = Key::deserialize(StrDeserializer::<serde::de::value::Error>::new(key)).ok()?;
+    Some(KeyboardShortcut::new(modifiers, key))
+}
+
+/// Formats `shortcut` so [`parse_shortcut`] reads it back.
+pub fn shortcut_to_string(shortcut: &KeyboardShortcut) -> String {
+    let modifiers = shortcut.modifiers;
+    let mut text = String::new();
+    if modifiers.command || modifiers.ctrl || modifiers.mac_cmd {
+        text.push_str("Ctrl+");
+    }
+    if modifiers.shift {
+        text.push_str("Shift+");
+    }
+    if modifiers.alt {
+        text.push_str("Alt+");
+    }
+    // The variant name, which is what serde expects
+    text.push_str(&format!("{:?}", shortcut.key));
+    text
+}
+
+#[derive(Debug, Clone)]
+struct Binding {
+    action: Action,
+    name: String,
+    shortcut: Option<KeyboardShortcut>,
+    default: Option<KeyboardShortcut>,
+}
+
+/// Maps [`Action`]s to keyboard shortcuts which can be changed at runtime.
+///
+/// Shortcuts are consumed before [`crate::App::update`] runs, so widgets do not see them.
+#[derive(Debug, Clone)]
+pub struct Shortcuts {
+    bindings: Vec<Binding>,
+    /// The action whose shortcut is being changed in [`Self::ui`].
+    recording: Option<usize>,
+}
+
+impl Default for Shortcuts {
+    fn default() -> Self {
+        let mut shortcuts = Self {
+            bindings: Vec::new(),
+            recording: None,
+        };
+        shortcuts.register(
+            Action::Quit,
+            "Quit",
+            Some(KeyboardShortcut::new(Modifiers::COMMAND, Key::Q)),
+        );
+        shortcuts.register(
+            Action::ToggleFullscreen,
+            "Toggle fullscreen",
+            Some(KeyboardShortcut::new(Modifiers::NONE, Key::F11)),
+        );
+        shortcuts.register(
+            Action::Screenshot,
+            "Take screenshot",
+            Some(KeyboardShortcut::new(Modifiers::NONE, Key::F12)),
+        );
+        #[cfg(not(target_arch = "wasm32"))]
+        shortcuts.register(
+            Action::ToggleRecording,
+            "Start or stop recording",
+            Some(KeyboardShortcut::new(Modifiers::SHIFT, Key::F12)),
+        );
+        #[cfg(not(target_arch = "wasm32"))]
+        shortcuts.register(
+            Action::SaveReplay,
+            "Save replay of the last seconds",
+            Some(KeyboardShortcut::new(
+                Modifiers::COMMAND.plus(Modifiers::SHIFT),
+                Key::F12,
+            )),
+        );
+        shortcuts.register(
+            Action::ToggleClickThrough,
+            "Toggle click-through",
+            Some(KeyboardShortcut::new(
+                Modifiers::COMMAND.plus(Modifiers::SHIFT),
+                Key::O,
+            )),
+        );
+        shortcuts.register(
+            Action::ToggleDiagnostics,
+            "Toggle frame time graph",
+            Some(KeyboardShortcut::new(Modifiers::NONE, Key::F3)),
+        );
+        shortcuts.register(
+            Action::ToggleSettings,
+            "Show settings",
+            Some(KeyboardShortcut::new(
+                Modifiers::COMMAND.plus(Modifiers::SHIFT),
+                Key::S,
+            )),
+        );
+        shortcuts.register(
+            Action::CommandPalette,
+            "Command palette",
+            Some(KeyboardShortcut::new(
+                Modifiers::COMMAND.plus(Modifiers::SHIFT),
+                Key::P,
+            )),
+        );
+        shortcuts.register(
+            Action::ZoomIn,
+            "Zoom in",
+            Some(egui::gui_zoom::kb_shortcuts::ZOOM_IN),
+        );
+        shortcuts.register(
+            Action::ZoomOut,
+            "Zoom out",
+            Some(egui::gui_zoom::kb_shortcuts::ZOOM_OUT),
+        );
+        shortcuts.register(
+            Action::ZoomReset,
+            "Reset zoom",
+            Some(egui::gui_zoom::kb_shortcuts::ZOOM_RESET),
+        );
+        shortcuts.register(
+            Action::Undo,
+            "Undo",
+            Some(KeyboardShortcut::new(Modifiers::COMMAND, Key::Z)),
+        );
+        shortcuts.register(
+            Action::Redo,
+            "Redo",
+            Some(KeyboardShortcut::new(Modifiers::COMMAND, Key::Y)),
+        );
+        shortcuts
+    }
+}
+
+impl Shortcuts {
+    /// Adds `action` with a display `name` and default `shortcut`, replacing an existing binding
+    /// of the same action.
+    pub fn register(
+        &mut self,
+        action: Action,
+        name: impl Into<String>,
+        shortcut: Option<KeyboardShortcut>,
+    ) {
+        let binding = Binding {
+            action,
+            name: name.into(),
+            shortcut,
+            default: shortcut,
+        };
+        match self
+            .bindings
+            .iter_mut()
+            .find(|existing| existing.action == binding.action)
+        {
+            Some(existing) => *existing = binding,
+            None => self.bindings.push(binding),
+        }
+    }
+
+    /// All registered actions with their current shortcuts.
+    pub fn bindings(&self) -> impl Iterator<Item = (&Action, Option<KeyboardShortcut>)> {
+        self.bindings
+            .iter()
+            .map(|binding| (&binding.action, binding.shortcut))
+    }
+
+    /// All registered actions with their display names, as listed in the command palette.
+    pub fn commands(&self) -> impl Iterator<Item = (&Action, &str)> {
+        self.bindings
+            .iter()
+            .map(|binding| (&binding.action, binding.name.as_str()))
+    }
+
+    pub fn name(&self, action: &Action) -> Option<&str> {
+        Some(&self.binding(action)?.name)
+    }
+
+    pub fn shortcut(&self, action: &Action) -> Option<KeyboardShortcut> {
+        self.binding(action)?.shortcut
+    }
+
+    /// Rebinds `action`, `None` removes its shortcut.
+    pub fn set_shortcut(&mut self, action: &Action, shortcut: Option<KeyboardShortcut>) {
+        if let Some(binding) = self
+            .bindings
+            .iter_mut()
+            .find(|binding| &binding.action == action)
+        {
+            binding.shortcut = shortcut;
+        }
+    }
+
+    pub fn reset(&mut self) {
+        for binding in &mut self.bindings {
+            binding.shortcut = binding.default;
+        }
+    }
+
+    /// Consumes the pressed shortcuts and returns their actions.
+    ///
+    /// Shortcuts with more modifiers are checked first, so Ctrl+Shift+S is not taken for Ctrl+S.
+    /// Undo and redo are left to text edits with keyboard focus.
+    pub(crate) fn consume(&self, ctx: &egui::Context) -> Vec<Action> {
+        if self.recording.is_some() {
+            return Vec::new();
+        }
+        let editing_text = ctx.wants_keyboard_input();
+        let mut bindings: Vec<_> = self
+            .bindings
+            .iter()
+            .filter(|binding| {
+                !(editing_text && matches!(binding.action, Action::Undo | Action::Redo))
+            })
+            .filter_map(|binding| Some((&binding.action, binding.shortcut?)))
+            .collect();
+        bindings.sort_by_key(|(_, shortcut)| std::cmp::Reverse(modifier_count(shortcut.modifiers)));
+        ctx.input_mut(|input| {
+            bindings
+                .into_iter()
+                .filter(|(_, shortcut)| input.consume_shortcut(shortcut))
+                .map(|(action, _)| action.clone())
+                .collect()
+        })
+    }
+
+    /// Lists all bindings. Clicking a shortcut records a new one from the next key press.
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        if let Some(index) = self.recording {
+            if let Some(shortcut) = recorded_shortcut(ui.ctx()) {
+                self.bindings[index].shortcut = shortcut;
+                self.recording = None;
+            }
+        }
+
+        egui::Grid::new("shortcuts").striped(true).show(ui, |ui| {
+            for (index, binding) in self.bindings.iter_mut().enumerate() {
+                ui.label(&binding.name);
+                let text = if self.recording == Some(index) {
+                    "Press keys… (Esc to clear)".to_owned()
+                } else {
+                    binding.shortcut.map_or("None".to_owned(), |shortcut| {
+                        ui.ctx().format_shortcut(&shortcut)
+                    })
+                };
+                if ui.button(text).clicked() {
+                    self.recording = Some(index);
+                }
+                ui.end_row();
+            }
+        });
+        if ui.button("Reset to defaults").clicked() {
+            self.reset();
+            self.recording = None;
+        }
+    }
+
+    fn binding(&self, action: &Action) -> Option<&Binding> {
+        self.bindings
+            .iter()
+            .find(|binding| &binding.action == action)
+    }
+}
+
+/// The shortcut pressed this frame, `Some(None)` if it was cleared with Escape.
+fn recorded_shortcut(ctx: &egui::Context) -> Option<Option<KeyboardShortcut>> {
+    ctx.input(|input| {
+        input.events.iter().find_map(|event| match event {
+            egui::Event::Key {
+                key: Key::Escape,
+                pressed: true,
+                ..
+            } => Some(None),
+            egui::Event::Key {
+                key,
+                pressed: true,
+                modifiers,
+                ..
+            } => Some(Some(KeyboardShortcut::new(*modifiers, *key))),
+            _ => None,
+        })
+    })
+}
+
+fn modifier_count(modifiers: Modifiers) -> usize {
+    [
+        modifiers.alt,
+        modifiers.ctrl,
+        modifiers.shift,
+        modifiers.mac_cmd,
+        modifiers.command,
+    ]
+    .into_iter()
+    .filter(|&pressed| pressed)
+    .count()
+}
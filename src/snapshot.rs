@@ -0,0 +1,184 @@
+//! Golden image tests of UI scenes rendered with the [`crate::headless`] renderer.
+//!
+//! [`Snapshots::check`] compares a rendered image against the reference
+//! `<directory>/<name>.png`. Set the [`UPDATE_ENV_VAR`] environment variable to write the
+//! rendered images as the new references instead, e.g.
+//! `UPDATE_SNAPSHOTS=1 cargo test --test snapshots`. New scenes need this once, a missing
+//! reference fails the check.
+
+use std::path::{Path, PathBuf};
+
+use image::{Rgba, RgbaImage};
+
+/// Environment variable which makes [`Snapshots::check`] overwrite the references.
+pub const UPDATE_ENV_VAR: &str = "UPDATE_SNAPSHOTS";
+
+/// How much a rendered image may deviate from its reference, to allow for small differences
+/// between GPUs and drivers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tolerance {
+    /// Largest difference of a color channel for which two pixels are still considered equal.
+    pub channel: u8,
+    /// Number of pixels which may differ by more than `channel`.
+    pub pixels: usize,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self {
+            channel: 2,
+            pixels: 0,
+        }
+    }
+}
+
+/// Result of comparing two images with [`compare`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Comparison {
+    Matches,
+    SizeDiffers {
+        expected: (u32, u32),
+        actual: (u32, u32),
+    },
+    PixelsDiffer {
+        /// Number of pixels which differ by more than the tolerance.
+        count: usize,
+        /// Largest difference of a color channel.
+        max_difference: u8,
+        /// The differing pixels in red on a dimmed copy of the image.
+        diff: RgbaImage,
+    },
+}
+
+/// Compares `actual` against `expected` with the given `tolerance`.
+pub fn compare(expected: &RgbaImage, actual: &RgbaImage, tolerance: Tolerance) -> Comparison {
+    if expected.dimensions() != actual.dimensions() {
+        return Comparison::SizeDiffers {
+            expected: expected.dimensions(),
+            actual: actual.dimensions(),
+        };
+    }
+
+    let mut count = 0;
+    let mut max_difference = 0;
+    let mut diff = RgbaImage::new(actual.width(), actual.height());
+    for ((expected, actual), diff) in expected
+        .pixels()
+        .zip(actual.pixels())
+        .zip(diff.pixels_mut())
+    {
+        let difference = expected
+            .0
+            .iter()
+            .zip(actual.0)
+            .map(|(&expected, actual)| expected.abs_diff(actual))
+            .max()
+            .unwrap_or(0);
+        max_difference = max_difference.max(difference);
+        *diff = if difference > tolerance.channel {
+            count += 1;
+            Rgba([255, 0, 0, 255])
+        } else {
+            let [r, g, b, _] = actual.0;
+            Rgba([r / 4, g / 4, b / 4, 255])
+        };
+    }
+
+    if count > tolerance.pixels {
+        Comparison::PixelsDiffer {
+            count,
+            max_difference,
+            diff,
+        }
+    } else {
+        Comparison::Matches
+    }
+}
+
+/// A directory of reference images.
+#[derive(Debug, Clone)]
+pub struct Snapshots {
+    directory: PathBuf,
+    tolerance: Tolerance,
+}
+
+impl Snapshots {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+            tolerance: Tolerance::default(),
+        }
+    }
+
+    pub fn with_tolerance(mut self, tolerance: Tolerance) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Compares `image` against the reference named `name`, panicking with a description of the
+    /// difference if it does not match.
+    ///
+    /// On a mismatch, `<name>.new.png` and `<name>.diff.png` are written next to the reference.
+    pub fn check(&self, name: &str, image: &RgbaImage) {
+        let path = self.directory.join(format!("{name}.png"));
+        if std::env::var_os(UPDATE_ENV_VAR).is_some() {
+            std::fs::create_dir_all(&self.directory).unwrap_or_else(|err| {
+                panic!("Failed to create {}: {err}", self.directory.display())
+            });
+            save(image, &path);
+            log::info!("Wrote snapshot {}", path.display());
+            return;
+        }
+        if !path.exists() {
+            panic!(
+                "Snapshot {name} has no reference at {}. Run with {UPDATE_ENV_VAR}=1 to write it.",
+                path.display()
+            );
+        }
+
+        let expected = image::open(&path)
+            .unwrap_or_else(|err| panic!("Failed to read snapshot {}: {err}", path.display()))
+            .into_rgba8();
+        let new_path = self.directory.join(format!("{name}.new.png"));
+        let diff_path = self.directory.join(format!("{name}.diff.png"));
+        match compare(&expected, image, self.tolerance) {
+            Comparison::Matches => {
+                // Left behind by a previous failure
+                let _ = std::fs::remove_file(new_path);
+                let _ = std::fs::remove_file(diff_path);
+            }
+            Comparison::SizeDiffers { expected, actual } => {
+                save(image, &new_path);
+                panic!(
+                    "Snapshot {name} has size {actual:?} instead of {expected:?}, see {}. \
+                     Run with {UPDATE_ENV_VAR}=1 to accept it.",
+                    new_path.display()
+                );
+            }
+            Comparison::PixelsDiffer {
+                count,
+                max_difference,
+                diff,
+            } => {
+                save(image, &new_path);
+                save(&diff, &diff_path);
+                panic!(
+                    "Snapshot {name} differs in {count} pixels by up to {max_difference}, see {} \
+                     and {}. Run with {UPDATE_ENV_VAR}=1 to accept it.",
+                    new_path.display(),
+                    diff_path.display()
+                );
+            }
+        }
+    }
+}
+
+fn save(image: &RgbaImage, path: &Path) {
+    image
+        .save(path)
+        .unwrap_or_else(|err| panic!("Failed to write snapshot {}: {err}", path.display()));
+}
@@ -0,0 +1,95 @@
+use wgpu::SurfaceError;
+
+/// A surface frames can be acquired from, abstracted so the recovery logic can be tested without
+/// a GPU.
+pub trait RenderSurface {
+    type Texture;
+
+    fn get_current_texture(&mut self) -> Result<Self::Texture, SurfaceError>;
+
+    /// Reconfigures the surface with its current configuration.
+    fn reconfigure(&mut self);
+}
+
+/// Why no surface texture could be acquired for this frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AcquireError {
+    /// The frame should be skipped and retried later.
+    SkipFrame,
+    /// The surface is still lost after reconfiguring it, which happens if the device was lost.
+    /// The frame should be skipped, see [`LossTracker`].
+    Lost,
+    /// The GPU ran out of memory, rendering can not continue.
+    OutOfMemory,
+}
+
+/// Acquires the next texture of `surface`, recovering from lost or outdated surfaces by
+/// reconfiguring them once.
+pub fn acquire_texture<S: RenderSurface>(surface: &mut S) -> Result<S::Texture, AcquireError> {
+    match surface.get_current_texture() {
+        Ok(texture) => Ok(texture),
+        Err(SurfaceError::Lost | SurfaceError::Outdated) => {
+            surface.reconfigure();
+            surface.get_current_texture().map_err(|err| {
+                log::warn!("Surface still unavailable after reconfiguring: {err}");
+                match err {
+                    SurfaceError::OutOfMemory => AcquireError::OutOfMemory,
+                    SurfaceError::Lost => AcquireError::Lost,
+                    _ => AcquireError::SkipFrame,
+                }
+            })
+        }
+        Err(SurfaceError::Timeout) => {
+            log::debug!("Timed out acquiring surface texture, skipping frame");
+            Err(AcquireError::SkipFrame)
+        }
+        Err(SurfaceError::OutOfMemory) => Err(AcquireError::OutOfMemory),
+    }
+}
+
+/// Decides when a surface that stays lost means the device is gone and has to be recreated.
+#[derive(Debug, Default)]
+pub struct LossTracker {
+    consecutive_losses: u32,
+}
+
+impl LossTracker {
+    /// Number of frames in a row the surface has to be lost for.
+    pub const THRESHOLD: u32 = 3;
+
+    /// Records the outcome of acquiring a texture, returns `true` once the device should be
+    /// recreated.
+    pub fn record<T>(&mut self, result: &Result<T, AcquireError>) -> bool {
+        match result {
+            Err(AcquireError::Lost) => {
+                self.consecutive_losses += 1;
+                if self.consecutive_losses >= Self::THRESHOLD {
+                    self.consecutive_losses = 0;
+                    return true;
+                }
+            }
+            Err(_) => {}
+            Ok(_) => self.consecutive_losses = 0,
+        }
+        false
+    }
+}
+
+/// A wgpu surface together with the device and configuration needed to reconfigure it.
+pub struct ConfiguredSurface<'a> {
+    pub surface: &'a wgpu::Surface,
+    pub device: &'a wgpu::Device,
+    pub config: &'a wgpu::SurfaceConfiguration,
+}
+
+impl RenderSurface for ConfiguredSurface<'_> {
+    type Texture = wgpu::SurfaceTexture;
+
+    fn get_current_texture(&mut self) -> Result<Self::Texture, SurfaceError> {
+        self.surface.get_current_texture()
+    }
+
+    fn reconfigure(&mut self) {
+        self.surface.configure(self.device, self.config);
+    }
+}
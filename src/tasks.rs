@@ -0,0 +1,300 @@
+use std::{
+    panic::AssertUnwindSafe,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, Sender},
+        Arc,
+    },
+    thread::JoinHandle,
+};
+
+use egui::mutex::Mutex;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Identifies a task spawned on a [`TaskManager`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TaskId(u64);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaskState {
+    Queued,
+    Running,
+    Finished,
+    Cancelled,
+    /// The task panicked with the given message.
+    Failed(String),
+}
+
+impl TaskState {
+    pub fn is_done(&self) -> bool {
+        matches!(
+            self,
+            TaskState::Finished | TaskState::Cancelled | TaskState::Failed(_)
+        )
+    }
+}
+
+#[derive(Debug)]
+struct TaskStatus {
+    state: TaskState,
+    progress: f32,
+    message: String,
+}
+
+struct SharedTask {
+    status: Mutex<TaskStatus>,
+    cancelled: AtomicBool,
+    ctx: egui::Context,
+}
+
+/// Handed to a running task to report progress and check for cancellation.
+pub struct TaskContext {
+    shared: Arc<SharedTask>,
+}
+
+impl TaskContext {
+    /// Sets the progress between `0.0` and `1.0` and repaints the UI.
+    pub fn set_progress(&self, progress: f32) {
+        self.shared.status.lock().progress = progress.clamp(0.0, 1.0);
+        self.shared.ctx.request_repaint();
+    }
+
+    /// Sets a short description of what the task is currently doing.
+    pub fn set_message(&self, message: impl Into<String>) {
+        self.shared.status.lock().message = message.into();
+        self.shared.ctx.request_repaint();
+    }
+
+    /// Whether the task should stop as soon as possible.
+    pub fn is_cancelled(&self) -> bool {
+        self.shared.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// A snapshot of a task for displaying it.
+#[derive(Debug, Clone)]
+pub struct TaskInfo {
+    pub id: TaskId,
+    pub name: String,
+    pub state: TaskState,
+    pub progress: f32,
+    pub message: String,
+}
+
+struct Task {
+    id: TaskId,
+    name: String,
+    shared: Arc<SharedTask>,
+}
+
+impl Task {
+    fn info(&self) -> TaskInfo {
+        let status = self.shared.status.lock();
+        TaskInfo {
+            id: self.id,
+            name: self.name.clone(),
+            state: status.state.clone(),
+            progress: status.progress,
+            message: status.message.clone(),
+        }
+    }
+}
+
+/// Runs closures on a fixed pool of worker threads and tracks their progress for the UI.
+///
+/// Tasks are cancelled cooperatively by checking [`TaskContext::is_cancelled`].
+pub struct TaskManager {
+    sender: Sender<Job>,
+    _workers: Vec<JoinHandle<()>>,
+    tasks: Vec<Task>,
+    next_id: u64,
+}
+
+impl TaskManager {
+    /// Starts a pool with `threads` workers.
+    pub fn new(threads: usize) -> Self {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let workers = (0..threads.max(1))
+            .map(|index| {
+                let receiver = receiver.clone();
+                std::thread::Builder::new()
+                    .name(format!("task worker {index}"))
+                    .spawn(move || worker_loop(&receiver))
+                    .expect("Failed to spawn task worker")
+            })
+            .collect();
+        Self {
+            sender,
+            _workers: workers,
+            tasks: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Queues `task` and repaints `ctx` whenever it reports progress or finishes.
+    pub fn spawn(
+        &mut self,
+        ctx: &egui::Context,
+        name: impl Into<String>,
+        task: impl FnOnce(&TaskContext) + Send + 'static,
+    ) -> TaskId {
+        let id = TaskId(self.next_id);
+        self.next_id += 1;
+
+        let shared = Arc::new(SharedTask {
+            status: Mutex::new(TaskStatus {
+                state: TaskState::Queued,
+                progress: 0.0,
+                message: String::new(),
+            }),
+            cancelled: AtomicBool::new(false),
+            ctx: ctx.clone(),
+        });
+        let task_context = TaskContext {
+            shared: shared.clone(),
+        };
+        let job = Box::new(move || {
+            let shared = task_context.shared.clone();
+            if task_context.is_cancelled() {
+                shared.status.lock().state = TaskState::Cancelled;
+                return;
+            }
+            shared.status.lock().state = TaskState::Running;
+            let result = std::panic::catch_unwind(AssertUnwindSafe(|| task(&task_context)));
+            shared.status.lock().state = match result {
+                Err(panic) => TaskState::Failed(panic_message(&*panic)),
+                Ok(()) if task_context.is_cancelled() => TaskState::Cancelled,
+                Ok(()) => TaskState::Finished,
+            };
+            shared.ctx.request_repaint();
+        });
+
+        if self.sender.send(job).is_err() {
+            shared.status.lock().state = TaskState::Failed("The task pool has shut down".into());
+        }
+        self.tasks.push(Task {
+            id,
+            name: name.into(),
+            shared,
+        });
+        id
+    }
+
+    /// Asks the task to stop. Queued tasks are not started at all.
+    pub fn cancel(&self, id: TaskId) {
+        if let Some(task) = self.tasks.iter().find(|task| task.id == id) {
+            task.shared.cancelled.store(true, Ordering::Relaxed);
+        }
+    }
+
+    pub fn tasks(&self) -> impl Iterator<Item = TaskInfo> + '_ {
+        self.tasks.iter().map(Task::info)
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.tasks().any(|task| !task.state.is_done())
+    }
+
+    /// Forgets all finished, cancelled and failed tasks.
+    pub fn clear_done(&mut self) {
+        self.tasks
+            .retain(|task| !task.shared.status.lock().state.is_done());
+    }
+
+    /// Lists all tasks with their progress and a button to cancel them.
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        if self.tasks.is_empty() {
+            ui.label("No tasks");
+            return;
+        }
+        for task in self.tasks().collect::<Vec<_>>() {
+            ui.horizontal(|ui| {
+                ui.label(&task.name);
+                match &task.state {
+                    TaskState::Queued => {
+                        ui.weak("Queued");
+                    }
+                    TaskState::Running => {
+                        ui.add(
+                            egui::ProgressBar::new(task.progress)
+                                .desired_width(120.0)
+                                .show_percentage(),
+                        );
+                    }
+                    TaskState::Finished => {
+                        ui.label("Done");
+                    }
+                    TaskState::Cancelled => {
+                        ui.weak("Cancelled");
+                    }
+                    TaskState::Failed(message) => {
+                        ui.colored_label(ui.visuals().error_fg_color, message);
+                    }
+                }
+                if !task.state.is_done() && ui.small_button("Cancel").clicked() {
+                    self.cancel(task.id);
+                }
+            });
+            if !task.message.is_empty() && !task.state.is_done() {
+                ui.weak(&task.message);
+            }
+        }
+        if ui.button("Clear finished").clicked() {
+            self.clear_done();
+        }
+    }
+
+    /// Shows the running tasks as a toast in the bottom right corner.
+    pub fn show_toast(&mut self, ctx: &egui::Context) {
+        let running: Vec<_> = self.tasks().filter(|task| !task.state.is_done()).collect();
+        if running.is_empty() {
+            return;
+        }
+        egui::Area::new("task toast")
+            .anchor(egui::Align2::RIGHT_BOTTOM, [-8.0, -8.0])
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    for task in running {
+                        ui.add(
+                            egui::ProgressBar::new(task.progress)
+                                .desired_width(200.0)
+                                .text(task.name),
+                        );
+                    }
+                });
+            });
+    }
+}
+
+impl Default for TaskManager {
+    /// A pool with one worker per available CPU core.
+    fn default() -> Self {
+        Self::new(
+            std::thread::available_parallelism()
+                .map(usize::from)
+                .unwrap_or(1),
+        )
+    }
+}
+
+fn worker_loop(receiver: &Mutex<Receiver<Job>>) {
+    loop {
+        // Only hold the lock while waiting, so other workers can pick up jobs meanwhile
+        let job = receiver.lock().recv();
+        match job {
+            Ok(job) => job(),
+            Err(_) => return,
+        }
+    }
+}
+
+fn panic_message(panic: &(dyn std::any::Any + Send)) -> String {
+    panic
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| panic.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "Task panicked".to_owned())
+}
@@ -0,0 +1,206 @@
+//! Tessellates the shapes of a frame on a worker thread.
+//!
+//! [`TessellationPipeline`] double-buffers the frames: the shapes of a frame are handed to the
+//! worker and the meshes of the previous frame, tessellated while the event loop handled the
+//! input in between, are painted instead. Heavy UIs then only block the event loop thread for
+//! building the UI, at the cost of showing it one frame later. Once egui does not ask for
+//! another frame right away, the pipeline is drained and the last frame is tessellated inline,
+//! so it is not left waiting on the worker.
+//!
+//! Unlike [`egui::Context::tessellate`], the worker does not lock the context while it runs, so
+//! the next frame can be built in the meantime. The paint stats of the context are not updated.
+//!
+//! ```no_run
+//! # fn frame(ctx: &egui::Context, mut output: egui::FullOutput) {
+//! use egui_tests::tessellation::TessellationPipeline;
+//!
+//! let mut pipeline = TessellationPipeline::default();
+//! let continuous = output.viewport_output[&egui::ViewportId::ROOT].repaint_delay.is_zero();
+//! let frame = pipeline.advance(ctx, &mut output, continuous);
+//! // ... paint `frame.paint_jobs` after applying `frame.textures_delta`
+//! # }
+//! ```
+
+use std::{
+    sync::mpsc,
+    thread::{self, JoinHandle},
+};
+
+use egui::{
+    epaint::{tessellate_shapes, ClippedShape},
+    ClippedPrimitive, Context, FullOutput, TexturesDelta,
+};
+
+/// Tessellates a frame with everything it needs from the context captured.
+type Job = Box<dyn FnOnce() -> Vec<ClippedPrimitive> + Send>;
+
+/// A thread tessellating one frame at a time, see [`TessellationPipeline`] for how it is used.
+pub struct TessellationWorker {
+    jobs: Option<mpsc::Sender<Job>>,
+    results: mpsc::Receiver<Vec<ClippedPrimitive>>,
+    pending: bool,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl std::fmt::Debug for TessellationWorker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TessellationWorker")
+            .field("pending", &self.pending)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for TessellationWorker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TessellationWorker {
+    pub fn new() -> Self {
+        let (jobs, job_receiver) = mpsc::channel::<Job>();
+        let (result_sender, results) = mpsc::channel();
+        let thread = thread::Builder::new()
+            .name("egui tessellation".to_owned())
+            .spawn(move || {
+                for job in job_receiver {
+                    if result_sender.send(job()).is_err() {
+                        break;
+                    }
+                }
+            })
+            .expect("Failed to spawn the tessellation thread");
+
+        Self {
+            jobs: Some(jobs),
+            results,
+            pending: false,
+            thread: Some(thread),
+        }
+    }
+
+    /// Starts tessellating `shapes` and returns right away. The font atlas and the tessellation
+    /// options are read from `context` now, so call this after the frame has ended. The result
+    /// of a previous frame that was not collected with [`Self::wait`] is discarded.
+    pub fn submit(&mut self, context: &Context, shapes: Vec<ClippedShape>, pixels_per_point: f32) {
+        self.wait();
+        let options = context.tessellation_options(|options| *options);
+        let (font_tex_size, prepared_discs) = context.fonts(|fonts| {
+            let atlas = fonts.texture_atlas();
+            let atlas = atlas.lock();
+            (atlas.size(), atlas.prepared_discs())
+        });
+        let job: Job = Box::new(move || {
+            tessellate_shapes(
+                pixels_per_point,
+                options,
+                font_tex_size,
+                prepared_discs,
+                shapes,
+            )
+        });
+        self.pending = self
+            .jobs
+            .as_ref()
+            .is_some_and(|jobs| jobs.send(job).is_ok());
+    }
+
+    /// Whether shapes were submitted whose result has not been collected yet.
+    pub fn is_pending(&self) -> bool {
+        self.pending
+    }
+
+    /// Blocks until the submitted shapes are tessellated and returns the paint jobs, or `None`
+    /// if nothing was submitted.
+    pub fn wait(&mut self) -> Option<Vec<ClippedPrimitive>> {
+        if !std::mem::take(&mut self.pending) {
+            return None;
+        }
+        profile_scope!("wait_for_tessellation");
+        self.results.recv().ok()
+    }
+}
+
+impl Drop for TessellationWorker {
+    fn drop(&mut self) {
+        // Ends the loop of the thread
+        self.jobs = None;
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// The meshes of a frame and the texture updates to apply before painting them.
+#[derive(Debug, Default)]
+pub struct PreparedFrame {
+    pub paint_jobs: Vec<ClippedPrimitive>,
+    pub textures_delta: TexturesDelta,
+}
+
+/// Paints every frame one frame later while the worker tessellates it, see the
+/// [module docs](self).
+#[derive(Debug, Default)]
+pub struct TessellationPipeline {
+    worker: TessellationWorker,
+    /// Texture updates of the frame on the worker, which its meshes rely on.
+    in_flight: TexturesDelta,
+}
+
+impl TessellationPipeline {
+    /// Takes the shapes and texture updates of `output` and returns the frame to paint now.
+    ///
+    /// While `continuous`, i.e. egui wants the next frame right away, that is the previous
+    /// frame and `output` is tessellated on the worker. Otherwise the pipeline is drained and
+    /// `output` is tessellated inline.
+    pub fn advance(
+        &mut self,
+        context: &Context,
+        output: &mut FullOutput,
+        continuous: bool,
+    ) -> PreparedFrame {
+        let shapes = std::mem::take(&mut output.shapes);
+        let textures_delta = std::mem::take(&mut output.textures_delta);
+        let previous = self.worker.wait().map(|paint_jobs| PreparedFrame {
+            paint_jobs,
+            textures_delta: std::mem::take(&mut self.in_flight),
+        });
+
+        match previous {
+            Some(previous) if continuous => {
+                self.worker.submit(context, shapes, output.pixels_per_point);
+                self.in_flight = textures_delta;
+                previous
+            }
+            Some(PreparedFrame {
+                textures_delta: mut previous_delta,
+                ..
+            }) => {
+                // The meshes of the previous frame are outdated, but not its texture updates
+                previous_delta.append(textures_delta);
+                PreparedFrame {
+                    paint_jobs: context.tessellate(shapes, output.pixels_per_point),
+                    textures_delta: previous_delta,
+                }
+            }
+            None if continuous => {
+                // Fills the pipeline, showing this frame now and again with the next one
+                self.worker
+                    .submit(context, shapes.clone(), output.pixels_per_point);
+                PreparedFrame {
+                    paint_jobs: context.tessellate(shapes, output.pixels_per_point),
+                    textures_delta,
+                }
+            }
+            None => PreparedFrame {
+                paint_jobs: context.tessellate(shapes, output.pixels_per_point),
+                textures_delta,
+            },
+        }
+    }
+
+    /// Whether a frame is on the worker, to be painted by the next call to [`Self::advance`].
+    pub fn is_pending(&self) -> bool {
+        self.worker.is_pending()
+    }
+}
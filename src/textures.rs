@@ -0,0 +1,348 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+use egui::{
+    epaint::{ImageData, ImageDelta},
+    mutex::RwLock,
+    TextureId,
+};
+
+use crate::gpu_memory::GpuMemory;
+
+/// Lets the app display its own wgpu textures in egui, e.g. the render target of a 3D scene.
+///
+/// ```no_run
+/// # fn example(ui: &mut egui::Ui, frame: &egui_tests::Frame, view: &wgpu::TextureView) {
+/// let id = frame.textures().register(view, wgpu::FilterMode::Linear);
+/// ui.image((id, egui::vec2(256.0, 256.0)));
+/// # }
+/// ```
+///
+/// When the device is lost and recreated, every registered id keeps pointing to the same slot of
+/// the new renderer, showing a transparent placeholder until the app gives it a texture of the
+/// new device with [`Self::update`]. [`Self::is_lost`] tells which ones need that.
+/// [`NativeTexture`]s are recreated by their next [`NativeTexture::resize`].
+#[derive(Clone)]
+pub struct TextureRegistry {
+    shared: Arc<RwLock<Shared>>,
+    renderer: Arc<RwLock<egui_wgpu::Renderer>>,
+    memory: GpuMemory,
+}
+
+/// State of the registry shared by all of its clones.
+struct Shared {
+    device: Arc<wgpu::Device>,
+    /// Incremented every time the device is recreated.
+    generation: u64,
+    /// Ids of the user textures registered here, `true` if their texture was lost with a
+    /// previous device.
+    registered: BTreeMap<u64, bool>,
+    /// One past the highest user texture id the renderer handed out.
+    next_user_id: u64,
+}
+
+impl std::fmt::Debug for TextureRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TextureRegistry").finish_non_exhaustive()
+    }
+}
+
+impl TextureRegistry {
+    pub(crate) fn new(
+        device: Arc<wgpu::Device>,
+        renderer: Arc<RwLock<egui_wgpu::Renderer>>,
+        memory: GpuMemory,
+    ) -> Self {
+        Self {
+            shared: Arc::new(RwLock::new(Shared {
+                device,
+                generation: 0,
+                registered: BTreeMap::new(),
+                next_user_id: 0,
+            })),
+            renderer,
+            memory,
+        }
+    }
+
+    /// The current device, textures to register have to be created with it.
+    pub fn device(&self) -> Arc<wgpu::Device> {
+        self.shared.read().device.clone()
+    }
+
+    /// Number of times the device was recreated.
+    pub fn generation(&self) -> u64 {
+        self.shared.read().generation
+    }
+
+    /// Whether the texture of `id` was lost with a previous device and has to be given a new one
+    /// with [`Self::update`].
+    pub fn is_lost(&self, id: TextureId) -> bool {
+        let TextureId::User(user_id) = id else {
+            return false;
+        };
+        self.shared
+            .read()
+            .registered
+            .get(&user_id)
+            .copied()
+            .unwrap_or(false)
+    }
+
+    /// Statistics of the textures registered here and the ones managed by egui.
+    pub fn memory(&self) -> &GpuMemory {
+        &self.memory
+    }
+
+    /// Makes `view` available to egui under the returned id until it is [freed](Self::free).
+    pub fn register(&self, view: &wgpu::TextureView, filter: wgpu::FilterMode) -> TextureId {
+        let mut renderer = self.renderer.write();
+        let mut shared = self.shared.write();
+        let id = renderer.register_native_texture(&shared.device, view, filter);
+        if let TextureId::User(user_id) = id {
+            shared.registered.insert(user_id, false);
+            shared.next_user_id = shared.next_user_id.max(user_id + 1);
+        }
+        self.memory.register_native(id);
+        id
+    }
+
+    /// Points an already registered id to a new view, e.g. after the texture was resized or
+    /// lost with the device.
+    pub fn update(&self, id: TextureId, view: &wgpu::TextureView, filter: wgpu::FilterMode) {
+        let mut renderer = self.renderer.write();
+        let mut shared = self.shared.write();
+        renderer.update_egui_texture_from_wgpu_texture(&shared.device, view, filter, id);
+        if let TextureId::User(user_id) = id {
+            if let Some(lost) = shared.registered.get_mut(&user_id) {
+                *lost = false;
+            }
+        }
+    }
+
+    pub fn free(&self, id: TextureId) {
+        let mut renderer = self.renderer.write();
+        let mut shared = self.shared.write();
+        renderer.free_texture(&id);
+        if let TextureId::User(user_id) = id {
+            shared.registered.remove(&user_id);
+        }
+        self.memory.free_native(id);
+    }
+
+    /// Switches to the recreated `device` after the renderer was replaced. Every user texture
+    /// id handed out before is taken again in the new renderer, so ids are never reused and the
+    /// registered ones stay valid with a placeholder.
+    pub(crate) fn device_recreated(&self, device: Arc<wgpu::Device>) {
+        let mut renderer = self.renderer.write();
+        let mut shared = self.shared.write();
+        let placeholder = create_texture(&device, [1, 1], wgpu::TextureFormat::Rgba8Unorm)
+            .create_view(&wgpu::TextureViewDescriptor::default());
+        for user_id in 0..shared.next_user_id {
+            let id =
+                renderer.register_native_texture(&device, &placeholder, wgpu::FilterMode::Nearest);
+            debug_assert_eq!(id, TextureId::User(user_id));
+            match shared.registered.get_mut(&user_id) {
+                Some(lost) => {
+                    *lost = true;
+                    self.memory.set_native_size(id, 4);
+                }
+                None => renderer.free_texture(&id),
+            }
+        }
+        shared.device = device;
+        shared.generation += 1;
+    }
+}
+
+/// A texture owned by the app which is registered with egui for as long as it lives.
+///
+/// It can be rendered to and sampled from, so it is suited as the render target of a scene that
+/// is shown inside the UI with `ui.image((texture.id(), size))`.
+///
+/// After the device was recreated, the texture belongs to the lost device until the next
+/// [`Self::resize`] creates it again, see [`Self::is_lost`].
+#[derive(Debug)]
+pub struct NativeTexture {
+    registry: TextureRegistry,
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+    filter: wgpu::FilterMode,
+    id: TextureId,
+    /// Generation of the registry the texture was created in.
+    generation: u64,
+}
+
+impl NativeTexture {
+    pub fn new(
+        registry: &TextureRegistry,
+        size: [u32; 2],
+        format: wgpu::TextureFormat,
+        filter: wgpu::FilterMode,
+    ) -> Self {
+        let generation = registry.generation();
+        let texture = create_texture(&registry.device(), size, format);
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let id = registry.register(&view, filter);
+        registry.memory.set_native_size(id, texture_bytes(&texture));
+        Self {
+            registry: registry.clone(),
+            texture,
+            view,
+            filter,
+            id,
+            generation,
+        }
+    }
+
+    /// Whether the texture belongs to a device that was lost, until [`Self::resize`] is called.
+    pub fn is_lost(&self) -> bool {
+        self.generation != self.registry.generation()
+    }
+
+    pub fn id(&self) -> TextureId {
+        self.id
+    }
+
+    pub fn texture(&self) -> &wgpu::Texture {
+        &self.texture
+    }
+
+    pub fn view(&self) -> &wgpu::TextureView {
+        &self.view
+    }
+
+    pub fn size(&self) -> [u32; 2] {
+        [self.texture.width(), self.texture.height()]
+    }
+
+    /// Recreates the texture with a new size, or on the recreated device if it was lost, keeping
+    /// its id. The content is lost.
+    pub fn resize(&mut self, size: [u32; 2]) {
+        let size = size.map(|extent| extent.max(1));
+        if size == self.size() && !self.is_lost() {
+            return;
+        }
+        self.generation = self.registry.generation();
+        self.texture = create_texture(&self.registry.device(), size, self.texture.format());
+        self.view = self
+            .texture
+            .create_view(&wgpu::TextureViewDescriptor::default());
+        self.registry.update(self.id, &self.view, self.filter);
+        self.registry
+            .memory
+            .set_native_size(self.id, texture_bytes(&self.texture));
+    }
+}
+
+impl Drop for NativeTexture {
+    fn drop(&mut self) {
+        self.registry.free(self.id);
+    }
+}
+
+fn create_texture(
+    device: &wgpu::Device,
+    size: [u32; 2],
+    format: wgpu::TextureFormat,
+) -> wgpu::Texture {
+    device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("native egui texture"),
+        size: wgpu::Extent3d {
+            width: size[0].max(1),
+            height: size[1].max(1),
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+            | wgpu::TextureUsages::TEXTURE_BINDING
+            | wgpu::TextureUsages::COPY_DST,
+        view_formats: &[],
+    })
+}
+
+fn texture_bytes(texture: &wgpu::Texture) -> u64 {
+    let block_size = texture.format().block_size(None).unwrap_or(4);
+    u64::from(texture.width()) * u64::from(texture.height()) * u64::from(block_size)
+}
+
+/// Frees the textures egui does not need anymore. Called after the frame has been recorded,
+/// wgpu keeps them alive until the commands using them have been executed.
+pub(crate) fn free_textures(
+    renderer: &mut egui_wgpu::Renderer,
+    memory: &GpuMemory,
+    ids: &[TextureId],
+) {
+    for id in ids {
+        renderer.free_texture(id);
+        memory.free_texture(*id);
+    }
+}
+
+/// CPU copies of the textures managed by egui, to upload them again to a recreated device.
+///
+/// egui only sends the font atlas and images loaded through it once, so they would be lost
+/// with the device otherwise. This keeps a second copy of them in memory.
+#[derive(Default)]
+pub(crate) struct TextureShadow {
+    textures: HashMap<TextureId, ImageDelta>,
+}
+
+impl TextureShadow {
+    /// Applies an update sent by egui to the copy of the texture.
+    pub fn update(&mut self, id: TextureId, delta: &ImageDelta) {
+        match (delta.pos, self.textures.get_mut(&id)) {
+            (None, _) => {
+                self.textures.insert(id, delta.clone());
+            }
+            (Some(pos), Some(texture)) => patch(&mut texture.image, &delta.image, pos),
+            (Some(_), None) => log::warn!("Partial update of unknown texture {id:?}"),
+        }
+    }
+
+    pub fn free(&mut self, id: TextureId) {
+        self.textures.remove(&id);
+    }
+
+    /// The full images of all textures, to be uploaded as if egui just sent them.
+    pub fn textures(&self) -> impl Iterator<Item = (TextureId, &ImageDelta)> {
+        self.textures.iter().map(|(&id, delta)| (id, delta))
+    }
+}
+
+fn patch(target: &mut ImageData, patch: &ImageData, pos: [usize; 2]) {
+    match (target, patch) {
+        (ImageData::Color(target), ImageData::Color(patch)) => {
+            let target = Arc::make_mut(target);
+            let width = target.size[0];
+            copy_region(&mut target.pixels, width, &patch.pixels, patch.size, pos);
+        }
+        (ImageData::Font(target), ImageData::Font(patch)) => {
+            let width = target.size[0];
+            copy_region(&mut target.pixels, width, &patch.pixels, patch.size, pos);
+        }
+        _ => log::warn!("Texture update changes the image type"),
+    }
+}
+
+/// Copies the rows of `patch` into `target` at `pos`.
+fn copy_region<T: Copy>(
+    target: &mut [T],
+    target_width: usize,
+    patch: &[T],
+    patch_size: [usize; 2],
+    pos: [usize; 2],
+) {
+    if patch_size[0] == 0 {
+        return;
+    }
+    for (row, line) in patch.chunks_exact(patch_size[0]).enumerate() {
+        let start = (pos[1] + row) * target_width + pos[0];
+        target[start..start + line.len()].copy_from_slice(line);
+    }
+}
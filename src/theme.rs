@@ -0,0 +1,105 @@
+use egui::{Color32, Visuals};
+use serde::{Deserialize, Serialize};
+
+/// Which theme the UI should use.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ThemePreference {
+    Dark,
+    Light,
+    /// Follow the theme of the operating system.
+    #[default]
+    System,
+}
+
+impl ThemePreference {
+    /// Shows a radio button for every preference, returns `true` if the preference changed.
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let before = *self;
+        ui.horizontal(|ui| {
+            ui.selectable_value(self, Self::Dark, "🌙 Dark");
+            ui.selectable_value(self, Self::Light, "☀ Light");
+            ui.selectable_value(self, Self::System, "💻 System");
+        });
+        *self != before
+    }
+}
+
+/// Tracks the theme preference and the current system theme, and applies the resulting visuals
+/// to the egui context whenever either changes.
+#[derive(Debug, Clone)]
+pub struct Theme {
+    preference: ThemePreference,
+    system_dark: bool,
+    transparent_panels: bool,
+    dirty: bool,
+}
+
+impl Theme {
+    pub fn new(preference: ThemePreference, system_theme: Option<winit::window::Theme>) -> Self {
+        Self {
+            preference,
+            // Dark is egui's default, so it is used when the system theme is unknown
+            system_dark: system_theme != Some(winit::window::Theme::Light),
+            transparent_panels: false,
+            dirty: true,
+        }
+    }
+
+    /// Leaves panel backgrounds transparent, so a transparent window stays see-through.
+    pub fn with_transparent_panels(mut self, transparent_panels: bool) -> Self {
+        self.transparent_panels = transparent_panels;
+        self
+    }
+
+    pub fn transparent_panels(&self) -> bool {
+        self.transparent_panels
+    }
+
+    pub fn set_transparent_panels(&mut self, transparent_panels: bool) {
+        self.dirty |= self.transparent_panels != transparent_panels;
+        self.transparent_panels = transparent_panels;
+    }
+
+    pub fn preference(&self) -> ThemePreference {
+        self.preference
+    }
+
+    pub fn set_preference(&mut self, preference: ThemePreference) {
+        self.dirty |= self.preference != preference;
+        self.preference = preference;
+    }
+
+    /// Called when the operating system switches between dark and light mode.
+    pub fn set_system_theme(&mut self, system_theme: winit::window::Theme) {
+        let system_dark = system_theme == winit::window::Theme::Dark;
+        self.dirty |= self.system_dark != system_dark;
+        self.system_dark = system_dark;
+    }
+
+    pub fn is_dark(&self) -> bool {
+        match self.preference {
+            ThemePreference::Dark => true,
+            ThemePreference::Light => false,
+            ThemePreference::System => self.system_dark,
+        }
+    }
+
+    pub fn visuals(&self) -> Visuals {
+        let mut visuals = if self.is_dark() {
+            Visuals::dark()
+        } else {
+            Visuals::light()
+        };
+        if self.transparent_panels {
+            visuals.panel_fill = Color32::TRANSPARENT;
+        }
+        visuals
+    }
+
+    /// Applies the visuals to `ctx` if the effective theme changed since the last call.
+    pub fn apply(&mut self, ctx: &egui::Context) {
+        if std::mem::take(&mut self.dirty) {
+            ctx.set_visuals(self.visuals());
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use std::time::Duration;
+
+/// Most fixed updates run per frame, the remaining time is dropped so a slow frame does not
+/// lead to even slower frames catching up.
+const MAX_STEPS_PER_FRAME: u32 = 8;
+
+/// Runs a simulation at a fixed rate, independent of how often frames are drawn.
+///
+/// Each frame, the elapsed time is split into steps of equal length, see
+/// [`crate::App::fixed_update`]. The time left over is exposed as [`Self::interpolation`], to
+/// draw the simulated state in between the last two updates.
+#[derive(Debug, Clone)]
+pub struct FixedTimestep {
+    step: Duration,
+    accumulator: Duration,
+    ticks: u64,
+}
+
+impl FixedTimestep {
+    /// Creates a timestep running `rate` updates per second.
+    pub fn new(rate: u32) -> Self {
+        Self {
+            step: Duration::from_secs_f64(1.0 / rate.max(1) as f64),
+            accumulator: Duration::ZERO,
+            ticks: 0,
+        }
+    }
+
+    /// Simulated time per update.
+    pub fn step(&self) -> Duration {
+        self.step
+    }
+
+    /// Number of updates run so far.
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    /// Adds the time of a frame and returns how many updates are due.
+    pub fn advance(&mut self, frame_time: Duration) -> u32 {
+        self.accumulator += frame_time;
+        let mut steps = 0;
+        while self.accumulator >= self.step {
+            self.accumulator -= self.step;
+            steps += 1;
+            if steps == MAX_STEPS_PER_FRAME {
+                self.accumulator = self.accumulator.min(self.step / 2);
+                break;
+            }
+        }
+        self.ticks += u64::from(steps);
+        steps
+    }
+
+    /// Progress from the last towards the next update, between `0.0` and `1.0`.
+    pub fn interpolation(&self) -> f32 {
+        (self.accumulator.as_secs_f64() / self.step.as_secs_f64()) as f32
+    }
+}
@@ -0,0 +1,84 @@
+use egui::{Align, Align2, CursorIcon, Id, Layout, PointerButton, Sense, ViewportCommand};
+use winit::window::ResizeDirection;
+
+use crate::Frame;
+
+/// Height of the title bar in points.
+const HEIGHT: f32 = 28.0;
+/// Width of the invisible border that can be dragged to resize the window, in points.
+const GRIP_WIDTH: f32 = 5.0;
+
+/// Draws a title bar for windows without decorations, see [`Frame::set_decorations`].
+///
+/// Dragging it moves the window and double clicking toggles maximization. Must be called before
+/// any other panel is added.
+pub fn title_bar(ctx: &egui::Context, frame: &mut Frame, title: &str) {
+    egui::TopBottomPanel::top("title bar")
+        .exact_height(HEIGHT)
+        .show(ctx, |ui| {
+            let rect = ui.max_rect();
+            let response = ui.interact(rect, Id::new("title bar"), Sense::click_and_drag());
+            let maximized = ctx.input(|i| i.viewport().maximized.unwrap_or(false));
+            if response.double_clicked() {
+                ctx.send_viewport_cmd(ViewportCommand::Maximized(!maximized));
+            } else if response.drag_started_by(PointerButton::Primary) {
+                ctx.send_viewport_cmd(ViewportCommand::StartDrag);
+            }
+
+            ui.painter().text(
+                rect.center(),
+                Align2::CENTER_CENTER,
+                title,
+                egui::TextStyle::Body.resolve(ui.style()),
+                ui.visuals().text_color(),
+            );
+
+            ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                if ui.button("🗙").on_hover_text("Close").clicked() {
+                    frame.quit();
+                }
+                let (icon, tooltip) = if maximized {
+                    ("🗗", "Restore")
+                } else {
+                    ("🗖", "Maximize")
+                };
+                if ui.button(icon).on_hover_text(tooltip).clicked() {
+                    ctx.send_viewport_cmd(ViewportCommand::Maximized(!maximized));
+                }
+                if ui.button("🗕").on_hover_text("Minimize").clicked() {
+                    ctx.send_viewport_cmd(ViewportCommand::Minimized(true));
+                }
+            });
+        });
+}
+
+/// Lets the edges of a window without decorations be dragged to resize it.
+pub fn resize_grips(ctx: &egui::Context, frame: &mut Frame) {
+    if ctx.input(|i| i.viewport().maximized.unwrap_or(false)) {
+        return;
+    }
+    let Some(pos) = ctx.input(|i| i.pointer.hover_pos()) else {
+        return;
+    };
+    let rect = ctx.screen_rect();
+    let west = pos.x - rect.left() < GRIP_WIDTH;
+    let east = rect.right() - pos.x < GRIP_WIDTH;
+    let north = pos.y - rect.top() < GRIP_WIDTH;
+    let south = rect.bottom() - pos.y < GRIP_WIDTH;
+    let (direction, cursor) = match (north, south, west, east) {
+        (true, _, true, _) => (ResizeDirection::NorthWest, CursorIcon::ResizeNorthWest),
+        (true, _, _, true) => (ResizeDirection::NorthEast, CursorIcon::ResizeNorthEast),
+        (_, true, true, _) => (ResizeDirection::SouthWest, CursorIcon::ResizeSouthWest),
+        (_, true, _, true) => (ResizeDirection::SouthEast, CursorIcon::ResizeSouthEast),
+        (true, ..) => (ResizeDirection::North, CursorIcon::ResizeNorth),
+        (_, true, ..) => (ResizeDirection::South, CursorIcon::ResizeSouth),
+        (_, _, true, _) => (ResizeDirection::West, CursorIcon::ResizeWest),
+        (_, _, _, true) => (ResizeDirection::East, CursorIcon::ResizeEast),
+        _ => return,
+    };
+
+    ctx.set_cursor_icon(cursor);
+    if ctx.input(|i| i.pointer.primary_pressed()) {
+        frame.begin_resize(direction);
+    }
+}
@@ -0,0 +1,104 @@
+//! Icon in the system tray with a context menu to show, hide and quit the app.
+//!
+//! On Linux the icon lives on its own thread running the gtk main loop, which has to be
+//! installed together with `libappindicator`.
+
+use tray_icon::{
+    menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem},
+    ClickType, Icon, TrayIcon, TrayIconBuilder, TrayIconEvent,
+};
+
+use crate::{proxy::UserEvent, EventLoopProxy};
+
+/// Interaction with the tray icon, forwarded to the event loop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TrayEvent {
+    /// The icon was clicked, which restores the window.
+    Clicked,
+    ToggleVisibility,
+    Quit,
+}
+
+pub(crate) struct Tray {
+    // Kept alive on the gtk thread on Linux
+    _icon: Option<TrayIcon>,
+}
+
+impl Tray {
+    /// Adds the icon to the system tray, with `tooltip` shown when hovering it.
+    pub(crate) fn new(proxy: EventLoopProxy, tooltip: String) -> Option<Self> {
+        #[cfg(target_os = "linux")]
+        {
+            std::thread::spawn(move || {
+                if let Err(err) = gtk::init() {
+                    log::warn!("The tray icon is not available: {err}");
+                    return;
+                }
+                // The icon is removed again when dropped, so it is kept until gtk stops
+                let _icon = create_icon(proxy, &tooltip);
+                gtk::main();
+            });
+            Some(Self { _icon: None })
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            let icon = create_icon(proxy, &tooltip)?;
+            Some(Self { _icon: Some(icon) })
+        }
+    }
+}
+
+fn create_icon(proxy: EventLoopProxy, tooltip: &str) -> Option<TrayIcon> {
+    let toggle_visibility = MenuItem::new("Show/Hide", true, None);
+    let quit = MenuItem::new("Quit", true, None);
+    let menu = Menu::new();
+    if let Err(err) =
+        menu.append_items(&[&toggle_visibility, &PredefinedMenuItem::separator(), &quit])
+    {
+        log::warn!("Failed to create the tray menu: {err}");
+        return None;
+    }
+
+    let icon = TrayIconBuilder::new()
+        .with_menu(Box::new(menu))
+        .with_tooltip(tooltip)
+        .with_icon(default_icon())
+        .build()
+        .map_err(|err| log::warn!("Failed to create the tray icon: {err}"))
+        .ok()?;
+
+    // The events arrive on another thread on some platforms, so they wake up the event loop
+    let toggle_visibility = toggle_visibility.id().clone();
+    let quit = quit.id().clone();
+    let menu_proxy = proxy.clone();
+    MenuEvent::set_event_handler(Some(move |event: MenuEvent| {
+        if event.id == toggle_visibility {
+            menu_proxy.send_event(UserEvent::Tray(TrayEvent::ToggleVisibility));
+        } else if event.id == quit {
+            menu_proxy.send_event(UserEvent::Tray(TrayEvent::Quit));
+        }
+    }));
+    TrayIconEvent::set_event_handler(Some(move |event: TrayIconEvent| {
+        if event.click_type == ClickType::Left {
+            proxy.send_event(UserEvent::Tray(TrayEvent::Clicked));
+        }
+    }));
+
+    Some(icon)
+}
+
+/// A filled circle, used until the app provides an icon of its own.
+fn default_icon() -> Icon {
+    const SIZE: u32 = 32;
+    let center = (SIZE as f32 - 1.0) / 2.0;
+    let rgba = (0..SIZE * SIZE)
+        .flat_map(|i| {
+            let (x, y) = ((i % SIZE) as f32, (i / SIZE) as f32);
+            let distance = ((x - center).powi(2) + (y - center).powi(2)).sqrt();
+            let alpha = (center - distance).clamp(0.0, 1.0);
+            [90, 170, 255, (alpha * 255.0) as u8]
+        })
+        .collect();
+    Icon::from_rgba(rgba, SIZE, SIZE).expect("The default icon has a valid size")
+}
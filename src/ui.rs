@@ -0,0 +1,589 @@
+use std::sync::Arc;
+
+use egui::{mutex::RwLock, Context, FullOutput, ViewportId, ViewportInfo};
+use egui_wgpu::renderer::ScreenDescriptor;
+use wgpu::{Color, LoadOp, StoreOp};
+use winit::{dpi::PhysicalPosition, event::WindowEvent, window::Window};
+
+use crate::{
+    diagnostics::{GpuMark, GpuTimer},
+    gestures::TouchGestures,
+    gpu::GpuContext,
+    gpu_memory::GpuMemory,
+    graphics::Graphics,
+    textures::{free_textures, TextureRegistry, TextureShadow},
+    theme::ThemePreference,
+    uploads::Uploads,
+};
+
+/// Settings applied to the egui context before the first frame.
+#[derive(Debug, Clone, Default)]
+pub struct UiConfig {
+    pub theme: ThemePreference,
+    /// Fonts replacing egui's defaults, see [`crate::fonts::FontLoader`]. The icon font of
+    /// [`crate::icons`] is added to them.
+    pub fonts: Option<egui::FontDefinitions>,
+    pub touch_gestures: TouchGestures,
+    /// Zoom factor applied at startup instead of the persisted one.
+    pub zoom_factor: Option<f32>,
+    /// Size in bytes of the textures of images loaded by URI, `None` uses
+    /// [`crate::images::DEFAULT_CACHE_SIZE`].
+    #[cfg(feature = "image-loaders")]
+    pub image_cache_size: Option<usize>,
+}
+
+/// Owns the egui context together with its winit input state and wgpu renderer.
+pub struct EguiState {
+    pub context: Context,
+    winit_state: egui_winit::State,
+    renderer: Arc<RwLock<egui_wgpu::Renderer>>,
+    screen_descriptor: ScreenDescriptor,
+    viewport_info: ViewportInfo,
+    ime: ImeState,
+    texture_shadow: TextureShadow,
+    memory: GpuMemory,
+    textures: TextureRegistry,
+    /// Multiplies the alpha of everything egui paints in the root viewport.
+    opacity: f32,
+    #[cfg(feature = "image-loaders")]
+    image_cache: Arc<crate::images::BoundedTextureLoader>,
+    #[cfg(all(feature = "threaded-tessellation", not(target_arch = "wasm32")))]
+    tessellation: crate::tessellation::TessellationPipeline,
+    /// Frame handed out by the pipeline for the next [`Self::render`].
+    #[cfg(all(feature = "threaded-tessellation", not(target_arch = "wasm32")))]
+    prepared: Option<crate::tessellation::PreparedFrame>,
+}
+
+impl EguiState {
+    pub fn new(graphics: &Graphics, window: &Window, ui_config: &UiConfig) -> Self {
+        let context = Context::default();
+        let fonts = ui_config.fonts.clone().unwrap_or_default();
+        #[cfg(feature = "icons")]
+        let fonts = {
+            let mut fonts = fonts;
+            crate::icons::add_to_fonts(&mut fonts);
+            fonts
+        };
+        context.set_fonts(fonts);
+        // Zooming goes through the remappable shortcuts instead
+        context.options_mut(|options| options.zoom_with_keyboard = false);
+        // Deferred viewports are spawned as native windows by the `ViewportManager`
+        context.set_embed_viewports(cfg!(target_arch = "wasm32"));
+        #[cfg(feature = "image-loaders")]
+        let image_cache = crate::images::install(
+            &context,
+            ui_config
+                .image_cache_size
+                .unwrap_or(crate::images::DEFAULT_CACHE_SIZE),
+        );
+
+        let winit_state = egui_winit::State::new(
+            context.viewport_id(),
+            window,
+            Some(window.scale_factor() as f32),
+            Some(graphics.max_texture_side()),
+        );
+
+        let renderer = egui_wgpu::Renderer::new(
+            &graphics.device,
+            graphics.format,
+            None,
+            graphics.msaa_samples,
+        );
+
+        let size = crate::graphics::surface_size(window);
+        let screen_descriptor = ScreenDescriptor {
+            pixels_per_point: window.scale_factor() as f32,
+            size_in_pixels: [size.width, size.height],
+        };
+
+        let renderer = Arc::new(RwLock::new(renderer));
+        let memory = GpuMemory::default();
+        let textures =
+            TextureRegistry::new(graphics.device.clone(), renderer.clone(), memory.clone());
+
+        Self {
+            context,
+            winit_state,
+            renderer,
+            screen_descriptor,
+            viewport_info: ViewportInfo::default(),
+            ime: ImeState::default(),
+            texture_shadow: TextureShadow::default(),
+            memory,
+            textures,
+            opacity: 1.0,
+            #[cfg(feature = "image-loaders")]
+            image_cache,
+            #[cfg(all(feature = "threaded-tessellation", not(target_arch = "wasm32")))]
+            tessellation: Default::default(),
+            #[cfg(all(feature = "threaded-tessellation", not(target_arch = "wasm32")))]
+            prepared: None,
+        }
+    }
+
+    /// Replaces the renderer with one for the recreated device of `graphics` and uploads the
+    /// textures managed by egui again. Registered native textures keep their ids, but are lost
+    /// until the app updates them, see [`TextureRegistry`].
+    pub fn recreate_renderer(&mut self, graphics: &Graphics) {
+        let mut renderer = egui_wgpu::Renderer::new(
+            &graphics.device,
+            graphics.format,
+            None,
+            graphics.msaa_samples,
+        );
+        for (id, delta) in self.texture_shadow.textures() {
+            renderer.update_texture(&graphics.device, &graphics.queue, id, delta);
+        }
+        // Shared with the texture registry and GPU context handed out before
+        *self.renderer.write() = renderer;
+        self.textures.device_recreated(graphics.device.clone());
+    }
+
+    /// Gives the app access to the wgpu objects used for rendering.
+    pub fn gpu_context(&self, graphics: &Graphics) -> GpuContext {
+        GpuContext {
+            adapter: graphics.adapter.clone(),
+            device: graphics.device.clone(),
+            queue: graphics.queue.clone(),
+            renderer: self.renderer.clone(),
+            surface_config: graphics.config.clone(),
+            format: graphics.format,
+            msaa_samples: graphics.msaa_samples,
+        }
+    }
+
+    /// Gives the app access to the renderer to display its own textures.
+    pub fn texture_registry(&self) -> TextureRegistry {
+        self.textures.clone()
+    }
+
+    pub fn on_window_event(&mut self, event: &WindowEvent) -> egui_winit::EventResponse {
+        self.winit_state.on_window_event(&self.context, event)
+    }
+
+    /// Exposes the widget tree to screen readers. Has to be called before the window is shown.
+    #[cfg(all(feature = "accesskit", not(target_arch = "wasm32")))]
+    pub(crate) fn init_accesskit(
+        &mut self,
+        window: &Window,
+        proxy: winit::event_loop::EventLoopProxy<crate::proxy::UserEvent>,
+    ) {
+        let context = self.context.clone();
+        self.winit_state.init_accesskit(window, proxy, move || {
+            context.enable_accesskit();
+            context.accesskit_placeholder_tree_update()
+        });
+    }
+
+    #[cfg(all(feature = "accesskit", not(target_arch = "wasm32")))]
+    pub(crate) fn on_accesskit_action_request(&mut self, request: egui::accesskit::ActionRequest) {
+        self.winit_state.on_accesskit_action_request(request);
+    }
+
+    /// Adds input events which did not come from winit to the next frame.
+    pub fn push_events(&mut self, events: impl IntoIterator<Item = egui::Event>) {
+        self.winit_state.egui_input_mut().events.extend(events);
+    }
+
+    /// Lets text fields show the soft keyboard while they have the focus.
+    #[cfg(target_os = "android")]
+    pub(crate) fn set_android_app(&mut self, android_app: Option<crate::AndroidApp>) {
+        self.ime.android_app = android_app;
+    }
+
+    /// Fades the UI of the root viewport, `0.0` being invisible.
+    pub fn set_opacity(&mut self, opacity: f32) {
+        self.opacity = opacity.clamp(0.0, 1.0);
+    }
+
+    pub fn resize(&mut self, width: u32, height: u32, window: &Window) {
+        self.screen_descriptor.size_in_pixels = [width, height];
+        self.screen_descriptor.pixels_per_point =
+            egui_winit::pixels_per_point(&self.context, window);
+    }
+
+    /// Runs one egui frame of the root viewport, calling `run_ui` to build the UI.
+    pub fn run(&mut self, window: &Window, run_ui: impl FnOnce(&Context)) -> FullOutput {
+        let raw_input = take_viewport_input(
+            &self.context,
+            &mut self.winit_state,
+            &mut self.viewport_info,
+            window,
+        );
+        self.context.begin_frame(raw_input);
+        {
+            profile_scope!("ui");
+            run_ui(&self.context);
+        }
+        let mut output = self.context.end_frame();
+        #[cfg(feature = "image-loaders")]
+        self.image_cache.end_frame(&self.context);
+        // Changes with the zoom factor, not only with the scale factor of the window
+        self.screen_descriptor.pixels_per_point = output.pixels_per_point;
+        handle_platform_output(
+            &self.context,
+            &mut self.winit_state,
+            &mut self.ime,
+            window,
+            &mut output,
+        );
+
+        if let Some(viewport_output) = output.viewport_output.get_mut(&ViewportId::ROOT) {
+            egui_winit::process_viewport_commands(
+                &self.context,
+                &mut self.viewport_info,
+                std::mem::take(&mut viewport_output.commands),
+                window,
+                true,
+                &mut false,
+            );
+        }
+
+        output
+    }
+
+    /// Hands the shapes of `output` to the tessellation pipeline if the `threaded-tessellation`
+    /// feature is enabled, for `render` to paint the previous frame in the meantime.
+    /// `continuous` is whether egui wants the next frame right away, otherwise the pipeline is
+    /// drained.
+    pub fn begin_tessellation(&mut self, output: &mut FullOutput, continuous: bool) {
+        #[cfg(all(feature = "threaded-tessellation", not(target_arch = "wasm32")))]
+        {
+            self.prepared = Some(self.tessellation.advance(&self.context, output, continuous));
+        }
+        #[cfg(not(all(feature = "threaded-tessellation", not(target_arch = "wasm32"))))]
+        let _ = (output, continuous);
+    }
+
+    /// The meshes and texture updates handed out by [`Self::begin_tessellation`].
+    #[cfg(all(feature = "threaded-tessellation", not(target_arch = "wasm32")))]
+    fn tessellated(&mut self) -> Option<(Vec<egui::ClippedPrimitive>, egui::TexturesDelta)> {
+        self.prepared
+            .take()
+            .map(|frame| (frame.paint_jobs, frame.textures_delta))
+    }
+
+    #[cfg(not(all(feature = "threaded-tessellation", not(target_arch = "wasm32"))))]
+    fn tessellated(&mut self) -> Option<(Vec<egui::ClippedPrimitive>, egui::TexturesDelta)> {
+        None
+    }
+
+    /// Tessellates the egui output, or takes the frame [`Self::begin_tessellation`] handed out
+    /// instead, and records the egui render pass into `encoder`.
+    ///
+    /// With MSAA enabled, `view` is the multisampled target which gets resolved into
+    /// `resolve_target`. The end of the uploads and of the render pass are marked on `gpu_timer`.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn render(
+        &mut self,
+        graphics: &Graphics,
+        uploads: &mut Uploads,
+        encoder: &mut wgpu::CommandEncoder,
+        view: &wgpu::TextureView,
+        resolve_target: Option<&wgpu::TextureView>,
+        load: LoadOp<Color>,
+        mut output: FullOutput,
+        gpu_timer: Option<&mut GpuTimer>,
+    ) -> PaintStats {
+        let (paint_jobs, textures_delta) = self.tessellated().unwrap_or_else(|| {
+            let paint_jobs = tessellate(
+                &self.context,
+                std::mem::take(&mut output.shapes),
+                output.pixels_per_point,
+            );
+            (paint_jobs, std::mem::take(&mut output.textures_delta))
+        });
+        paint(
+            &mut self.renderer.write(),
+            &mut self.texture_shadow,
+            &self.memory,
+            graphics,
+            uploads,
+            encoder,
+            view,
+            resolve_target,
+            load,
+            &self.screen_descriptor,
+            self.opacity,
+            paint_jobs,
+            textures_delta,
+            gpu_timer,
+        )
+    }
+
+    /// Same as [`Self::render`], but for a viewport other than the root.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn render_viewport(
+        &mut self,
+        graphics: &Graphics,
+        uploads: &mut Uploads,
+        encoder: &mut wgpu::CommandEncoder,
+        view: &wgpu::TextureView,
+        resolve_target: Option<&wgpu::TextureView>,
+        screen_descriptor: &ScreenDescriptor,
+        output: FullOutput,
+    ) {
+        let paint_jobs = tessellate(&self.context, output.shapes, output.pixels_per_point);
+        paint(
+            &mut self.renderer.write(),
+            &mut self.texture_shadow,
+            &self.memory,
+            graphics,
+            uploads,
+            encoder,
+            view,
+            resolve_target,
+            LoadOp::Clear(Color::TRANSPARENT),
+            screen_descriptor,
+            1.0,
+            paint_jobs,
+            output.textures_delta,
+            None,
+        );
+    }
+}
+
+/// Enables the IME while egui edits text and keeps its candidate window at the text cursor.
+#[derive(Debug, Default)]
+pub(crate) struct ImeState {
+    allowed: bool,
+    position: Option<PhysicalPosition<f32>>,
+    /// Shows the soft keyboard, which winit 0.28 does not do on Android.
+    #[cfg(target_os = "android")]
+    android_app: Option<crate::AndroidApp>,
+}
+
+impl ImeState {
+    fn update(
+        &mut self,
+        window: &Window,
+        text_cursor_pos: Option<egui::Pos2>,
+        pixels_per_point: f32,
+    ) {
+        let allowed = text_cursor_pos.is_some();
+        if allowed != self.allowed {
+            self.allowed = allowed;
+            window.set_ime_allowed(allowed);
+            #[cfg(target_os = "android")]
+            if let Some(android_app) = &self.android_app {
+                if allowed {
+                    android_app.show_soft_input(true);
+                } else {
+                    android_app.hide_soft_input(false);
+                }
+            }
+        }
+
+        // egui points only match logical pixels without zoom, so convert to physical pixels.
+        // winit 0.28 has no cursor area yet, egui reports the bottom left of the cursor so the
+        // candidate window opens below the line
+        let position = text_cursor_pos
+            .map(|pos| PhysicalPosition::new(pos.x * pixels_per_point, pos.y * pixels_per_point));
+        if position != self.position {
+            self.position = position;
+            if let Some(position) = position {
+                window.set_ime_position(position);
+            }
+        }
+    }
+}
+
+/// Applies the platform output of a frame, handling the IME ourselves instead of egui-winit.
+pub(crate) fn handle_platform_output(
+    context: &Context,
+    winit_state: &mut egui_winit::State,
+    ime: &mut ImeState,
+    window: &Window,
+    output: &mut FullOutput,
+) {
+    let mut platform_output = std::mem::take(&mut output.platform_output);
+    ime.update(
+        window,
+        platform_output.text_cursor_pos.take(),
+        output.pixels_per_point,
+    );
+    winit_state.handle_platform_output(window, context, platform_output);
+}
+
+/// Collects the accumulated input of a viewport, including its up to date [`ViewportInfo`].
+pub(crate) fn take_viewport_input(
+    context: &Context,
+    winit_state: &mut egui_winit::State,
+    viewport_info: &mut ViewportInfo,
+    window: &Window,
+) -> egui::RawInput {
+    profile_scope!("input");
+    egui_winit::update_viewport_info(viewport_info, context, window);
+    let mut raw_input = winit_state.take_egui_input(window);
+    // Keeps panels and windows clear of the notch and home indicator
+    #[cfg(target_os = "ios")]
+    if let Some(safe_area) = ios_safe_area(context, window) {
+        raw_input.screen_rect = Some(safe_area);
+    }
+    raw_input
+        .viewports
+        .insert(raw_input.viewport_id, viewport_info.clone());
+    viewport_info.events.clear();
+    raw_input
+}
+
+/// The part of the screen not covered by the notch, rounded corners or home indicator, in points.
+///
+/// winit 0.28 reports the safe area as the inner position and size of the window on iOS.
+#[cfg(target_os = "ios")]
+fn ios_safe_area(context: &Context, window: &Window) -> Option<egui::Rect> {
+    let pixels_per_point = egui_winit::pixels_per_point(context, window);
+    let outer = window.outer_position().ok()?;
+    let inner = window.inner_position().ok()?;
+    let size = window.inner_size();
+    let min = egui::pos2((inner.x - outer.x) as f32, (inner.y - outer.y) as f32);
+    let size = egui::vec2(size.width as f32, size.height as f32);
+    Some(egui::Rect::from_min_size(
+        min / pixels_per_point,
+        size / pixels_per_point,
+    ))
+}
+
+/// What rendering the egui output of a frame took.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PaintStats {
+    pub draw_calls: usize,
+}
+
+fn tessellate(
+    context: &Context,
+    shapes: Vec<egui::epaint::ClippedShape>,
+    pixels_per_point: f32,
+) -> Vec<egui::ClippedPrimitive> {
+    profile_scope!("tessellate");
+    context.tessellate(shapes, pixels_per_point)
+}
+
+/// Uploads `textures_delta` and draws `paint_jobs`.
+#[allow(clippy::too_many_arguments)]
+fn paint(
+    renderer: &mut egui_wgpu::Renderer,
+    texture_shadow: &mut TextureShadow,
+    memory: &GpuMemory,
+    graphics: &Graphics,
+    uploads: &mut Uploads,
+    encoder: &mut wgpu::CommandEncoder,
+    view: &wgpu::TextureView,
+    resolve_target: Option<&wgpu::TextureView>,
+    load: LoadOp<Color>,
+    screen_descriptor: &ScreenDescriptor,
+    opacity: f32,
+    mut paint_jobs: Vec<egui::ClippedPrimitive>,
+    tdelta: egui::TexturesDelta,
+    mut gpu_timer: Option<&mut GpuTimer>,
+) -> PaintStats {
+    if opacity < 1.0 {
+        // Vertex colors are premultiplied, so all channels fade
+        for job in &mut paint_jobs {
+            if let egui::epaint::Primitive::Mesh(mesh) = &mut job.primitive {
+                for vertex in &mut mesh.vertices {
+                    vertex.color = vertex.color.gamma_multiply(opacity);
+                }
+            }
+        }
+    }
+    memory.record_frame(&paint_jobs);
+
+    {
+        profile_scope!("upload_buffers");
+        for (t_id, tdelta) in tdelta.set {
+            update_texture(renderer, graphics, uploads, encoder, t_id, &tdelta);
+            texture_shadow.update(t_id, &tdelta);
+            memory.set_texture(t_id, &tdelta);
+        }
+        for t_id in &tdelta.free {
+            texture_shadow.free(*t_id);
+        }
+
+        let callback_buffers = renderer.update_buffers(
+            &graphics.device,
+            &graphics.queue,
+            encoder,
+            &paint_jobs,
+            screen_descriptor,
+        );
+        // Command buffers of paint callbacks, which run before the frame submitted later
+        if !callback_buffers.is_empty() {
+            graphics.queue.submit(callback_buffers);
+        }
+    }
+    if let Some(gpu_timer) = gpu_timer.as_deref_mut() {
+        gpu_timer.mark(encoder, GpuMark::UploadDone);
+    }
+
+    profile_scope!("render_pass");
+    let mut egui_render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: None,
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view,
+            ops: wgpu::Operations {
+                load,
+                // The multisampled target is not needed anymore once it has been resolved
+                store: if resolve_target.is_some() {
+                    StoreOp::Discard
+                } else {
+                    StoreOp::Store
+                },
+            },
+            resolve_target,
+        })],
+        depth_stencil_attachment: None,
+        occlusion_query_set: None,
+        timestamp_writes: None,
+    });
+
+    renderer.render(&mut egui_render_pass, &paint_jobs, screen_descriptor);
+    drop(egui_render_pass);
+    if let Some(gpu_timer) = gpu_timer {
+        gpu_timer.mark(encoder, GpuMark::EguiDone);
+    }
+
+    free_textures(renderer, memory, &tdelta.free);
+    PaintStats {
+        draw_calls: paint_jobs.len(),
+    }
+}
+
+/// Applies a texture update of egui. Partial updates of existing textures are staged through
+/// `uploads`, new textures are allocated by the renderer, which also writes their content.
+fn update_texture(
+    renderer: &mut egui_wgpu::Renderer,
+    graphics: &Graphics,
+    uploads: &mut Uploads,
+    encoder: &mut wgpu::CommandEncoder,
+    id: egui::TextureId,
+    delta: &egui::epaint::ImageDelta,
+) {
+    let texture = renderer
+        .texture(&id)
+        .and_then(|(texture, _)| texture.as_ref());
+    let (Some(pos), Some(texture)) = (delta.pos, texture) else {
+        renderer.update_texture(&graphics.device, &graphics.queue, id, delta);
+        return;
+    };
+    let pixels: Vec<u8> = match &delta.image {
+        egui::ImageData::Color(image) => image.as_raw().to_vec(),
+        egui::ImageData::Font(image) => image
+            .srgba_pixels(None)
+            .flat_map(|color| color.to_array())
+            .collect(),
+    };
+    uploads.write_texture(
+        &graphics.device,
+        encoder,
+        texture,
+        wgpu::Origin3d {
+            x: pos[0] as u32,
+            y: pos[1] as u32,
+            z: 0,
+        },
+        [delta.image.width() as u32, delta.image.height() as u32],
+        &pixels,
+    );
+}
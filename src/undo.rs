@@ -0,0 +1,235 @@
+//! Undo and redo of edits made to a document, wired to [`Action::Undo`] and [`Action::Redo`]
+//! (Ctrl+Z and Ctrl+Y by default).
+//!
+//! Edits are described by a [`Command`] which knows how to apply and revert itself. Commands
+//! pushed in quick succession are merged, so dragging a slider is undone in one step.
+
+use std::time::Duration;
+
+use web_time::Instant;
+
+use crate::{shortcuts::Action, Frame};
+
+/// Commands pushed within this time after the previous one are merged if possible.
+const DEFAULT_MERGE_INTERVAL: Duration = Duration::from_millis(500);
+/// Steps kept for undoing by default.
+const DEFAULT_LIMIT: usize = 100;
+
+/// A reversible edit of a [`Self::Target`].
+pub trait Command {
+    type Target;
+
+    fn apply(&mut self, target: &mut Self::Target);
+
+    fn undo(&mut self, target: &mut Self::Target);
+
+    /// Folds `next`, which was pushed right after this command, into it. Returns `false` if the
+    /// commands have to be undone separately, e.g. because they edit different fields.
+    fn merge(&mut self, next: &Self) -> bool {
+        let _ = next;
+        false
+    }
+}
+
+/// Commands undone and redone together.
+#[derive(Debug)]
+struct Step<C> {
+    commands: Vec<C>,
+    /// When the last command was added, for merging.
+    time: Instant,
+    /// Whether more commands may be merged into this step.
+    open: bool,
+}
+
+/// The undo and redo history of a document, see [`crate::undo`].
+#[derive(Debug)]
+pub struct CommandStack<C> {
+    undo: Vec<Step<C>>,
+    redo: Vec<Step<C>>,
+    /// Nesting depth of [`Self::begin_group`].
+    group_depth: usize,
+    merge_interval: Duration,
+    limit: usize,
+}
+
+impl<C> Default for CommandStack<C> {
+    fn default() -> Self {
+        Self {
+            undo: Vec::new(),
+            redo: Vec::new(),
+            group_depth: 0,
+            merge_interval: DEFAULT_MERGE_INTERVAL,
+            limit: DEFAULT_LIMIT,
+        }
+    }
+}
+
+impl<C: Command> CommandStack<C> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Commands pushed within `interval` after the previous one are merged, `Duration::ZERO`
+    /// never merges.
+    pub fn with_merge_interval(mut self, interval: Duration) -> Self {
+        self.merge_interval = interval;
+        self
+    }
+
+    /// Drops the oldest steps beyond `limit`.
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = limit.max(1);
+        self
+    }
+
+    /// Applies `command` to `target` and records it.
+    pub fn execute(&mut self, target: &mut C::Target, mut command: C) {
+        command.apply(target);
+        self.push(command);
+    }
+
+    /// Records `command` whose change was already made, e.g. by a widget editing the target
+    /// directly. Clears the redo history.
+    pub fn push(&mut self, command: C) {
+        self.redo.clear();
+        let now = Instant::now();
+        if let Some(step) = self.undo.last_mut().filter(|step| step.open) {
+            let recent = now.duration_since(step.time) < self.merge_interval;
+            let merged = recent
+                && step
+                    .commands
+                    .last_mut()
+                    .is_some_and(|last| last.merge(&command));
+            if self.group_depth > 0 || merged {
+                if !merged {
+                    step.commands.push(command);
+                }
+                step.time = now;
+                return;
+            }
+            step.open = false;
+        }
+        self.undo.push(Step {
+            commands: vec![command],
+            time: now,
+            open: true,
+        });
+        if self.undo.len() > self.limit {
+            self.undo.remove(0);
+        }
+    }
+
+    /// Starts a group, all commands pushed until the matching [`Self::end_group`] are undone in
+    /// one step. Groups can be nested.
+    pub fn begin_group(&mut self) {
+        if self.group_depth == 0 {
+            self.seal();
+            // An empty step which the commands of the group go into
+            self.undo.push(Step {
+                commands: Vec::new(),
+                time: Instant::now(),
+                open: true,
+            });
+        }
+        self.group_depth += 1;
+    }
+
+    pub fn end_group(&mut self) {
+        self.group_depth = self.group_depth.saturating_sub(1);
+        if self.group_depth == 0 {
+            if self
+                .undo
+                .last()
+                .is_some_and(|step| step.commands.is_empty())
+            {
+                self.undo.pop();
+            }
+            self.seal();
+        }
+    }
+
+    /// Stops merging into the last step, e.g. when a drag ends.
+    pub fn seal(&mut self) {
+        if let Some(step) = self.undo.last_mut() {
+            step.open = false;
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Number of steps which can be undone.
+    pub fn undo_len(&self) -> usize {
+        self.undo.len()
+    }
+
+    /// Number of steps which can be redone.
+    pub fn redo_len(&self) -> usize {
+        self.redo.len()
+    }
+
+    /// Reverts the last step, returns `false` if there is none.
+    pub fn undo(&mut self, target: &mut C::Target) -> bool {
+        self.group_depth = 0;
+        let Some(mut step) = self.undo.pop() else {
+            return false;
+        };
+        for command in step.commands.iter_mut().rev() {
+            command.undo(target);
+        }
+        step.open = false;
+        self.redo.push(step);
+        true
+    }
+
+    /// Applies the last undone step again, returns `false` if there is none.
+    pub fn redo(&mut self, target: &mut C::Target) -> bool {
+        let Some(mut step) = self.redo.pop() else {
+            return false;
+        };
+        for command in &mut step.commands {
+            command.apply(target);
+        }
+        self.undo.push(step);
+        true
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.group_depth = 0;
+    }
+
+    /// Undoes or redoes if the shortcut of [`Action::Undo`] or [`Action::Redo`] was pressed.
+    pub fn handle_actions(&mut self, frame: &Frame, target: &mut C::Target) {
+        if frame.action_triggered(&Action::Undo) {
+            self.undo(target);
+        }
+        if frame.action_triggered(&Action::Redo) {
+            self.redo(target);
+        }
+    }
+
+    /// Undo and redo buttons.
+    pub fn ui(&mut self, ui: &mut egui::Ui, target: &mut C::Target) {
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(self.can_undo(), egui::Button::new("⟲ Undo"))
+                .clicked()
+            {
+                self.undo(target);
+            }
+            if ui
+                .add_enabled(self.can_redo(), egui::Button::new("⟳ Redo"))
+                .clicked()
+            {
+                self.redo(target);
+            }
+        });
+    }
+}
@@ -0,0 +1,126 @@
+use std::num::NonZeroU64;
+
+use wgpu::util::StagingBelt;
+
+/// Size of the staging buffers, enough for all uniforms of a frame in one of them.
+const CHUNK_SIZE: u64 = 4096;
+
+/// Uploads the per-frame data of the renderers through a staging belt kept across frames.
+///
+/// The copies are recorded into the frame encoder, in order with the passes reading them, so the
+/// same buffer can be rewritten between passes. [`Self::finish`] has to be called before the
+/// encoder is submitted and [`Self::recall`] after it.
+///
+/// Besides the uniforms, this carries the partial texture updates of egui, e.g. new glyphs in the
+/// font atlas. egui_wgpu still writes its vertex and index buffers and newly allocated textures
+/// through the queue, since it does not expose them, so they are not counted here.
+pub(crate) struct Uploads {
+    belt: StagingBelt,
+    /// Reused as the source of texture copies, which need padded rows.
+    texture_staging: Option<wgpu::Buffer>,
+    bytes: u64,
+}
+
+impl Uploads {
+    pub fn new() -> Self {
+        Self {
+            belt: StagingBelt::new(CHUNK_SIZE),
+            texture_staging: None,
+            bytes: 0,
+        }
+    }
+
+    /// Copies `data` to `offset` in `target`, which needs [`wgpu::BufferUsages::COPY_DST`].
+    pub fn write(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::Buffer,
+        offset: wgpu::BufferAddress,
+        data: &[u8],
+    ) {
+        let Some(size) = NonZeroU64::new(data.len() as u64) else {
+            return;
+        };
+        self.belt
+            .write_buffer(encoder, target, offset, size, device)
+            .copy_from_slice(data);
+        self.bytes += size.get();
+    }
+
+    /// Copies the tightly packed RGBA8 `pixels` of a `size` region to `origin` in `texture`,
+    /// which needs [`wgpu::TextureUsages::COPY_DST`].
+    pub fn write_texture(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        texture: &wgpu::Texture,
+        origin: wgpu::Origin3d,
+        size: [u32; 2],
+        pixels: &[u8],
+    ) {
+        let [width, height] = size;
+        let row_bytes = width as usize * 4;
+        let padded_row = (width * 4).next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+        let Some(staged) = NonZeroU64::new(u64::from(padded_row) * u64::from(height)) else {
+            return;
+        };
+        debug_assert_eq!(pixels.len(), row_bytes * height as usize);
+
+        let staging = match &self.texture_staging {
+            Some(buffer) if buffer.size() >= staged.get() => buffer,
+            _ => self
+                .texture_staging
+                .insert(device.create_buffer(&wgpu::BufferDescriptor {
+                    label: Some("texture staging"),
+                    size: staged.get().next_power_of_two(),
+                    usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::COPY_SRC,
+                    mapped_at_creation: false,
+                })),
+        };
+        {
+            let mut view = self.belt.write_buffer(encoder, staging, 0, staged, device);
+            for (row, pixels) in pixels.chunks_exact(row_bytes).enumerate() {
+                let start = row * padded_row as usize;
+                view[start..start + row_bytes].copy_from_slice(pixels);
+            }
+        }
+        encoder.copy_buffer_to_texture(
+            wgpu::ImageCopyBuffer {
+                buffer: staging,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_row),
+                    rows_per_image: Some(height),
+                },
+            },
+            wgpu::ImageCopyTexture {
+                texture,
+                mip_level: 0,
+                origin,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+        self.bytes += staged.get();
+    }
+
+    /// Closes the staging buffers written since the last call.
+    pub fn finish(&mut self) {
+        self.belt.finish();
+    }
+
+    /// Makes the staging buffers of submitted encoders available again once the GPU is done.
+    pub fn recall(&mut self) {
+        self.belt.recall();
+    }
+
+    /// Bytes staged since the last call, including those of other viewports.
+    pub fn take_bytes(&mut self) -> u64 {
+        std::mem::take(&mut self.bytes)
+    }
+}
@@ -0,0 +1,284 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    sync::Arc,
+};
+
+use egui::{
+    DeferredViewportUiCallback, ViewportClass, ViewportEvent, ViewportId, ViewportIdMap,
+    ViewportInfo, ViewportOutput,
+};
+use egui_wgpu::renderer::ScreenDescriptor;
+use winit::{
+    dpi::PhysicalSize,
+    event::WindowEvent,
+    event_loop::EventLoopWindowTarget,
+    window::{Window, WindowId},
+};
+
+use crate::{
+    graphics::{create_msaa_view, Graphics},
+    surface::{acquire_texture, ConfiguredSurface},
+    ui::{handle_platform_output, take_viewport_input, EguiState, ImeState},
+    uploads::Uploads,
+};
+
+/// A native window spawned for a deferred egui viewport.
+struct Viewport {
+    // Declared before `window`, so the surface is dropped first
+    surface: wgpu::Surface,
+    config: wgpu::SurfaceConfiguration,
+    msaa_view: Option<wgpu::TextureView>,
+    window: Window,
+    winit_state: egui_winit::State,
+    ime: ImeState,
+    screen_descriptor: ScreenDescriptor,
+    info: ViewportInfo,
+    viewport_ui_cb: Option<Arc<DeferredViewportUiCallback>>,
+}
+
+/// Creates and destroys native windows for the viewports requested by egui.
+///
+/// Only deferred viewports (see [`egui::Context::show_viewport_deferred`]) get their own window,
+/// immediate viewports are embedded into their parent.
+#[derive(Default)]
+pub struct ViewportManager {
+    viewports: ViewportIdMap<Viewport>,
+    window_ids: HashMap<WindowId, ViewportId>,
+}
+
+impl ViewportManager {
+    /// Returns the viewport that owns the window with the given id, if it is not the root.
+    pub fn viewport_id(&self, window_id: WindowId) -> Option<ViewportId> {
+        self.window_ids.get(&window_id).copied()
+    }
+
+    /// Spawns windows for new viewports, closes windows of viewports that are gone and applies
+    /// viewport commands and repaint requests from an egui frame.
+    pub fn handle_output<T>(
+        &mut self,
+        target: &EventLoopWindowTarget<T>,
+        graphics: &Graphics,
+        egui: &EguiState,
+        viewport_output: &ViewportIdMap<ViewportOutput>,
+    ) {
+        self.viewports
+            .retain(|id, _| viewport_output.contains_key(id));
+        self.window_ids
+            .retain(|_, id| viewport_output.contains_key(id));
+
+        for (&id, output) in viewport_output {
+            if id == ViewportId::ROOT || output.class != ViewportClass::Deferred {
+                continue;
+            }
+
+            let viewport = match self.viewports.entry(id) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let window =
+                        match egui_winit::create_window(&egui.context, target, &output.builder) {
+                            Ok(window) => window,
+                            Err(err) => {
+                                log::error!("Failed to create window for viewport {id:?}: {err}");
+                                continue;
+                            }
+                        };
+                    let viewport = match Viewport::new(id, window, graphics, &egui.context) {
+                        Ok(viewport) => viewport,
+                        Err(err) => {
+                            log::error!("Failed to create surface for viewport {id:?}: {err}");
+                            continue;
+                        }
+                    };
+                    self.window_ids.insert(viewport.window.id(), id);
+                    viewport.window.request_redraw();
+                    entry.insert(viewport)
+                }
+            };
+
+            viewport.viewport_ui_cb = output.viewport_ui_cb.clone();
+            egui_winit::process_viewport_commands(
+                &egui.context,
+                &mut viewport.info,
+                output.commands.iter().cloned(),
+                &viewport.window,
+                viewport.window.has_focus(),
+                &mut false,
+            );
+            if output.repaint_delay.is_zero() {
+                viewport.window.request_redraw();
+            }
+        }
+    }
+
+    /// Forwards a window event to the viewport owning the window.
+    pub fn on_window_event(
+        &mut self,
+        graphics: &Graphics,
+        egui: &EguiState,
+        id: ViewportId,
+        event: &WindowEvent,
+    ) {
+        let Some(viewport) = self.viewports.get_mut(&id) else {
+            return;
+        };
+
+        match event {
+            WindowEvent::Resized(new_size) => {
+                viewport.resize(graphics, *new_size);
+            }
+            WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
+                let _ = viewport.winit_state.on_window_event(&egui.context, event);
+                viewport.resize(graphics, **new_inner_size);
+            }
+            WindowEvent::CloseRequested => {
+                // It is up to the user code to stop showing the viewport
+                viewport.info.events.push(ViewportEvent::Close);
+                viewport.window.request_redraw();
+            }
+            other => {
+                let response = viewport.winit_state.on_window_event(&egui.context, other);
+                if response.repaint {
+                    viewport.window.request_redraw();
+                }
+            }
+        }
+    }
+
+    pub fn request_redraw(&self, id: ViewportId) {
+        if let Some(viewport) = self.viewports.get(&id) {
+            viewport.window.request_redraw();
+        }
+    }
+
+    /// Runs the deferred ui callback of a viewport and renders it into its window.
+    ///
+    /// Returns the viewport output of the frame, which still needs to be handled.
+    pub(crate) fn redraw(
+        &mut self,
+        graphics: &Graphics,
+        egui: &mut EguiState,
+        uploads: &mut Uploads,
+        id: ViewportId,
+    ) -> Option<ViewportIdMap<ViewportOutput>> {
+        let viewport = self.viewports.get_mut(&id)?;
+        let viewport_ui_cb = viewport.viewport_ui_cb.clone()?;
+
+        let mut surface = ConfiguredSurface {
+            surface: &viewport.surface,
+            device: &graphics.device,
+            config: &viewport.config,
+        };
+        let surface_texture = match acquire_texture(&mut surface) {
+            Ok(surface_texture) => surface_texture,
+            Err(err) => {
+                log::warn!("Failed to acquire texture for viewport {id:?}: {err:?}");
+                viewport.window.request_redraw();
+                return None;
+            }
+        };
+
+        let raw_input = take_viewport_input(
+            &egui.context,
+            &mut viewport.winit_state,
+            &mut viewport.info,
+            &viewport.window,
+        );
+        let mut output = egui.context.run(raw_input, |ctx| viewport_ui_cb(ctx));
+        handle_platform_output(
+            &egui.context,
+            &mut viewport.winit_state,
+            &mut viewport.ime,
+            &viewport.window,
+            &mut output,
+        );
+        let viewport_output = std::mem::take(&mut output.viewport_output);
+        viewport.screen_descriptor.pixels_per_point = output.pixels_per_point;
+
+        let view = graphics.create_surface_view(&surface_texture.texture);
+        let mut encoder = graphics
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+
+        let (target_view, resolve_target) = match &viewport.msaa_view {
+            Some(msaa_view) => (msaa_view, Some(&view)),
+            None => (&view, None),
+        };
+        egui.render_viewport(
+            graphics,
+            uploads,
+            &mut encoder,
+            target_view,
+            resolve_target,
+            &viewport.screen_descriptor,
+            output,
+        );
+
+        uploads.finish();
+        graphics.queue.submit(Some(encoder.finish()));
+        uploads.recall();
+        surface_texture.present();
+
+        Some(viewport_output)
+    }
+}
+
+impl Viewport {
+    fn resize(&mut self, graphics: &Graphics, new_size: PhysicalSize<u32>) {
+        self.screen_descriptor.size_in_pixels = [new_size.width, new_size.height];
+        self.config.width = new_size.width.max(1);
+        self.config.height = new_size.height.max(1);
+        self.surface.configure(&graphics.device, &self.config);
+        self.msaa_view = create_msaa_view(
+            &graphics.device,
+            &self.config,
+            graphics.format,
+            graphics.msaa_samples,
+        );
+        self.window.request_redraw();
+    }
+
+    fn new(
+        id: ViewportId,
+        window: Window,
+        graphics: &Graphics,
+        context: &egui::Context,
+    ) -> Result<Self, wgpu::CreateSurfaceError> {
+        let size = window.inner_size();
+        let surface = unsafe { graphics.instance.create_surface(&window) }?;
+        let config = wgpu::SurfaceConfiguration {
+            width: size.width.max(1),
+            height: size.height.max(1),
+            ..graphics.config.clone()
+        };
+        surface.configure(&graphics.device, &config);
+        let msaa_view = create_msaa_view(
+            &graphics.device,
+            &config,
+            graphics.format,
+            graphics.msaa_samples,
+        );
+
+        let winit_state = egui_winit::State::new(
+            id,
+            &window,
+            Some(window.scale_factor() as f32),
+            Some(graphics.max_texture_side()),
+        );
+        let screen_descriptor = ScreenDescriptor {
+            pixels_per_point: egui_winit::pixels_per_point(context, &window),
+            size_in_pixels: [size.width, size.height],
+        };
+
+        Ok(Self {
+            surface,
+            config,
+            msaa_view,
+            window,
+            winit_state,
+            ime: ImeState::default(),
+            screen_descriptor,
+            info: ViewportInfo::default(),
+            viewport_ui_cb: None,
+        })
+    }
+}
@@ -0,0 +1,288 @@
+//! A table which only lays out the rows in view, so datasets with millions of rows stay smooth.
+
+use std::{cmp::Ordering, hash::Hash};
+
+use egui::{
+    pos2, vec2, Align, Align2, CursorIcon, Id, Layout, Rect, ScrollArea, Sense, TextStyle, Ui,
+};
+
+const MIN_COLUMN_WIDTH: f32 = 24.0;
+
+pub struct Column {
+    title: String,
+    width: f32,
+    sortable: bool,
+}
+
+impl Column {
+    /// A sortable column, `width` in points. The width can be changed by dragging the edge of the
+    /// header.
+    pub fn new(title: impl Into<String>, width: f32) -> Self {
+        Self {
+            title: title.into(),
+            width: width.max(MIN_COLUMN_WIDTH),
+            sortable: true,
+        }
+    }
+
+    pub fn sortable(mut self, sortable: bool) -> Self {
+        self.sortable = sortable;
+        self
+    }
+
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+/// Rows of `T` below a header which stays in place while scrolling vertically. Clicking the
+/// header of a column sorts by it, ascending, descending and unsorted in turn.
+///
+/// The sorted order is kept between frames and only sorted again when the sorting changes, the
+/// number of rows changes or after [`Self::invalidate`].
+///
+/// ```no_run
+/// # use egui_tests::virtual_table::{Column, VirtualTable};
+/// # fn ui(ui: &mut egui::Ui, table: &mut VirtualTable, rows: &[(u32, String)]) {
+/// table.show(
+///     ui,
+///     rows,
+///     |a, b, column| match column {
+///         0 => a.0.cmp(&b.0),
+///         _ => a.1.cmp(&b.1),
+///     },
+///     |ui, row, column| {
+///         match column {
+///             0 => ui.label(row.0.to_string()),
+///             _ => ui.label(&row.1),
+///         };
+///     },
+/// );
+/// # }
+/// ```
+pub struct VirtualTable {
+    id: Id,
+    columns: Vec<Column>,
+    row_height: Option<f32>,
+    striped: bool,
+    sort: Option<(usize, SortOrder)>,
+    /// Indices of the rows in display order.
+    order: Vec<usize>,
+    order_valid: bool,
+}
+
+impl VirtualTable {
+    pub fn new(id_source: impl Hash, columns: impl IntoIterator<Item = Column>) -> Self {
+        Self {
+            id: Id::new(id_source),
+            columns: columns.into_iter().collect(),
+            row_height: None,
+            striped: true,
+            sort: None,
+            order: Vec::new(),
+            order_valid: false,
+        }
+    }
+
+    /// Height of every row in points, the height of a button by default.
+    pub fn with_row_height(mut self, row_height: f32) -> Self {
+        self.row_height = Some(row_height);
+        self
+    }
+
+    /// Shades every other row, on by default.
+    pub fn with_striped(mut self, striped: bool) -> Self {
+        self.striped = striped;
+        self
+    }
+
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    /// The column and order the rows are sorted by, `None` shows them in their original order.
+    pub fn sort(&self) -> Option<(usize, SortOrder)> {
+        self.sort
+    }
+
+    pub fn set_sort(&mut self, sort: Option<(usize, SortOrder)>) {
+        if self.sort != sort {
+            self.sort = sort;
+            self.invalidate();
+        }
+    }
+
+    /// Sorts the rows again in the next frame, after they changed in place.
+    pub fn invalidate(&mut self) {
+        self.order_valid = false;
+    }
+
+    /// The indices of `rows` in the order they are shown. `compare` compares two rows by the
+    /// values of a column.
+    pub fn row_order<T>(
+        &mut self,
+        rows: &[T],
+        compare: impl Fn(&T, &T, usize) -> Ordering,
+    ) -> &[usize] {
+        if !self.order_valid || self.order.len() != rows.len() {
+            self.order = (0..rows.len()).collect();
+            if let Some((column, order)) = self.sort {
+                self.order.sort_by(|&a, &b| {
+                    let ordering = compare(&rows[a], &rows[b], column);
+                    match order {
+                        SortOrder::Ascending => ordering,
+                        SortOrder::Descending => ordering.reverse(),
+                    }
+                });
+            }
+            self.order_valid = true;
+        }
+        &self.order
+    }
+
+    fn toggle_sort(&mut self, column: usize) {
+        let sort = match self.sort {
+            Some((sorted, SortOrder::Ascending)) if sorted == column => {
+                Some((column, SortOrder::Descending))
+            }
+            Some((sorted, SortOrder::Descending)) if sorted == column => None,
+            _ => Some((column, SortOrder::Ascending)),
+        };
+        self.set_sort(sort);
+    }
+
+    fn total_width(&self, ui: &Ui) -> f32 {
+        let spacing = ui.spacing().item_spacing.x;
+        self.columns
+            .iter()
+            .map(|column| column.width + spacing)
+            .sum::<f32>()
+    }
+
+    /// Shows the header and the rows in view, `cell` adds the contents of a row in a column.
+    pub fn show<T>(
+        &mut self,
+        ui: &mut Ui,
+        rows: &[T],
+        compare: impl Fn(&T, &T, usize) -> Ordering,
+        mut cell: impl FnMut(&mut Ui, &T, usize),
+    ) {
+        let row_height = self
+            .row_height
+            .unwrap_or_else(|| ui.spacing().interact_size.y);
+        ScrollArea::horizontal()
+            .id_source(self.id.with("horizontal"))
+            .show(ui, |ui| {
+                ui.vertical(|ui| {
+                    self.header_ui(ui);
+                    ui.separator();
+
+                    let spacing = ui.spacing().item_spacing.x;
+                    let total_width = self.total_width(ui);
+                    let widths: Vec<f32> = self.columns.iter().map(|column| column.width).collect();
+                    let striped = self.striped;
+                    let id = self.id;
+                    let order = self.row_order(rows, compare);
+                    ScrollArea::vertical()
+                        .id_source(id.with("vertical"))
+                        .auto_shrink([false, true])
+                        .show_rows(ui, row_height, order.len(), |ui, range| {
+                            let start = range.start;
+                            for (position, &index) in order[range].iter().enumerate() {
+                                let (rect, _) = ui.allocate_exact_size(
+                                    vec2(total_width, row_height),
+                                    Sense::hover(),
+                                );
+                                if striped && (start + position) % 2 == 1 {
+                                    ui.painter().rect_filled(
+                                        rect,
+                                        0.0,
+                                        ui.visuals().faint_bg_color,
+                                    );
+                                }
+                                let mut x = rect.left();
+                                for (column, width) in widths.iter().enumerate() {
+                                    let cell_rect = Rect::from_min_size(
+                                        pos2(x, rect.top()),
+                                        vec2(*width, row_height),
+                                    );
+                                    let mut cell_ui = ui
+                                        .child_ui(cell_rect, Layout::left_to_right(Align::Center));
+                                    cell_ui.set_clip_rect(cell_rect.intersect(ui.clip_rect()));
+                                    cell(&mut cell_ui, &rows[index], column);
+                                    x += width + spacing;
+                                }
+                            }
+                        });
+                });
+            });
+    }
+
+    fn header_ui(&mut self, ui: &mut Ui) {
+        let height = ui.spacing().interact_size.y;
+        let spacing = ui.spacing().item_spacing.x;
+        let (rect, _) = ui.allocate_exact_size(vec2(self.total_width(ui), height), Sense::hover());
+        let font = TextStyle::Button.resolve(ui.style());
+        let mut x = rect.left();
+        let mut clicked = None;
+        for (index, column) in self.columns.iter_mut().enumerate() {
+            let cell = Rect::from_min_size(pos2(x, rect.top()), vec2(column.width, height));
+            let sense = if column.sortable {
+                Sense::click()
+            } else {
+                Sense::hover()
+            };
+            let response = ui.interact(cell, self.id.with(("header", index)), sense);
+            if response.clicked() {
+                clicked = Some(index);
+            }
+            let arrow = match self.sort {
+                Some((sorted, SortOrder::Ascending)) if sorted == index => " ⏶",
+                Some((sorted, SortOrder::Descending)) if sorted == index => " ⏷",
+                _ => "",
+            };
+            let color = if column.sortable && response.hovered() {
+                ui.visuals().strong_text_color()
+            } else {
+                ui.visuals().text_color()
+            };
+            ui.painter()
+                .with_clip_rect(cell.intersect(ui.clip_rect()))
+                .text(
+                    cell.left_center(),
+                    Align2::LEFT_CENTER,
+                    format!("{}{arrow}", column.title),
+                    font.clone(),
+                    color,
+                );
+
+            // Dragging the gap after the column resizes it
+            let handle = Rect::from_x_y_ranges(
+                cell.right() - 2.0..=cell.right() + spacing + 2.0,
+                cell.y_range(),
+            );
+            let resize = ui.interact(handle, self.id.with(("resize", index)), Sense::drag());
+            if resize.dragged() {
+                column.width = (column.width + resize.drag_delta().x).max(MIN_COLUMN_WIDTH);
+            }
+            let stroke = if resize.hovered() || resize.dragged() {
+                ui.ctx().set_cursor_icon(CursorIcon::ResizeColumn);
+                ui.visuals().widgets.hovered.fg_stroke
+            } else {
+                ui.visuals().widgets.noninteractive.bg_stroke
+            };
+            ui.painter()
+                .vline(cell.right() + spacing / 2.0, rect.y_range(), stroke);
+            x += column.width + spacing;
+        }
+        if let Some(index) = clicked {
+            self.toggle_sort(index);
+        }
+    }
+}
@@ -0,0 +1,1322 @@
+use std::time::Duration;
+
+use egui::ViewportId;
+use wgpu::{Color, LoadOp};
+use winit::{
+    dpi::LogicalSize,
+    event::{Event, StartCause, WindowEvent},
+    event_loop::{ControlFlow, EventLoop, EventLoopBuilder, EventLoopWindowTarget},
+    window::{Fullscreen, ResizeDirection, Window, WindowBuilder, WindowId, WindowLevel},
+};
+
+#[cfg(all(feature = "tray", not(target_arch = "wasm32")))]
+use crate::tray::TrayEvent;
+use crate::{
+    animations::Animated,
+    backdrop::Backdrop,
+    composite::{self, Compositor},
+    compute::ComputeStage,
+    diagnostics::{GpuMark, GpuTimer},
+    frosted_glass::FrostRenderer,
+    gpu_errors::GpuErrors,
+    graphics::{surface_size, Graphics, GraphicsConfig},
+    hdr::{HdrSettings, ToneMapper, HDR_FORMAT},
+    icon::{self, WindowIcon},
+    monitors::{MonitorPlacement, MonitorTracker},
+    notifications,
+    overlay::ClickThrough,
+    persistence::{EguiMemoryPersistence, PreferencesPersistence, WindowStatePersistence},
+    plugin::Plugins,
+    post_process::{PostProcess, PostProcessor},
+    proxy::UserEvent,
+    screenshot::{self, TextureCapture},
+    settings::SettingsWindow,
+    shortcuts::Shortcuts,
+    surface::{acquire_texture, AcquireError, LossTracker},
+    theme::Theme,
+    timestep::FixedTimestep,
+    ui::{EguiState, UiConfig},
+    uploads::Uploads,
+    viewport::ViewportManager,
+    App, EventLoopProxy, Frame, FramePacer, RenderMode, SceneFrame, UnfocusedPolicy,
+};
+
+/// Settings used when creating the main window.
+#[derive(Debug, Clone)]
+pub struct WindowConfig {
+    pub title: String,
+    pub transparent: bool,
+    /// Whether the window has a title bar and borders drawn by the OS.
+    pub decorations: bool,
+    /// Radius in points of the rounded corners cut out of a transparent window, `0.0` keeps
+    /// them square.
+    pub corner_radius: f32,
+    /// Effect drawn by the OS behind the transparent parts of the window.
+    pub backdrop: Backdrop,
+    /// macOS only: hides the title and extends the content behind the titlebar, leaving the
+    /// window buttons on top of it. See [`Frame::safe_area_insets`].
+    pub transparent_titlebar: bool,
+    pub icon: Option<WindowIcon>,
+    pub inner_size: Option<LogicalSize<f64>>,
+    /// Upper bound for the redraw rate, `None` redraws as often as egui requests.
+    pub max_fps: Option<u32>,
+    pub render_mode: RenderMode,
+    /// Redraw rate while the window is not focused.
+    pub unfocused: UnfocusedPolicy,
+    /// Seconds the UI takes to fade in after launch, `None` shows it right away.
+    pub fade_in: Option<f32>,
+    /// Color the window is cleared with before egui paints, if there is no scene renderer.
+    pub clear_color: egui::Rgba,
+    /// Rate of [`App::fixed_update`] per second. Frames are drawn continuously while set.
+    pub fixed_update_rate: Option<u32>,
+    /// Identifier of the application, state is only persisted between runs if this is set.
+    pub app_id: Option<String>,
+    /// Keeps the window on top and lets mouse input pass through it except where egui widgets
+    /// are hovered.
+    pub click_through: bool,
+    pub window_level: WindowLevel,
+    pub fullscreen: FullscreenMode,
+    /// Monitor the window is placed on at startup, instead of where the OS or the persisted
+    /// window state puts it.
+    pub monitor: Option<MonitorPlacement>,
+    /// System-wide hotkey that hides and shows the window, even while it is unfocused.
+    #[cfg(all(feature = "global-hotkey", not(target_arch = "wasm32")))]
+    pub visibility_hotkey: Option<crate::hotkeys::HotKey>,
+    /// Shows an icon in the system tray and hides the window there when it is minimized.
+    #[cfg(all(feature = "tray", not(target_arch = "wasm32")))]
+    pub tray: bool,
+    /// Lets gamepads navigate the UI, see [`crate::gamepad`].
+    #[cfg(all(feature = "gamepad", not(target_arch = "wasm32")))]
+    pub gamepad: bool,
+    /// How long the last presented frames are kept to be exported, see
+    /// [`crate::recorder::ReplayBuffer`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub replay_duration: Option<Duration>,
+    /// TOML file with settings applied at startup and whenever it changes, see
+    /// [`crate::config`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub config_path: Option<std::path::PathBuf>,
+    /// The activity passed to `android_main`, required to run on Android.
+    #[cfg(target_os = "android")]
+    pub android_app: Option<crate::AndroidApp>,
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self {
+            title: "egui".to_owned(),
+            transparent: true,
+            decorations: true,
+            corner_radius: 0.0,
+            backdrop: Backdrop::None,
+            transparent_titlebar: false,
+            icon: None,
+            inner_size: None,
+            max_fps: None,
+            render_mode: RenderMode::Reactive,
+            unfocused: UnfocusedPolicy::Unchanged,
+            fade_in: None,
+            clear_color: egui::Rgba::TRANSPARENT,
+            fixed_update_rate: None,
+            app_id: None,
+            click_through: false,
+            window_level: WindowLevel::Normal,
+            fullscreen: FullscreenMode::Windowed,
+            monitor: None,
+            #[cfg(all(feature = "global-hotkey", not(target_arch = "wasm32")))]
+            visibility_hotkey: None,
+            #[cfg(all(feature = "tray", not(target_arch = "wasm32")))]
+            tray: false,
+            #[cfg(all(feature = "gamepad", not(target_arch = "wasm32")))]
+            gamepad: false,
+            #[cfg(not(target_arch = "wasm32"))]
+            replay_duration: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            config_path: None,
+            #[cfg(target_os = "android")]
+            android_app: None,
+        }
+    }
+}
+
+impl WindowConfig {
+    fn window_builder(&self) -> WindowBuilder {
+        let mut builder = WindowBuilder::new()
+            .with_title(&self.title)
+            .with_transparent(self.transparent)
+            .with_decorations(self.decorations);
+        // Has to come after the decorations, which reset the titlebar options
+        #[cfg(target_os = "macos")]
+        if self.transparent_titlebar {
+            use winit::platform::macos::WindowBuilderExtMacOS;
+
+            builder = builder
+                .with_titlebar_transparent(true)
+                .with_title_hidden(true)
+                .with_fullsize_content_view(true);
+        }
+        if let Some(inner_size) = self.inner_size {
+            builder = builder.with_inner_size(inner_size);
+        }
+        builder.with_window_level(self.initial_window_level())
+    }
+
+    fn initial_window_level(&self) -> WindowLevel {
+        if self.click_through {
+            WindowLevel::AlwaysOnTop
+        } else {
+            self.window_level
+        }
+    }
+}
+
+/// How the main window covers its monitor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FullscreenMode {
+    #[default]
+    Windowed,
+    /// A window without decorations covering the whole monitor.
+    Borderless,
+    /// Takes exclusive control of the monitor with its best video mode. Falls back to
+    /// borderless where that is not supported.
+    Exclusive,
+}
+
+impl FullscreenMode {
+    pub(crate) fn of(window: &Window) -> Self {
+        match window.fullscreen() {
+            None => FullscreenMode::Windowed,
+            Some(Fullscreen::Borderless(_)) => FullscreenMode::Borderless,
+            Some(Fullscreen::Exclusive(_)) => FullscreenMode::Exclusive,
+        }
+    }
+
+    fn to_winit(self, window: &Window) -> Option<Fullscreen> {
+        let monitor = window.current_monitor();
+        match self {
+            FullscreenMode::Windowed => None,
+            FullscreenMode::Borderless => Some(Fullscreen::Borderless(monitor)),
+            FullscreenMode::Exclusive => {
+                // Prefer the highest resolution, then the highest refresh rate
+                let video_mode = monitor.as_ref().and_then(|monitor| {
+                    monitor.video_modes().max_by_key(|mode| {
+                        let size = mode.size();
+                        (size.width * size.height, mode.refresh_rate_millihertz())
+                    })
+                });
+                match video_mode {
+                    Some(video_mode) => Some(Fullscreen::Exclusive(video_mode)),
+                    None => {
+                        log::warn!("Exclusive fullscreen is not supported, using borderless");
+                        Some(Fullscreen::Borderless(monitor))
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A change to the main window requested through the [`Frame`] during an update.
+#[derive(Debug, Clone)]
+pub(crate) enum WindowCommand {
+    SetWindowLevel(WindowLevel),
+    SetFullscreen(FullscreenMode),
+    SetDecorations(bool),
+    SetIcon(Option<WindowIcon>),
+    Place(MonitorPlacement),
+    SetBackdrop {
+        previous: Backdrop,
+        backdrop: Backdrop,
+    },
+    BeginResize(ResizeDirection),
+    SetVisible(bool),
+}
+
+impl WindowCommand {
+    fn apply(self, window: &Window) {
+        match self {
+            WindowCommand::SetWindowLevel(level) => window.set_window_level(level),
+            WindowCommand::SetFullscreen(mode) => window.set_fullscreen(mode.to_winit(window)),
+            WindowCommand::SetDecorations(decorations) => window.set_decorations(decorations),
+            WindowCommand::Place(placement) => placement.apply(window),
+            WindowCommand::SetIcon(icon) => icon::apply(window, icon.as_ref()),
+            WindowCommand::SetBackdrop { previous, backdrop } => backdrop.apply(previous, window),
+            WindowCommand::BeginResize(direction) => {
+                if let Err(err) = window.drag_resize_window(direction) {
+                    log::warn!("Failed to resize the window: {err}");
+                }
+            }
+            WindowCommand::SetVisible(visible) => window.set_visible(visible),
+        }
+    }
+}
+
+/// Creates the main window and runs `app` until the window is closed.
+pub fn run(
+    window_config: WindowConfig,
+    mut graphics_config: GraphicsConfig,
+    ui_config: UiConfig,
+    app: impl App + 'static,
+) {
+    graphics_config.transparent = window_config.transparent;
+    #[cfg_attr(not(target_os = "android"), allow(unused_mut))]
+    let mut event_loop_builder = EventLoopBuilder::with_user_event();
+    #[cfg(target_os = "android")]
+    {
+        use winit::platform::android::EventLoopBuilderExtAndroid;
+
+        let android_app = window_config
+            .android_app
+            .clone()
+            .expect("Running on Android requires `AppBuilder::with_android_app`");
+        event_loop_builder.with_android_app(android_app);
+    }
+    #[cfg_attr(not(target_os = "android"), allow(unused_mut))]
+    let mut event_loop = event_loop_builder.build();
+    #[cfg(target_os = "android")]
+    wait_for_resume(&mut event_loop);
+
+    let window_persistence = window_config
+        .app_id
+        .as_deref()
+        .and_then(WindowStatePersistence::new);
+    let mut window_builder = window_config.window_builder();
+    if let Some(window_state) = window_persistence
+        .as_ref()
+        .and_then(|persistence| persistence.load(&event_loop))
+    {
+        window_builder = window_state.apply_to_builder(window_builder);
+    }
+    // AccessKit has to be initialized before the window is shown for the first time
+    #[cfg(all(feature = "accesskit", not(target_arch = "wasm32")))]
+    let window_builder = window_builder.with_visible(false);
+    let window = window_builder.build(&event_loop).unwrap();
+    // Before going fullscreen, which covers the monitor the window is on
+    if let Some(placement) = window_config.monitor {
+        placement.apply(&window);
+    }
+    if window_config.fullscreen != FullscreenMode::Windowed {
+        WindowCommand::SetFullscreen(window_config.fullscreen).apply(&window);
+    }
+    if window_config.icon.is_some() {
+        icon::apply(&window, window_config.icon.as_ref());
+    }
+    if window_config.backdrop != Backdrop::None {
+        window_config.backdrop.apply(Backdrop::None, &window);
+    }
+
+    // Running inside the runtime lets the app spawn tasks from the UI thread
+    #[cfg(all(feature = "tokio", not(target_arch = "wasm32")))]
+    crate::runtime::build().block_on(run_event_loop(
+        event_loop,
+        window,
+        window_config,
+        window_persistence,
+        graphics_config,
+        ui_config,
+        app,
+    ));
+
+    #[cfg(all(not(feature = "tokio"), not(target_arch = "wasm32")))]
+    pollster::block_on(run_event_loop(
+        event_loop,
+        window,
+        window_config,
+        window_persistence,
+        graphics_config,
+        ui_config,
+        app,
+    ));
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        attach_canvas(&window);
+        wasm_bindgen_futures::spawn_local(run_event_loop(
+            event_loop,
+            window,
+            window_config,
+            window_persistence,
+            graphics_config,
+            ui_config,
+            app,
+        ));
+    }
+}
+
+/// Runs the event loop until the app is resumed for the first time. Android only provides the
+/// native window the surface is created for between `Resumed` and `Suspended`.
+#[cfg(target_os = "android")]
+fn wait_for_resume(event_loop: &mut EventLoop<UserEvent>) {
+    use winit::platform::run_return::EventLoopExtRunReturn;
+
+    event_loop.run_return(|event, _, control_flow| match event {
+        Event::Resumed => control_flow.set_exit(),
+        _ => control_flow.set_wait(),
+    });
+}
+
+/// Appends the canvas winit renders into to the document body.
+#[cfg(target_arch = "wasm32")]
+fn attach_canvas(window: &Window) {
+    use winit::platform::web::WindowExtWebSys;
+
+    web_sys::window()
+        .and_then(|win| win.document())
+        .and_then(|doc| doc.body())
+        .and_then(|body| body.append_child(&window.canvas()).ok())
+        .expect("Failed to append canvas to document body");
+}
+
+async fn run_event_loop(
+    event_loop: EventLoop<UserEvent>,
+    window: Window,
+    window_config: WindowConfig,
+    window_persistence: Option<WindowStatePersistence>,
+    graphics_config: GraphicsConfig,
+    ui_config: UiConfig,
+    mut app: impl App + 'static,
+) {
+    let graphics = Graphics::new(&window, &graphics_config).await;
+
+    app.setup(&graphics.device, &graphics.queue);
+
+    #[cfg_attr(
+        not(any(
+            all(feature = "accesskit", not(target_arch = "wasm32")),
+            target_os = "android"
+        )),
+        allow(unused_mut)
+    )]
+    let mut egui = EguiState::new(&graphics, &window, &ui_config);
+    #[cfg(target_os = "android")]
+    egui.set_android_app(window_config.android_app.clone());
+    #[cfg(all(feature = "accesskit", not(target_arch = "wasm32")))]
+    {
+        egui.init_accesskit(&window, event_loop.create_proxy());
+        window.set_visible(true);
+    }
+    let memory_persistence = window_config
+        .app_id
+        .as_deref()
+        .and_then(EguiMemoryPersistence::new);
+    if let Some(persistence) = &memory_persistence {
+        persistence.load(&egui.context);
+    }
+    if let Some(zoom_factor) = ui_config.zoom_factor {
+        egui.context.set_zoom_factor(zoom_factor);
+    }
+
+    let proxy = EventLoopProxy::new(event_loop.create_proxy());
+    // Lets requests from other threads wake up the event loop. On the web the proxy can not be
+    // shared with egui, as it is not `Send`.
+    #[cfg(not(target_arch = "wasm32"))]
+    egui.context.set_request_repaint_callback({
+        let proxy = proxy.clone();
+        move |info| {
+            proxy.send_event(UserEvent::RequestRepaint {
+                viewport_id: info.viewport_id,
+                delay: info.delay,
+            });
+        }
+    });
+
+    #[cfg(all(feature = "global-hotkey", not(target_arch = "wasm32")))]
+    let global_hotkeys = window_config
+        .visibility_hotkey
+        .and_then(|hotkey| crate::hotkeys::GlobalHotkeys::new(proxy.clone(), hotkey));
+    #[cfg(all(feature = "tray", not(target_arch = "wasm32")))]
+    let tray = window_config
+        .tray
+        .then(|| crate::tray::Tray::new(proxy.clone(), window_config.title.clone()))
+        .flatten();
+    #[cfg(all(feature = "gamepad", not(target_arch = "wasm32")))]
+    if window_config.gamepad {
+        crate::gamepad::spawn(proxy.clone());
+    }
+    let mut frame = Frame {
+        pacer: {
+            let mut pacer = FramePacer::new(window_config.max_fps);
+            pacer.set_render_mode(window_config.render_mode);
+            pacer.set_unfocused_policy(window_config.unfocused);
+            pacer
+        },
+        timestep: window_config.fixed_update_rate.map(FixedTimestep::new),
+        opacity: Animated::new(if window_config.fade_in.is_some() {
+            0.0
+        } else {
+            1.0
+        }),
+        fade_request: window_config.fade_in.map(|seconds| (1.0, seconds)),
+        hide_after_fade: false,
+        clear_color: window_config.clear_color,
+        touch_gestures: ui_config.touch_gestures,
+        theme: Theme::new(ui_config.theme, window.theme())
+            .with_transparent_panels(window_config.transparent),
+        screenshot_request: None,
+        click_through: ClickThrough::new(window_config.click_through),
+        window_level: window_config.initial_window_level(),
+        fullscreen_mode: FullscreenMode::of(&window),
+        decorations: window_config.decorations,
+        corner_radius: window_config.corner_radius,
+        backdrop: window_config.backdrop,
+        monitors: MonitorTracker::new(),
+        transparent_titlebar: cfg!(target_os = "macos") && window_config.transparent_titlebar,
+        window_commands: Vec::new(),
+        present_mode: graphics.config.present_mode,
+        supported_present_modes: graphics.supported_present_modes(),
+        requested_present_mode: None,
+        settings: SettingsWindow::new(
+            window_config
+                .app_id
+                .as_deref()
+                .and_then(PreferencesPersistence::new),
+        ),
+        command_palette: Default::default(),
+        dialogs: Default::default(),
+        plugins: Plugins::builtin(),
+        diagnostics: Default::default(),
+        textures: egui.texture_registry(),
+        gpu: egui.gpu_context(&graphics),
+        gpu_errors: GpuErrors::new(&graphics.device),
+        #[cfg(not(target_arch = "wasm32"))]
+        recorder: Default::default(),
+        #[cfg(not(target_arch = "wasm32"))]
+        replay: {
+            let mut replay = crate::recorder::ReplayBuffer::default();
+            replay.set_duration(window_config.replay_duration);
+            replay
+        },
+        #[cfg(not(target_arch = "wasm32"))]
+        config_file: window_config
+            .config_path
+            .clone()
+            .map(crate::config::ConfigFile::load),
+        hdr: graphics.hdr,
+        hdr_settings: HdrSettings::default(),
+        post_process: PostProcess::default(),
+        frosted_glass: Default::default(),
+        proxy,
+        messages: Vec::new(),
+        shortcuts: Shortcuts::default(),
+        triggered_actions: Vec::new(),
+        pending_actions: Vec::new(),
+        quit_requested: false,
+        close_requested: false,
+        close_confirmation: None,
+        gpu_recreate_requested: false,
+        #[cfg(feature = "puffin")]
+        profiler: Default::default(),
+    };
+    SettingsWindow::load(&mut frame, &egui.context);
+    #[cfg(not(target_arch = "wasm32"))]
+    frame.apply_config();
+    let mut handler = EventHandler {
+        resources: GpuResources::new(&graphics),
+        window,
+        #[cfg(not(target_arch = "wasm32"))]
+        graphics_config,
+        window_persistence,
+        memory_persistence,
+        graphics: Some(graphics),
+        egui,
+        frame,
+        app,
+        viewports: ViewportManager::default(),
+        surface_losses: LossTracker::default(),
+        first_frame: true,
+        first_resize_happened: cfg!(not(target_os = "windows")),
+        suspended: false,
+        occluded: false,
+        exit_code: 0,
+        #[cfg(all(feature = "global-hotkey", not(target_arch = "wasm32")))]
+        global_hotkeys,
+        #[cfg(all(feature = "tray", not(target_arch = "wasm32")))]
+        tray,
+        #[cfg(all(
+            any(feature = "global-hotkey", feature = "tray"),
+            not(target_arch = "wasm32")
+        ))]
+        window_visible: true,
+    };
+    // Moving to `ApplicationHandler` needs winit 0.30, which egui-winit 0.24 does not support
+    event_loop.run(move |event, target, control_flow| match event {
+        Event::NewEvents(cause) => handler.new_events(cause, control_flow),
+        Event::Suspended => handler.suspended(),
+        Event::Resumed => handler.resumed(),
+        Event::WindowEvent { window_id, event } => {
+            handler.window_event(window_id, event);
+        }
+        Event::RedrawRequested(window_id) => {
+            handler.redraw_requested(target, window_id, control_flow);
+        }
+        Event::UserEvent(event) => handler.user_event(event, control_flow),
+        // The process exits right after, without dropping anything
+        Event::LoopDestroyed => handler.exiting(),
+        _ => {}
+    });
+}
+
+/// The main window and everything rendering it while the event loop runs.
+///
+/// The closure passed to winit 0.28's `EventLoop::run` only dispatches to its methods, one per
+/// event.
+struct EventHandler<A> {
+    window: Window,
+    #[cfg(not(target_arch = "wasm32"))]
+    graphics_config: GraphicsConfig,
+    window_persistence: Option<WindowStatePersistence>,
+    memory_persistence: Option<EguiMemoryPersistence>,
+    /// `None` after the device was lost, until the GPU context could be created again.
+    graphics: Option<Graphics>,
+    egui: EguiState,
+    frame: Frame,
+    app: A,
+    viewports: ViewportManager,
+    resources: GpuResources,
+    surface_losses: LossTracker,
+    first_frame: bool,
+    first_resize_happened: bool,
+    /// Nothing is rendered while suspended, the native window may be gone, e.g. on Android.
+    suspended: bool,
+    /// The window is fully hidden, e.g. covered by other windows, nothing is rendered meanwhile.
+    occluded: bool,
+    /// Exit code of the process once the event loop ends.
+    exit_code: i32,
+    #[cfg(all(feature = "global-hotkey", not(target_arch = "wasm32")))]
+    global_hotkeys: Option<crate::hotkeys::GlobalHotkeys>,
+    #[cfg(all(feature = "tray", not(target_arch = "wasm32")))]
+    tray: Option<crate::tray::Tray>,
+    #[cfg(all(
+        any(feature = "global-hotkey", feature = "tray"),
+        not(target_arch = "wasm32")
+    ))]
+    window_visible: bool,
+}
+
+impl<A: App> EventHandler<A> {
+    fn new_events(&mut self, cause: StartCause, control_flow: &mut ControlFlow) {
+        match cause {
+            StartCause::Init => control_flow.set_wait(),
+            StartCause::ResumeTimeReached { .. } => self.window.request_redraw(),
+            _ => {}
+        }
+    }
+
+    /// Mobile apps may be killed in the background without exiting, so the state is saved here.
+    fn suspended(&mut self) {
+        self.suspended = true;
+        save_state(
+            &self.window,
+            &self.egui.context,
+            &self.frame,
+            self.window_persistence.as_ref(),
+            self.memory_persistence.as_ref(),
+        );
+    }
+
+    /// Recreates the surface for the native window, which Android destroys while suspended.
+    fn resumed(&mut self) {
+        if !self.suspended {
+            return;
+        }
+        // Without a GPU context, the next redraw creates it with a new surface
+        if let Some(graphics) = &mut self.graphics {
+            if let Err(err) = graphics.recreate_surface(&self.window) {
+                log::error!("Failed to recreate the surface: {err}");
+                return;
+            }
+        }
+        self.suspended = false;
+        let size = surface_size(&self.window);
+        self.egui.resize(size.width, size.height, &self.window);
+        self.window.request_redraw();
+    }
+
+    fn window_event(&mut self, window_id: WindowId, event: WindowEvent<'_>) {
+        let Self {
+            window,
+            graphics,
+            egui,
+            frame,
+            viewports,
+            first_resize_happened,
+            occluded,
+            #[cfg(all(feature = "tray", not(target_arch = "wasm32")))]
+            tray,
+            #[cfg(all(feature = "tray", not(target_arch = "wasm32")))]
+            window_visible,
+            ..
+        } = self;
+        if window_id != window.id() {
+            if let (Some(viewport_id), Some(graphics)) =
+                (viewports.viewport_id(window_id), graphics)
+            {
+                viewports.on_window_event(graphics, egui, viewport_id, &event);
+            }
+            return;
+        }
+        match event {
+            WindowEvent::Resized(new_size) => {
+                if !*first_resize_happened {
+                    *first_resize_happened = true;
+                    return;
+                }
+                egui.resize(new_size.width, new_size.height, window);
+                if let Some(graphics) = graphics {
+                    graphics.resize(new_size.width, new_size.height);
+                }
+                // On macos the window needs to be redrawn manually after resizing
+                window.request_redraw();
+            }
+            WindowEvent::ScaleFactorChanged {
+                scale_factor,
+                new_inner_size,
+            } => {
+                let new_size = *new_inner_size;
+                // egui picks up the new scale factor from the event
+                let _ = egui.on_window_event(&WindowEvent::ScaleFactorChanged {
+                    scale_factor,
+                    new_inner_size,
+                });
+                egui.resize(new_size.width, new_size.height, window);
+                if let Some(graphics) = graphics {
+                    graphics.resize(new_size.width, new_size.height);
+                }
+                window.request_redraw();
+            }
+            WindowEvent::CloseRequested => {
+                frame.request_close();
+                window.request_redraw();
+            }
+            WindowEvent::Occluded(is_occluded) => {
+                *occluded = is_occluded;
+                // Minimizing hides the window in the tray
+                #[cfg(all(feature = "tray", not(target_arch = "wasm32")))]
+                if is_occluded && tray.is_some() && window.is_minimized() == Some(true) {
+                    *window_visible = false;
+                    window.set_visible(false);
+                }
+                if !is_occluded {
+                    window.request_redraw();
+                }
+            }
+            WindowEvent::Focused(focused) => {
+                frame.pacer.set_focused(focused);
+                let result = egui.on_window_event(&WindowEvent::Focused(focused));
+                if result.repaint {
+                    window.request_redraw();
+                }
+            }
+            WindowEvent::ThemeChanged(system_theme) => {
+                frame.theme.set_system_theme(system_theme);
+                window.request_redraw();
+            }
+            other => {
+                let result = egui.on_window_event(&other);
+                if result.repaint {
+                    window.request_redraw();
+                }
+            }
+        }
+    }
+
+    fn redraw_requested(
+        &mut self,
+        target: &EventLoopWindowTarget<UserEvent>,
+        window_id: WindowId,
+        control_flow: &mut ControlFlow,
+    ) {
+        // Sleeps until resumed, any repaint delay would keep waking the loop up
+        if self.suspended {
+            control_flow.set_wait();
+            return;
+        }
+        let Self {
+            window,
+            #[cfg(not(target_arch = "wasm32"))]
+            graphics_config,
+            window_persistence,
+            memory_persistence,
+            graphics,
+            egui,
+            frame,
+            app,
+            viewports,
+            resources,
+            surface_losses,
+            first_frame,
+            exit_code,
+            occluded,
+            ..
+        } = self;
+        if window_id != window.id() {
+            let (Some(viewport_id), Some(graphics)) = (viewports.viewport_id(window_id), graphics)
+            else {
+                return;
+            };
+            if let Some(viewport_output) =
+                viewports.redraw(graphics, egui, &mut resources.uploads, viewport_id)
+            {
+                viewports.handle_output(target, graphics, egui, &viewport_output);
+                if viewport_output
+                    .get(&ViewportId::ROOT)
+                    .is_some_and(|root| root.repaint_delay.is_zero())
+                {
+                    window.request_redraw();
+                }
+            }
+            return;
+        }
+
+        // Nothing is visible, `Resized` or `Occluded` request a redraw once it is shown again
+        if *occluded || window.is_minimized() == Some(true) {
+            control_flow.set_wait();
+            return;
+        }
+
+        frame.pacer.wait();
+        if std::mem::take(&mut frame.gpu_recreate_requested) || frame.gpu_errors.take_device_lost()
+        {
+            // A window can only have one surface at a time, so the old ones have to be gone
+            // before the new context creates its own. Deferred viewports are spawned again.
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                *viewports = ViewportManager::default();
+                *graphics = None;
+            }
+            #[cfg(target_arch = "wasm32")]
+            log::error!("The GPU device was lost, reload the page to recover");
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if graphics.is_none() {
+            match pollster::block_on(Graphics::try_new(window, graphics_config)) {
+                Ok(new_graphics) => {
+                    log::warn!("Recreated the GPU context");
+                    let graphics = graphics.insert(new_graphics);
+                    egui.recreate_renderer(graphics);
+                    frame.gpu_recreated(egui, graphics);
+                    *resources = GpuResources::new(graphics);
+                    app.setup(&graphics.device, &graphics.queue);
+                }
+                Err(err) => {
+                    log::error!("Failed to recreate the GPU context, retrying: {err}");
+                    control_flow.set_wait_timeout(Duration::from_secs(1));
+                    return;
+                }
+            }
+        }
+        let Some(graphics) = graphics else {
+            return;
+        };
+        // Fullscreen transitions do not report a resize on every platform
+        let size = surface_size(window);
+        if [size.width.max(1), size.height.max(1)]
+            != [graphics.config.width, graphics.config.height]
+        {
+            egui.resize(size.width, size.height, window);
+            graphics.resize(size.width, size.height);
+        }
+        let acquired = acquire_texture(&mut graphics.configured_surface());
+        if surface_losses.record(&acquired) {
+            log::warn!("The surface keeps getting lost, assuming the device was lost");
+            frame.gpu_recreate_requested = true;
+        }
+        let surface_texture = match acquired {
+            Ok(surface_texture) => surface_texture,
+            Err(AcquireError::SkipFrame | AcquireError::Lost) => {
+                window.request_redraw();
+                return;
+            }
+            Err(AcquireError::OutOfMemory) => {
+                log::error!("Out of memory while acquiring the surface texture, exiting");
+                *exit_code = 1;
+                control_flow.set_exit();
+                return;
+            }
+        };
+        frame.pacer.begin_frame();
+        frame.gpu.surface_config.clone_from(&graphics.config);
+        // The user may also leave fullscreen through the OS
+        frame.fullscreen_mode = FullscreenMode::of(window);
+        frame.monitors.update(window);
+        if let Some(timestep) = &mut frame.timestep {
+            for _ in 0..timestep.advance(frame.pacer.frame_time()) {
+                app.fixed_update(timestep.step());
+            }
+        }
+        #[cfg(feature = "puffin")]
+        frame.profiler.new_frame();
+        frame.gpu_errors.collect();
+
+        frame.frosted_glass.begin_frame();
+        let mut opacity = 1.0;
+        let mut output = egui.run(window, |ctx| {
+            frame.theme.apply(ctx);
+            frame.touch_gestures.apply(ctx);
+            frame.handle_shortcuts(ctx);
+            Plugins::update(frame, ctx);
+            app.update(ctx, frame);
+            let first_frame = std::mem::take(first_frame);
+            if first_frame {
+                SettingsWindow::load_shortcuts(frame);
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            frame.reload_config(ctx, first_frame);
+            Plugins::ui_after_update(frame, ctx);
+            if !frame.pending_actions.is_empty() {
+                ctx.request_repaint();
+            }
+            notifications::show(ctx);
+            frame.gpu_errors.show(ctx);
+            #[cfg(not(target_arch = "wasm32"))]
+            frame.recorder.show_indicator(ctx);
+            opacity = frame.update_opacity(ctx);
+        });
+        egui.set_opacity(opacity);
+        if !frame.messages.is_empty() {
+            log::debug!("Dropping {} unhandled messages", frame.messages.len());
+            frame.messages.clear();
+        }
+        frame.handle_close_request(&egui.context, || app.can_close());
+        if frame.quit_requested {
+            save_state(
+                window,
+                &egui.context,
+                frame,
+                window_persistence.as_ref(),
+                memory_persistence.as_ref(),
+            );
+            control_flow.set_exit();
+            return;
+        }
+        frame.click_through.update(&egui.context, window);
+        for command in frame.window_commands.drain(..) {
+            command.apply(window);
+        }
+        if let Some(present_mode) = frame.requested_present_mode.take() {
+            frame.present_mode = graphics.set_present_mode(present_mode);
+        }
+        let viewport_output = std::mem::take(&mut output.viewport_output);
+        let repaint_delay = viewport_output
+            .get(&ViewportId::ROOT)
+            .map_or(Duration::MAX, |viewport| viewport.repaint_delay);
+        viewports.handle_output(target, graphics, egui, &viewport_output);
+        egui.begin_tessellation(&mut output, repaint_delay.is_zero());
+
+        frame.gpu_errors.push_scopes(&graphics.device);
+        let surface_view = graphics.create_surface_view(&surface_texture.texture);
+        // Corners are only rounded while the window does not fill the screen
+        let corner_radius = frame.corner_radius * window.scale_factor() as f32;
+        let round_corners = corner_radius > 0.0
+            && frame.fullscreen_mode == FullscreenMode::Windowed
+            && !window.is_maximized();
+        let composite = round_corners
+            || composite::needs_alpha_conversion(graphics.config.alpha_mode, graphics.format);
+        let frame_view = if composite {
+            let compositor = resources
+                .compositor
+                .get_or_insert_with(|| Compositor::new(&graphics.device, graphics.format));
+            compositor.target_view(
+                &graphics.device,
+                graphics.format,
+                [graphics.config.width, graphics.config.height],
+            )
+        } else {
+            &surface_view
+        };
+        let (view, resolve_target) = graphics.render_target(frame_view);
+        let mut encoder = graphics
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        if let Some(gpu_timer) = &mut resources.gpu_timer {
+            gpu_timer.begin(&mut encoder);
+        }
+
+        resources.compute_stage.run(
+            app.compute_hooks(),
+            &graphics.device,
+            &graphics.queue,
+            &mut resources.uploads,
+            &mut encoder,
+            frame.frame_time(),
+            [graphics.config.width, graphics.config.height],
+        );
+        let load = match app.scene_renderer() {
+            Some(scene_renderer) => {
+                let size_in_pixels = [graphics.config.width, graphics.config.height];
+                // HDR, post processing and frosted glass need the scene in a texture of its own
+                let offscreen = resources.tone_mapper.is_some()
+                    || frame.post_process.is_active()
+                    || !frame.frosted_glass.is_empty();
+                let (scene_view, scene_format) = if offscreen {
+                    {
+                        let format = resources.post_processor.format();
+                        (
+                            resources
+                                .post_processor
+                                .scene_view(&graphics.device, size_in_pixels),
+                            format,
+                        )
+                    }
+                } else {
+                    (view, graphics.format)
+                };
+                scene_renderer.render(&mut SceneFrame {
+                    device: &graphics.device,
+                    queue: &graphics.queue,
+                    encoder: &mut encoder,
+                    view: scene_view,
+                    format: scene_format,
+                    sample_count: graphics.msaa_samples,
+                    depth_view: graphics.depth_view(),
+                    depth_format: graphics.depth_format,
+                    size_in_pixels,
+                    time: frame.time(),
+                    interpolation: frame
+                        .timestep
+                        .as_ref()
+                        .map_or(1.0, FixedTimestep::interpolation),
+                });
+                if offscreen {
+                    resources.post_processor.run(
+                        &graphics.device,
+                        &mut resources.uploads,
+                        &mut encoder,
+                        &mut frame.post_process,
+                        resources
+                            .tone_mapper
+                            .as_ref()
+                            .map(|tone_mapper| (tone_mapper, &frame.hdr_settings)),
+                        view,
+                        frame.pacer.frame_time(),
+                    );
+                }
+                if let Some(scene) = resources
+                    .post_processor
+                    .result_view()
+                    .filter(|_| offscreen && !frame.frosted_glass.is_empty())
+                {
+                    resources
+                        .frost_renderer
+                        .get_or_insert_with(|| {
+                            FrostRenderer::new(
+                                &graphics.device,
+                                resources.post_processor.format(),
+                                graphics.format,
+                                graphics.msaa_samples,
+                            )
+                        })
+                        .render(
+                            &graphics.device,
+                            &mut resources.uploads,
+                            &mut encoder,
+                            scene,
+                            view,
+                            size_in_pixels,
+                            egui.context.pixels_per_point(),
+                            &frame.frosted_glass,
+                        );
+                }
+                LoadOp::Load
+            }
+            None => {
+                let [r, g, b, a] = frame.clear_color.to_array().map(f64::from);
+                LoadOp::Clear(Color { r, g, b, a })
+            }
+        };
+
+        if let Some(gpu_timer) = &mut resources.gpu_timer {
+            gpu_timer.mark(&mut encoder, GpuMark::SceneDone);
+        }
+
+        let paint = egui.render(
+            graphics,
+            &mut resources.uploads,
+            &mut encoder,
+            view,
+            resolve_target,
+            load,
+            output,
+            resources.gpu_timer.as_mut(),
+        );
+        if frame.diagnostics.visible() {
+            frame
+                .diagnostics
+                .set_memory(frame.textures.memory().stats());
+        }
+        if composite {
+            if let Some(compositor) = &resources.compositor {
+                compositor.apply(
+                    &graphics.device,
+                    &mut resources.uploads,
+                    &mut encoder,
+                    &surface_view,
+                    if round_corners { corner_radius } else { 0.0 },
+                    graphics.config.alpha_mode,
+                    graphics.format,
+                );
+            }
+        }
+        if let Some(gpu_timer) = &mut resources.gpu_timer {
+            gpu_timer.end(&mut encoder);
+        }
+
+        let capture = frame
+            .screenshot_request
+            .take()
+            .filter(|_| {
+                let supported = graphics
+                    .config
+                    .usage
+                    .contains(wgpu::TextureUsages::COPY_SRC);
+                if !supported {
+                    log::warn!("The surface does not support screenshots");
+                }
+                supported
+            })
+            .map(|path| {
+                let capture = TextureCapture::record(
+                    &graphics.device,
+                    &mut encoder,
+                    &surface_texture.texture,
+                );
+                (capture, path)
+            });
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let recording_capture = ((frame.recorder.wants_frame() || frame.replay.wants_frame())
+            && graphics
+                .config
+                .usage
+                .contains(wgpu::TextureUsages::COPY_SRC))
+        .then(|| TextureCapture::record(&graphics.device, &mut encoder, &surface_texture.texture));
+
+        resources.uploads.finish();
+        graphics.queue.submit(Some(encoder.finish()));
+        resources.uploads.recall();
+        frame.gpu_errors.pop_scopes(&graphics.device);
+        if let Some(gpu_timer) = &mut resources.gpu_timer {
+            gpu_timer.after_submit();
+            if let Some(gpu_time) = gpu_timer.read(&graphics.device) {
+                frame.diagnostics.set_gpu_time(gpu_time);
+            }
+        }
+        if let Some((capture, path)) = capture {
+            if let Some(image) = capture.read(&graphics.device) {
+                screenshot::save_png(&image, &path);
+            }
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(image) = recording_capture.and_then(|capture| capture.read(&graphics.device)) {
+            if frame.replay.wants_frame() {
+                frame.replay.push(&image);
+            }
+            if frame.recorder.wants_frame() {
+                frame.recorder.push(image);
+            }
+        }
+        surface_texture.present();
+        frame.pacer.end_frame();
+        frame.diagnostics.record(
+            frame.pacer.frame_time(),
+            frame.pacer.work_time(),
+            paint.draw_calls,
+            resources.uploads.take_bytes(),
+        );
+
+        // Sleep until egui wants the next frame, e.g. for a running animation
+        let repaint_delay = if frame.timestep.is_some() || frame.gpu_errors.has_pending() {
+            Duration::ZERO
+        } else {
+            frame.pacer.repaint_delay(repaint_delay)
+        };
+        match frame.pacer.unfocused_delay() {
+            // Unlike `FramePacer::wait`, sleeping in the event loop still wakes up for input
+            Some(delay) => control_flow.set_wait_timeout(repaint_delay.max(delay)),
+            None if repaint_delay.is_zero() => {
+                window.request_redraw();
+                control_flow.set_wait();
+            }
+            None => control_flow.set_wait_timeout(repaint_delay),
+        }
+    }
+
+    fn user_event(&mut self, event: UserEvent, control_flow: &mut ControlFlow) {
+        let Self {
+            window,
+            #[cfg(all(
+                any(feature = "gamepad", feature = "accesskit"),
+                not(target_arch = "wasm32")
+            ))]
+            egui,
+            frame,
+            viewports,
+            #[cfg(all(feature = "global-hotkey", not(target_arch = "wasm32")))]
+            global_hotkeys,
+            #[cfg(all(
+                any(feature = "global-hotkey", feature = "tray"),
+                not(target_arch = "wasm32")
+            ))]
+            window_visible,
+            ..
+        } = self;
+        match event {
+            UserEvent::RequestRepaint { viewport_id, delay }
+                if delay.is_zero() && !frame.pacer.is_event_driven() =>
+            {
+                if viewport_id == ViewportId::ROOT {
+                    window.request_redraw();
+                } else {
+                    viewports.request_redraw(viewport_id);
+                }
+            }
+            // E.g. `request_repaint_after` from a timer on another thread, the loop redraws once
+            // the timeout is reached
+            UserEvent::RequestRepaint { delay, .. } if !frame.pacer.is_event_driven() => {
+                wake_up_after(control_flow, delay);
+            }
+
+            #[cfg(all(feature = "global-hotkey", not(target_arch = "wasm32")))]
+            UserEvent::GlobalHotKey(id)
+                if global_hotkeys
+                    .as_ref()
+                    .is_some_and(|hotkeys| hotkeys.is_toggle_visibility(id)) =>
+            {
+                *window_visible = !*window_visible;
+                window.set_visible(*window_visible);
+                if *window_visible {
+                    window.focus_window();
+                    window.request_redraw();
+                }
+            }
+
+            #[cfg(all(feature = "tray", not(target_arch = "wasm32")))]
+            UserEvent::Tray(tray_event) => match tray_event {
+                TrayEvent::Clicked | TrayEvent::ToggleVisibility => {
+                    *window_visible = tray_event == TrayEvent::Clicked || !*window_visible;
+                    window.set_visible(*window_visible);
+                    if *window_visible {
+                        window.set_minimized(false);
+                        window.focus_window();
+                        window.request_redraw();
+                    }
+                }
+                // Shown, in case the app asks to confirm
+                TrayEvent::Quit => {
+                    *window_visible = true;
+                    window.set_visible(true);
+                    frame.request_close();
+                    window.request_redraw();
+                }
+            },
+
+            // Like keyboard input, gamepads only control the focused window
+            #[cfg(all(feature = "gamepad", not(target_arch = "wasm32")))]
+            UserEvent::Gamepad(events) if window.has_focus() => {
+                egui.push_events(events);
+                window.request_redraw();
+            }
+
+            #[cfg(all(feature = "accesskit", not(target_arch = "wasm32")))]
+            UserEvent::AccessKitActionRequest(event) => {
+                egui.on_accesskit_action_request(event.request);
+                window.request_redraw();
+            }
+
+            UserEvent::Message(message) => {
+                frame.messages.push(message);
+                window.request_redraw();
+            }
+            _ => {}
+        }
+    }
+
+    fn exiting(&mut self) {
+        let Self {
+            graphics,
+            frame,
+            app,
+            ..
+        } = self;
+        Plugins::shutdown(frame);
+        if let Some(graphics) = graphics {
+            app.on_exit(&graphics.device, &graphics.queue);
+        }
+        // Recordings still being encoded would be cut off by the exit
+        #[cfg(not(target_arch = "wasm32"))]
+        frame.recorder.finish();
+        if let Some(graphics) = graphics {
+            graphics.device.poll(wgpu::Maintain::Wait);
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.exit_code != 0 {
+            std::process::exit(self.exit_code);
+        }
+    }
+}
+
+/// Makes the event loop wake up after `delay` at the latest, keeping an earlier wake-up.
+fn wake_up_after(control_flow: &mut ControlFlow, delay: Duration) {
+    let mut lowered = ControlFlow::Wait;
+    lowered.set_wait_timeout(delay);
+    match (*control_flow, lowered) {
+        (ControlFlow::Wait, _) => *control_flow = lowered,
+        (ControlFlow::WaitUntil(current), ControlFlow::WaitUntil(new)) if new < current => {
+            *control_flow = lowered;
+        }
+        _ => {}
+    }
+}
+
+/// Persists the window geometry, preferences and egui memory before exiting.
+fn save_state(
+    window: &Window,
+    ctx: &egui::Context,
+    frame: &Frame,
+    window_persistence: Option<&WindowStatePersistence>,
+    memory_persistence: Option<&EguiMemoryPersistence>,
+) {
+    SettingsWindow::save(frame, ctx);
+    if let Some(persistence) = window_persistence {
+        persistence.save(window);
+    }
+    if let Some(persistence) = memory_persistence {
+        persistence.save(ctx);
+    }
+}
+
+/// Everything the render loop creates on the device, recreated together with it.
+struct GpuResources {
+    gpu_timer: Option<GpuTimer>,
+    compute_stage: ComputeStage,
+    tone_mapper: Option<ToneMapper>,
+    post_processor: PostProcessor,
+    compositor: Option<Compositor>,
+    frost_renderer: Option<FrostRenderer>,
+    uploads: Uploads,
+}
+
+impl GpuResources {
+    fn new(graphics: &Graphics) -> Self {
+        Self {
+            gpu_timer: GpuTimer::new(&graphics.device, &graphics.queue),
+            compute_stage: ComputeStage::new(&graphics.device),
+            tone_mapper: graphics
+                .hdr
+                .then(|| ToneMapper::new(&graphics.device, graphics.format, graphics.msaa_samples)),
+            post_processor: PostProcessor::new(
+                &graphics.device,
+                if graphics.hdr {
+                    HDR_FORMAT
+                } else {
+                    graphics.format
+                },
+                graphics.format,
+                graphics.msaa_samples,
+            ),
+            compositor: None,
+            frost_renderer: None,
+            uploads: Uploads::new(),
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use egui_tests::adapter::{parse_backends, select_adapter};
+use wgpu::{AdapterInfo, Backend, Backends, DeviceType, PowerPreference};
+
+fn adapter(name: &str, device_type: DeviceType) -> AdapterInfo {
+    AdapterInfo {
+        name: name.to_owned(),
+        vendor: 0,
+        device: 0,
+        device_type,
+        driver: String::new(),
+        driver_info: String::new(),
+        backend: Backend::Vulkan,
+    }
+}
+
+#[test]
+fn parses_backend_lists() {
+    assert_eq!(parse_backends("Vulkan"), Some(Backends::VULKAN));
+    assert_eq!(
+        parse_backends("dx12, gl"),
+        Some(Backends::DX12 | Backends::GL)
+    );
+    assert_eq!(parse_backends("metal"), Some(Backends::METAL));
+    assert_eq!(parse_backends("glide"), None);
+    assert_eq!(parse_backends(""), None);
+}
+
+#[test]
+fn prefers_device_type_matching_power_preference() {
+    let adapters = [
+        adapter("integrated", DeviceType::IntegratedGpu),
+        adapter("discrete", DeviceType::DiscreteGpu),
+    ];
+    assert_eq!(
+        select_adapter(&adapters, PowerPreference::HighPerformance),
+        Some(1)
+    );
+    assert_eq!(
+        select_adapter(&adapters, PowerPreference::LowPower),
+        Some(0)
+    );
+    assert_eq!(select_adapter(&adapters, PowerPreference::None), Some(0));
+}
+
+#[test]
+fn prefers_any_hardware_adapter_over_software() {
+    let adapters = [
+        adapter("llvmpipe", DeviceType::Cpu),
+        adapter("virtual", DeviceType::VirtualGpu),
+        adapter("integrated", DeviceType::IntegratedGpu),
+    ];
+    assert_eq!(
+        select_adapter(&adapters, PowerPreference::HighPerformance),
+        Some(2)
+    );
+}
+
+#[test]
+fn needs_fallback_without_hardware_adapter() {
+    let adapters = [adapter("llvmpipe", DeviceType::Cpu)];
+    assert_eq!(select_adapter(&adapters, PowerPreference::None), None);
+    assert_eq!(select_adapter(&[], PowerPreference::None), None);
+}
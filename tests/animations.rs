@@ -0,0 +1,62 @@
+use egui_tests::animations::{Animated, Easing, Lerp};
+
+/// Runs an empty frame at `time` seconds.
+fn frame_at(ctx: &egui::Context, time: f64) {
+    let input = egui::RawInput {
+        time: Some(time),
+        ..Default::default()
+    };
+    let _ = ctx.run(input, |_| {});
+}
+
+#[test]
+fn easings_start_at_zero_and_end_at_one() {
+    for easing in [
+        Easing::Linear,
+        Easing::EaseInQuad,
+        Easing::EaseOutQuad,
+        Easing::EaseInOutQuad,
+        Easing::EaseInCubic,
+        Easing::EaseOutCubic,
+        Easing::EaseInOutCubic,
+        Easing::EaseOutBack,
+    ] {
+        assert!(easing.apply(0.0).abs() < 1e-5, "{easing:?}");
+        assert!((easing.apply(1.0) - 1.0).abs() < 1e-5, "{easing:?}");
+    }
+    assert_eq!(Easing::EaseInOutCubic.apply(0.5), 0.5);
+    assert!(Easing::EaseOutBack.apply(0.8) > 1.0);
+}
+
+#[test]
+fn interpolates_colors_per_channel() {
+    let from = egui::Color32::from_rgba_premultiplied(0, 100, 200, 255);
+    let color = from.lerp(egui::Color32::TRANSPARENT, 0.5);
+    assert_eq!(
+        color,
+        egui::Color32::from_rgba_premultiplied(0, 50, 100, 128)
+    );
+}
+
+#[test]
+fn moves_towards_the_target_over_its_duration() {
+    let ctx = egui::Context::default();
+    let mut value = Animated::new(0.0_f32)
+        .with_duration(1.0)
+        .with_easing(Easing::Linear);
+    frame_at(&ctx, 10.0);
+    assert_eq!(value.animate(&ctx, 100.0), 0.0);
+    assert!(value.is_animating(&ctx));
+
+    frame_at(&ctx, 10.25);
+    assert!((value.value(&ctx) - 25.0).abs() < 1e-3);
+
+    // Retargeting continues from the current value
+    value.set_target(&ctx, 0.0);
+    frame_at(&ctx, 10.75);
+    assert!((value.value(&ctx) - 12.5).abs() < 1e-3);
+
+    frame_at(&ctx, 12.0);
+    assert_eq!(value.value(&ctx), 0.0);
+    assert!(!value.is_animating(&ctx));
+}
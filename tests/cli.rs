@@ -0,0 +1,50 @@
+use clap::Parser;
+use egui_tests::cli::{CliArgs, Vsync};
+use log::LevelFilter;
+use wgpu::Backends;
+
+#[test]
+fn parses_all_options() {
+    let args = CliArgs::try_parse_from([
+        "app",
+        "--backend",
+        "vulkan,gl",
+        "--vsync",
+        "off",
+        "--scale",
+        "1.5",
+        "--fullscreen",
+        "--transparent",
+        "false",
+        "--monitor",
+        "1",
+        "--log-level",
+        "debug",
+    ])
+    .unwrap();
+    assert_eq!(
+        args,
+        CliArgs {
+            backend: Some(Backends::VULKAN | Backends::GL),
+            vsync: Some(Vsync::Off),
+            scale: Some(1.5),
+            fullscreen: true,
+            transparent: Some(false),
+            monitor: Some(1),
+            log_level: Some(LevelFilter::Debug),
+        }
+    );
+}
+
+#[test]
+fn keeps_builder_configuration_without_options() {
+    assert_eq!(
+        CliArgs::try_parse_from(["app"]).unwrap(),
+        CliArgs::default()
+    );
+}
+
+#[test]
+fn rejects_unknown_backend() {
+    assert!(CliArgs::try_parse_from(["app", "--backend", "glide"]).is_err());
+}
@@ -0,0 +1,21 @@
+use egui_tests::clipboard::rgba_to_color_image;
+
+#[test]
+fn keeps_small_images() {
+    let rgba = [255, 0, 0, 255].repeat(4 * 3);
+    let image = rgba_to_color_image(4, 3, &rgba, 16).unwrap();
+    assert_eq!(image.size, [4, 3]);
+    assert_eq!(image.pixels[0], egui::Color32::RED);
+}
+
+#[test]
+fn scales_down_large_images_keeping_aspect_ratio() {
+    let rgba = [0; 4].repeat(400 * 100);
+    let image = rgba_to_color_image(400, 100, &rgba, 200).unwrap();
+    assert_eq!(image.size, [200, 50]);
+}
+
+#[test]
+fn rejects_mismatched_buffer() {
+    assert!(rgba_to_color_image(4, 4, &[0; 8], 16).is_none());
+}
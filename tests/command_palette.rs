@@ -0,0 +1,48 @@
+use egui_tests::{
+    command_palette::{fuzzy_score, CommandPalette},
+    shortcuts::{Action, Shortcuts},
+};
+
+#[test]
+fn fuzzy_score_needs_characters_in_order() {
+    assert_eq!(fuzzy_score("", "Quit"), Some(0));
+    assert!(fuzzy_score("tfs", "Toggle fullscreen").is_some());
+    assert!(fuzzy_score("TOGGLE", "Toggle fullscreen").is_some());
+    assert_eq!(fuzzy_score("fst", "Toggle fullscreen"), None);
+    assert_eq!(fuzzy_score("quitx", "Quit"), None);
+}
+
+#[test]
+fn word_starts_and_consecutive_characters_score_higher() {
+    let word_start = fuzzy_score("zi", "Zoom in").unwrap();
+    let scattered = fuzzy_score("zi", "Fizzing").unwrap();
+    assert!(word_start > scattered);
+
+    let consecutive = fuzzy_score("scr", "Take screenshot").unwrap();
+    let gaps = fuzzy_score("scr", "Show recorder").unwrap();
+    assert!(consecutive > gaps);
+}
+
+#[test]
+fn recent_commands_rank_first() {
+    let mut shortcuts = Shortcuts::default();
+    let jump = Action::Custom("jump".to_owned());
+    shortcuts.register(jump.clone(), "Jump to line", None);
+
+    let mut palette = CommandPalette::default();
+    let matches = palette.matches(&shortcuts);
+    assert_eq!(matches.first(), Some(&&Action::Quit));
+    assert!(matches.contains(&&jump));
+    assert!(!matches.contains(&&Action::CommandPalette));
+
+    palette.record(&Action::ZoomReset);
+    palette.record(&jump);
+    let matches = palette.matches(&shortcuts);
+    assert_eq!(&matches[..2], [&jump, &Action::ZoomReset]);
+
+    palette.set_query("zoom");
+    let matches = palette.matches(&shortcuts);
+    assert_eq!(matches.first(), Some(&&Action::ZoomReset));
+    assert_eq!(matches.len(), 3);
+    assert_eq!(palette.recent(), ["jump", "zoom_reset"]);
+}
@@ -0,0 +1,82 @@
+use egui::{Key, KeyboardShortcut, Modifiers};
+use egui_tests::{
+    config::{AppConfig, WindowOptions},
+    shortcuts::{parse_shortcut, shortcut_to_string, Action},
+    theme::ThemePreference,
+};
+
+#[test]
+fn missing_settings_use_defaults() {
+    assert_eq!(AppConfig::from_toml("").unwrap(), AppConfig::default());
+
+    let config = AppConfig::from_toml("max_fps = 30\n[window]\ndecorations = false").unwrap();
+    assert_eq!(config.max_fps, Some(30));
+    assert_eq!(config.theme, None);
+    assert_eq!(
+        config.window,
+        WindowOptions {
+            decorations: Some(false),
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn round_trips_through_toml() {
+    let mut config = AppConfig {
+        theme: Some(ThemePreference::Light),
+        max_fps: Some(60),
+        window: WindowOptions {
+            always_on_top: Some(true),
+            fullscreen: Some(false),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    config
+        .keybindings
+        .insert("quit".to_owned(), "Ctrl+Shift+Q".to_owned());
+    assert_eq!(AppConfig::from_toml(&config.to_toml()).unwrap(), config);
+}
+
+#[test]
+fn rejects_invalid_types() {
+    assert!(AppConfig::from_toml("max_fps = \"fast\"").is_err());
+}
+
+#[test]
+fn parses_shortcuts() {
+    assert_eq!(
+        parse_shortcut("Ctrl+Shift+S"),
+        Some(KeyboardShortcut::new(
+            Modifiers::COMMAND.plus(Modifiers::SHIFT),
+            Key::S
+        ))
+    );
+    assert_eq!(
+        parse_shortcut("F11"),
+        Some(KeyboardShortcut::new(Modifiers::NONE, Key::F11))
+    );
+    assert_eq!(parse_shortcut("Hyper+S"), None);
+    assert_eq!(parse_shortcut("Ctrl+NoSuchKey"), None);
+}
+
+#[test]
+fn formats_shortcuts_readable_by_parser() {
+    let shortcut = KeyboardShortcut::new(Modifiers::COMMAND.plus(Modifiers::ALT), Key::ArrowUp);
+    assert_eq!(shortcut_to_string(&shortcut), "Ctrl+Alt+ArrowUp");
+    assert_eq!(
+        parse_shortcut(&shortcut_to_string(&shortcut)),
+        Some(shortcut)
+    );
+}
+
+#[test]
+fn maps_action_ids() {
+    assert_eq!(
+        Action::from_id("toggle_fullscreen"),
+        Action::ToggleFullscreen
+    );
+    assert_eq!(Action::ToggleFullscreen.id(), "toggle_fullscreen");
+    assert_eq!(Action::from_id("jump"), Action::Custom("jump".to_owned()));
+}
@@ -0,0 +1,24 @@
+use egui::{pos2, Rect};
+use egui_tests::frosted_glass::{scissor_rect, FrostedGlass};
+
+#[test]
+fn converts_rects_to_pixels_clipped_to_the_target() {
+    let rect = Rect::from_min_max(pos2(10.0, 20.0), pos2(110.0, 70.0));
+    assert_eq!(
+        scissor_rect(rect, 2.0, [1000, 1000]),
+        Some([20, 40, 200, 100])
+    );
+    assert_eq!(scissor_rect(rect, 2.0, [100, 60]), Some([20, 40, 80, 20]));
+
+    let outside = Rect::from_min_max(pos2(-50.0, -50.0), pos2(-10.0, 10.0));
+    assert_eq!(scissor_rect(outside, 1.0, [100, 100]), None);
+}
+
+#[test]
+fn ignores_empty_rects() {
+    let mut frosted_glass = FrostedGlass::default();
+    frosted_glass.add(Rect::from_min_max(pos2(10.0, 10.0), pos2(10.0, 50.0)));
+    assert!(frosted_glass.is_empty());
+    frosted_glass.add(Rect::from_min_max(pos2(10.0, 10.0), pos2(20.0, 50.0)));
+    assert_eq!(frosted_glass.rects().len(), 1);
+}
@@ -0,0 +1,22 @@
+use egui_tests::gpu_errors::{GpuError, GpuErrorKind};
+
+fn validation_error(message: &str) -> GpuError {
+    GpuError {
+        kind: GpuErrorKind::Validation,
+        message: message.to_owned(),
+    }
+}
+
+#[test]
+fn detects_errors_of_a_lost_device() {
+    // As wgpu 0.18 formats an error of a submission to a lost device
+    let lost = validation_error(
+        "Validation Error\n\nCaused by:\n    In Queue::submit\n    Parent device is lost\n",
+    );
+    assert!(lost.is_device_lost());
+
+    let other = validation_error(
+        "Validation Error\n\nCaused by:\n    In Device::create_buffer\n    Buffer size 0 is invalid\n",
+    );
+    assert!(!other.is_device_lost());
+}
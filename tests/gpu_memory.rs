@@ -0,0 +1,105 @@
+use egui::{
+    epaint::{ImageDelta, Mesh, Primitive},
+    ClippedPrimitive, Color32, ColorImage, Rect, TextureId, TextureOptions, TexturesDelta,
+};
+use egui_tests::gpu_memory::{AllocationStats, GpuMemory, UNUSED_AFTER_FRAMES};
+
+fn image(size: [usize; 2]) -> ColorImage {
+    ColorImage::new(size, Color32::WHITE)
+}
+
+fn drawing(texture_id: TextureId) -> ClippedPrimitive {
+    let mut mesh = Mesh::with_texture(texture_id);
+    mesh.add_rect_with_uv(Rect::EVERYTHING, Rect::EVERYTHING, Color32::WHITE);
+    ClippedPrimitive {
+        clip_rect: Rect::EVERYTHING,
+        primitive: Primitive::Mesh(mesh),
+    }
+}
+
+#[test]
+fn counts_egui_textures_until_freed() {
+    let memory = GpuMemory::default();
+    let id = TextureId::Managed(1);
+    memory.set_texture(
+        id,
+        &ImageDelta::full(image([16, 8]), TextureOptions::LINEAR),
+    );
+    // Partial updates keep the size of the full texture
+    memory.set_texture(
+        id,
+        &ImageDelta::partial([0, 0], image([2, 2]), TextureOptions::LINEAR),
+    );
+    assert_eq!(
+        memory.stats().egui_textures,
+        AllocationStats {
+            count: 1,
+            bytes: 16 * 8 * 4
+        }
+    );
+
+    memory.free_texture(id);
+    assert_eq!(memory.stats().egui_textures, AllocationStats::default());
+}
+
+#[test]
+fn reports_native_textures_that_are_not_drawn() {
+    let memory = GpuMemory::default();
+    let drawn = TextureId::User(1);
+    let forgotten = TextureId::User(2);
+    memory.register_native(drawn);
+    memory.register_native(forgotten);
+    memory.set_native_size(forgotten, 1024);
+
+    for _ in 0..UNUSED_AFTER_FRAMES {
+        memory.record_frame(&[drawing(drawn)]);
+    }
+    let stats = memory.stats();
+    assert_eq!(stats.unused_textures, [forgotten]);
+    assert_eq!(
+        stats.native_textures,
+        AllocationStats {
+            count: 2,
+            bytes: 1024
+        }
+    );
+    assert_eq!(stats.buffers.count, 1);
+    assert!(stats.buffers.bytes > 0);
+
+    memory.free_native(forgotten);
+    assert!(memory.stats().unused_textures.is_empty());
+}
+
+#[test]
+fn texture_count_returns_to_baseline_after_set_and_free_cycles() {
+    let memory = GpuMemory::default();
+    let font = TextureId::Managed(0);
+    memory.set_texture(
+        font,
+        &ImageDelta::full(image([64, 64]), TextureOptions::LINEAR),
+    );
+    let baseline = memory.stats().egui_textures;
+
+    for index in 1..=1000 {
+        let id = TextureId::Managed(index);
+        let mut delta = TexturesDelta::default();
+        delta.set.push((
+            id,
+            ImageDelta::full(image([32, 32]), TextureOptions::LINEAR),
+        ));
+        for (id, image) in &delta.set {
+            memory.set_texture(*id, image);
+        }
+        assert_eq!(memory.stats().egui_textures.count, baseline.count + 1);
+
+        // egui frees a dropped texture with the next frame
+        let delta = TexturesDelta {
+            free: vec![id],
+            ..Default::default()
+        };
+        for id in &delta.free {
+            memory.free_texture(*id);
+        }
+    }
+    assert_eq!(memory.stats().egui_textures, baseline);
+}
@@ -0,0 +1,75 @@
+use egui_tests::headless::{HeadlessConfig, HeadlessRenderer, SKIP_ENV_VAR};
+
+/// Renders with any adapter, including software ones, unless the tests are skipped explicitly.
+fn renderer(config: &HeadlessConfig) -> Option<HeadlessRenderer> {
+    if std::env::var_os(SKIP_ENV_VAR).is_some() {
+        return None;
+    }
+    let mut config = config.clone();
+    config.graphics.backends = wgpu::Backends::all();
+    let renderer = HeadlessRenderer::new(&config).unwrap_or_else(|err| {
+        panic!("No adapter to render with: {err}. Set {SKIP_ENV_VAR}=1 to skip GPU tests.")
+    });
+    Some(renderer)
+}
+
+#[test]
+fn renders_image_of_configured_size() {
+    let config = HeadlessConfig {
+        size_in_pixels: [320, 240],
+        pixels_per_point: 2.0,
+        ..Default::default()
+    };
+    let Some(mut renderer) = renderer(&config) else {
+        return;
+    };
+
+    let image = renderer
+        .render(|ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| ui.label("Hello"));
+        })
+        .unwrap();
+    assert_eq!(image.dimensions(), (320, 240));
+    // The panel covers the whole image
+    assert!(image.pixels().all(|pixel| pixel.0[3] == 255));
+}
+
+#[test]
+fn encodes_png() {
+    let Some(mut renderer) = renderer(&HeadlessConfig::default()) else {
+        return;
+    };
+
+    let bytes = renderer.render_png(|_| {}).unwrap();
+    assert!(bytes.starts_with(b"\x89PNG"));
+}
+
+#[test]
+fn frees_textures_egui_drops() {
+    let Some(mut renderer) = renderer(&HeadlessConfig::default()) else {
+        return;
+    };
+
+    let mut ids = Vec::new();
+    for index in 0..100 {
+        renderer
+            .render(|ctx| {
+                // Dropped at the end of the frame, so egui frees it with the next one
+                let texture = ctx.load_texture(
+                    format!("texture {index}"),
+                    egui::ColorImage::new([64, 64], egui::Color32::from_gray(index)),
+                    Default::default(),
+                );
+                ids.push(texture.id());
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    ui.image((texture.id(), egui::vec2(64.0, 64.0)));
+                });
+            })
+            .unwrap();
+    }
+    renderer.render(|_| {}).unwrap();
+
+    assert!(ids.iter().all(|&id| !renderer.has_texture(id)));
+    // Only the font atlas is left
+    assert_eq!(renderer.memory().stats().egui_textures.count, 1);
+}
@@ -0,0 +1,25 @@
+use egui::Color32;
+use egui_tests::icon::WindowIcon;
+
+#[test]
+fn decodes_the_embedded_png() {
+    let icon = WindowIcon::from_png(include_bytes!("../assets/icon.png")).unwrap();
+    assert_eq!(icon.size(), [64, 64]);
+    assert_eq!(icon.rgba().len(), 64 * 64 * 4);
+}
+
+#[test]
+fn badge_only_covers_the_top_right_corner() {
+    let icon = WindowIcon::from_rgba(vec![0; 16 * 16 * 4], 16, 16);
+    let badged = icon.with_badge(Color32::RED);
+
+    let pixel = |x: usize, y: usize| &badged.rgba()[(y * 16 + x) * 4..][..4];
+    assert_eq!(pixel(12, 3), &[255, 0, 0, 255]);
+    assert_eq!(pixel(2, 13), &[0, 0, 0, 0]);
+}
+
+#[test]
+#[should_panic]
+fn rejects_mismatched_sizes() {
+    WindowIcon::from_rgba(vec![0; 10], 16, 16);
+}
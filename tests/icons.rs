@@ -0,0 +1,24 @@
+#![cfg(feature = "icons")]
+
+use std::collections::HashSet;
+
+use egui_tests::icons::{add_to_fonts, Icon};
+
+#[test]
+fn icons_have_distinct_glyphs() {
+    let glyphs: HashSet<_> = Icon::ALL.iter().map(|icon| icon.glyph()).collect();
+    assert_eq!(glyphs.len(), Icon::ALL.len());
+    assert_eq!(Icon::Save.with_text("Save"), format!("{} Save", Icon::Save));
+}
+
+#[test]
+fn icon_font_is_a_fallback() {
+    let mut fonts = egui::FontDefinitions::default();
+    add_to_fonts(&mut fonts);
+    add_to_fonts(&mut fonts);
+    for family in [egui::FontFamily::Proportional, egui::FontFamily::Monospace] {
+        let names = &fonts.families[&family];
+        assert_eq!(names.last().map(String::as_str), Some("phosphor"));
+        assert_eq!(names.iter().filter(|name| *name == "phosphor").count(), 1);
+    }
+}
@@ -0,0 +1,30 @@
+#![cfg(feature = "plot")]
+
+use egui_tests::metrics::MetricsStore;
+
+#[test]
+fn oldest_samples_are_dropped_beyond_capacity() {
+    let store = MetricsStore::new(3);
+    for time in 0..5 {
+        store.push_at("fps", f64::from(time), f64::from(time) * 10.0);
+    }
+    assert_eq!(
+        store.samples("fps"),
+        [[2.0, 20.0], [3.0, 30.0], [4.0, 40.0]]
+    );
+    assert_eq!(store.samples_since("fps", 3.0), [[3.0, 30.0], [4.0, 40.0]]);
+    assert_eq!(store.latest("fps"), Some(40.0));
+    assert!(store.samples("missing").is_empty());
+}
+
+#[test]
+fn clones_share_series() {
+    let store = MetricsStore::default();
+    let worker = store.clone();
+    std::thread::spawn(move || worker.push("load", 0.5))
+        .join()
+        .unwrap();
+    assert_eq!(store.names(), ["load"]);
+    store.clear();
+    assert!(store.names().is_empty());
+}
@@ -0,0 +1,48 @@
+use egui_tests::monitors::{MonitorInfo, MonitorPlacement};
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+
+fn monitor(x: i32, y: i32, width: u32, height: u32, scale_factor: f64) -> MonitorInfo {
+    MonitorInfo {
+        name: None,
+        position: PhysicalPosition::new(x, y),
+        size: PhysicalSize::new(width, height),
+        scale_factor,
+        refresh_rate_millihertz: None,
+        primary: false,
+    }
+}
+
+#[test]
+fn centers_on_a_monitor_keeping_the_size() {
+    let monitors = [
+        monitor(0, 0, 1920, 1080, 1.0),
+        monitor(1920, 0, 3840, 2160, 2.0),
+    ];
+    let target = MonitorPlacement::Center(1).target(&monitors, PhysicalSize::new(840, 160));
+    assert_eq!(target, Some((PhysicalPosition::new(3420, 1000), None)));
+}
+
+#[test]
+fn spans_the_bounding_box_of_all_monitors() {
+    let monitors = [
+        monitor(0, 0, 1920, 1080, 1.0),
+        monitor(-1280, -200, 1280, 1024, 1.25),
+    ];
+    let target = MonitorPlacement::SpanAll.target(&monitors, PhysicalSize::new(800, 600));
+    assert_eq!(
+        target,
+        Some((
+            PhysicalPosition::new(-1280, -200),
+            Some(PhysicalSize::new(3200, 1280))
+        ))
+    );
+}
+
+#[test]
+fn missing_monitor_has_no_target() {
+    let monitors = [monitor(0, 0, 1920, 1080, 1.0)];
+    assert_eq!(
+        MonitorPlacement::Fill(3).target(&monitors, PhysicalSize::new(800, 600)),
+        None
+    );
+}
@@ -0,0 +1,72 @@
+use std::time::Duration;
+
+use egui_tests::{FramePacer, RenderMode, UnfocusedPolicy};
+
+#[test]
+fn caps_the_frame_rate_while_unfocused() {
+    let mut pacer = FramePacer::new(Some(60));
+    pacer.set_unfocused_policy(UnfocusedPolicy::MaxFps(10));
+    assert_eq!(
+        pacer.min_frame_interval(),
+        Some(Duration::from_secs_f64(1.0 / 60.0))
+    );
+    assert_eq!(pacer.unfocused_delay(), None);
+
+    pacer.set_focused(false);
+    assert_eq!(pacer.min_frame_interval(), Some(Duration::from_millis(100)));
+    // No frame was drawn yet, so the next one is due right away
+    assert_eq!(pacer.unfocused_delay(), Some(Duration::ZERO));
+    assert!(!pacer.is_event_driven());
+}
+
+#[test]
+fn keeps_the_lower_cap_while_unfocused() {
+    let mut pacer = FramePacer::new(Some(5));
+    pacer.set_unfocused_policy(UnfocusedPolicy::MaxFps(10));
+    pacer.set_focused(false);
+    assert_eq!(pacer.min_frame_interval(), Some(Duration::from_millis(200)));
+}
+
+#[test]
+fn sleeps_until_input_when_event_driven() {
+    let mut pacer = FramePacer::new(None);
+    pacer.set_unfocused_policy(UnfocusedPolicy::EventDriven);
+    assert!(!pacer.is_event_driven());
+    pacer.set_focused(false);
+    assert!(pacer.is_event_driven());
+    assert_eq!(pacer.unfocused_delay(), Some(Duration::MAX));
+    assert_eq!(pacer.min_frame_interval(), None);
+}
+
+#[test]
+fn ignores_the_repaint_delay_when_continuous() {
+    let mut pacer = FramePacer::new(None);
+    let requested = Duration::from_millis(500);
+    assert_eq!(pacer.repaint_delay(requested), requested);
+    pacer.set_render_mode(RenderMode::Continuous);
+    assert_eq!(pacer.repaint_delay(requested), Duration::ZERO);
+}
+
+#[test]
+fn tracks_the_time_of_each_frame() {
+    let mut pacer = FramePacer::new(None);
+    pacer.begin_frame();
+    let first = pacer.time();
+    assert_eq!(first.frame_index, 0);
+    assert_eq!(first.delta, Duration::ZERO);
+    assert_eq!(first.elapsed, Duration::ZERO);
+
+    std::thread::sleep(Duration::from_millis(5));
+    pacer.begin_frame();
+    let second = pacer.time();
+    assert_eq!(second.frame_index, 1);
+    assert!(second.delta >= Duration::from_millis(5));
+    assert_eq!(second.smoothed_delta, second.delta);
+    assert_eq!(second.elapsed, second.delta);
+
+    pacer.begin_frame();
+    let third = pacer.time();
+    assert_eq!(third.frame_index, 2);
+    assert_eq!(third.elapsed, second.elapsed + third.delta);
+    assert!(third.smoothed_delta <= second.smoothed_delta);
+}
@@ -0,0 +1,15 @@
+use egui_tests::persistence::EguiMemoryPersistence;
+
+#[test]
+fn loading_memory_keeps_the_options() {
+    let path = std::env::temp_dir().join(format!("egui_memory_{}.ron", std::process::id()));
+    let persistence = EguiMemoryPersistence::with_path(&path);
+    persistence.save(&egui::Context::default());
+
+    let ctx = egui::Context::default();
+    ctx.options_mut(|options| options.zoom_with_keyboard = false);
+    persistence.load(&ctx);
+    let _ = std::fs::remove_file(&path);
+
+    assert!(!ctx.options(|options| options.zoom_with_keyboard));
+}
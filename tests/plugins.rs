@@ -0,0 +1,33 @@
+use egui_tests::plugin::{Plugin, Plugins};
+
+struct Named(&'static str);
+
+impl Plugin for Named {
+    fn name(&self) -> &str {
+        self.0
+    }
+}
+
+#[test]
+fn plugins_keep_order_and_replace_by_name() {
+    let mut plugins = Plugins::default();
+    plugins.add(Named("a"));
+    plugins.add(Named("b"));
+    plugins.add(Named("a"));
+    assert_eq!(plugins.names().collect::<Vec<_>>(), ["a", "b"]);
+    assert!(plugins.is_enabled("a"));
+    assert!(!plugins.contains("c"));
+}
+
+#[test]
+fn enabled_state_applies_to_plugins_added_later() {
+    let mut plugins = Plugins::default();
+    plugins.set_enabled("later", false);
+    assert!(!plugins.is_enabled("later"));
+    plugins.add(Named("later"));
+    assert!(plugins.contains("later"));
+    assert!(!plugins.is_enabled("later"));
+
+    plugins.set_enabled("later", true);
+    assert!(plugins.is_enabled("later"));
+}
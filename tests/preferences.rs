@@ -0,0 +1,29 @@
+use egui_tests::{settings::Preferences, theme::ThemePreference};
+
+#[test]
+fn missing_preferences_use_defaults() {
+    let preferences: Preferences = ron::from_str("(vsync: false)").unwrap();
+    assert_eq!(
+        preferences,
+        Preferences {
+            vsync: false,
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn round_trips_through_ron() {
+    let mut preferences = Preferences {
+        theme: ThemePreference::Light,
+        max_fps: Some(60),
+        scale: 1.5,
+        transparent: true,
+        ..Default::default()
+    };
+    preferences
+        .shortcuts
+        .insert("quit".to_owned(), String::new());
+    let text = ron::to_string(&preferences).unwrap();
+    assert_eq!(ron::from_str::<Preferences>(&text).unwrap(), preferences);
+}
@@ -0,0 +1,21 @@
+#![cfg(feature = "shader-playground")]
+
+use egui_tests::shader_playground::{validate, DEFAULT_SHADER};
+
+#[test]
+fn default_shader_compiles() {
+    assert_eq!(validate(DEFAULT_SHADER), Ok(()));
+}
+
+#[test]
+fn reports_lines_of_the_edited_source() {
+    let source = "@fragment\nfn fs_main() -> @location(0) vec4<f32> {\n    return undefined;\n}\n";
+    let err = validate(source).unwrap_err();
+    assert_eq!(err.line, Some(3));
+}
+
+#[test]
+fn requires_a_fragment_entry_point() {
+    let err = validate("fn helper() {}\n").unwrap_err();
+    assert_eq!(err.line, None);
+}
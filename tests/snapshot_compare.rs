@@ -0,0 +1,63 @@
+use egui_tests::snapshot::{compare, Comparison, Tolerance};
+use image::{Rgba, RgbaImage};
+
+fn filled(width: u32, height: u32, color: [u8; 4]) -> RgbaImage {
+    RgbaImage::from_pixel(width, height, Rgba(color))
+}
+
+#[test]
+fn matches_within_channel_tolerance() {
+    let expected = filled(4, 4, [100, 100, 100, 255]);
+    let actual = filled(4, 4, [102, 99, 100, 255]);
+    assert_eq!(
+        compare(&expected, &actual, Tolerance::default()),
+        Comparison::Matches
+    );
+}
+
+#[test]
+fn reports_differing_pixels() {
+    let expected = filled(4, 4, [0, 0, 0, 255]);
+    let mut actual = expected.clone();
+    actual.put_pixel(1, 2, Rgba([0, 50, 0, 255]));
+
+    let Comparison::PixelsDiffer {
+        count,
+        max_difference,
+        diff,
+    } = compare(&expected, &actual, Tolerance::default())
+    else {
+        panic!("expected a difference");
+    };
+    assert_eq!(count, 1);
+    assert_eq!(max_difference, 50);
+    assert_eq!(diff.get_pixel(1, 2), &Rgba([255, 0, 0, 255]));
+    assert_eq!(diff.get_pixel(0, 0), &Rgba([0, 0, 0, 255]));
+}
+
+#[test]
+fn allows_some_differing_pixels() {
+    let expected = filled(4, 4, [0, 0, 0, 255]);
+    let mut actual = expected.clone();
+    actual.put_pixel(3, 3, Rgba([255, 255, 255, 255]));
+    let tolerance = Tolerance {
+        pixels: 1,
+        ..Default::default()
+    };
+    assert_eq!(compare(&expected, &actual, tolerance), Comparison::Matches);
+}
+
+#[test]
+fn reports_size_mismatch() {
+    assert_eq!(
+        compare(
+            &filled(4, 4, [0; 4]),
+            &filled(4, 2, [0; 4]),
+            Tolerance::default()
+        ),
+        Comparison::SizeDiffers {
+            expected: (4, 4),
+            actual: (4, 2),
+        }
+    );
+}
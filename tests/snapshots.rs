@@ -0,0 +1,64 @@
+//! Renders UI scenes headlessly and compares them against the references in
+//! `tests/snapshots`. Run with `UPDATE_SNAPSHOTS=1` to regenerate them, or with
+//! `SKIP_GPU_TESTS=1` to skip them on machines without any adapter.
+
+use egui_tests::{
+    headless::{HeadlessConfig, HeadlessRenderer, SKIP_ENV_VAR},
+    snapshot::Snapshots,
+};
+
+/// Renders a few frames so layouts sizing themselves to their content settle, then checks the
+/// last one against the reference named `name`.
+fn check_scene(name: &str, mut run_ui: impl FnMut(&egui::Context)) {
+    if std::env::var_os(SKIP_ENV_VAR).is_some() {
+        return;
+    }
+    let mut config = HeadlessConfig {
+        size_in_pixels: [400, 300],
+        ..Default::default()
+    };
+    // The references are rendered with whatever adapter there is, software ones included
+    config.graphics.backends = wgpu::Backends::all();
+    let mut renderer = HeadlessRenderer::new(&config).unwrap_or_else(|err| {
+        panic!("No adapter to render snapshot {name} with: {err}. Set {SKIP_ENV_VAR}=1 to skip it.")
+    });
+    renderer.context().set_visuals(egui::Visuals::dark());
+
+    for _ in 0..2 {
+        renderer.render(&mut run_ui).unwrap();
+    }
+    let image = renderer.render(&mut run_ui).unwrap();
+    Snapshots::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/snapshots")).check(name, &image);
+}
+
+#[test]
+fn label() {
+    check_scene("label", |ctx| {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.heading("Heading");
+            ui.label("A label with some text");
+        });
+    });
+}
+
+#[test]
+fn widgets() {
+    check_scene("widgets", |ctx| {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            let _ = ui.button("Button");
+            ui.checkbox(&mut true, "Checkbox");
+            ui.add(egui::Slider::new(&mut 0.5, 0.0..=1.0).text("Slider"));
+            ui.text_edit_singleline(&mut "Text edit".to_owned());
+        });
+    });
+}
+
+#[test]
+fn window() {
+    check_scene("window", |ctx| {
+        egui::CentralPanel::default().show(ctx, |_| {});
+        egui::Window::new("Window")
+            .default_pos([40.0, 40.0])
+            .show(ctx, |ui| ui.label("Inside a window"));
+    });
+}
@@ -0,0 +1,102 @@
+use egui_tests::{
+    composite::needs_alpha_conversion,
+    graphics::{
+        resolve_present_mode, select_alpha_mode, select_hdr_surface_format, select_surface_format,
+    },
+};
+use wgpu::{CompositeAlphaMode, PresentMode, TextureFormat};
+
+#[test]
+fn picks_format_matching_gamma_correction() {
+    let supported = [TextureFormat::Bgra8Unorm, TextureFormat::Bgra8UnormSrgb];
+    assert_eq!(
+        select_surface_format(&supported, true),
+        (TextureFormat::Bgra8UnormSrgb, TextureFormat::Bgra8UnormSrgb)
+    );
+    assert_eq!(
+        select_surface_format(&supported, false),
+        (TextureFormat::Bgra8Unorm, TextureFormat::Bgra8Unorm)
+    );
+}
+
+#[test]
+fn renders_through_srgb_view_if_surface_is_linear_only() {
+    assert_eq!(
+        select_surface_format(&[TextureFormat::Rgba8Unorm], true),
+        (TextureFormat::Rgba8Unorm, TextureFormat::Rgba8UnormSrgb)
+    );
+}
+
+#[test]
+fn falls_back_to_similar_present_mode() {
+    let supported = [PresentMode::Fifo, PresentMode::Immediate];
+    assert_eq!(
+        resolve_present_mode(PresentMode::Mailbox, &supported),
+        PresentMode::Immediate
+    );
+    assert_eq!(
+        resolve_present_mode(PresentMode::FifoRelaxed, &supported),
+        PresentMode::Fifo
+    );
+    assert_eq!(
+        resolve_present_mode(PresentMode::Immediate, &[PresentMode::Fifo]),
+        PresentMode::Fifo
+    );
+}
+
+#[test]
+fn detects_hdr_surface_formats() {
+    assert_eq!(
+        select_hdr_surface_format(&[TextureFormat::Bgra8UnormSrgb, TextureFormat::Rgb10a2Unorm]),
+        Some(TextureFormat::Rgb10a2Unorm)
+    );
+    assert_eq!(
+        select_hdr_surface_format(&[TextureFormat::Rgb10a2Unorm, TextureFormat::Rgba16Float]),
+        Some(TextureFormat::Rgba16Float)
+    );
+    assert_eq!(
+        select_hdr_surface_format(&[TextureFormat::Bgra8UnormSrgb]),
+        None
+    );
+}
+
+#[test]
+fn transparent_windows_prefer_premultiplied_alpha() {
+    let supported = [
+        CompositeAlphaMode::Opaque,
+        CompositeAlphaMode::PostMultiplied,
+        CompositeAlphaMode::PreMultiplied,
+    ];
+    assert_eq!(
+        select_alpha_mode(&supported, true),
+        CompositeAlphaMode::PreMultiplied
+    );
+    assert_eq!(
+        select_alpha_mode(&supported, false),
+        CompositeAlphaMode::Opaque
+    );
+    assert_eq!(
+        select_alpha_mode(&[CompositeAlphaMode::Opaque], true),
+        CompositeAlphaMode::Opaque
+    );
+}
+
+#[test]
+fn converts_alpha_only_where_the_compositor_expects_otherwise() {
+    assert!(!needs_alpha_conversion(
+        CompositeAlphaMode::Opaque,
+        TextureFormat::Bgra8UnormSrgb
+    ));
+    assert!(!needs_alpha_conversion(
+        CompositeAlphaMode::PreMultiplied,
+        TextureFormat::Bgra8Unorm
+    ));
+    assert!(needs_alpha_conversion(
+        CompositeAlphaMode::PreMultiplied,
+        TextureFormat::Bgra8UnormSrgb
+    ));
+    assert!(needs_alpha_conversion(
+        CompositeAlphaMode::PostMultiplied,
+        TextureFormat::Bgra8Unorm
+    ));
+}
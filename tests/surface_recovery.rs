@@ -0,0 +1,110 @@
+use std::collections::VecDeque;
+
+use egui_tests::surface::{acquire_texture, AcquireError, LossTracker, RenderSurface};
+use wgpu::SurfaceError;
+
+/// Returns queued results instead of talking to a GPU and counts reconfigurations.
+struct MockSurface {
+    results: VecDeque<Result<u32, SurfaceError>>,
+    reconfigured: usize,
+}
+
+impl MockSurface {
+    fn new(results: impl IntoIterator<Item = Result<u32, SurfaceError>>) -> Self {
+        Self {
+            results: results.into_iter().collect(),
+            reconfigured: 0,
+        }
+    }
+}
+
+impl RenderSurface for MockSurface {
+    type Texture = u32;
+
+    fn get_current_texture(&mut self) -> Result<u32, SurfaceError> {
+        self.results
+            .pop_front()
+            .expect("surface was polled more often than expected")
+    }
+
+    fn reconfigure(&mut self) {
+        self.reconfigured += 1;
+    }
+}
+
+#[test]
+fn returns_texture_without_reconfiguring() {
+    let mut surface = MockSurface::new([Ok(1)]);
+    assert_eq!(acquire_texture(&mut surface), Ok(1));
+    assert_eq!(surface.reconfigured, 0);
+}
+
+#[test]
+fn reconfigures_lost_and_outdated_surfaces() {
+    for err in [SurfaceError::Lost, SurfaceError::Outdated] {
+        let mut surface = MockSurface::new([Err(err), Ok(2)]);
+        assert_eq!(acquire_texture(&mut surface), Ok(2));
+        assert_eq!(surface.reconfigured, 1);
+    }
+}
+
+#[test]
+fn skips_frame_when_reconfiguring_does_not_help() {
+    let mut surface = MockSurface::new([Err(SurfaceError::Outdated), Err(SurfaceError::Outdated)]);
+    assert_eq!(acquire_texture(&mut surface), Err(AcquireError::SkipFrame));
+    assert_eq!(surface.reconfigured, 1);
+}
+
+#[test]
+fn reports_surface_lost_after_reconfiguring() {
+    let mut surface = MockSurface::new([Err(SurfaceError::Lost), Err(SurfaceError::Lost)]);
+    assert_eq!(acquire_texture(&mut surface), Err(AcquireError::Lost));
+    assert_eq!(surface.reconfigured, 1);
+}
+
+#[test]
+fn skips_frame_on_timeout() {
+    let mut surface = MockSurface::new([Err(SurfaceError::Timeout)]);
+    assert_eq!(acquire_texture(&mut surface), Err(AcquireError::SkipFrame));
+    assert_eq!(surface.reconfigured, 0);
+}
+
+#[test]
+fn reports_out_of_memory() {
+    let mut surface = MockSurface::new([Err(SurfaceError::OutOfMemory)]);
+    assert_eq!(
+        acquire_texture(&mut surface),
+        Err(AcquireError::OutOfMemory)
+    );
+
+    let mut surface =
+        MockSurface::new([Err(SurfaceError::Outdated), Err(SurfaceError::OutOfMemory)]);
+    assert_eq!(
+        acquire_texture(&mut surface),
+        Err(AcquireError::OutOfMemory)
+    );
+}
+
+#[test]
+fn assumes_device_lost_after_consecutive_losses() {
+    let mut tracker = LossTracker::default();
+    let lost: Result<(), _> = Err(AcquireError::Lost);
+    for _ in 1..LossTracker::THRESHOLD {
+        assert!(!tracker.record(&lost));
+    }
+    assert!(tracker.record(&lost));
+    // Starts counting again after reporting the loss
+    assert!(!tracker.record(&lost));
+}
+
+#[test]
+fn acquired_texture_resets_losses() {
+    let mut tracker = LossTracker::default();
+    let lost: Result<(), _> = Err(AcquireError::Lost);
+    for _ in 1..LossTracker::THRESHOLD {
+        tracker.record(&lost);
+    }
+    assert!(!tracker.record(&Ok(())));
+    assert!(!tracker.record::<()>(&Err(AcquireError::SkipFrame)));
+    assert!(!tracker.record(&lost));
+}
@@ -0,0 +1,51 @@
+use std::time::{Duration, Instant};
+
+use egui_tests::tasks::{TaskManager, TaskState};
+
+fn wait_until_done(tasks: &TaskManager) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while tasks.is_busy() {
+        assert!(Instant::now() < deadline, "tasks did not finish in time");
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
+
+#[test]
+fn reports_progress_and_finishes() {
+    let ctx = egui::Context::default();
+    let mut tasks = TaskManager::new(2);
+    let id = tasks.spawn(&ctx, "task", |task| task.set_progress(0.5));
+    wait_until_done(&tasks);
+
+    let info = tasks.tasks().find(|task| task.id == id).unwrap();
+    assert_eq!(info.state, TaskState::Finished);
+    assert_eq!(info.progress, 0.5);
+}
+
+#[test]
+fn cancels_running_task() {
+    let ctx = egui::Context::default();
+    let mut tasks = TaskManager::new(1);
+    let id = tasks.spawn(&ctx, "task", |task| {
+        while !task.is_cancelled() {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    });
+    tasks.cancel(id);
+    wait_until_done(&tasks);
+
+    assert_eq!(tasks.tasks().next().unwrap().state, TaskState::Cancelled);
+}
+
+#[test]
+fn reports_panics_as_failures() {
+    let ctx = egui::Context::default();
+    let mut tasks = TaskManager::new(1);
+    tasks.spawn(&ctx, "task", |_| panic!("broken"));
+    wait_until_done(&tasks);
+
+    assert_eq!(
+        tasks.tasks().next().unwrap().state,
+        TaskState::Failed("broken".to_owned())
+    );
+}
@@ -0,0 +1,76 @@
+#![cfg(feature = "threaded-tessellation")]
+
+use egui::{
+    epaint::{ImageDelta, Primitive},
+    ClippedPrimitive, Color32, ColorImage, Context, FullOutput, TextureId, TextureOptions,
+};
+use egui_tests::tessellation::{TessellationPipeline, TessellationWorker};
+
+fn run_ui(ctx: &Context) -> FullOutput {
+    ctx.run(Default::default(), |ctx| {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.heading("Tessellation");
+            let _ = ui.button("Click me");
+            ui.add(egui::Slider::new(&mut 0.5, 0.0..=1.0));
+        });
+    })
+}
+
+fn vertex_counts(paint_jobs: &[ClippedPrimitive]) -> Vec<usize> {
+    paint_jobs
+        .iter()
+        .map(|job| match &job.primitive {
+            Primitive::Mesh(mesh) => mesh.vertices.len(),
+            Primitive::Callback(_) => 0,
+        })
+        .collect()
+}
+
+#[test]
+fn matches_tessellating_on_the_context() {
+    let ctx = Context::default();
+    run_ui(&ctx);
+    let output = run_ui(&ctx);
+
+    let mut worker = TessellationWorker::new();
+    assert!(worker.wait().is_none());
+    worker.submit(&ctx, output.shapes.clone(), output.pixels_per_point);
+    assert!(worker.is_pending());
+    let threaded = worker.wait().unwrap();
+    assert!(!worker.is_pending());
+
+    let inline = ctx.tessellate(output.shapes, output.pixels_per_point);
+    assert!(!inline.is_empty());
+    assert_eq!(vertex_counts(&threaded), vertex_counts(&inline));
+}
+
+#[test]
+fn paints_the_previous_frame_until_drained() {
+    let ctx = Context::default();
+    run_ui(&ctx);
+    let mut pipeline = TessellationPipeline::default();
+
+    let mut first = run_ui(&ctx);
+    let expected = vertex_counts(&ctx.tessellate(first.shapes.clone(), first.pixels_per_point));
+    // An empty pipeline paints the frame right away and again with the next one
+    let frame = pipeline.advance(&ctx, &mut first, true);
+    assert_eq!(vertex_counts(&frame.paint_jobs), expected);
+    assert!(pipeline.is_pending());
+
+    let mut second = ctx.run(Default::default(), |_| {});
+    let image = ColorImage::new([2, 2], Color32::RED);
+    second.textures_delta.set.push((
+        TextureId::Managed(42),
+        ImageDelta::full(image, TextureOptions::LINEAR),
+    ));
+    let frame = pipeline.advance(&ctx, &mut second, true);
+    assert_eq!(vertex_counts(&frame.paint_jobs), expected);
+    assert!(frame.textures_delta.set.is_empty());
+
+    // Draining skips the meshes of the second frame, but keeps its texture updates
+    let mut third = run_ui(&ctx);
+    let frame = pipeline.advance(&ctx, &mut third, false);
+    assert_eq!(vertex_counts(&frame.paint_jobs), expected);
+    assert_eq!(frame.textures_delta.set[0].0, TextureId::Managed(42));
+    assert!(!pipeline.is_pending());
+}
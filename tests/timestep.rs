@@ -0,0 +1,21 @@
+use std::time::Duration;
+
+use egui_tests::timestep::FixedTimestep;
+
+#[test]
+fn splits_frames_into_fixed_steps() {
+    let mut timestep = FixedTimestep::new(100);
+    assert_eq!(timestep.advance(Duration::from_millis(25)), 2);
+    assert!((timestep.interpolation() - 0.5).abs() < 1e-3);
+    assert_eq!(timestep.advance(Duration::from_millis(5)), 1);
+    assert_eq!(timestep.ticks(), 3);
+    assert!(timestep.interpolation() < 1e-3);
+}
+
+#[test]
+fn drops_time_after_a_long_frame() {
+    let mut timestep = FixedTimestep::new(60);
+    assert_eq!(timestep.advance(Duration::from_secs(5)), 8);
+    assert!(timestep.interpolation() <= 0.5);
+    assert_eq!(timestep.advance(Duration::ZERO), 0);
+}
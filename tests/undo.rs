@@ -0,0 +1,106 @@
+use std::time::Duration;
+
+use egui_tests::undo::{Command, CommandStack};
+
+/// Adds to a number, edits of the same sign merge.
+struct Add(i32);
+
+impl Command for Add {
+    type Target = i32;
+
+    fn apply(&mut self, target: &mut i32) {
+        *target += self.0;
+    }
+
+    fn undo(&mut self, target: &mut i32) {
+        *target -= self.0;
+    }
+
+    fn merge(&mut self, next: &Self) -> bool {
+        if self.0.signum() != next.0.signum() {
+            return false;
+        }
+        self.0 += next.0;
+        true
+    }
+}
+
+#[test]
+fn undo_and_redo_revert_and_reapply() {
+    let mut stack = CommandStack::new().with_merge_interval(Duration::ZERO);
+    let mut value = 0;
+    stack.execute(&mut value, Add(1));
+    stack.execute(&mut value, Add(2));
+    assert_eq!((value, stack.undo_len()), (3, 2));
+
+    assert!(stack.undo(&mut value));
+    assert_eq!(value, 1);
+    assert!(stack.undo(&mut value));
+    assert!(!stack.undo(&mut value));
+    assert_eq!((value, stack.redo_len()), (0, 2));
+
+    assert!(stack.redo(&mut value));
+    assert_eq!(value, 1);
+
+    stack.execute(&mut value, Add(5));
+    assert!(!stack.can_redo());
+    assert_eq!(value, 6);
+}
+
+#[test]
+fn rapid_edits_merge() {
+    let mut stack = CommandStack::new().with_merge_interval(Duration::from_secs(60));
+    let mut value = 0;
+    stack.execute(&mut value, Add(1));
+    stack.execute(&mut value, Add(1));
+    stack.execute(&mut value, Add(-1));
+    assert_eq!(stack.undo_len(), 2);
+
+    stack.seal();
+    stack.execute(&mut value, Add(-1));
+    assert_eq!(stack.undo_len(), 3);
+
+    stack.undo(&mut value);
+    stack.undo(&mut value);
+    assert_eq!(value, 2);
+    stack.undo(&mut value);
+    assert_eq!(value, 0);
+}
+
+#[test]
+fn groups_undo_in_one_step() {
+    let mut stack = CommandStack::new().with_merge_interval(Duration::ZERO);
+    let mut value = 0;
+    stack.execute(&mut value, Add(1));
+    stack.begin_group();
+    stack.execute(&mut value, Add(2));
+    stack.begin_group();
+    stack.execute(&mut value, Add(-4));
+    stack.end_group();
+    stack.execute(&mut value, Add(3));
+    stack.end_group();
+    assert_eq!((value, stack.undo_len()), (2, 2));
+
+    stack.undo(&mut value);
+    assert_eq!(value, 1);
+    stack.redo(&mut value);
+    assert_eq!(value, 2);
+
+    stack.begin_group();
+    stack.end_group();
+    assert_eq!(stack.undo_len(), 2);
+}
+
+#[test]
+fn limit_drops_oldest_steps() {
+    let mut stack = CommandStack::new()
+        .with_merge_interval(Duration::ZERO)
+        .with_limit(2);
+    let mut value = 0;
+    for _ in 0..3 {
+        stack.execute(&mut value, Add(1));
+    }
+    assert_eq!(stack.undo_len(), 2);
+    while stack.undo(&mut value) {}
+    assert_eq!(value, 1);
+}
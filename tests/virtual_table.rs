@@ -0,0 +1,41 @@
+use egui_tests::virtual_table::{Column, SortOrder, VirtualTable};
+
+fn compare(a: &(u32, &str), b: &(u32, &str), column: usize) -> std::cmp::Ordering {
+    match column {
+        0 => a.0.cmp(&b.0),
+        _ => a.1.cmp(b.1),
+    }
+}
+
+#[test]
+fn rows_are_sorted_by_the_selected_column() {
+    let rows = [(3, "b"), (1, "c"), (2, "a")];
+    let mut table = VirtualTable::new(
+        "table",
+        [Column::new("Id", 40.0), Column::new("Name", 80.0)],
+    );
+    assert_eq!(table.row_order(&rows, compare), [0, 1, 2]);
+
+    table.set_sort(Some((0, SortOrder::Ascending)));
+    assert_eq!(table.row_order(&rows, compare), [1, 2, 0]);
+    table.set_sort(Some((1, SortOrder::Descending)));
+    assert_eq!(table.row_order(&rows, compare), [1, 0, 2]);
+    table.set_sort(None);
+    assert_eq!(table.row_order(&rows, compare), [0, 1, 2]);
+}
+
+#[test]
+fn order_is_kept_until_invalidated() {
+    let mut rows = vec![(2, "a"), (1, "b")];
+    let mut table = VirtualTable::new("table", [Column::new("Id", 40.0)]);
+    table.set_sort(Some((0, SortOrder::Ascending)));
+    assert_eq!(table.row_order(&rows, compare), [1, 0]);
+
+    rows[0].0 = 0;
+    assert_eq!(table.row_order(&rows, compare), [1, 0]);
+    table.invalidate();
+    assert_eq!(table.row_order(&rows, compare), [0, 1]);
+
+    rows.push((5, "c"));
+    assert_eq!(table.row_order(&rows, compare), [0, 1, 2]);
+}